@@ -0,0 +1,322 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ReceiveMsg;
+use cw_utils::one_coin;
+use oraiswap::asset::AssetInfo;
+use sha2::{Digest, Sha256};
+
+use coharvest_bid_pool::helper::into_cosmos_msg;
+
+use crate::{
+    error::ContractError,
+    msg::{ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
+    state::{Airdrop, Config, AIRDROPS, CLAIMED, CONFIG},
+};
+
+// version info for migration
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        bid_pool: deps.api.addr_validate(&msg.bid_pool)?,
+    };
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::UpdateConfig { owner } => execute_update_config(deps, info, owner),
+        ExecuteMsg::Fund { round } => {
+            let coin = one_coin(&info)?;
+            execute_fund(
+                deps,
+                info.sender,
+                round,
+                AssetInfo::NativeToken { denom: coin.denom },
+                coin.amount,
+            )
+        }
+        ExecuteMsg::RegisterMerkleRoot {
+            round,
+            merkle_root,
+            expiration,
+        } => execute_register_merkle_root(deps, env, info, round, merkle_root, expiration),
+        ExecuteMsg::Claim {
+            round,
+            amount,
+            proof,
+        } => execute_claim(deps, env, info, round, amount, proof),
+        ExecuteMsg::Clawback { round } => execute_clawback(deps, env, info, round),
+    }
+}
+
+fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::Fund { round } => {
+            let sender = deps.api.addr_validate(&cw20_msg.sender)?;
+            execute_fund(
+                deps,
+                sender,
+                round,
+                AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+                cw20_msg.amount,
+            )
+        }
+    }
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+// the first funding sets the asset of the airdrop, later ones must send the same asset
+fn execute_fund(
+    deps: DepsMut,
+    sender: Addr,
+    round: u64,
+    asset_info: AssetInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if sender != config.bid_pool && sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut airdrop = AIRDROPS
+        .may_load(deps.storage, round)?
+        .unwrap_or_else(|| Airdrop {
+            asset_info: asset_info.clone(),
+            total_amount: Uint128::zero(),
+            claimed_amount: Uint128::zero(),
+            merkle_root: None,
+            expiration: None,
+            clawed_back: false,
+        });
+    if airdrop.asset_info != asset_info || airdrop.clawed_back {
+        return Err(ContractError::InvalidFunds {});
+    }
+    airdrop.total_amount = airdrop.total_amount.checked_add(amount)?;
+    AIRDROPS.save(deps.storage, round, &airdrop)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "fund"),
+        ("round", &round.to_string()),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+fn execute_register_merkle_root(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    merkle_root: String,
+    expiration: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut airdrop = AIRDROPS
+        .may_load(deps.storage, round)?
+        .ok_or(ContractError::AirdropNotFound { round })?;
+    if airdrop.merkle_root.is_some() || airdrop.clawed_back {
+        return Err(ContractError::MerkleRootRegistered { round });
+    }
+    let mut root = [0u8; 32];
+    hex::decode_to_slice(&merkle_root, &mut root)
+        .map_err(|_| StdError::generic_err("Merkle root must be a hex encoded sha256 hash"))?;
+    if expiration <= env.block.time.seconds() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Expiration must be in the future",
+        )));
+    }
+    airdrop.merkle_root = Some(merkle_root.clone());
+    airdrop.expiration = Some(expiration);
+    AIRDROPS.save(deps.storage, round, &airdrop)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "register_merkle_root"),
+        ("round", &round.to_string()),
+        ("merkle_root", &merkle_root),
+        ("expiration", &expiration.to_string()),
+    ]))
+}
+
+fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut airdrop = AIRDROPS
+        .may_load(deps.storage, round)?
+        .ok_or(ContractError::AirdropNotFound { round })?;
+    let (Some(merkle_root), Some(expiration)) = (&airdrop.merkle_root, airdrop.expiration) else {
+        return Err(ContractError::MerkleRootNotRegistered { round });
+    };
+    if airdrop.clawed_back || env.block.time.seconds() >= expiration {
+        return Err(ContractError::Expired { round });
+    }
+    if CLAIMED.has(deps.storage, (round, &info.sender)) {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+    verify_proof(merkle_root, info.sender.as_str(), amount, &proof)?;
+
+    // a root summing above the funded amount can't drain the other airdrops
+    airdrop.claimed_amount = airdrop.claimed_amount.checked_add(amount)?;
+    if airdrop.claimed_amount > airdrop.total_amount {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Airdrop of round {} does not hold enough funds",
+            round
+        ))));
+    }
+    CLAIMED.save(deps.storage, (round, &info.sender), &true)?;
+    AIRDROPS.save(deps.storage, round, &airdrop)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "claim"),
+            ("round", &round.to_string()),
+            ("address", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ])
+        .add_message(into_cosmos_msg(
+            &airdrop.asset_info,
+            info.sender.to_string(),
+            amount,
+        )?))
+}
+
+fn execute_clawback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut airdrop = AIRDROPS
+        .may_load(deps.storage, round)?
+        .ok_or(ContractError::AirdropNotFound { round })?;
+    if airdrop.clawed_back
+        || airdrop
+            .expiration
+            .map_or(false, |expiration| env.block.time.seconds() < expiration)
+    {
+        return Err(ContractError::NotExpired { round });
+    }
+    let amount = airdrop.total_amount.checked_sub(airdrop.claimed_amount)?;
+    airdrop.claimed_amount = airdrop.total_amount;
+    airdrop.clawed_back = true;
+    AIRDROPS.save(deps.storage, round, &airdrop)?;
+
+    let mut response = Response::new().add_attributes(vec![
+        ("action", "clawback"),
+        ("round", &round.to_string()),
+        ("amount", &amount.to_string()),
+    ]);
+    if !amount.is_zero() {
+        response = response.add_message(into_cosmos_msg(
+            &airdrop.asset_info,
+            config.owner.to_string(),
+            amount,
+        )?);
+    }
+    Ok(response)
+}
+
+// hashes the leaf of (address, amount) up the proof, each pair of nodes in ascending order
+fn verify_proof(
+    merkle_root: &str,
+    address: &str,
+    amount: Uint128,
+    proof: &[String],
+) -> Result<(), ContractError> {
+    let mut hash: [u8; 32] = Sha256::digest(format!("{}{}", address, amount)).into();
+    for node in proof {
+        let mut sibling = [0u8; 32];
+        hex::decode_to_slice(node, &mut sibling).map_err(|_| ContractError::InvalidProof {})?;
+        let (first, second) = if hash <= sibling {
+            (hash, sibling)
+        } else {
+            (sibling, hash)
+        };
+        hash = Sha256::digest([first, second].concat()).into();
+    }
+
+    let mut root = [0u8; 32];
+    hex::decode_to_slice(merkle_root, &mut root).map_err(|_| ContractError::InvalidProof {})?;
+    if hash != root {
+        return Err(ContractError::InvalidProof {});
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::Airdrop { round } => to_json_binary(&AIRDROPS.load(deps.storage, round)?),
+        QueryMsg::IsClaimed { round, address } => {
+            to_json_binary(&CLAIMED.has(deps.storage, (round, &deps.api.addr_validate(&address)?)))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,42 @@
+use cosmwasm_std::{OverflowError, StdError};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Airdrop of round {round} does not exist")]
+    AirdropNotFound { round: u64 },
+
+    #[error("Funds do not match the asset of the airdrop")]
+    InvalidFunds {},
+
+    #[error("Merkle root of round {round} is already registered")]
+    MerkleRootRegistered { round: u64 },
+
+    #[error("Merkle root of round {round} is not registered")]
+    MerkleRootNotRegistered { round: u64 },
+
+    #[error("Airdrop of round {round} has expired")]
+    Expired { round: u64 },
+
+    #[error("Airdrop of round {round} has not expired")]
+    NotExpired { round: u64 },
+
+    #[error("Already claimed")]
+    AlreadyClaimed {},
+
+    #[error("Invalid Merkle proof")]
+    InvalidProof {},
+}
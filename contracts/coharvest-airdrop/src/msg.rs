@@ -0,0 +1,75 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::state::{Airdrop, Config};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub bid_pool: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateConfig {
+        owner: Option<String>,
+    },
+    // funds the airdrop of round with the attached native tokens, only the bid pool or owner
+    Fund {
+        round: u64,
+    },
+    // only owner, once per round: the leaves are sha256(address + amount) and each pair of
+    // nodes is hashed in ascending order
+    RegisterMerkleRoot {
+        round: u64,
+        merkle_root: String,
+        expiration: u64,
+    },
+    Claim {
+        round: u64,
+        amount: Uint128,
+        proof: Vec<String>,
+    },
+    // only owner, sends the unclaimed tokens back once the airdrop has expired, or at any time
+    // before a merkle root is registered
+    Clawback {
+        round: u64,
+    },
+}
+
+// same json as ExecuteMsg::Fund, so the bid pool sends cw20 and native tokens with one message
+#[cw_serde]
+pub enum Cw20HookMsg {
+    Fund { round: u64 },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(Airdrop)]
+    Airdrop { round: u64 },
+    #[returns(bool)]
+    IsClaimed { round: u64, address: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub bid_pool: Addr,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            bid_pool: config.bid_pool,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
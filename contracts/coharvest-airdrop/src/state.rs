@@ -0,0 +1,26 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+use oraiswap::asset::AssetInfo;
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// mapping round --> airdrop of the leftover of the round
+pub const AIRDROPS: Map<u64, Airdrop> = Map::new("airdrops");
+// mapping (round, address) --> true once the address has claimed
+pub const CLAIMED: Map<(u64, &Addr), bool> = Map::new("claimed");
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,    // owner address
+    pub bid_pool: Addr, // bid pool sending the leftover of its rounds
+}
+
+#[cw_serde]
+pub struct Airdrop {
+    pub asset_info: AssetInfo, // token airdropped, the one of the first funding
+    pub total_amount: Uint128, // tokens received for the round
+    pub claimed_amount: Uint128, // tokens claimed or clawed back
+    pub merkle_root: Option<String>, // hex encoded root of the (address, amount) leaves
+    pub expiration: Option<u64>, // claims are closed from this timestamp
+    pub clawed_back: bool,
+}
@@ -0,0 +1,275 @@
+use cosmwasm_std::{
+    coins, from_json,
+    testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    to_json_binary, Addr, BankMsg, CosmosMsg, OwnedDeps, StdError, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use oraiswap::asset::AssetInfo;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg},
+    state::Airdrop,
+};
+
+const OWNER: &str = "owner";
+const BID_POOL: &str = "bid_pool";
+const USDC: &str = "usdc";
+
+fn init() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        bid_pool: BID_POOL.to_string(),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    deps
+}
+
+fn leaf(address: &str, amount: u128) -> [u8; 32] {
+    Sha256::digest(format!("{}{}", address, amount)).into()
+}
+
+// tree of two leaves: alice is owed 60 and bob 40
+fn merkle_root() -> String {
+    let (alice, bob) = (leaf("alice", 60_000000), leaf("bob", 40_000000));
+    let pair = if alice <= bob {
+        [alice, bob].concat()
+    } else {
+        [bob, alice].concat()
+    };
+    hex::encode(Sha256::digest(pair))
+}
+
+fn fund_cw20(sender: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_json_binary(&Cw20HookMsg::Fund { round: 1 }).unwrap(),
+    })
+}
+
+// the proof of a leaf is the leaf of the other address
+fn claim(amount: u128, other: &str) -> ExecuteMsg {
+    let other_amount = if other == "bob" { 40_000000 } else { 60_000000 };
+    ExecuteMsg::Claim {
+        round: 1,
+        amount: Uint128::from(amount),
+        proof: vec![hex::encode(leaf(other, other_amount))],
+    }
+}
+
+#[test]
+fn test_fund_and_claim() {
+    let mut deps = init();
+    let mut env = mock_env();
+
+    // only the bid pool or owner fund the airdrops
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &[]),
+        fund_cw20("alice", 100_000000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &[]),
+        fund_cw20(BID_POOL, 100_000000),
+    )
+    .unwrap();
+    // the asset of the round is set by its first funding
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &coins(10, "orai")),
+        ExecuteMsg::Fund { round: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        claim(60_000000, "bob"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MerkleRootNotRegistered { round: 1 });
+
+    let register = ExecuteMsg::RegisterMerkleRoot {
+        round: 1,
+        merkle_root: merkle_root(),
+        expiration: env.block.time.plus_seconds(1000).seconds(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        register.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        register.clone(),
+    )
+    .unwrap();
+    let err = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), register).unwrap_err();
+    assert_eq!(err, ContractError::MerkleRootRegistered { round: 1 });
+
+    // bob can't claim the amount of alice
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        claim(60_000000, "alice"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidProof {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        claim(60_000000, "bob"),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "alice".to_string(),
+                amount: Uint128::from(60_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        claim(60_000000, "bob"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AlreadyClaimed {});
+    let claimed: bool = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::IsClaimed {
+                round: 1,
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(claimed);
+
+    // the owner claws back what bob did not claim once the airdrop has expired
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::Clawback { round: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotExpired { round: 1 });
+    env.block.time = env.block.time.plus_seconds(1000);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        claim(40_000000, "alice"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Expired { round: 1 });
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::Clawback { round: 1 },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2].value, "40000000");
+
+    let airdrop: Airdrop =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Airdrop { round: 1 }).unwrap())
+            .unwrap();
+    assert_eq!(
+        airdrop,
+        Airdrop {
+            asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(USDC),
+            },
+            total_amount: Uint128::from(100_000000u128),
+            claimed_amount: Uint128::from(100_000000u128),
+            merkle_root: Some(merkle_root()),
+            expiration: Some(env.block.time.seconds()),
+            clawed_back: true,
+        }
+    );
+}
+
+#[test]
+fn test_native_airdrop_exceeding_funds() {
+    let mut deps = init();
+    let env = mock_env();
+
+    // the root owes 100 but only 50 were funded
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(BID_POOL, &coins(50_000000, "orai")),
+        ExecuteMsg::Fund { round: 1 },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RegisterMerkleRoot {
+            round: 1,
+            merkle_root: merkle_root(),
+            expiration: env.block.time.plus_seconds(1000).seconds(),
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        claim(40_000000, "alice"),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: coins(40_000000, "orai"),
+        })]
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        claim(60_000000, "bob"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Airdrop of round 1 does not hold enough funds"
+        ))
+    );
+}
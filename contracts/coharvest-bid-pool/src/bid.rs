@@ -1,28 +1,91 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use cosmwasm_std::{
-    to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response,
-    StdError, StdResult, Uint128, WasmMsg,
+    to_json_binary, to_json_string, to_json_vec, Addr, Attribute, BankMsg, Binary, Coin, CosmosMsg,
+    Decimal, Deps, DepsMut, Env, Event, MessageInfo, Order, QuerierWrapper, Response, StdError,
+    StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
-use oraiswap::asset::{Asset, AssetInfo};
+use cw4::{Cw4QueryMsg, MemberResponse, TotalWeightResponse};
+use cw_utils::must_pay;
+use oraiswap::{
+    asset::{Asset, AssetInfo, PairInfo},
+    pair::{Cw20HookMsg as PairCw20HookMsg, PoolResponse, QueryMsg as PairQueryMsg},
+};
+use sha2::{Digest, Sha256};
 
 use crate::{
     error::ContractError,
-    helper::into_cosmos_msg,
+    helper::{
+        into_cosmos_msg, into_send_msg, optional_label, prepare_hook_msgs,
+        prepare_post_finalize_msgs, push_change, query_balance, query_pair_exchange_rate,
+        record_token_flows,
+    },
+    idle_funds::recall_lent_distribution,
+    lock::{carry_locked_residue, take_lock_boost},
+    matching::{
+        checked_mul_decimal, fill_bid_pool, performance_fee, premium_amount,
+        process_calc_distribution_amount, process_calc_rebate_amount, split_bid_amount,
+    },
+    msg::{
+        AirdropExecuteMsg, BidPoolHookMsg, DistributeResponse, LsdAdapterExecuteMsg,
+        LsdAdapterQueryMsg, NotifierExecuteMsg, RedeemRateResponse, RoutedBid, SignedBid,
+        SignedBidPayload, StakingCw20HookMsg, VestingCw20HookMsg, WrapperExecuteMsg,
+    },
+    reinvest::{prepare_reinvest, reinvest_round},
     state::{
-        pop_bid_idx, read_bids_by_round, read_or_create_bid_pool, store_bid, Bid, BidPool,
-        BiddingInfo, DistributionInfo, BID, BIDDING_INFO, BID_POOL, CONFIG, DISTRIBUTION_INFO,
-        LAST_ROUND_ID,
+        checkpoint_round_totals, count_number_bidders_in_round, count_number_bids_in_round,
+        credit_shadow_balance, pop_bid_idx, read_bids_by_round, read_bids_by_slot,
+        read_or_create_bid_pool, read_pause_flags, read_round_config, read_round_state,
+        record_burn_stats, save_round_state, store_bid, Bid, BidPool, BiddingInfo, Config, Dispute,
+        DistributionInfo, FinalizeProgress, FinalizerGroup, LpWithdrawRate, PauseFlags, Payout,
+        PayoutTotals, PendingLsdRedeem, PendingTransfer, PremiumCurve, RateBand, ReinvestShare,
+        RoundConfig, RoundState, RoundSummary, RoundTemplate, SlotBonus, BID, BIDDER_PUBKEY,
+        BIDDING_INFO, BIDS_BY_HEIGHT, BIDS_BY_ROUND, BIDS_BY_SLOT, BIDS_BY_TIME, BIDS_BY_USER,
+        BID_IDX, BID_NONCE, BID_NOTIFIERS, BID_POOL, BID_RATE_LIMIT, CONFIG, DEPOSITS, DISPUTES,
+        DISPUTE_PARAMS, DISTRIBUTE_CURSOR, DISTRIBUTION_INFO, FINALIZER_GROUP, FINALIZE_APPROVALS,
+        FINALIZE_BOUNTY, FINALIZE_PROGRESS, ICA_FUNDING, ICA_ROUND_REQUEST, ICA_TREASURY,
+        LAST_ROUND_ID, LEFTOVER_AIRDROP, LOCKED_BIDS, LOCKS, LP_BID_TOTALS, LP_UNSETTLED_SHARES,
+        LP_WITHDRAW_RATES, MIN_EXCHANGE_RATES, NUM_BIDDERS_BY_ROUND, NUM_BIDS_BY_ROUND,
+        NUM_PRUNED_ROUNDS, OPEN_TRANCHES, ORACLE_RATE_BANDS, PAUSE_FLAGS, PAYOUTS, PAYOUT_TOTALS,
+        PENDING_LSD_REDEEM, PENDING_TRANSFER, POOL_TOTAL_CHECKPOINTS, RECENT_BIDS, REFUND_CURSOR,
+        REFUND_DEADLINE, REINVEST_CONFIG, ROUND_CONFIG, ROUND_SUMMARIES, ROUND_TEMPLATES,
+        ROUND_TOTAL_CHECKPOINTS, SETTLE_AFTER, SLOT_BONUSES, SLOT_DISTRIBUTE_CURSOR, TRANSFER_TAX,
+        TRUSTED_ROUTERS, USER_TOTAL_MATCHED, YIELD_POSITION,
     },
 };
 
+// each distributed bid emits up to 2 transfers, keep batches small enough to fit in a block
+const DEFAULT_DISTRIBUTE_LIMIT: u64 = 100;
+const MAX_DISTRIBUTE_LIMIT: u64 = 300;
+// slots matched by each ContinueFinalize call
+const DEFAULT_FINALIZE_LIMIT: u64 = 10;
+const MAX_FINALIZE_LIMIT: u64 = 50;
+const MAX_SLOT_BONUSES_PER_ROUND: usize = 5;
+// notifications are sent with a gas limit and their failures are ignored in the reply
+pub const NOTIFY_REPLY_ID: u64 = 1;
+pub const TRANSFER_REPLY_ID: u64 = 3;
+pub const LSD_REDEEM_REPLY_ID: u64 = 5;
+const NOTIFY_GAS_LIMIT: u64 = 200_000;
+// bids and checkpoints removed by each PruneRound call
+const DEFAULT_PRUNE_LIMIT: u64 = 100;
+const MAX_PRUNE_LIMIT: u64 = 300;
+const MAX_ROUTED_BIDS: usize = 50;
+// seconds after the end of a round before its bids can be refunded if it is not finalized
+pub const DEFAULT_REFUND_DEADLINE: u64 = 7 * 86400;
+pub const MIN_REFUND_DEADLINE: u64 = 86400;
+const DEFAULT_REFUND_LIMIT: u64 = 30;
+const MAX_REFUND_LIMIT: u64 = 100;
+
 // only owner can call this function
 pub fn execute_create_new_round(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     start_time: u64,
     end_time: u64,
     total_distribution: Uint128,
+    min_exchange_rate: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     if config.owner != info.sender {
@@ -30,7 +93,21 @@ pub fn execute_create_new_round(
     }
 
     // create new bidding round info
-    let response = process_create_new_round(deps, env, start_time, end_time, total_distribution)?;
+    let mut response = process_create_new_round(
+        deps.branch(),
+        env,
+        info.sender,
+        start_time,
+        end_time,
+        total_distribution,
+    )?;
+
+    // committed before any bid so bidders know their worst case payout
+    if let Some(min_exchange_rate) = min_exchange_rate {
+        let round = LAST_ROUND_ID.load(deps.storage)?;
+        MIN_EXCHANGE_RATES.save(deps.storage, round, &min_exchange_rate)?;
+        response = response.add_attribute("min_exchange_rate", min_exchange_rate.to_string());
+    }
 
     Ok(response.add_attribute("created_by", "owner"))
 }
@@ -46,8 +123,8 @@ pub fn execute_create_new_round_from_treasury(
     // check the distribute token in the bidding is valid
     assert_token_match_funds(config.distribution_token, funds.info)?;
 
-    // check sender is treasury contract
-    if sender != config.treasury {
+    // check sender is treasury contract or the interchain account of the remote treasury
+    if sender != config.treasury && ICA_TREASURY.may_load(deps.storage)?.as_ref() != Some(&sender) {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -65,6 +142,7 @@ pub fn execute_create_new_round_from_treasury(
             end_time: env.block.time.seconds(),
             total_bid_amount: Uint128::zero(),
             total_bid_matched: Uint128::zero(),
+            creator: None,
         }
     };
 
@@ -74,25 +152,288 @@ pub fn execute_create_new_round_from_treasury(
         )));
     }
 
-    // startTime = max(current time, end time of last round + 1)
-    let start_time = env.block.time.seconds().max(last_round.end_time + 1);
+    // the configured gap only applies after an existing round
+    let gap = if last_round_id != 0 {
+        config.min_gap_between_rounds.max(1)
+    } else {
+        1
+    };
+    // startTime = max(current time, end time of last round + gap)
+    let start_time = env.block.time.seconds().max(last_round.end_time + gap);
     let end_time = start_time + config.bidding_duration;
     let total_distribution = funds.amount;
 
-    let response = process_create_new_round(deps, env, start_time, end_time, total_distribution)?;
+    let response =
+        process_create_new_round(deps, env, sender, start_time, end_time, total_distribution)?;
 
     Ok(response.add_attribute("created_by", "treasury"))
 }
 
+// repairs the aggregates of the round from the bids, which are the ground truth
+pub fn execute_recompute_round_totals(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let finalized = read_round_state(deps.storage, &env, round)? > RoundState::Ended;
+    if finalized || FINALIZE_PROGRESS.has(deps.storage, round) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} is finalized, its totals can not be recomputed",
+            round
+        ))));
+    }
+
+    let tally = tally_round_bids(deps.storage, round)?;
+    // pools without any bid left are emptied
+    let mut pool_totals: BTreeMap<u16, Uint128> = BID_POOL
+        .prefix(round)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|slot| slot.map(|slot| (slot, Uint128::zero())))
+        .collect::<StdResult<_>>()?;
+    pool_totals.extend(tally.pool_totals);
+
+    let previous_total = bidding_info.total_bid_amount;
+    bidding_info.total_bid_amount = tally.total_bid_amount;
+    BIDDING_INFO.save(deps.storage, round, &bidding_info)?;
+    for (slot, pool_total) in pool_totals {
+        let mut bid_pool = read_or_create_bid_pool(deps.storage, round, slot)?;
+        bid_pool.total_bid_amount = pool_total;
+        BID_POOL.save(deps.storage, (round, slot), &bid_pool)?;
+        checkpoint_round_totals(
+            deps.storage,
+            env.block.time.seconds(),
+            &bidding_info,
+            &bid_pool,
+        )?;
+    }
+    NUM_BIDS_BY_ROUND.save(deps.storage, round, &tally.num_bids)?;
+    NUM_BIDDERS_BY_ROUND.save(deps.storage, round, &tally.num_bidders)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "recompute_round_totals"),
+        ("round", &round.to_string()),
+        ("previous_total_bid_amount", &previous_total.to_string()),
+        ("total_bid_amount", &tally.total_bid_amount.to_string()),
+        ("num_bids", &tally.num_bids.to_string()),
+        ("num_bidders", &tally.num_bidders.to_string()),
+    ]))
+}
+
+// aggregates of a round derived from its bids
+pub struct RoundTally {
+    pub total_bid_amount: Uint128,
+    pub pool_totals: BTreeMap<u16, Uint128>, // slots without bids are left out
+    pub num_bids: u64,
+    pub num_bidders: u64,
+    pub num_distributed: u64,
+    pub total_received: Uint128,
+    pub total_residue: Uint128, // residues paid by distribute or claimed
+}
+
+pub fn tally_round_bids(storage: &dyn Storage, round: u64) -> StdResult<RoundTally> {
+    // LP bids only count once their liquidity is withdrawn at finalize
+    let lp_withdraw_rate = LP_WITHDRAW_RATES.may_load(storage, round)?;
+    let mut tally = RoundTally {
+        total_bid_amount: Uint128::zero(),
+        pool_totals: BTreeMap::new(),
+        num_bids: 0,
+        num_bidders: 0,
+        num_distributed: 0,
+        total_received: Uint128::zero(),
+        total_residue: Uint128::zero(),
+    };
+    let mut bidders = BTreeSet::new();
+    for idx in BIDS_BY_ROUND
+        .prefix(round)
+        .keys(storage, None, None, Order::Ascending)
+    {
+        let bid = BID.load(storage, idx?)?;
+        let amount = match &lp_withdraw_rate {
+            Some(rate) if !bid.lp_amount.is_zero() && !bid.is_distributed => {
+                checked_mul_decimal(bid.lp_amount, rate.underlying_per_share)?
+            }
+            _ => bid.amount,
+        };
+        tally.total_bid_amount = tally.total_bid_amount.checked_add(amount)?;
+        let pool_total = tally.pool_totals.entry(bid.premium_slot).or_default();
+        *pool_total = pool_total.checked_add(amount)?;
+        tally.num_bids += 1;
+        if bid.is_distributed {
+            tally.num_distributed += 1;
+            tally.total_received = tally.total_received.checked_add(bid.amount_received)?;
+        }
+        if bid.is_distributed || bid.residue_claimed {
+            tally.total_residue = tally.total_residue.checked_add(bid.residue_bid)?;
+        }
+        bidders.insert(bid.bidder);
+    }
+    tally.num_bidders = bidders.len() as u64;
+
+    Ok(tally)
+}
+
+pub fn execute_request_ica_round(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    total_distribution: Uint128,
+) -> Result<Response, ContractError> {
+    if ICA_TREASURY.may_load(deps.storage)?.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if ICA_ROUND_REQUEST.exists(deps.storage) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "An interchain account round is already pending",
+        )));
+    }
+    if total_distribution.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Total distribution must be positive",
+        )));
+    }
+    ICA_ROUND_REQUEST.save(deps.storage, &total_distribution)?;
+
+    let response = Response::new().add_attributes(vec![
+        ("action", "request_ica_round"),
+        ("total_distribution", &total_distribution.to_string()),
+    ]);
+    try_open_ica_round(deps, env, info.sender, response)
+}
+
+// anyone can fund the pending round, the funds are only released as a round distribution
+pub fn execute_fund_ica_round(
+    deps: DepsMut,
+    env: Env,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_token_match_funds(config.distribution_token, funds.info)?;
+    let Some(ica_treasury) = ICA_TREASURY.may_load(deps.storage)? else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No interchain account treasury is set",
+        )));
+    };
+
+    let funded = ICA_FUNDING
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(funds.amount)?;
+    ICA_FUNDING.save(deps.storage, &funded)?;
+
+    let response = Response::new().add_attributes(vec![
+        ("action", "fund_ica_round"),
+        ("amount", &funds.amount.to_string()),
+        ("funded", &funded.to_string()),
+    ]);
+    try_open_ica_round(deps, env, ica_treasury, response)
+}
+
+// the round is created through the treasury path once the request is fully funded, the funding
+// left over is kept for the next request
+fn try_open_ica_round(
+    deps: DepsMut,
+    env: Env,
+    ica_treasury: Addr,
+    response: Response,
+) -> Result<Response, ContractError> {
+    let Some(requested) = ICA_ROUND_REQUEST.may_load(deps.storage)? else {
+        return Ok(response);
+    };
+    let funded = ICA_FUNDING.may_load(deps.storage)?.unwrap_or_default();
+    if funded < requested {
+        return Ok(response);
+    }
+
+    ICA_ROUND_REQUEST.remove(deps.storage);
+    let funded = funded - requested;
+    if funded.is_zero() {
+        ICA_FUNDING.remove(deps.storage);
+    } else {
+        ICA_FUNDING.save(deps.storage, &funded)?;
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let round_response = execute_create_new_round_from_treasury(
+        deps,
+        env,
+        ica_treasury,
+        Asset {
+            info: config.distribution_token,
+            amount: requested,
+        },
+    )?;
+    Ok(response
+        .add_submessages(round_response.messages)
+        .add_attributes(round_response.attributes)
+        .add_events(round_response.events))
+}
+
+pub fn execute_cancel_ica_round(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let ica_treasury = ICA_TREASURY.may_load(deps.storage)?;
+    if config.owner != info.sender && ica_treasury.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let Some(ica_treasury) = ica_treasury else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No interchain account treasury is set",
+        )));
+    };
+
+    ICA_ROUND_REQUEST.remove(deps.storage);
+    let funded = ICA_FUNDING.may_load(deps.storage)?.unwrap_or_default();
+    ICA_FUNDING.remove(deps.storage);
+
+    let mut response = Response::new().add_attributes(vec![
+        ("action", "cancel_ica_round"),
+        ("refund", &funded.to_string()),
+    ]);
+    if !funded.is_zero() {
+        response = response.add_message(into_cosmos_msg(
+            &config.distribution_token,
+            ica_treasury.to_string(),
+            funded,
+        )?);
+    }
+    Ok(response)
+}
+
 fn process_create_new_round(
     deps: DepsMut,
     env: Env,
+    creator: Addr,
     start_time: u64,
     end_time: u64,
     total_distribution: Uint128,
 ) -> Result<Response, ContractError> {
+    if read_pause_flags(deps.storage)?.create_round {
+        return Err(ContractError::Paused {
+            operation: "create_round".to_string(),
+        });
+    }
+
     // create new bidding round info
     let mut last_round = LAST_ROUND_ID.load(deps.storage)?;
+
+    let min_gap = CONFIG.load(deps.storage)?.min_gap_between_rounds;
+    if min_gap > 0 && last_round > 0 {
+        let previous_round = BIDDING_INFO.load(deps.storage, last_round)?;
+        if start_time < previous_round.end_time + min_gap {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "A new round must start at least {} seconds after the last round ends",
+                min_gap
+            ))));
+        }
+    }
     last_round += 1;
 
     let bidding_info = BiddingInfo {
@@ -101,6 +442,7 @@ fn process_create_new_round(
         end_time,
         total_bid_amount: Uint128::zero(),
         total_bid_matched: Uint128::zero(),
+        creator: Some(creator),
     };
 
     let distribution_info = DistributionInfo {
@@ -116,16 +458,24 @@ fn process_create_new_round(
     }
 
     // store
+    save_round_state(deps.storage, &env, last_round, RoundState::Created)?;
     LAST_ROUND_ID.save(deps.storage, &last_round)?;
     BIDDING_INFO.save(deps.storage, last_round, &bidding_info)?;
     DISTRIBUTION_INFO.save(deps.storage, last_round, &distribution_info)?;
 
-    Ok(Response::new().add_attributes(vec![
-        ("action", "create_new_bidding_round"),
-        ("round", &last_round.to_string()),
-        ("start_time", &start_time.to_string()),
-        ("end_time", &end_time.to_string()),
-    ]))
+    let hook_msgs = prepare_hook_msgs(
+        deps.storage,
+        BidPoolHookMsg::RoundCreated { round: last_round },
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "create_new_bidding_round"),
+            ("round", &last_round.to_string()),
+            ("start_time", &start_time.to_string()),
+            ("end_time", &end_time.to_string()),
+        ])
+        .add_submessages(hook_msgs))
 }
 
 pub fn execute_update_round(
@@ -138,19 +488,23 @@ pub fn execute_update_round(
     total_distribution: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-
-    // check sender is treasury contract
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
-
     let mut bidding_info = BIDDING_INFO.load(deps.storage, idx)?;
+    assert_round_creator(&env, &config, &info.sender, &bidding_info)?;
+
     let mut distribution = DISTRIBUTION_INFO.load(deps.storage, idx)?;
+    let (old_start_time, old_end_time, old_total_distribution) = (
+        bidding_info.start_time,
+        bidding_info.end_time,
+        distribution.total_distribution,
+    );
 
     // cannot update if round is ended
-    if bidding_info.finished(&env) {
-        return Err(ContractError::RoundEnded {});
-    }
+    assert_round_state(
+        deps.storage,
+        &env,
+        idx,
+        &[RoundState::Created, RoundState::Open],
+    )?;
 
     if let Some(total_distribution) = total_distribution {
         distribution.total_distribution = total_distribution;
@@ -180,201 +534,2776 @@ pub fn execute_update_round(
     BIDDING_INFO.save(deps.storage, idx, &bidding_info)?;
     DISTRIBUTION_INFO.save(deps.storage, idx, &distribution)?;
 
-    Ok(Response::new().add_attributes(vec![("action", "update_round")]))
+    let mut changes = vec![];
+    push_change(
+        &mut changes,
+        "start_time",
+        old_start_time.to_string(),
+        bidding_info.start_time.to_string(),
+    );
+    push_change(
+        &mut changes,
+        "end_time",
+        old_end_time.to_string(),
+        bidding_info.end_time.to_string(),
+    );
+    push_change(
+        &mut changes,
+        "total_distribution",
+        old_total_distribution.to_string(),
+        distribution.total_distribution.to_string(),
+    );
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "update_round"),
+            ("round", &idx.to_string()),
+        ])
+        .add_attributes(changes))
 }
 
-//  Underlying asset is submitted to create a bid record
-pub fn execute_submit_bid(
-    deps: DepsMut,
-    env: Env,
+// checks the round is in one of allowed, handlers of open rounds, rounds not ended yet and rounds
+// not finalized yet keep their specific errors
+pub fn assert_round_state(
+    storage: &dyn Storage,
+    env: &Env,
     round: u64,
-    premium_slot: u8,
-    bidder: String,
-    funds: Asset,
-) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    let amount = funds.amount;
-    // check the token participating in the bidding is valid
-    assert_token_match_funds(config.underlying_token, funds.info)?;
-    if config.min_deposit_amount > amount {
-        return Err(ContractError::Std(StdError::generic_err(format!(
-            "Minimum deposit is {}, got {}",
-            config.min_deposit_amount, amount
-        ))));
+    allowed: &[RoundState],
+) -> Result<RoundState, ContractError> {
+    let state = read_round_state(storage, env, round)?;
+    if allowed.contains(&state) {
+        return Ok(state);
     }
-
-    if premium_slot < 1 || premium_slot > config.max_slot {
-        return Err(ContractError::Std(StdError::generic_err(format!(
-            "premium slot must be within the range 1 and {}, reaching {}",
-            config.max_slot, premium_slot
-        ))));
-    }
-
-    // get bid pool info
-    let mut bidding_info: BiddingInfo = BIDDING_INFO.load(deps.storage, round)?;
-
-    if !bidding_info.opening(&env) {
+    if allowed == [RoundState::Open] {
         return Err(ContractError::BidNotOpen {});
     }
-
-    // read or create bid_pool, make sure slot is valid
-    let mut bid_pool = read_or_create_bid_pool(deps.storage, round, premium_slot)?;
-    bidding_info.total_bid_amount += amount;
-    bid_pool.total_bid_amount += amount;
-
-    // create bid object
-    let bid_idx = pop_bid_idx(deps.storage)?;
-    let bid = Bid {
-        idx: bid_idx,
-        round,
-        timestamp: env.block.time.seconds(),
-        premium_slot,
-        bidder: deps.api.addr_validate(&bidder)?,
-        amount,
-        residue_bid: amount,
-        amount_received: Uint128::zero(),
-        is_distributed: false,
-    };
-
-    // store bid info
-    BIDDING_INFO.save(deps.storage, round, &bidding_info)?;
-    BID_POOL.save(deps.storage, (round, premium_slot), &bid_pool)?;
-    store_bid(deps.storage, bid_idx, &bid)?;
-    Ok(Response::new().add_attributes(vec![
-        ("action", "submit_bid"),
-        ("round", &round.to_string()),
-        ("bidder", &bidder),
-        ("bid_idx", &bid_idx.to_string()),
-        ("premium_slot", &premium_slot.to_string()),
-        ("amount", &amount.to_string()),
-    ]))
+    if state == RoundState::Cancelled {
+        return Err(ContractError::InvalidRoundState { round, state });
+    }
+    if allowed.iter().all(|allowed| state < *allowed) {
+        return Err(ContractError::BidNotEnded {});
+    }
+    if allowed.iter().all(|allowed| *allowed <= RoundState::Open) {
+        return Err(ContractError::RoundEnded {});
+    }
+    Err(ContractError::InvalidRoundState { round, state })
 }
 
-fn assert_token_match_funds(expected: AssetInfo, funds: AssetInfo) -> Result<(), ContractError> {
-    if expected.ne(&funds) {
-        return Err(ContractError::InvalidFunds {});
+// the owner manages every round, other creators only their own rounds before they start
+fn assert_round_creator(
+    env: &Env,
+    config: &Config,
+    sender: &Addr,
+    bidding_info: &BiddingInfo,
+) -> Result<(), ContractError> {
+    if *sender == config.owner {
+        return Ok(());
+    }
+    if bidding_info.creator.as_ref() != Some(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if bidding_info.start_time <= env.block.time.seconds() {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} has already started",
+            bidding_info.round
+        ))));
     }
     Ok(())
 }
 
-// only admin can call this method
-// when the bidding round ends, admin will finalized this bidding, update the exchange rate and calculate the amount allocated to all bid pool.
-// total number of matched token will be burn. And if after allocation there are still distributed tokens left, send them back to the owner
-pub fn execute_finalize_bidding_round_result(
+// cancels the last round before it starts, returning the distribution to its creator
+pub fn execute_cancel_round(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     round: u64,
-    exchange_rate: Decimal,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    if config.owner != info.sender {
-        return Err(ContractError::Unauthorized {});
-    }
-
-    let mut bidding_info = BIDDING_INFO.load(deps.storage, round)?;
-
-    // check that bidding round must have ended
-    if !bidding_info.finished(&env) {
-        return Err(ContractError::BidNotEnded {});
-    }
-
-    let mut distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
-    if distribution_info.is_released {
+    let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    assert_round_creator(&env, &config, &info.sender, &bidding_info)?;
+    if bidding_info.start_time <= env.block.time.seconds() {
         return Err(ContractError::Std(StdError::generic_err(format!(
-            "round {} has been finalized",
+            "Round {} has already started",
             round
         ))));
     }
-
-    // update exchange_rate and mark this round as finalized
-    distribution_info.exchange_rate = exchange_rate;
-    distribution_info.is_released = true;
-    let mut bid_pools = bidding_info.read_all_bid_pool(deps.storage)?;
-
-    // calculate the amount allocated to all bid pool
-    let mut distribution_amount = distribution_info.total_distribution;
-    let total_matched =
-        process_calc_distribution_amount(&mut bid_pools, &mut distribution_amount, exchange_rate)?;
-
-    distribution_info.actual_distributed =
-        distribution_info.total_distribution - distribution_amount;
-    bidding_info.total_bid_matched = total_matched;
-
-    for bid_pool in bid_pools {
-        BID_POOL.save(deps.storage, (round, bid_pool.slot), &bid_pool)?;
+    // round ids stay contiguous
+    if round != LAST_ROUND_ID.load(deps.storage)? {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Only the last round can be cancelled",
+        )));
     }
 
-    DISTRIBUTION_INFO.save(deps.storage, round, &distribution_info)?;
-    BIDDING_INFO.save(deps.storage, round, &bidding_info)?;
-
+    let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    let creator = bidding_info.creator.unwrap_or(config.owner);
     let mut msgs: Vec<CosmosMsg> = vec![];
-
-    // burn total_matched
-    match config.underlying_token {
-        AssetInfo::NativeToken { denom } => msgs.push(CosmosMsg::Bank(BankMsg::Burn {
-            amount: vec![Coin {
-                denom,
-                amount: total_matched,
-            }],
-        })),
-        AssetInfo::Token { contract_addr } => msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: contract_addr.to_string(),
-            msg: to_json_binary(&Cw20ExecuteMsg::Burn {
-                amount: total_matched,
-            })?,
-            funds: vec![],
-        })),
-    };
-
-    // transfer remaining to owner
-    if !distribution_amount.is_zero() {
-        match config.distribution_token {
-            AssetInfo::NativeToken { denom } => msgs.push(CosmosMsg::Bank(BankMsg::Send {
-                to_address: config.owner.to_string(),
-                amount: vec![Coin {
-                    denom,
-                    amount: distribution_amount,
-                }],
-            })),
-            AssetInfo::Token { contract_addr } => msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: contract_addr.to_string(),
-                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: config.owner.to_string(),
-                    amount: distribution_amount,
-                })?,
-                funds: vec![],
-            })),
-        };
+    if !distribution_info.total_distribution.is_zero() {
+        msgs.push(into_cosmos_msg(
+            &config.distribution_token,
+            creator.to_string(),
+            distribution_info.total_distribution,
+        )?);
     }
+    for bonus in SLOT_BONUSES
+        .may_load(deps.storage, round)?
+        .unwrap_or_default()
+    {
+        msgs.push(into_cosmos_msg(
+            &bonus.asset.info,
+            bonus.sponsor.to_string(),
+            bonus.asset.amount,
+        )?);
+    }
+
+    save_round_state(deps.storage, &env, round, RoundState::Cancelled)?;
+    BIDDING_INFO.remove(deps.storage, round);
+    DISTRIBUTION_INFO.remove(deps.storage, round);
+    ROUND_CONFIG.remove(deps.storage, round);
+    MIN_EXCHANGE_RATES.remove(deps.storage, round);
+    ORACLE_RATE_BANDS.remove(deps.storage, round);
+    SLOT_BONUSES.remove(deps.storage, round);
+    LAST_ROUND_ID.save(deps.storage, &(round - 1))?;
 
     Ok(Response::new()
         .add_attributes(vec![
-            ("action", "finalize_bidding_round_result"),
+            ("action", "cancel_round"),
             ("round", &round.to_string()),
-            ("exchange_rate", &exchange_rate.to_string()),
-            ("total_matched", &total_matched.to_string()),
-            (
-                "actual_distributed",
-                &distribution_info.actual_distributed.to_string(),
-            ),
+            ("creator", creator.as_str()),
         ])
         .add_messages(msgs))
 }
 
-// after bidding round finalized, call this function to send the allocated tokens to all bidder, and if the bid still has bid token, transfer back to the bidder
-pub fn execute_distribute(
+// only owner can configure a round, until the round has ended
+pub fn execute_update_round_config(
     deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     round: u64,
-    start_after: Option<u64>,
-    limit: Option<u64>,
+    round_config: RoundConfig,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let mut distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let state = assert_round_state(
+        deps.storage,
+        &env,
+        round,
+        &[RoundState::Created, RoundState::Open],
+    )?;
+
+    assert_valid_round_config(deps.as_ref(), &config, &round_config)?;
+    // LP bids already placed are withdrawn from the pair they were bid with
+    if round_config.lp_pair != read_round_config(deps.storage, round)?.lp_pair
+        && state == RoundState::Open
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "LP pair can only be changed before the round starts",
+        )));
+    }
+    if round_config.lsd_adapter != read_round_config(deps.storage, round)?.lsd_adapter
+        && state == RoundState::Open
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "LSD adapter can only be changed before the round starts",
+        )));
+    }
+    // bid pools keep the premium rate they were created with
+    if round_config.premium_curve != read_round_config(deps.storage, round)?.premium_curve
+        && state == RoundState::Open
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Premium curve can only be changed before the round starts",
+        )));
+    }
+
+    let old_round_config = read_round_config(deps.storage, round)?;
+    ROUND_CONFIG.save(deps.storage, round, &round_config)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "update_round_config"),
+            ("round", &round.to_string()),
+        ])
+        .add_attributes(round_config_changes(&old_round_config, &round_config)?))
+}
+
+// before and after values of the fields changed by an update, unchanged fields are left out
+fn round_config_changes(old: &RoundConfig, new: &RoundConfig) -> StdResult<Vec<Attribute>> {
+    let mut changes = vec![];
+    for (field, old, new) in [
+        ("vested_rewards", old.vested_rewards, new.vested_rewards),
+        ("credit_payouts", old.credit_payouts, new.credit_payouts),
+        (
+            "distribute_by_slot",
+            old.distribute_by_slot,
+            new.distribute_by_slot,
+        ),
+        (
+            "lend_distribution",
+            old.lend_distribution,
+            new.lend_distribution,
+        ),
+        ("tranched", old.tranched, new.tranched),
+    ] {
+        push_change(&mut changes, field, old.to_string(), new.to_string());
+    }
+    push_change(
+        &mut changes,
+        "lp_pair",
+        optional_label(&old.lp_pair),
+        optional_label(&new.lp_pair),
+    );
+    push_change(
+        &mut changes,
+        "total_bid_threshold",
+        optional_label(&old.total_bid_threshold),
+        optional_label(&new.total_bid_threshold),
+    );
+    push_change(
+        &mut changes,
+        "min_participants",
+        optional_label(&old.min_participants),
+        optional_label(&new.min_participants),
+    );
+    push_change(
+        &mut changes,
+        "premium_curve",
+        to_json_string(&old.premium_curve)?,
+        to_json_string(&new.premium_curve)?,
+    );
+    push_change(
+        &mut changes,
+        "performance_fee",
+        optional_label(&old.performance_fee),
+        optional_label(&new.performance_fee),
+    );
+    push_change(
+        &mut changes,
+        "lsd_adapter",
+        optional_label(&old.lsd_adapter),
+        optional_label(&new.lsd_adapter),
+    );
+    Ok(changes)
+}
+
+// only owner can store a template, saving under an existing name replaces it
+pub fn execute_save_round_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    template: RoundTemplate,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if template.duration == 0 {
+        return Err(ContractError::InvalidBiddingTimeRange {});
+    }
+    assert_valid_round_config(deps.as_ref(), &config, &template.round_config)?;
+
+    ROUND_TEMPLATES.save(deps.storage, &name, &template)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "save_round_template"), ("name", &name)]))
+}
+
+pub fn execute_create_round_from_template(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    start_time: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let Some(template) = ROUND_TEMPLATES.may_load(deps.storage, &name)? else {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round template {} does not exist",
+            name
+        ))));
+    };
+    // the contract config may have changed since the template was saved
+    assert_valid_round_config(deps.as_ref(), &config, &template.round_config)?;
+
+    let response = process_create_new_round(
+        deps.branch(),
+        env,
+        info.sender,
+        start_time,
+        start_time + template.duration,
+        template.total_distribution,
+    )?;
+    let round = LAST_ROUND_ID.load(deps.storage)?;
+    ROUND_CONFIG.save(deps.storage, round, &template.round_config)?;
+
+    Ok(response.add_attributes(vec![("created_by", "owner"), ("template", &name)]))
+}
+
+fn assert_valid_round_config(
+    deps: Deps,
+    config: &Config,
+    round_config: &RoundConfig,
+) -> Result<(), ContractError> {
+    if round_config.vested_rewards && config.vesting_contract.is_none() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Vesting contract is not configured",
+        )));
+    }
+    if let Some(lp_pair) = &round_config.lp_pair {
+        deps.api.addr_validate(lp_pair.as_str())?;
+    }
+    if let Some(lsd_adapter) = &round_config.lsd_adapter {
+        deps.api.addr_validate(lsd_adapter.as_str())?;
+        if round_config.lp_pair.is_some() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "A round accepts either LP tokens or a staking derivative",
+            )));
+        }
+    }
+    if let PremiumCurve::Breakpoints { points } = &round_config.premium_curve {
+        let is_increasing = points.windows(2).all(|pair| {
+            pair[0].slot < pair[1].slot && pair[0].premium_rate <= pair[1].premium_rate
+        });
+        if points.is_empty() || points[0].slot == 0 || !is_increasing {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Premium breakpoints must have increasing slots and premium rates",
+            )));
+        }
+    }
+    if round_config
+        .performance_fee
+        .map_or(false, |fee_rate| fee_rate > Decimal::one())
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Performance fee must not exceed 100%",
+        )));
+    }
+    // the highest slot has the highest premium, make sure it can be computed
+    round_config
+        .premium_curve
+        .premium_rate(config.premium_rate_per_slot, config.max_slot)?;
+    Ok(())
+}
+
+//  Underlying asset is submitted to create a bid record
+pub fn execute_submit_bid(
+    deps: DepsMut,
+    env: Env,
+    round: u64,
+    premium_slot: u16,
+    bidder: String,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    let (response, _) = submit_bid(deps, env, round, premium_slot, bidder, funds, true)?;
+    Ok(response)
+}
+
+// reinvested bids are placed by the contract during a distribute, the bidder did not submit
+// them and they do not count against the bid rate limit
+pub fn submit_reinvested_bid(
+    deps: DepsMut,
+    env: Env,
+    round: u64,
+    premium_slot: u16,
+    bidder: String,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    let (response, _) = submit_bid(deps, env, round, premium_slot, bidder, funds, false)?;
+    Ok(response)
+}
+
+// returns the idx of the created bid with the response
+fn submit_bid(
+    deps: DepsMut,
+    env: Env,
+    round: u64,
+    premium_slot: u16,
+    bidder: String,
+    funds: Asset,
+    rate_limited: bool,
+) -> Result<(Response, u64), ContractError> {
+    if read_pause_flags(deps.storage)?.submit_bid {
+        return Err(ContractError::Paused {
+            operation: "submit_bid".to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let amount = funds.amount;
+    // check the token participating in the bidding is valid
+    assert_token_match_funds(config.underlying_token, funds.info)?;
+    if config.min_deposit_amount > amount {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Minimum deposit is {}, got {}",
+            config.min_deposit_amount, amount
+        ))));
+    }
+
+    if premium_slot < 1 || premium_slot > config.max_slot {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "premium slot must be within the range 1 and {}, reaching {}",
+            config.max_slot, premium_slot
+        ))));
+    }
+
+    // get bid pool info
+    let mut bidding_info: BiddingInfo = BIDDING_INFO.load(deps.storage, round)?;
+    assert_round_state(deps.storage, &env, round, &[RoundState::Open])?;
+
+    // checked before the bid pool is created, a rate limited bid leaves no empty pool behind
+    let bidder_addr = deps.api.addr_validate(&bidder)?;
+    if rate_limited {
+        record_bid_submission(deps.storage, &bidder_addr, env.block.time.seconds())?;
+    }
+
+    // read or create bid_pool, make sure slot is valid
+    let mut bid_pool = read_or_create_bid_pool(deps.storage, round, premium_slot)?;
+    bidding_info.total_bid_amount = bidding_info.total_bid_amount.checked_add(amount)?;
+    bid_pool.total_bid_amount = bid_pool.total_bid_amount.checked_add(amount)?;
+
+    // create bid object
+    let bid_idx = pop_bid_idx(deps.storage)?;
+    let bid = Bid {
+        idx: bid_idx,
+        round,
+        timestamp: env.block.time.seconds(),
+        premium_slot,
+        bidder: bidder_addr,
+        amount,
+        residue_bid: amount,
+        amount_received: Uint128::zero(),
+        is_distributed: false,
+        auto_compound: false,
+        stake_residue: false,
+        reinvest: false,
+        lp_amount: Uint128::zero(),
+        residue_claimed: false,
+        performance_fee: Uint128::zero(),
+        height: env.block.height,
+    };
+
+    // store bid info
+    BIDDING_INFO.save(deps.storage, round, &bidding_info)?;
+    BID_POOL.save(deps.storage, (round, premium_slot), &bid_pool)?;
+    store_bid(deps.storage, bid_idx, &bid)?;
+    checkpoint_round_totals(
+        deps.storage,
+        env.block.time.seconds(),
+        &bidding_info,
+        &bid_pool,
+    )?;
+    let response = Response::new().add_attributes(vec![
+        ("action", "submit_bid"),
+        ("round", &round.to_string()),
+        ("bidder", &bidder),
+        ("bid_idx", &bid_idx.to_string()),
+        ("premium_slot", &premium_slot.to_string()),
+        ("amount", &amount.to_string()),
+        (
+            "pool_total_bid_amount",
+            &bid_pool.total_bid_amount.to_string(),
+        ),
+        (
+            "round_total_bid_amount",
+            &bidding_info.total_bid_amount.to_string(),
+        ),
+        ("timestamp", &bid.timestamp.to_string()),
+    ]);
+    Ok((response, bid_idx))
+}
+
+// the router pays for the bids, each of them is owned by the bidder it names. The data holds
+// the idxs of the created bids, in the order of the routed bids
+pub fn execute_submit_routed_bids(
+    mut deps: DepsMut,
+    env: Env,
+    router: Addr,
+    bids: Vec<RoutedBid>,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    if !TRUSTED_ROUTERS.has(deps.storage, &router) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if bids.is_empty() || bids.len() > MAX_ROUTED_BIDS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "A router can submit between 1 and {} bids at once",
+            MAX_ROUTED_BIDS
+        ))));
+    }
+    let total_amount = bids
+        .iter()
+        .try_fold(Uint128::zero(), |total, bid| total.checked_add(bid.amount))?;
+    if total_amount != funds.amount {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Routed bids total {}, got {}",
+            total_amount, funds.amount
+        ))));
+    }
+
+    // one event per bid, as submitted directly by the bidder
+    let mut events = vec![];
+    let mut idxs = vec![];
+    for bid in bids {
+        let response = execute_submit_bid(
+            deps.branch(),
+            env.clone(),
+            bid.round,
+            bid.premium_slot,
+            bid.bidder,
+            Asset {
+                info: funds.info.clone(),
+                amount: bid.amount,
+            },
+        )?;
+        events.push(Event::new("routed_bid").add_attributes(response.attributes));
+        idxs.push(BID_IDX.load(deps.storage)? - 1);
+    }
+
+    Ok(Response::new()
+        .add_events(events)
+        .add_attributes(vec![
+            ("action", "submit_routed_bids"),
+            ("router", router.as_str()),
+            ("total_amount", &total_amount.to_string()),
+        ])
+        .set_data(to_json_binary(&idxs)?))
+}
+
+// the balance is measured around the TransferFrom, the bid is submitted in the reply
+pub fn execute_submit_bid_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    premium_slot: u16,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let AssetInfo::Token { contract_addr } = &config.underlying_token else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Only cw20 underlying tokens can be pulled",
+        )));
+    };
+    let balance_before = query_balance(
+        &deps.querier,
+        &config.underlying_token,
+        env.contract.address.clone(),
+    )?;
+    PENDING_TRANSFER.save(
+        deps.storage,
+        &PendingTransfer {
+            bidder: info.sender.clone(),
+            round,
+            premium_slot,
+            amount,
+            balance_before,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            },
+            TRANSFER_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            ("action", "submit_bid_from"),
+            ("bidder", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+pub fn reply_transfer(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending = PENDING_TRANSFER.load(deps.storage)?;
+    PENDING_TRANSFER.remove(deps.storage);
+    let config = CONFIG.load(deps.storage)?;
+    let balance_after = query_balance(
+        &deps.querier,
+        &config.underlying_token,
+        env.contract.address.clone(),
+    )?;
+    let received = balance_after.checked_sub(pending.balance_before)?;
+    // the transfer is not seen by the execute entry point
+    credit_shadow_balance(deps.storage, &config, &config.underlying_token, received)?;
+
+    let response = execute_submit_bid(
+        deps,
+        env,
+        pending.round,
+        pending.premium_slot,
+        pending.bidder.to_string(),
+        Asset {
+            info: config.underlying_token,
+            amount: received,
+        },
+    )?;
+    Ok(response.add_attribute(
+        "transfer_tax",
+        pending.amount.saturating_sub(received).to_string(),
+    ))
+}
+
+// keeps the bid timestamps of the sliding window, rejecting the bid once the limit is reached
+fn record_bid_submission(
+    storage: &mut dyn Storage,
+    bidder: &Addr,
+    now: u64,
+) -> Result<(), ContractError> {
+    let Some(rate_limit) = BID_RATE_LIMIT.may_load(storage)? else {
+        return Ok(());
+    };
+
+    let mut recent_bids = RECENT_BIDS.may_load(storage, bidder)?.unwrap_or_default();
+    recent_bids.retain(|timestamp| timestamp + rate_limit.window > now);
+    if recent_bids.len() >= rate_limit.max_bids as usize {
+        return Err(ContractError::RateLimited {
+            max_bids: rate_limit.max_bids,
+            window: rate_limit.window,
+        });
+    }
+    recent_bids.push(now);
+    RECENT_BIDS.save(storage, bidder, &recent_bids)?;
+
+    Ok(())
+}
+
+pub fn execute_register_bidder_pubkey(
+    deps: DepsMut,
+    info: MessageInfo,
+    pubkey: Binary,
+) -> Result<Response, ContractError> {
+    BIDDER_PUBKEY.save(deps.storage, &info.sender, &pubkey)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "register_bidder_pubkey"),
+        ("bidder", info.sender.as_str()),
+    ]))
+}
+
+// anyone can relay a bid signed by the registered pubkey of the bidder, the nonce prevents replays
+pub fn execute_submit_signed_bid(
+    deps: DepsMut,
+    env: Env,
+    bid: SignedBid,
+    signature: Binary,
+    pubkey: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // the bid is recorded for the signed amount, a taxed transfer would deliver less
+    if TRANSFER_TAX.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The underlying token takes a transfer tax, bid with SubmitBidFrom",
+        )));
+    }
+    let bidder = deps.api.addr_validate(&bid.bidder)?;
+
+    if BIDDER_PUBKEY.may_load(deps.storage, &bidder)? != Some(pubkey.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time.seconds() > bid.deadline {
+        return Err(ContractError::SignatureExpired {});
+    }
+    let nonce = BID_NONCE
+        .may_load(deps.storage, &bidder)?
+        .unwrap_or_default();
+    if bid.nonce != nonce {
+        return Err(ContractError::InvalidNonce { expected: nonce });
+    }
+
+    let payload = SignedBidPayload {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        bid: bid.clone(),
+    };
+    let hash = Sha256::digest(to_json_vec(&payload)?);
+    let verified = deps
+        .api
+        .secp256k1_verify(&hash, &signature, &pubkey)
+        .map_err(|_| ContractError::InvalidSignature {})?;
+    if !verified {
+        return Err(ContractError::InvalidSignature {});
+    }
+    BID_NONCE.save(deps.storage, &bidder, &(nonce + 1))?;
+
+    // funds can only be pulled from a cw20 allowance
+    let AssetInfo::Token { contract_addr } = config.underlying_token.clone() else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Signed bids need a cw20 underlying token",
+        )));
+    };
+    let transfer_from = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: contract_addr.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+            owner: bidder.to_string(),
+            recipient: env.contract.address.to_string(),
+            amount: bid.amount,
+        })?,
+        funds: vec![],
+    });
+
+    // the pulled tokens are not funds of the execute, its entry point only sees them sent out
+    credit_shadow_balance(deps.storage, &config, &config.underlying_token, bid.amount)?;
+
+    let response = execute_submit_bid(
+        deps,
+        env,
+        bid.round,
+        bid.premium_slot,
+        bid.bidder,
+        Asset {
+            info: config.underlying_token,
+            amount: bid.amount,
+        },
+    )?;
+
+    Ok(response
+        .add_attribute("nonce", nonce.to_string())
+        .add_message(transfer_from))
+}
+
+pub fn execute_deposit(
+    deps: DepsMut,
+    depositor: Addr,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_token_match_funds(config.underlying_token, funds.info)?;
+
+    let balance = DEPOSITS
+        .may_load(deps.storage, &depositor)?
+        .unwrap_or_default()
+        .checked_add(funds.amount)?;
+    DEPOSITS.save(deps.storage, &depositor, &balance)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "deposit"),
+        ("depositor", depositor.as_str()),
+        ("amount", &funds.amount.to_string()),
+        ("balance", &balance.to_string()),
+    ]))
+}
+
+pub fn execute_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let balance = deduct_deposit(deps.storage, &info.sender, amount)?;
+
+    Ok(Response::new()
+        .add_message(into_cosmos_msg(
+            &config.underlying_token,
+            info.sender.to_string(),
+            amount,
+        )?)
+        .add_attributes(vec![
+            ("action", "withdraw"),
+            ("depositor", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+            ("balance", &balance.to_string()),
+        ]))
+}
+
+pub fn execute_submit_bid_from_balance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    premium_slot: u16,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let (response, _) =
+        submit_bid_from_balance(deps, env, &info.sender, round, premium_slot, amount)?;
+    Ok(response)
+}
+
+// returns the idx of the created bid with the response
+pub fn submit_bid_from_balance(
+    deps: DepsMut,
+    env: Env,
+    bidder: &Addr,
+    round: u64,
+    premium_slot: u16,
+    amount: Uint128,
+) -> Result<(Response, u64), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    deduct_deposit(deps.storage, bidder, amount)?;
+
+    submit_bid(
+        deps,
+        env,
+        round,
+        premium_slot,
+        bidder.to_string(),
+        Asset {
+            info: config.underlying_token,
+            amount,
+        },
+        true,
+    )
+}
+
+// returns the balance left after deducting amount from the deposit of the user
+fn deduct_deposit(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let balance = DEPOSITS.may_load(storage, user)?.unwrap_or_default();
+    if balance < amount {
+        return Err(ContractError::InsufficientBalance { balance });
+    }
+    let balance = balance - amount;
+    if balance.is_zero() {
+        DEPOSITS.remove(storage, user);
+    } else {
+        DEPOSITS.save(storage, user, &balance)?;
+    }
+    Ok(balance)
+}
+
+// LP tokens are held until the round is finalized, then withdrawn and the underlying leg is bid
+#[allow(clippy::too_many_arguments)]
+pub fn execute_submit_lp_bid(
+    deps: DepsMut,
+    env: Env,
+    round: u64,
+    premium_slot: u16,
+    bidder: String,
+    lp_token: Addr,
+    lp_amount: Uint128,
+) -> Result<Response, ContractError> {
+    if read_pause_flags(deps.storage)?.submit_bid {
+        return Err(ContractError::Paused {
+            operation: "submit_bid".to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let Some(lp_pair) = read_round_config(deps.storage, round)?.lp_pair else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Round does not accept LP tokens",
+        )));
+    };
+    let pair_info: PairInfo = deps
+        .querier
+        .query_wasm_smart(&lp_pair, &PairQueryMsg::Pair {})?;
+    if pair_info.liquidity_token != lp_token {
+        return Err(ContractError::InvalidFunds {});
+    }
+    let bid_idx = store_share_bid(deps, &env, &config, round, premium_slot, &bidder, lp_amount)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "submit_lp_bid"),
+        ("round", &round.to_string()),
+        ("bidder", &bidder),
+        ("bid_idx", &bid_idx.to_string()),
+        ("premium_slot", &premium_slot.to_string()),
+        ("lp_amount", &lp_amount.to_string()),
+    ]))
+}
+
+pub fn execute_submit_lsd_bid(
+    deps: DepsMut,
+    env: Env,
+    round: u64,
+    premium_slot: u16,
+    bidder: String,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    if read_pause_flags(deps.storage)?.submit_bid {
+        return Err(ContractError::Paused {
+            operation: "submit_bid".to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let Some(lsd_adapter) = read_round_config(deps.storage, round)?.lsd_adapter else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Round does not accept a staking derivative",
+        )));
+    };
+    let redeem_rate: RedeemRateResponse = deps
+        .querier
+        .query_wasm_smart(&lsd_adapter, &LsdAdapterQueryMsg::RedeemRate {})?;
+    assert_token_match_funds(redeem_rate.derivative, funds.info)?;
+    let bid_idx = store_share_bid(
+        deps,
+        &env,
+        &config,
+        round,
+        premium_slot,
+        &bidder,
+        funds.amount,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "submit_lsd_bid"),
+        ("round", &round.to_string()),
+        ("bidder", &bidder),
+        ("bid_idx", &bid_idx.to_string()),
+        ("premium_slot", &premium_slot.to_string()),
+        ("lsd_amount", &funds.amount.to_string()),
+    ]))
+}
+
+// stores a bid of LP or staking derivative tokens, returns its idx. The pool is created now, its
+// total only counts these bids once they are redeemed at finalize
+fn store_share_bid(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    round: u64,
+    premium_slot: u16,
+    bidder: &str,
+    share_amount: Uint128,
+) -> Result<u64, ContractError> {
+    if premium_slot < 1 || premium_slot > config.max_slot {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "premium slot must be within the range 1 and {}, reaching {}",
+            config.max_slot, premium_slot
+        ))));
+    }
+    assert_round_state(deps.storage, env, round, &[RoundState::Open])?;
+
+    let bid_pool = read_or_create_bid_pool(deps.storage, round, premium_slot)?;
+    BID_POOL.save(deps.storage, (round, premium_slot), &bid_pool)?;
+    let bidder_addr = deps.api.addr_validate(bidder)?;
+    record_bid_submission(deps.storage, &bidder_addr, env.block.time.seconds())?;
+    LP_BID_TOTALS.update(
+        deps.storage,
+        (round, premium_slot),
+        |total| -> StdResult<_> { Ok(total.unwrap_or_default().checked_add(share_amount)?) },
+    )?;
+
+    let bid_idx = pop_bid_idx(deps.storage)?;
+    let bid = Bid {
+        idx: bid_idx,
+        round,
+        timestamp: env.block.time.seconds(),
+        premium_slot,
+        bidder: bidder_addr,
+        amount: Uint128::zero(),
+        residue_bid: Uint128::zero(),
+        amount_received: Uint128::zero(),
+        is_distributed: false,
+        auto_compound: false,
+        stake_residue: false,
+        reinvest: false,
+        lp_amount: share_amount,
+        residue_claimed: false,
+        performance_fee: Uint128::zero(),
+        height: env.block.height,
+    };
+    store_bid(deps.storage, bid_idx, &bid)?;
+    Ok(bid_idx)
+}
+
+// token put up by the LP or staking derivative bids of a round
+fn query_share_token(
+    querier: &QuerierWrapper,
+    round_config: &RoundConfig,
+) -> Result<AssetInfo, ContractError> {
+    if let Some(lsd_adapter) = &round_config.lsd_adapter {
+        let redeem_rate: RedeemRateResponse =
+            querier.query_wasm_smart(lsd_adapter, &LsdAdapterQueryMsg::RedeemRate {})?;
+        return Ok(redeem_rate.derivative);
+    }
+    let lp_pair = round_config
+        .lp_pair
+        .as_ref()
+        .ok_or_else(|| StdError::generic_err("Round does not accept LP tokens"))?;
+    let pair_info: PairInfo = querier.query_wasm_smart(lp_pair, &PairQueryMsg::Pair {})?;
+    Ok(AssetInfo::Token {
+        contract_addr: pair_info.liquidity_token,
+    })
+}
+
+// LP or staking derivative tokens bid in each slot of round, and their sum
+fn read_share_totals(
+    storage: &dyn Storage,
+    config: &Config,
+    round: u64,
+) -> Result<(Uint128, Vec<(u16, Uint128)>), ContractError> {
+    let mut total_share = Uint128::zero();
+    let share_totals = (1..=config.max_slot)
+        .map(|slot| {
+            let share_total = LP_BID_TOTALS
+                .may_load(storage, (round, slot))?
+                .unwrap_or_default();
+            total_share = total_share.checked_add(share_total)?;
+            Ok((slot, share_total))
+        })
+        .collect::<Result<Vec<(u16, Uint128)>, ContractError>>()?;
+    Ok((total_share, share_totals))
+}
+
+// adds the underlying the share bids of each slot are worth to the pool and round totals
+fn credit_share_bids(
+    storage: &mut dyn Storage,
+    bidding_info: &mut BiddingInfo,
+    share_totals: Vec<(u16, Uint128)>,
+    underlying_per_share: Decimal,
+) -> Result<(), ContractError> {
+    let round = bidding_info.round;
+    for (slot, share_total) in share_totals {
+        if share_total.is_zero() {
+            continue;
+        }
+        let amount = checked_mul_decimal(share_total, underlying_per_share)?;
+        let mut bid_pool = BID_POOL.load(storage, (round, slot))?;
+        bid_pool.total_bid_amount = bid_pool.total_bid_amount.checked_add(amount)?;
+        bidding_info.total_bid_amount = bidding_info.total_bid_amount.checked_add(amount)?;
+        BID_POOL.save(storage, (round, slot), &bid_pool)?;
+    }
+    Ok(())
+}
+
+// withdraws the LP bids of the round and adds the underlying they are worth to the pool totals
+fn withdraw_lp_bids(
+    deps: DepsMut,
+    config: &Config,
+    bidding_info: &mut BiddingInfo,
+) -> Result<Option<CosmosMsg>, ContractError> {
+    let round = bidding_info.round;
+    let Some(lp_pair) = read_round_config(deps.storage, round)?.lp_pair else {
+        return Ok(None);
+    };
+    let (total_lp, lp_totals) = read_share_totals(deps.storage, config, round)?;
+    if total_lp.is_zero() {
+        return Ok(None);
+    }
+
+    let (rate, withdraw_msg) = withdraw_liquidity(&deps.querier, config, &lp_pair, total_lp)?;
+    credit_share_bids(
+        deps.storage,
+        bidding_info,
+        lp_totals,
+        rate.underlying_per_share,
+    )?;
+    LP_WITHDRAW_RATES.save(deps.storage, round, &rate)?;
+    LP_UNSETTLED_SHARES.save(deps.storage, round, &total_lp)?;
+    // both legs of the liquidity come back without notifying the contract
+    credit_shadow_balance(
+        deps.storage,
+        config,
+        &config.underlying_token,
+        checked_mul_decimal(total_lp, rate.underlying_per_share)?,
+    )?;
+    credit_shadow_balance(
+        deps.storage,
+        config,
+        &rate.other_asset,
+        checked_mul_decimal(total_lp, rate.other_per_share)?,
+    )?;
+
+    Ok(Some(withdraw_msg))
+}
+
+// the other leg of the LP bid has been paid back, rounds are dropped once all of them are
+fn settle_lp_shares(storage: &mut dyn Storage, round: u64, lp_amount: Uint128) -> StdResult<()> {
+    let Some(unsettled) = LP_UNSETTLED_SHARES.may_load(storage, round)? else {
+        return Ok(());
+    };
+    let unsettled = unsettled.saturating_sub(lp_amount);
+    if unsettled.is_zero() {
+        LP_UNSETTLED_SHARES.remove(storage, round);
+    } else {
+        LP_UNSETTLED_SHARES.save(storage, round, &unsettled)?;
+    }
+    Ok(())
+}
+
+// the LP tokens are worth their share of both reserves of the pair
+fn withdraw_liquidity(
+    querier: &QuerierWrapper,
+    config: &Config,
+    lp_pair: &Addr,
+    total_lp: Uint128,
+) -> Result<(LpWithdrawRate, CosmosMsg), ContractError> {
+    let pair_info: PairInfo = querier.query_wasm_smart(lp_pair, &PairQueryMsg::Pair {})?;
+    let pool: PoolResponse = querier.query_wasm_smart(lp_pair, &PairQueryMsg::Pool {})?;
+    let Some(underlying) = pool
+        .assets
+        .iter()
+        .find(|asset| asset.info.eq(&config.underlying_token))
+    else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "LP pair does not trade the underlying token",
+        )));
+    };
+    let other = pool
+        .assets
+        .iter()
+        .find(|asset| asset.info.ne(&config.underlying_token))
+        .unwrap_or(underlying);
+    let rate = LpWithdrawRate {
+        underlying_per_share: Decimal::checked_from_ratio(underlying.amount, pool.total_share)?,
+        other_asset: other.info.clone(),
+        other_per_share: Decimal::checked_from_ratio(other.amount, pool.total_share)?,
+    };
+
+    let withdraw_msg = into_send_msg(
+        &AssetInfo::Token {
+            contract_addr: pair_info.liquidity_token,
+        },
+        lp_pair.to_string(),
+        total_lp,
+        to_json_binary(&PairCw20HookMsg::WithdrawLiquidity {})?,
+    )?;
+    Ok((rate, withdraw_msg))
+}
+
+// the staking derivative bids of round are sent to the adapter, the balance is measured around
+// the redeem so the pools are credited with what the adapter paid rather than the rate it quotes
+fn prepare_lsd_redeem(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    lsd_adapter: &Addr,
+    round: u64,
+    exchange_rate: Decimal,
+    chunked: bool,
+) -> Result<Option<SubMsg>, ContractError> {
+    let (total_derivative, _) = read_share_totals(deps.storage, config, round)?;
+    if total_derivative.is_zero() {
+        return Ok(None);
+    }
+    let redeem_rate: RedeemRateResponse = deps
+        .querier
+        .query_wasm_smart(lsd_adapter, &LsdAdapterQueryMsg::RedeemRate {})?;
+    let balance_before = query_balance(
+        &deps.querier,
+        &config.underlying_token,
+        env.contract.address.clone(),
+    )?;
+    PENDING_LSD_REDEEM.save(
+        deps.storage,
+        &PendingLsdRedeem {
+            round,
+            exchange_rate,
+            chunked,
+            derivative: redeem_rate.derivative.clone(),
+            total_derivative,
+            balance_before,
+        },
+    )?;
+
+    Ok(Some(SubMsg::reply_on_success(
+        into_send_msg(
+            &redeem_rate.derivative,
+            lsd_adapter.to_string(),
+            total_derivative,
+            to_json_binary(&LsdAdapterExecuteMsg::Redeem {})?,
+        )?,
+        LSD_REDEEM_REPLY_ID,
+    )))
+}
+
+pub fn reply_lsd_redeem(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending = PENDING_LSD_REDEEM.load(deps.storage)?;
+    PENDING_LSD_REDEEM.remove(deps.storage);
+    let config = CONFIG.load(deps.storage)?;
+    let balance_after = query_balance(
+        &deps.querier,
+        &config.underlying_token,
+        env.contract.address.clone(),
+    )?;
+    let received = balance_after.checked_sub(pending.balance_before)?;
+
+    let (_, derivative_totals) = read_share_totals(deps.storage, &config, pending.round)?;
+    let rate = LpWithdrawRate {
+        underlying_per_share: Decimal::checked_from_ratio(received, pending.total_derivative)?,
+        other_asset: pending.derivative,
+        other_per_share: Decimal::zero(),
+    };
+    let mut bidding_info = BIDDING_INFO.load(deps.storage, pending.round)?;
+    credit_share_bids(
+        deps.storage,
+        &mut bidding_info,
+        derivative_totals,
+        rate.underlying_per_share,
+    )?;
+    BIDDING_INFO.save(deps.storage, pending.round, &bidding_info)?;
+    LP_WITHDRAW_RATES.save(deps.storage, pending.round, &rate)?;
+    // the redeem is not seen by the execute entry point, nor are the messages finalizing the
+    // round below
+    credit_shadow_balance(deps.storage, &config, &config.underlying_token, received)?;
+
+    let response = finalize_bidding_round(
+        deps.branch(),
+        env,
+        config.clone(),
+        pending.round,
+        pending.exchange_rate,
+        pending.chunked,
+    )?;
+    record_token_flows(deps.storage, &config, vec![], &response.messages)?;
+    Ok(response.add_attribute("redeemed", received.to_string()))
+}
+
+// native coins are taken as is, or wrapped first when the underlying token is their cw20 wrapper
+pub fn native_funds_to_underlying(
+    config: &Config,
+    coin: Coin,
+) -> StdResult<(Asset, Option<CosmosMsg>)> {
+    match &config.underlying_token {
+        AssetInfo::Token { contract_addr }
+            if config.wrapped_denom.as_ref() == Some(&coin.denom) =>
+        {
+            let wrap_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&WrapperExecuteMsg::Deposit {})?,
+                funds: vec![coin.clone()],
+            });
+            Ok((
+                Asset {
+                    info: config.underlying_token.clone(),
+                    amount: coin.amount,
+                },
+                Some(wrap_msg),
+            ))
+        }
+        _ => Ok((
+            Asset {
+                info: AssetInfo::NativeToken { denom: coin.denom },
+                amount: coin.amount,
+            },
+            None,
+        )),
+    }
+}
+
+fn assert_token_match_funds(expected: AssetInfo, funds: AssetInfo) -> Result<(), ContractError> {
+    if expected.ne(&funds) {
+        return Err(ContractError::InvalidFunds {});
+    }
+    Ok(())
+}
+
+// only admin or the keeper registry can call this method
+// when the bidding round ends, admin will finalized this bidding, update the exchange rate and calculate the amount allocated to all bid pool.
+// total number of matched token will be burn. And if after allocation there are still distributed tokens left, send them back to the owner
+pub fn execute_finalize_bidding_round_result(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    exchange_rate: Decimal,
+) -> Result<Response, ContractError> {
+    finalize(deps, env, info, round, exchange_rate, false)
+}
+
+// rounds with many slots are finalized over several transactions, the slots are matched by
+// ContinueFinalize and the burn and refunds are sent with the last slot
+pub fn execute_start_finalize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    exchange_rate: Decimal,
+) -> Result<Response, ContractError> {
+    finalize(deps, env, info, round, exchange_rate, true)
+}
+
+fn finalize(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    exchange_rate: Decimal,
+    chunked: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender && config.keeper_registry.as_ref() != Some(&info.sender) {
+        let Some(finalizer_group) = FINALIZER_GROUP.may_load(deps.storage)? else {
+            return Err(ContractError::Unauthorized {});
+        };
+        if !approve_finalize(
+            deps.branch(),
+            &finalizer_group,
+            &info.sender,
+            round,
+            exchange_rate,
+        )? {
+            return Ok(Response::new().add_attributes(vec![
+                ("action", "approve_finalize"),
+                ("round", &round.to_string()),
+                ("finalizer", info.sender.as_str()),
+                ("exchange_rate", &exchange_rate.to_string()),
+            ]));
+        }
+    }
+    if read_pause_flags(deps.storage)?.finalize {
+        return Err(ContractError::Paused {
+            operation: "finalize".to_string(),
+        });
+    }
+
+    // a lent distribution is pulled back before anything is paid out of it
+    let recall_msgs = recall_lent_distribution(deps.branch(), &env, &config, round)?;
+    let mut response = finalize_bidding_round(deps, env, config, round, exchange_rate, chunked)?;
+    response
+        .messages
+        .splice(0..0, recall_msgs.into_iter().map(SubMsg::new));
+    Ok(response)
+}
+
+// the bounty is capped by the distribution of the round and paid once the round is finalized
+// only owner, before the round is finalized. Without a band the round can't be finalized at the
+// oracle rate
+pub fn execute_set_oracle_rate_band(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    band: Option<RateBand>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    assert_round_state(
+        deps.storage,
+        &env,
+        round,
+        &[RoundState::Created, RoundState::Open, RoundState::Ended],
+    )?;
+
+    let Some(band) = band else {
+        ORACLE_RATE_BANDS.remove(deps.storage, round);
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "remove_oracle_rate_band"),
+            ("round", &round.to_string()),
+        ]));
+    };
+    if band.min_rate.is_zero() || band.min_rate > band.max_rate {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Oracle rate band must be non-empty and above 0",
+        )));
+    }
+    // the band can't reach below the rate committed to the bidders
+    assert_min_exchange_rate(deps.storage, round, band.min_rate)?;
+    ORACLE_RATE_BANDS.save(deps.storage, round, &band)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_oracle_rate_band"),
+        ("round", &round.to_string()),
+        ("min_rate", &band.min_rate.to_string()),
+        ("max_rate", &band.max_rate.to_string()),
+    ]))
+}
+
+pub fn execute_finalize_at_oracle_rate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+) -> Result<Response, ContractError> {
+    let Some(bounty) = FINALIZE_BOUNTY.may_load(deps.storage)? else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Permissionless finalize is not enabled",
+        )));
+    };
+    if read_pause_flags(deps.storage)?.finalize {
+        return Err(ContractError::Paused {
+            operation: "finalize".to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let Some(oracle_pair) = &config.oracle_pair else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Oracle pair is not configured",
+        )));
+    };
+    let exchange_rate = query_pair_exchange_rate(
+        &deps.querier,
+        oracle_pair,
+        &config.underlying_token,
+        &config.distribution_token,
+    )?;
+    let Some(band) = ORACLE_RATE_BANDS.may_load(deps.storage, round)? else {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} has no oracle rate band",
+            round
+        ))));
+    };
+    if exchange_rate < band.min_rate || exchange_rate > band.max_rate {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Oracle rate {} is outside the band of round {}",
+            exchange_rate, round
+        ))));
+    }
+
+    let recall_msgs = recall_lent_distribution(deps.branch(), &env, &config, round)?;
+    let mut distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    let bounty = bounty.min(distribution_info.total_distribution);
+    distribution_info.total_distribution -= bounty;
+    DISTRIBUTION_INFO.save(deps.storage, round, &distribution_info)?;
+
+    let distribution_token = config.distribution_token.clone();
+    let mut response = finalize_bidding_round(deps, env, config, round, exchange_rate, false)?;
+    response
+        .messages
+        .splice(0..0, recall_msgs.into_iter().map(SubMsg::new));
+    if !bounty.is_zero() {
+        response = response.add_message(into_cosmos_msg(
+            &distribution_token,
+            info.sender.to_string(),
+            bounty,
+        )?);
+    }
+    Ok(response.add_attributes(vec![
+        ("finalizer", info.sender.as_str()),
+        ("bounty", &bounty.to_string()),
+    ]))
+}
+
+fn finalizer_weight(querier: &QuerierWrapper, group: &Addr, member: &Addr) -> StdResult<u64> {
+    let member: MemberResponse = querier.query_wasm_smart(
+        group,
+        &Cw4QueryMsg::Member {
+            addr: member.to_string(),
+            at_height: None,
+        },
+    )?;
+    Ok(member.weight.unwrap_or_default())
+}
+
+// records the exchange rate approved by a member of the finalizer group, returns whether the
+// round can be finalized at it: right away without quorum, otherwise once the current weight of
+// the members approving the same rate reaches the quorum of the total weight
+fn approve_finalize(
+    deps: DepsMut,
+    finalizer_group: &FinalizerGroup,
+    member: &Addr,
+    round: u64,
+    exchange_rate: Decimal,
+) -> Result<bool, ContractError> {
+    if finalizer_weight(&deps.querier, &finalizer_group.group, member)? == 0 {
+        return Err(ContractError::Unauthorized {});
+    }
+    let Some(quorum) = finalizer_group.quorum else {
+        return Ok(true);
+    };
+
+    FINALIZE_APPROVALS.save(deps.storage, (round, member), &exchange_rate)?;
+    let approvals = FINALIZE_APPROVALS
+        .prefix(round)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Addr, Decimal)>>>()?;
+    let mut approved_weight = Uint128::zero();
+    for (approver, _) in approvals.iter().filter(|(_, rate)| *rate == exchange_rate) {
+        approved_weight = approved_weight.checked_add(Uint128::from(finalizer_weight(
+            &deps.querier,
+            &finalizer_group.group,
+            approver,
+        )?))?;
+    }
+    let total_weight: TotalWeightResponse = deps.querier.query_wasm_smart(
+        &finalizer_group.group,
+        &Cw4QueryMsg::TotalWeight { at_height: None },
+    )?;
+    if approved_weight < checked_mul_decimal(Uint128::from(total_weight.weight), quorum)? {
+        return Ok(false);
+    }
+
+    for (approver, _) in approvals {
+        FINALIZE_APPROVALS.remove(deps.storage, (round, &approver));
+    }
+    Ok(true)
+}
+
+fn finalize_bidding_round(
+    mut deps: DepsMut,
+    env: Env,
+    config: Config,
+    round: u64,
+    exchange_rate: Decimal,
+    chunked: bool,
+) -> Result<Response, ContractError> {
+    let mut bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+
+    // check that bidding round must have ended
+    assert_round_state(deps.storage, &env, round, &[RoundState::Ended])?;
+    if YIELD_POSITION
+        .may_load(deps.storage)?
+        .map(|position| position.round)
+        == Some(round)
+    {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Idle funds of round {} must be withdrawn from the yield venue before finalize",
+            round
+        ))));
+    }
+
+    let mut distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    if FINALIZE_PROGRESS.has(deps.storage, round) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Finalize of round {} is in progress",
+            round
+        ))));
+    }
+    assert_min_exchange_rate(deps.storage, round, exchange_rate)?;
+
+    // nothing to match, the whole distribution goes back to the owner
+    if count_number_bids_in_round(deps.storage, round)? == 0 {
+        return finalize_empty_round(
+            deps.storage,
+            &env,
+            &config,
+            &mut bidding_info,
+            &mut distribution_info,
+            exchange_rate,
+        );
+    }
+
+    // staking derivative bids are redeemed first, the reply credits the pools with the
+    // underlying_token the adapter paid and finalizes the round
+    let round_config = read_round_config(deps.storage, round)?;
+    if let Some(lsd_adapter) = &round_config.lsd_adapter {
+        if !LP_WITHDRAW_RATES.has(deps.storage, round) {
+            if let Some(redeem_msg) = prepare_lsd_redeem(
+                deps.branch(),
+                &env,
+                &config,
+                lsd_adapter,
+                round,
+                exchange_rate,
+                chunked,
+            )? {
+                return Ok(Response::new()
+                    .add_submessage(redeem_msg)
+                    .add_attributes(vec![
+                        ("action", "redeem_lsd_bids"),
+                        ("round", &round.to_string()),
+                        ("exchange_rate", &exchange_rate.to_string()),
+                    ]));
+            }
+        }
+    }
+
+    // LP bids take part in the matching with their underlying leg
+    let withdraw_lp_msg = withdraw_lp_bids(deps.branch(), &config, &mut bidding_info)?;
+
+    // below the volume floor or with too few bidders nothing is matched,
+    // distribute refunds every bid
+    let below_threshold = round_config
+        .total_bid_threshold
+        .filter(|threshold| bidding_info.total_bid_amount < *threshold);
+    let num_participants = count_number_bidders_in_round(deps.storage, round)?;
+    let below_min_participants = round_config
+        .min_participants
+        .filter(|min_participants| num_participants < *min_participants);
+    if below_threshold.is_some() || below_min_participants.is_some() {
+        let mut response = finalize_empty_round(
+            deps.storage,
+            &env,
+            &config,
+            &mut bidding_info,
+            &mut distribution_info,
+            exchange_rate,
+        )?;
+        if let Some(threshold) = below_threshold {
+            response = response.add_attribute("total_bid_threshold", threshold.to_string());
+        }
+        if let Some(min_participants) = below_min_participants {
+            response = response.add_attributes(vec![
+                ("min_participants", min_participants.to_string()),
+                ("num_participants", num_participants.to_string()),
+            ]);
+        }
+        return Ok(response.add_messages(withdraw_lp_msg));
+    }
+
+    if chunked {
+        assert_rebate_exchange_rate(&config, exchange_rate)?;
+        BIDDING_INFO.save(deps.storage, round, &bidding_info)?;
+        FINALIZE_PROGRESS.save(
+            deps.storage,
+            round,
+            &FinalizeProgress {
+                exchange_rate,
+                last_slot: 0,
+                distribution_left: distribution_info.total_distribution,
+                total_matched: Uint128::zero(),
+            },
+        )?;
+        return Ok(Response::new()
+            .add_attributes(vec![
+                ("action", "start_finalize"),
+                ("round", &round.to_string()),
+                ("exchange_rate", &exchange_rate.to_string()),
+            ])
+            .add_messages(withdraw_lp_msg));
+    }
+
+    let refund_bonus_msgs = match_round(
+        deps.storage,
+        &env,
+        &mut bidding_info,
+        &mut distribution_info,
+        exchange_rate,
+    )?;
+
+    // the liquidity is withdrawn before burning the matched tokens
+    complete_finalize(
+        deps.storage,
+        &env,
+        &config,
+        &bidding_info,
+        &distribution_info,
+        withdraw_lp_msg.into_iter().collect(),
+        refund_bonus_msgs,
+    )
+}
+
+pub fn execute_continue_finalize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender && config.keeper_registry.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if read_pause_flags(deps.storage)?.finalize {
+        return Err(ContractError::Paused {
+            operation: "finalize".to_string(),
+        });
+    }
+    let Some(mut progress) = FINALIZE_PROGRESS.may_load(deps.storage, round)? else {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Finalize of round {} has not started",
+            round
+        ))));
+    };
+
+    // the slots are matched in order, exactly like a single finalize would
+    let limit = limit
+        .unwrap_or(DEFAULT_FINALIZE_LIMIT)
+        .min(MAX_FINALIZE_LIMIT) as u16;
+    let last_slot = progress
+        .last_slot
+        .saturating_add(limit)
+        .min(config.max_slot);
+    for slot in progress.last_slot + 1..=last_slot {
+        let mut bid_pool = read_or_create_bid_pool(deps.storage, round, slot)?;
+        let matched_amount = fill_bid_pool(
+            &mut bid_pool,
+            &mut progress.distribution_left,
+            progress.exchange_rate,
+            config.is_rebate(),
+        )?;
+        progress.total_matched = progress.total_matched.checked_add(matched_amount)?;
+        BID_POOL.save(deps.storage, (round, slot), &bid_pool)?;
+    }
+    progress.last_slot = last_slot;
+
+    if last_slot < config.max_slot {
+        FINALIZE_PROGRESS.save(deps.storage, round, &progress)?;
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "continue_finalize"),
+            ("round", &round.to_string()),
+            ("last_slot", &last_slot.to_string()),
+        ]));
+    }
+
+    FINALIZE_PROGRESS.remove(deps.storage, round);
+    let mut bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let mut distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    let refund_bonus_msgs = release_round(
+        deps.storage,
+        &env,
+        &mut bidding_info,
+        &mut distribution_info,
+        progress.exchange_rate,
+        progress.distribution_left,
+        progress.total_matched,
+    )?;
+
+    complete_finalize(
+        deps.storage,
+        &env,
+        &config,
+        &bidding_info,
+        &distribution_info,
+        vec![],
+        refund_bonus_msgs,
+    )
+}
+
+// burns the matched tokens, or waits for the dispute period, and notifies the hooks of the
+// finalized round
+fn complete_finalize(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    bidding_info: &BiddingInfo,
+    distribution_info: &DistributionInfo,
+    mut msgs: Vec<CosmosMsg>,
+    refund_bonus_msgs: Vec<CosmosMsg>,
+) -> Result<Response, ContractError> {
+    let round = bidding_info.round;
+    let exchange_rate = distribution_info.exchange_rate;
+    let total_matched = bidding_info.total_bid_matched;
+
+    // tranched rounds are settled once closed, the funding still to come is matched first
+    if read_round_config(storage, round)?.tranched {
+        OPEN_TRANCHES.save(storage, round, &1)?;
+    } else {
+        msgs.extend(settle_or_open_disputes(storage, env, config, round)?);
+    }
+
+    msgs.extend(refund_bonus_msgs);
+    msgs.extend(prepare_post_finalize_msgs(
+        storage,
+        bidding_info,
+        distribution_info,
+    )?);
+    let hook_msgs = prepare_hook_msgs(
+        storage,
+        BidPoolHookMsg::RoundFinalized {
+            round,
+            exchange_rate,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "finalize_bidding_round_result"),
+            ("round", &round.to_string()),
+            ("exchange_rate", &exchange_rate.to_string()),
+            ("total_matched", &total_matched.to_string()),
+            (
+                "actual_distributed",
+                &distribution_info.actual_distributed.to_string(),
+            ),
+        ])
+        .add_messages(msgs)
+        .add_submessages(hook_msgs))
+}
+
+// disputable rounds burn and return the leftover once the dispute period is over
+fn settle_or_open_disputes(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    round: u64,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    if let Some(dispute_params) = DISPUTE_PARAMS.may_load(storage)? {
+        SETTLE_AFTER.save(
+            storage,
+            round,
+            &(env.block.time.seconds() + dispute_params.dispute_period),
+        )?;
+        return Ok(vec![]);
+    }
+    settle_round(storage, config, round, env.block.time.seconds())
+}
+
+// matches funds against the bids the previous tranches of a tranched round left unmatched, slot
+// by slot at the exchange rate of the finalize. The round is closed once every bid is matched
+pub fn execute_add_tranche(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    round: u64,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    if read_pause_flags(deps.storage)?.finalize {
+        return Err(ContractError::Paused {
+            operation: "finalize".to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    if sender != config.owner && sender != config.treasury {
+        return Err(ContractError::Unauthorized {});
+    }
+    assert_token_match_funds(config.distribution_token.clone(), funds.info)?;
+    if funds.amount.is_zero() {
+        return Err(ContractError::InvalidFunds {});
+    }
+    let Some(num_tranches) = OPEN_TRANCHES.may_load(deps.storage, round)? else {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} does not take tranches",
+            round
+        ))));
+    };
+
+    let mut bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let mut distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    let bid_pools = bidding_info.read_all_bid_pool(deps.storage)?;
+    let mut distribution_left = funds.amount;
+    let mut tranche_matched = Uint128::zero();
+    for mut bid_pool in bid_pools {
+        if distribution_left.is_zero() {
+            break;
+        }
+        // the pool is matched again on its unmatched part only
+        let mut unmatched_pool = BidPool {
+            total_bid_amount: bid_pool
+                .total_bid_amount
+                .checked_sub(bid_pool.matched_amount)?,
+            ..bid_pool.clone()
+        };
+        let matched_amount = fill_bid_pool(
+            &mut unmatched_pool,
+            &mut distribution_left,
+            distribution_info.exchange_rate,
+            config.is_rebate(),
+        )?;
+        if matched_amount.is_zero() {
+            continue;
+        }
+        bid_pool.matched_amount = bid_pool.matched_amount.checked_add(matched_amount)?;
+        bid_pool.payout_amount = bid_pool
+            .payout_amount
+            .checked_add(unmatched_pool.payout_amount)?;
+        bid_pool.index_snapshot =
+            Decimal::checked_from_ratio(bid_pool.matched_amount, bid_pool.total_bid_amount)?;
+        bid_pool.received_per_token =
+            Decimal::checked_from_ratio(bid_pool.payout_amount, bid_pool.total_bid_amount)?;
+        tranche_matched = tranche_matched.checked_add(matched_amount)?;
+        BID_POOL.save(deps.storage, (round, bid_pool.slot), &bid_pool)?;
+    }
+
+    // the part of the tranche left unmatched is returned with the leftover when the round settles
+    distribution_info.total_distribution = distribution_info
+        .total_distribution
+        .checked_add(funds.amount)?;
+    distribution_info.actual_distributed = distribution_info
+        .actual_distributed
+        .checked_add(funds.amount.checked_sub(distribution_left)?)?;
+    bidding_info.total_bid_matched = bidding_info
+        .total_bid_matched
+        .checked_add(tranche_matched)?;
+    DISTRIBUTION_INFO.save(deps.storage, round, &distribution_info)?;
+    BIDDING_INFO.save(deps.storage, round, &bidding_info)?;
+
+    // bonuses still shared by matched bids are shared by the newly matched ones too
+    if let Some(mut bonuses) = SLOT_BONUSES.may_load(deps.storage, round)? {
+        let bid_pools = bidding_info.read_all_bid_pool(deps.storage)?;
+        for bonus in bonuses.iter_mut() {
+            if !bonus.eligible_matched.is_zero() {
+                bonus.eligible_matched = bonus_eligible_matched(bonus, &bid_pools)?;
+            }
+        }
+        SLOT_BONUSES.save(deps.storage, round, &bonuses)?;
+    }
+
+    let tranche = num_tranches + 1;
+    let mut msgs = vec![];
+    let closed = !distribution_left.is_zero()
+        || bidding_info.total_bid_matched >= bidding_info.total_bid_amount;
+    if closed {
+        msgs = close_tranches(deps.storage, &env, &config, round)?;
+    } else {
+        OPEN_TRANCHES.save(deps.storage, round, &tranche)?;
+    }
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        ("action", "add_tranche"),
+        ("round", &round.to_string()),
+        ("tranche", &tranche.to_string()),
+        ("amount", &funds.amount.to_string()),
+        ("total_matched", &tranche_matched.to_string()),
+        ("closed", &closed.to_string()),
+    ]))
+}
+
+// only owner, closes the tranches of the round before every bid is matched
+pub fn execute_close_tranches(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !OPEN_TRANCHES.has(deps.storage, round) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} does not take tranches",
+            round
+        ))));
+    }
+    let msgs = close_tranches(deps.storage, &env, &config, round)?;
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        ("action", "close_tranches"),
+        ("round", &round.to_string()),
+    ]))
+}
+
+// the round is settled like any finalized round, its bids can then be distributed
+fn close_tranches(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    round: u64,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    OPEN_TRANCHES.remove(storage, round);
+    settle_or_open_disputes(storage, env, config, round)
+}
+
+// the residues of a round still taking tranches shrink with each tranche
+fn assert_tranches_closed(storage: &dyn Storage, round: u64) -> Result<(), ContractError> {
+    if OPEN_TRANCHES.has(storage, round) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} still takes tranches",
+            round
+        ))));
+    }
+    Ok(())
+}
+
+fn assert_min_exchange_rate(
+    storage: &dyn Storage,
+    round: u64,
+    exchange_rate: Decimal,
+) -> Result<(), ContractError> {
+    if let Some(min_exchange_rate) = MIN_EXCHANGE_RATES.may_load(storage, round)? {
+        if exchange_rate < min_exchange_rate {
+            return Err(ContractError::ExchangeRateBelowMinimum {
+                exchange_rate,
+                min_exchange_rate,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn finalize_empty_round(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    bidding_info: &mut BiddingInfo,
+    distribution_info: &mut DistributionInfo,
+    exchange_rate: Decimal,
+) -> Result<Response, ContractError> {
+    let round = bidding_info.round;
+    save_round_state(storage, env, round, RoundState::Finalized)?;
+    distribution_info.exchange_rate = exchange_rate;
+    distribution_info.is_released = true;
+    distribution_info.actual_distributed = Uint128::zero();
+    bidding_info.total_bid_matched = Uint128::zero();
+    DISTRIBUTION_INFO.save(storage, round, distribution_info)?;
+    BIDDING_INFO.save(storage, round, bidding_info)?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !distribution_info.total_distribution.is_zero() {
+        msgs.push(into_cosmos_msg(
+            &config.distribution_token,
+            config.owner.to_string(),
+            distribution_info.total_distribution,
+        )?);
+    }
+    // without matched bids no slot can earn a bonus
+    for bonus in SLOT_BONUSES.may_load(storage, round)?.unwrap_or_default() {
+        msgs.push(into_cosmos_msg(
+            &bonus.asset.info,
+            bonus.sponsor.to_string(),
+            bonus.asset.amount,
+        )?);
+    }
+    msgs.extend(prepare_post_finalize_msgs(
+        storage,
+        bidding_info,
+        distribution_info,
+    )?);
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "finalize_bidding_round_result"),
+            ("round", &round.to_string()),
+            ("exchange_rate", &exchange_rate.to_string()),
+            ("total_matched", "0"),
+            (
+                "reclaimed_distribution",
+                &distribution_info.total_distribution.to_string(),
+            ),
+        ])
+        .add_messages(msgs)
+        .add_submessages(prepare_hook_msgs(
+            storage,
+            BidPoolHookMsg::RoundFinalized {
+                round,
+                exchange_rate,
+            },
+        )?))
+}
+
+// matches the bid pools of the round at exchange_rate, returns the refunds of the bonuses
+// left without matched bids. A round finalized again after a dispute is matched from scratch
+fn match_round(
+    storage: &mut dyn Storage,
+    env: &Env,
+    bidding_info: &mut BiddingInfo,
+    distribution_info: &mut DistributionInfo,
+    exchange_rate: Decimal,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let round = bidding_info.round;
+    let mut bid_pools = bidding_info.read_all_bid_pool(storage)?;
+    for bid_pool in bid_pools.iter_mut() {
+        bid_pool.index_snapshot = Decimal::zero();
+        bid_pool.received_per_token = Decimal::zero();
+        bid_pool.matched_amount = Uint128::zero();
+        bid_pool.payout_amount = Uint128::zero();
+    }
+
+    // calculate the amount allocated to all bid pool
+    let mut distribution_amount = distribution_info.total_distribution;
+    let total_matched = if assert_rebate_exchange_rate(&CONFIG.load(storage)?, exchange_rate)? {
+        process_calc_rebate_amount(&mut bid_pools, &mut distribution_amount)?
+    } else {
+        process_calc_distribution_amount(&mut bid_pools, &mut distribution_amount, exchange_rate)?
+    };
+
+    for bid_pool in bid_pools {
+        BID_POOL.save(storage, (round, bid_pool.slot), &bid_pool)?;
+    }
+
+    release_round(
+        storage,
+        env,
+        bidding_info,
+        distribution_info,
+        exchange_rate,
+        distribution_amount,
+        total_matched,
+    )
+}
+
+// rebate rounds can only be finalized at par, returns whether the round is a rebate round
+fn assert_rebate_exchange_rate(
+    config: &Config,
+    exchange_rate: Decimal,
+) -> Result<bool, ContractError> {
+    if config.is_rebate() && exchange_rate != Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Rebate rounds are finalized at an exchange rate of 1",
+        )));
+    }
+    Ok(config.is_rebate())
+}
+
+// records the result of the matched bid pools and marks the round as finalized, returns the
+// refunds of the bonuses left without matched bids
+fn release_round(
+    storage: &mut dyn Storage,
+    env: &Env,
+    bidding_info: &mut BiddingInfo,
+    distribution_info: &mut DistributionInfo,
+    exchange_rate: Decimal,
+    distribution_left: Uint128,
+    total_matched: Uint128,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let round = bidding_info.round;
+    save_round_state(storage, env, round, RoundState::Finalized)?;
+    let refinalized = distribution_info.is_released;
+
+    // update exchange_rate and mark this round as finalized
+    distribution_info.exchange_rate = exchange_rate;
+    distribution_info.is_released = true;
+    distribution_info.actual_distributed = distribution_info
+        .total_distribution
+        .checked_sub(distribution_left)?;
+    bidding_info.total_bid_matched = total_matched;
+
+    // bonuses are shared by the matched bids of their slots, or go back to the sponsor
+    let mut refund_bonus_msgs: Vec<CosmosMsg> = vec![];
+    if let Some(mut bonuses) = SLOT_BONUSES.may_load(storage, round)? {
+        let bid_pools = bidding_info.read_all_bid_pool(storage)?;
+        for bonus in bonuses.iter_mut() {
+            // already refunded when the round was first finalized
+            if refinalized && bonus.eligible_matched.is_zero() {
+                continue;
+            }
+            bonus.eligible_matched = bonus_eligible_matched(bonus, &bid_pools)?;
+            if bonus.eligible_matched.is_zero() {
+                refund_bonus_msgs.push(into_cosmos_msg(
+                    &bonus.asset.info,
+                    bonus.sponsor.to_string(),
+                    bonus.asset.amount,
+                )?);
+            }
+        }
+        SLOT_BONUSES.save(storage, round, &bonuses)?;
+    }
+
+    DISTRIBUTION_INFO.save(storage, round, distribution_info)?;
+    BIDDING_INFO.save(storage, round, bidding_info)?;
+
+    Ok(refund_bonus_msgs)
+}
+
+// underlying matched in the slots of the bonus, which is shared pro-rata to it
+fn bonus_eligible_matched(bonus: &SlotBonus, bid_pools: &[BidPool]) -> StdResult<Uint128> {
+    bid_pools
+        .iter()
+        .filter(|bid_pool| bonus.is_eligible(bid_pool.slot))
+        .try_fold(Uint128::zero(), |total, bid_pool| {
+            Ok(total.checked_add(checked_mul_decimal(
+                bid_pool.total_bid_amount,
+                bid_pool.index_snapshot,
+            )?)?)
+        })
+}
+
+// burns the matched tokens of a finalized round and transfers the leftover reward to the
+// leftover airdrop when one is set, to the owner otherwise
+fn settle_round(
+    storage: &mut dyn Storage,
+    config: &Config,
+    round: u64,
+    timestamp: u64,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let total_matched = BIDDING_INFO.load(storage, round)?.total_bid_matched;
+    let distribution_info = DISTRIBUTION_INFO.load(storage, round)?;
+    let distribution_amount = distribution_info
+        .total_distribution
+        .checked_sub(distribution_info.actual_distributed)?;
+    SETTLE_AFTER.remove(storage, round);
+    record_burn_stats(
+        storage,
+        timestamp,
+        if config.is_rebate() {
+            Uint128::zero()
+        } else {
+            total_matched
+        },
+        distribution_info.actual_distributed,
+    )?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+
+    // burn total_matched, cw20 tokens reject burning nothing. Rebate rounds pay it back instead
+    if !total_matched.is_zero() && !config.is_rebate() {
+        match &config.underlying_token {
+            AssetInfo::NativeToken { denom } => msgs.push(CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![Coin {
+                    denom: denom.to_owned(),
+                    amount: total_matched,
+                }],
+            })),
+            AssetInfo::Token { contract_addr } => msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Burn {
+                    amount: total_matched,
+                })?,
+                funds: vec![],
+            })),
+        };
+    }
+
+    // transfer remaining to the airdrop of the round or the owner
+    if !distribution_amount.is_zero() {
+        msgs.push(match LEFTOVER_AIRDROP.may_load(storage)? {
+            Some(airdrop) => into_send_msg(
+                &config.distribution_token,
+                airdrop.to_string(),
+                distribution_amount,
+                to_json_binary(&AirdropExecuteMsg::Fund { round })?,
+            )?,
+            None => into_cosmos_msg(
+                &config.distribution_token,
+                config.owner.to_string(),
+                distribution_amount,
+            )?,
+        });
+    }
+
+    Ok(msgs)
+}
+
+pub fn execute_dispute_round(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let Some(dispute_params) = DISPUTE_PARAMS.may_load(deps.storage)? else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Disputes are not enabled",
+        )));
+    };
+    match SETTLE_AFTER.may_load(deps.storage, round)? {
+        Some(settle_after) if env.block.time.seconds() < settle_after => {}
+        _ => {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Round {} is not in its dispute period",
+                round
+            ))))
+        }
+    }
+    if DISPUTES.has(deps.storage, round) {
+        return Err(ContractError::RoundDisputed { round });
+    }
+    if must_pay(&info, &dispute_params.bond.denom)? != dispute_params.bond.amount {
+        return Err(ContractError::InvalidFunds {});
+    }
+
+    let Some(oracle_pair) = config.oracle_pair else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Oracle pair is not configured",
+        )));
+    };
+    let oracle_rate = query_pair_exchange_rate(
+        &deps.querier,
+        &oracle_pair,
+        &config.underlying_token,
+        &config.distribution_token,
+    )?;
+    let exchange_rate = DISTRIBUTION_INFO.load(deps.storage, round)?.exchange_rate;
+    let deviation = if exchange_rate > oracle_rate {
+        exchange_rate - oracle_rate
+    } else {
+        oracle_rate - exchange_rate
+    };
+    if deviation <= oracle_rate * dispute_params.max_deviation {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Exchange rate is within the allowed deviation from the oracle",
+        )));
+    }
+
+    DISPUTES.save(
+        deps.storage,
+        round,
+        &Dispute {
+            disputer: info.sender.clone(),
+            bond: dispute_params.bond,
+            oracle_rate,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "dispute_round"),
+        ("round", &round.to_string()),
+        ("disputer", info.sender.as_str()),
+        ("exchange_rate", &exchange_rate.to_string()),
+        ("oracle_rate", &oracle_rate.to_string()),
+    ]))
+}
+
+// only owner can resolve a dispute, the round is settled on the next distribute
+pub fn execute_resolve_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    exchange_rate: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let Some(dispute) = DISPUTES.may_load(deps.storage, round)? else {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} has no open dispute",
+            round
+        ))));
+    };
+    DISPUTES.remove(deps.storage, round);
+    SETTLE_AFTER.save(deps.storage, round, &env.block.time.seconds())?;
+
+    let bond_msg = |recipient: &Addr| {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![dispute.bond.clone()],
+        })
+    };
+    let Some(exchange_rate) = exchange_rate else {
+        return Ok(Response::new()
+            .add_attributes(vec![
+                ("action", "resolve_dispute"),
+                ("round", &round.to_string()),
+                ("result", "rejected"),
+            ])
+            .add_message(bond_msg(&config.treasury)));
+    };
+
+    assert_min_exchange_rate(deps.storage, round, exchange_rate)?;
+    let mut bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let mut distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    let refund_bonus_msgs = match_round(
+        deps.storage,
+        &env,
+        &mut bidding_info,
+        &mut distribution_info,
+        exchange_rate,
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "resolve_dispute"),
+            ("round", &round.to_string()),
+            ("result", "upheld"),
+            ("exchange_rate", &exchange_rate.to_string()),
+            ("total_matched", &bidding_info.total_bid_matched.to_string()),
+            (
+                "actual_distributed",
+                &distribution_info.actual_distributed.to_string(),
+            ),
+        ])
+        .add_message(bond_msg(&dispute.disputer))
+        .add_messages(refund_bonus_msgs))
+}
+
+// after bidding round finalized, call this function to send the allocated tokens to all bidder, and if the bid still has bid token, transfer back to the bidder
+pub fn execute_distribute(
+    deps: DepsMut,
+    env: Env,
+    round: u64,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_DISTRIBUTE_LIMIT)
+        .min(MAX_DISTRIBUTE_LIMIT);
+
+    // the best priced bids are settled first, start_after is then read as the bid's position
+    if read_round_config(deps.storage, round)?.distribute_by_slot {
+        let cursor = SLOT_DISTRIBUTE_CURSOR.may_load(deps.storage, round)?;
+        let start_after = match start_after {
+            Some(idx) => Some((BID.load(deps.storage, idx)?.premium_slot, idx)),
+            None => cursor,
+        };
+        let bids = read_bids_by_slot(deps.storage, round, start_after, limit)?;
+
+        let cursor_update = bids
+            .last()
+            .filter(|last| cursor.map_or(true, |cursor| cursor < **last))
+            .map(|last| CursorUpdate::BySlot(*last));
+        let next_cursor = bids.last().map(|(_, idx)| *idx);
+        let bids_idx = bids.into_iter().map(|(_, idx)| idx).collect();
+        return process_distribute(deps, env, round, bids_idx, next_cursor, cursor_update);
+    }
+
+    // without an explicit start point, resume after the last processed bid
+    let cursor = DISTRIBUTE_CURSOR.may_load(deps.storage, round)?;
+    let start_after = start_after.or(cursor);
+
+    // load the next batch of bids in round
+    let bids_idx = read_bids_by_round(deps.storage, round, start_after, Some(limit), None)?;
+
+    let cursor_update = bids_idx
+        .last()
+        .filter(|last_idx| cursor.map_or(true, |cursor| cursor < **last_idx))
+        .map(|last_idx| CursorUpdate::ByIdx(*last_idx));
+    let next_cursor = bids_idx.last().copied();
+    process_distribute(deps, env, round, bids_idx, next_cursor, cursor_update)
+}
+
+// distributes the given bids of the round, leaving the cursor untouched
+pub fn execute_distribute_bids(
+    deps: DepsMut,
+    env: Env,
+    round: u64,
+    idxs: Vec<u64>,
+) -> Result<Response, ContractError> {
+    if idxs.len() as u64 > MAX_DISTRIBUTE_LIMIT {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "At most {} bids can be distributed at once",
+            MAX_DISTRIBUTE_LIMIT
+        ))));
+    }
+    for idx in idxs.iter() {
+        if !BIDS_BY_ROUND.has(deps.storage, (round, *idx)) {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Bid {} is not in round {}",
+                idx, round
+            ))));
+        }
+    }
+
+    process_distribute(deps, env, round, idxs, None, None)
+}
+
+// only owner or keeper, the bids and checkpoints are removed first in chunks of limit entries,
+// the call removing the last ones also removes the pools and the info of the round
+pub fn execute_prune_round(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender && config.keeper_registry.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if ROUND_SUMMARIES.has(deps.storage, round) && !BIDDING_INFO.has(deps.storage, round) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} is already pruned",
+            round
+        ))));
+    }
+    // the next round is checked against the last one
+    if round == LAST_ROUND_ID.load(deps.storage)? {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The last round can not be pruned",
+        )));
+    }
+    let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    let finalized = matches!(
+        read_round_state(deps.storage, &env, round)?,
+        RoundState::Finalized | RoundState::Distributing | RoundState::Completed
+    );
+    if !finalized || SETTLE_AFTER.has(deps.storage, round) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} is not settled",
+            round
+        ))));
+    }
+
+    if !ROUND_SUMMARIES.has(deps.storage, round) {
+        let summary = RoundSummary {
+            round,
+            start_time: bidding_info.start_time,
+            end_time: bidding_info.end_time,
+            total_bid_amount: bidding_info.total_bid_amount,
+            total_bid_matched: bidding_info.total_bid_matched,
+            exchange_rate: distribution_info.exchange_rate,
+            total_distribution: distribution_info.total_distribution,
+            actual_distributed: distribution_info.actual_distributed,
+            num_bids: count_number_bids_in_round(deps.storage, round)?,
+            num_bidders: count_number_bidders_in_round(deps.storage, round)?,
+        };
+        ROUND_SUMMARIES.save(deps.storage, round, &summary)?;
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_PRUNE_LIMIT).min(MAX_PRUNE_LIMIT) as usize;
+    let bids_idx = BIDS_BY_ROUND
+        .prefix(round)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<u64>>>()?;
+    for idx in bids_idx.iter() {
+        let bid = BID.load(deps.storage, *idx)?;
+        if !bid.is_distributed {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Bid {} of round {} is not distributed",
+                idx, round
+            ))));
+        }
+        BID.remove(deps.storage, *idx);
+        BIDS_BY_ROUND.remove(deps.storage, (round, *idx));
+        BIDS_BY_SLOT.remove(deps.storage, (round, bid.premium_slot, *idx));
+        BIDS_BY_HEIGHT.remove(deps.storage, (round, bid.height, *idx));
+        BIDS_BY_TIME.remove(deps.storage, (round, bid.timestamp, *idx));
+        BIDS_BY_USER.remove(deps.storage, (round, bid.bidder));
+    }
+    let mut num_pruned = bids_idx.len();
+
+    let round_checkpoints = ROUND_TOTAL_CHECKPOINTS
+        .prefix(round)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit - num_pruned)
+        .collect::<StdResult<Vec<u64>>>()?;
+    for timestamp in round_checkpoints.iter() {
+        ROUND_TOTAL_CHECKPOINTS.remove(deps.storage, (round, *timestamp));
+    }
+    num_pruned += round_checkpoints.len();
+
+    let pool_checkpoints = POOL_TOTAL_CHECKPOINTS
+        .sub_prefix(round)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit - num_pruned)
+        .collect::<StdResult<Vec<(u16, u64)>>>()?;
+    for (slot, timestamp) in pool_checkpoints.iter() {
+        POOL_TOTAL_CHECKPOINTS.remove(deps.storage, (round, *slot, *timestamp));
+    }
+    num_pruned += pool_checkpoints.len();
+
+    // a partial chunk means every bid and checkpoint has been removed
+    let finished = num_pruned < limit;
+    if finished {
+        let slots = BID_POOL
+            .prefix(round)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<u16>>>()?;
+        for slot in slots {
+            BID_POOL.remove(deps.storage, (round, slot));
+            LP_BID_TOTALS.remove(deps.storage, (round, slot));
+        }
+        BIDDING_INFO.remove(deps.storage, round);
+        DISTRIBUTION_INFO.remove(deps.storage, round);
+        NUM_BIDS_BY_ROUND.remove(deps.storage, round);
+        NUM_BIDDERS_BY_ROUND.remove(deps.storage, round);
+        DISTRIBUTE_CURSOR.remove(deps.storage, round);
+        SLOT_DISTRIBUTE_CURSOR.remove(deps.storage, round);
+        LP_WITHDRAW_RATES.remove(deps.storage, round);
+        LP_UNSETTLED_SHARES.remove(deps.storage, round);
+        SLOT_BONUSES.remove(deps.storage, round);
+        MIN_EXCHANGE_RATES.remove(deps.storage, round);
+        ORACLE_RATE_BANDS.remove(deps.storage, round);
+        ROUND_CONFIG.remove(deps.storage, round);
+        PAYOUT_TOTALS.remove(deps.storage, round);
+        let num_pruned_rounds = NUM_PRUNED_ROUNDS
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        NUM_PRUNED_ROUNDS.save(deps.storage, &(num_pruned_rounds + 1))?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "prune_round"),
+        ("round", &round.to_string()),
+        ("num_pruned", &num_pruned.to_string()),
+        ("finished", &finished.to_string()),
+    ]))
+}
+
+// the first call fails the round and sends back its distribution and slot bonuses, each call
+// then returns the full deposit of the next limit bids
+pub fn execute_trigger_refunds(
+    mut deps: DepsMut,
+    env: Env,
+    round: u64,
+    limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = assert_round_state(
+        deps.storage,
+        &env,
+        round,
+        &[RoundState::Ended, RoundState::Failed],
+    )?;
+    // the bids are refunded from the escrow, not from the other rounds
+    if YIELD_POSITION
+        .may_load(deps.storage)?
+        .map(|position| position.round)
+        == Some(round)
+    {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Idle funds of round {} must be withdrawn from the yield venue before refunds",
+            round
+        ))));
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if state == RoundState::Ended {
+        let refund_deadline = REFUND_DEADLINE
+            .may_load(deps.storage)?
+            .unwrap_or(DEFAULT_REFUND_DEADLINE);
+        let refund_after = BIDDING_INFO.load(deps.storage, round)?.end_time + refund_deadline;
+        if env.block.time.seconds() < refund_after {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Round {} can be refunded from {}",
+                round, refund_after
+            ))));
+        }
+        save_round_state(deps.storage, &env, round, RoundState::Failed)?;
+        // a chunked finalize still in progress is abandoned
+        FINALIZE_PROGRESS.remove(deps.storage, round);
+
+        msgs.extend(recall_lent_distribution(
+            deps.branch(),
+            &env,
+            &config,
+            round,
+        )?);
+        let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+        if !distribution_info.total_distribution.is_zero() {
+            msgs.push(into_cosmos_msg(
+                &config.distribution_token,
+                config.owner.to_string(),
+                distribution_info.total_distribution,
+            )?);
+        }
+        for bonus in SLOT_BONUSES
+            .may_load(deps.storage, round)?
+            .unwrap_or_default()
+        {
+            msgs.push(into_cosmos_msg(
+                &bonus.asset.info,
+                bonus.sponsor.to_string(),
+                bonus.asset.amount,
+            )?);
+        }
+        SLOT_BONUSES.remove(deps.storage, round);
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_REFUND_LIMIT).min(MAX_REFUND_LIMIT);
+    let cursor = REFUND_CURSOR.may_load(deps.storage, round)?;
+    let bids_idx = read_bids_by_round(deps.storage, round, cursor, Some(limit), None)?;
+    // LP and staking derivative bids get their tokens back, or what they were redeemed for when
+    // a chunked finalize already withdrew them
+    let lp_withdraw_rate = LP_WITHDRAW_RATES.may_load(deps.storage, round)?;
+    let mut share_token: Option<AssetInfo> = None;
+    let mut num_refunded = 0u64;
+    for idx in bids_idx.iter() {
+        let mut bid = BID.load(deps.storage, *idx)?;
+        if bid.is_distributed {
+            continue;
+        }
+        if !bid.lp_amount.is_zero() {
+            match &lp_withdraw_rate {
+                Some(rate) => {
+                    bid.amount = checked_mul_decimal(bid.lp_amount, rate.underlying_per_share)?;
+                    let other_amount = checked_mul_decimal(bid.lp_amount, rate.other_per_share)?;
+                    for (asset_info, amount) in [
+                        (&config.underlying_token, bid.amount),
+                        (&rate.other_asset, other_amount),
+                    ] {
+                        if !amount.is_zero() {
+                            msgs.push(into_cosmos_msg(asset_info, bid.bidder.to_string(), amount)?);
+                        }
+                    }
+                    settle_lp_shares(deps.storage, round, bid.lp_amount)?;
+                }
+                None => {
+                    let token = match share_token.take() {
+                        Some(token) => token,
+                        None => query_share_token(
+                            &deps.querier,
+                            &read_round_config(deps.storage, round)?,
+                        )?,
+                    };
+                    msgs.push(into_cosmos_msg(
+                        &token,
+                        bid.bidder.to_string(),
+                        bid.lp_amount,
+                    )?);
+                    share_token = Some(token);
+                }
+            }
+        } else if !bid.amount.is_zero() {
+            msgs.push(into_cosmos_msg(
+                &config.underlying_token,
+                bid.bidder.to_string(),
+                bid.amount,
+            )?);
+        }
+        // a failed round ends the locks of its bids, their deposit is refunded with the bid
+        if let Some(lock_id) = LOCKED_BIDS.may_load(deps.storage, *idx)? {
+            LOCKED_BIDS.remove(deps.storage, *idx);
+            LOCKS.remove(deps.storage, lock_id);
+        }
+        bid.residue_bid = bid.amount;
+        bid.is_distributed = true;
+        BID.save(deps.storage, *idx, &bid)?;
+        num_refunded += 1;
+    }
+    if let Some(last_idx) = bids_idx.last() {
+        REFUND_CURSOR.save(deps.storage, round, last_idx)?;
+    }
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        ("action", "trigger_refunds"),
+        ("round", &round.to_string()),
+        ("num_refunded", &num_refunded.to_string()),
+        // a partial chunk means every bid has been refunded
+        ("finished", &((bids_idx.len() as u64) < limit).to_string()),
+    ]))
+}
+
+// replays the payouts of a distribution batch without writing anything, returns the breached
+// invariant if the batch would overpay the round
+#[allow(clippy::too_many_arguments)]
+fn find_payout_breach(
+    storage: &dyn Storage,
+    round: u64,
+    bids_idx: &[u64],
+    index_snapshot: &[Decimal],
+    receiver_per_token: &[Decimal],
+    mut payout_totals: PayoutTotals,
+    max_received: Uint128,
+    total_unmatched: Uint128,
+) -> Result<Option<&'static str>, ContractError> {
+    for idx in bids_idx {
+        let mut bid = BID.load(storage, *idx)?;
+        if bid.is_distributed {
+            continue;
+        }
+        if !bid.lp_amount.is_zero() {
+            let rate = LP_WITHDRAW_RATES.load(storage, round)?;
+            bid.amount = checked_mul_decimal(bid.lp_amount, rate.underlying_per_share)?;
+        }
+
+        let slot = bid.premium_slot as usize;
+        if index_snapshot[slot] > Decimal::one() {
+            return Ok(Some("negative_residue"));
+        }
+        let (amount_received, residue_bid) =
+            split_bid_amount(bid.amount, index_snapshot[slot], receiver_per_token[slot])?;
+        if residue_bid > bid.amount {
+            return Ok(Some("negative_residue"));
+        }
 
-    if !distribution_info.is_released {
-        return Err(ContractError::BidNotEnded {});
+        payout_totals.total_received = payout_totals.total_received.checked_add(amount_received)?;
+        if !bid.residue_claimed {
+            payout_totals.total_residue = payout_totals.total_residue.checked_add(residue_bid)?;
+        }
+        if payout_totals.total_received > max_received {
+            return Ok(Some("payout_exceeds_distribution"));
+        }
+        if payout_totals.total_residue > total_unmatched {
+            return Ok(Some("residue_exceeds_unmatched"));
+        }
+    }
+    Ok(None)
+}
+
+// cursor a Distribute batch moves to, saved once the batch passes the payout invariants so a
+// tripped circuit breaker leaves the batch to the next Distribute
+enum CursorUpdate {
+    ByIdx(u64),
+    BySlot((u16, u64)),
+}
+
+// next_cursor is returned in the data until the round is completed
+fn process_distribute(
+    deps: DepsMut,
+    env: Env,
+    round: u64,
+    bids_idx: Vec<u64>,
+    next_cursor: Option<u64>,
+    cursor_update: Option<CursorUpdate>,
+) -> Result<Response, ContractError> {
+    if read_pause_flags(deps.storage)?.distribute {
+        return Err(ContractError::Paused {
+            operation: "distribute".to_string(),
+        });
     }
+    let config = CONFIG.load(deps.storage)?;
+    let mut distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    let state = assert_round_state(
+        deps.storage,
+        &env,
+        round,
+        &[
+            RoundState::Finalized,
+            RoundState::Distributing,
+            RoundState::Completed,
+        ],
+    )?;
+    assert_tranches_closed(deps.storage, round)?;
+
+    // payouts can never exceed what the matching allocated to the round
+    let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let total_unmatched = bidding_info
+        .total_bid_amount
+        .checked_sub(bidding_info.total_bid_matched)?;
+    let max_received = config.max_received(&distribution_info, &bidding_info)?;
+    let mut payout_totals = PAYOUT_TOTALS
+        .may_load(deps.storage, round)?
+        .unwrap_or_default();
 
     let mut index_snapshot = vec![Decimal::zero(); config.max_slot as usize + 1];
     let mut receiver_per_token = vec![Decimal::zero(); config.max_slot as usize + 1];
@@ -387,46 +3316,384 @@ pub fn execute_distribute(
         }
     }
 
-    // load all bid in round
-    let bids_idx = read_bids_by_round(deps.storage, round, start_after, limit, None)?;
+    // a breached invariant pauses the contract instead of reverting, so the state is kept for
+    // the investigation and nothing is paid until the owner unpauses
+    if let Some(reason) = find_payout_breach(
+        deps.storage,
+        round,
+        &bids_idx,
+        &index_snapshot,
+        &receiver_per_token,
+        payout_totals.clone(),
+        max_received,
+        total_unmatched,
+    )? {
+        PAUSE_FLAGS.save(
+            deps.storage,
+            &PauseFlags {
+                submit_bid: true,
+                create_round: true,
+                finalize: true,
+                distribute: true,
+            },
+        )?;
+        return Ok(Response::new()
+            .add_event(Event::new("invariant_breach").add_attributes(vec![
+                ("round", round.to_string()),
+                ("reason", reason.to_string()),
+            ]))
+            .add_attributes(vec![
+                ("action", "circuit_breaker".to_string()),
+                ("round", round.to_string()),
+            ]));
+    }
+    match cursor_update {
+        Some(CursorUpdate::ByIdx(last_idx)) => {
+            DISTRIBUTE_CURSOR.save(deps.storage, round, &last_idx)?
+        }
+        Some(CursorUpdate::BySlot(last)) => {
+            SLOT_DISTRIBUTE_CURSOR.save(deps.storage, round, &last)?
+        }
+        None => {}
+    }
+
+    // the first distribution after the dispute period settles the round
     let mut msgs: Vec<CosmosMsg> = vec![];
+    if let Some(settle_after) = SETTLE_AFTER.may_load(deps.storage, round)? {
+        if DISPUTES.has(deps.storage, round) {
+            return Err(ContractError::RoundDisputed { round });
+        }
+        if env.block.time.seconds() < settle_after {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Round {} can be disputed until {}",
+                round, settle_after
+            ))));
+        }
+        msgs = settle_round(deps.storage, &config, round, env.block.time.seconds())?;
+    }
 
+    let round_config = read_round_config(deps.storage, round)?;
+    let bonuses = SLOT_BONUSES
+        .may_load(deps.storage, round)?
+        .unwrap_or_default();
+    let vesting_contract = if round_config.vested_rewards {
+        config.vesting_contract.clone()
+    } else {
+        None
+    };
+    // rewards of reinvesting bids are bid in the next open round, paid as usual when none is open
+    let reinvest_round = reinvest_round(deps.storage, &env, round)?;
+    let mut reinvest_shares: Vec<ReinvestShare> = vec![];
+
+    let num_bids = count_number_bids_in_round(deps.storage, round)?;
+    let was_completed = distribution_info.num_bids_distributed >= num_bids;
+    let mut events: Vec<Event> = vec![];
+    let mut notify_msgs: Vec<SubMsg> = vec![];
+    let mut total_fee = Uint128::zero();
+    let mut data = DistributeResponse {
+        first_idx: bids_idx.first().copied(),
+        last_idx: bids_idx.last().copied(),
+        num_rewarded: 0,
+        num_refunded: 0,
+        num_skipped: 0,
+        next_cursor: None,
+    };
     for idx in bids_idx {
         // read bid
         let mut bid = BID.load(deps.storage, idx)?;
         if bid.is_distributed {
+            data.num_skipped += 1;
             continue;
         }
 
+        // LP bids bid their underlying leg, the other leg goes back to the bidder
+        if !bid.lp_amount.is_zero() {
+            let rate = LP_WITHDRAW_RATES.load(deps.storage, round)?;
+            bid.amount = checked_mul_decimal(bid.lp_amount, rate.underlying_per_share)?;
+            let other_amount = checked_mul_decimal(bid.lp_amount, rate.other_per_share)?;
+            if !other_amount.is_zero() {
+                msgs.push(into_cosmos_msg(
+                    &rate.other_asset,
+                    bid.bidder.to_string(),
+                    other_amount,
+                )?);
+            }
+            settle_lp_shares(deps.storage, round, bid.lp_amount)?;
+        }
+
         // calc allocated amount and remaining amount of bid
-        let amount_received = bid.amount * receiver_per_token[bid.premium_slot as usize];
-        let residue_bid = bid.amount * (Decimal::one() - index_snapshot[bid.premium_slot as usize]);
+        let (amount_received, residue_bid) = split_bid_amount(
+            bid.amount,
+            index_snapshot[bid.premium_slot as usize],
+            receiver_per_token[bid.premium_slot as usize],
+        )?;
 
-        if amount_received > Uint128::zero() {
-            msgs.push(into_cosmos_msg(
-                &config.distribution_token,
-                bid.bidder.to_string(),
+        // the treasury takes its share of the premium, the bidder is paid the rest
+        let matched_amount = bid.amount.checked_sub(residue_bid)?;
+        let fee = performance_fee(
+            amount_received,
+            matched_amount,
+            distribution_info.exchange_rate,
+            round_config.performance_fee,
+        )?;
+        let net_received = amount_received.checked_sub(fee)?;
+        total_fee = total_fee.checked_add(fee)?;
+
+        // residues claimed after finalize are already paid and counted
+        let unclaimed_residue = if bid.residue_claimed {
+            Uint128::zero()
+        } else {
+            residue_bid
+        };
+
+        payout_totals.total_received = payout_totals.total_received.checked_add(amount_received)?;
+        payout_totals.total_residue = payout_totals.total_residue.checked_add(unclaimed_residue)?;
+        if payout_totals.total_received > max_received {
+            return Err(ContractError::PayoutExceedsDistribution { round });
+        }
+        if payout_totals.total_residue > total_unmatched {
+            return Err(ContractError::ResidueExceedsUnmatched { round });
+        }
+
+        // locked bids are paid a boosted premium and carry their residue to the next round
+        let mut refunded_residue = unclaimed_residue;
+        if let Some(lock_id) = LOCKED_BIDS.may_load(deps.storage, idx)? {
+            LOCKED_BIDS.remove(deps.storage, idx);
+            let lock = LOCKS.load(deps.storage, lock_id)?;
+            let premium = premium_amount(
                 amount_received,
-            )?);
+                matched_amount,
+                distribution_info.exchange_rate,
+            )?;
+            let boost = take_lock_boost(deps.storage, &lock, premium)?;
+            if !boost.is_zero() {
+                msgs.push(into_cosmos_msg(
+                    &config.distribution_token,
+                    bid.bidder.to_string(),
+                    boost,
+                )?);
+            }
+            refunded_residue =
+                carry_locked_residue(deps.storage, lock_id, lock, unclaimed_residue)?;
+            events.push(Event::new("locked_bid").add_attributes(vec![
+                ("bid_idx", idx.to_string()),
+                ("lock_id", lock_id.to_string()),
+                ("boost", boost.to_string()),
+                (
+                    "carried_residue",
+                    unclaimed_residue.checked_sub(refunded_residue)?.to_string(),
+                ),
+            ]));
         }
 
-        if residue_bid > Uint128::zero() {
-            msgs.push(into_cosmos_msg(
-                &config.underlying_token,
-                bid.bidder.to_string(),
-                residue_bid,
-            )?);
+        // credited payouts are withdrawn later by the bidder, vested rewards are still streamed
+        let mut credited = Payout::default();
+        let staking_contract = config
+            .staking_contract
+            .as_ref()
+            .filter(|_| bid.auto_compound);
+
+        if net_received > Uint128::zero() {
+            match (&vesting_contract, staking_contract) {
+                // stream the payout to the bidder through the vesting contract
+                (Some(vesting_contract), _) => msgs.push(into_send_msg(
+                    &config.distribution_token,
+                    vesting_contract.to_string(),
+                    net_received,
+                    to_json_binary(&VestingCw20HookMsg::Vest {
+                        recipient: bid.bidder.to_string(),
+                    })?,
+                )?),
+                // swap the payout with the other reinvested rewards after the loop
+                (None, _) if reinvest_round.is_some() && bid.reinvest => {
+                    reinvest_shares.push(ReinvestShare {
+                        bid_idx: idx,
+                        bidder: bid.bidder.clone(),
+                        premium_slot: bid.premium_slot,
+                        reward: net_received,
+                    })
+                }
+                // bond the payout in the staking contract on behalf of the bidder
+                (None, Some(staking_contract)) => msgs.push(into_send_msg(
+                    &config.distribution_token,
+                    staking_contract.to_string(),
+                    net_received,
+                    to_json_binary(&StakingCw20HookMsg::Bond {
+                        staker: bid.bidder.to_string(),
+                    })?,
+                )?),
+                (None, None) if round_config.credit_payouts => {
+                    credited.distribution_amount = net_received
+                }
+                (None, None) => msgs.push(into_cosmos_msg(
+                    &config.distribution_token,
+                    bid.bidder.to_string(),
+                    net_received,
+                )?),
+            }
+        }
+
+        if refunded_residue > Uint128::zero() {
+            if let Some(residue_staking) = config
+                .residue_staking
+                .as_ref()
+                .filter(|_| bid.stake_residue)
+            {
+                // bond the residue in the staking contract on behalf of the bidder
+                msgs.push(into_send_msg(
+                    &config.underlying_token,
+                    residue_staking.to_string(),
+                    refunded_residue,
+                    to_json_binary(&StakingCw20HookMsg::Bond {
+                        staker: bid.bidder.to_string(),
+                    })?,
+                )?);
+            } else if round_config.credit_payouts {
+                credited.underlying_amount = refunded_residue;
+            } else {
+                msgs.push(into_cosmos_msg(
+                    &config.underlying_token,
+                    bid.bidder.to_string(),
+                    refunded_residue,
+                )?);
+            }
+        }
+
+        // share of the slot bonuses, pro-rata to the matched amount of the bid
+        for bonus in bonuses.iter() {
+            if !bonus.is_eligible(bid.premium_slot) || bonus.eligible_matched.is_zero() {
+                continue;
+            }
+            let bonus_amount =
+                matched_amount.multiply_ratio(bonus.asset.amount, bonus.eligible_matched);
+            if !bonus_amount.is_zero() {
+                msgs.push(into_cosmos_msg(
+                    &bonus.asset.info,
+                    bid.bidder.to_string(),
+                    bonus_amount,
+                )?);
+            }
+        }
+
+        if credited != Payout::default() {
+            PAYOUTS.update(
+                deps.storage,
+                &bid.bidder,
+                |payout| -> Result<_, ContractError> {
+                    let mut payout = payout.unwrap_or_default();
+                    payout.underlying_amount = payout
+                        .underlying_amount
+                        .checked_add(credited.underlying_amount)?;
+                    payout.distribution_amount = payout
+                        .distribution_amount
+                        .checked_add(credited.distribution_amount)?;
+                    Ok(payout)
+                },
+            )?;
+        }
+
+        if let Some(notifier) = BID_NOTIFIERS.may_load(deps.storage, idx)? {
+            BID_NOTIFIERS.remove(deps.storage, idx);
+            notify_msgs.push(
+                SubMsg::reply_on_error(
+                    WasmMsg::Execute {
+                        contract_addr: notifier.to_string(),
+                        msg: to_json_binary(&NotifierExecuteMsg::BidSettled {
+                            round,
+                            idx,
+                            bidder: bid.bidder.to_string(),
+                            amount_received: net_received,
+                            residue: residue_bid,
+                        })?,
+                        funds: vec![],
+                    },
+                    NOTIFY_REPLY_ID,
+                )
+                .with_gas_limit(NOTIFY_GAS_LIMIT),
+            );
+        }
+
+        if !matched_amount.is_zero() {
+            USER_TOTAL_MATCHED.update(
+                deps.storage,
+                &bid.bidder,
+                |total| -> StdResult<Uint128> {
+                    Ok(total.unwrap_or_default().checked_add(matched_amount)?)
+                },
+            )?;
         }
 
         bid.amount_received = amount_received;
         bid.residue_bid = residue_bid;
+        bid.performance_fee = fee;
         bid.is_distributed = true;
         distribution_info.num_bids_distributed += 1;
+        if amount_received.is_zero() {
+            data.num_refunded += 1;
+        } else {
+            data.num_rewarded += 1;
+        }
+
+        // one event per bid so payouts can be attributed without decoding the messages
+        events.push(Event::new("distribute_bid").add_attributes(vec![
+            ("round", round.to_string()),
+            ("bid_idx", idx.to_string()),
+            ("bidder", bid.bidder.to_string()),
+            ("reward", amount_received.to_string()),
+            ("residue", residue_bid.to_string()),
+            ("performance_fee", fee.to_string()),
+        ]));
 
         BID.save(deps.storage, idx, &bid)?;
     }
 
+    // the round is distributing from its first distributed bid until the last one
+    let next_state = if distribution_info.num_bids_distributed >= num_bids {
+        RoundState::Completed
+    } else if distribution_info.num_bids_distributed > 0 {
+        RoundState::Distributing
+    } else {
+        state
+    };
+    if next_state != state {
+        save_round_state(deps.storage, &env, round, next_state)?;
+    }
     DISTRIBUTION_INFO.save(deps.storage, round, &distribution_info)?;
+    PAYOUT_TOTALS.save(deps.storage, round, &payout_totals)?;
+    if !total_fee.is_zero() {
+        msgs.push(into_cosmos_msg(
+            &config.distribution_token,
+            config.treasury.to_string(),
+            total_fee,
+        )?);
+    }
+
+    if distribution_info.num_bids_distributed < num_bids {
+        data.next_cursor = next_cursor;
+    }
+
+    // the swap runs before the other messages move any underlying_token
+    let reinvest_msgs = match reinvest_round {
+        Some(reinvest_round) if !reinvest_shares.is_empty() => vec![prepare_reinvest(
+            deps.storage,
+            &deps.querier,
+            &env,
+            &config,
+            reinvest_round,
+            reinvest_shares,
+        )?],
+        _ => vec![],
+    };
+
+    // notify the hooks once, when the last bid of the round is distributed
+    let hook_msgs = if !was_completed && distribution_info.num_bids_distributed >= num_bids {
+        prepare_hook_msgs(
+            deps.storage,
+            BidPoolHookMsg::DistributionCompleted { round },
+        )?
+    } else {
+        vec![]
+    };
 
     Ok(Response::new()
         .add_attributes(vec![
@@ -436,42 +3703,469 @@ pub fn execute_distribute(
                 &distribution_info.num_bids_distributed.to_string(),
             ),
         ])
-        .add_messages(msgs))
+        .add_events(events)
+        .add_submessages(reinvest_msgs)
+        .add_messages(msgs)
+        .add_submessages(notify_msgs)
+        .add_submessages(hook_msgs)
+        .set_data(to_json_binary(&data)?))
 }
 
-pub fn process_calc_distribution_amount(
-    bid_pools: &mut Vec<BidPool>,
-    distribution_amount: &mut Uint128,
-    exchange_rate: Decimal,
-) -> StdResult<Uint128> {
-    let mut total_matched = Uint128::zero();
+// only the bidder, the notifier is removed once it has been sent the settlement of the bid
+pub fn execute_set_bid_notifier(
+    deps: DepsMut,
+    info: MessageInfo,
+    idx: u64,
+    notifier: Option<String>,
+) -> Result<Response, ContractError> {
+    let bid = BID.load(deps.storage, idx)?;
+    if bid.bidder != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if bid.is_distributed {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Bid has already been distributed",
+        )));
+    }
 
-    for bid_pool in bid_pools {
-        if bid_pool.total_bid_amount.is_zero() {
-            continue;
+    let Some(notifier) = notifier else {
+        BID_NOTIFIERS.remove(deps.storage, idx);
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "remove_bid_notifier"),
+            ("bid_idx", &idx.to_string()),
+        ]));
+    };
+    let notifier = deps.api.addr_validate(&notifier)?;
+    BID_NOTIFIERS.save(deps.storage, idx, &notifier)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_bid_notifier"),
+        ("bid_idx", &idx.to_string()),
+        ("notifier", notifier.as_str()),
+    ]))
+}
+
+// the residue is known once the round is matched, it can be claimed before the distribution
+pub fn execute_claim_residue(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    idx: u64,
+) -> Result<Response, ContractError> {
+    if read_pause_flags(deps.storage)?.distribute {
+        return Err(ContractError::Paused {
+            operation: "distribute".to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let mut bid = BID.load(deps.storage, idx)?;
+    if bid.bidder != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if bid.is_distributed || bid.residue_claimed {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Residue of bid {} has already been paid",
+            idx
+        ))));
+    }
+    // the underlying amount of LP bids is only known when they are distributed
+    if !bid.lp_amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "LP bids are refunded by distribute",
+        )));
+    }
+    // the residue of a lock with rounds left enters the next round
+    if let Some(lock_id) = LOCKED_BIDS.may_load(deps.storage, idx)? {
+        if LOCKS.load(deps.storage, lock_id)?.remaining_rounds > 0 {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Bid {} is locked until the last round of lock {}",
+                idx, lock_id
+            ))));
+        }
+    }
+
+    let round = bid.round;
+    assert_round_state(
+        deps.storage,
+        &env,
+        round,
+        &[
+            RoundState::Finalized,
+            RoundState::Distributing,
+            RoundState::Completed,
+        ],
+    )?;
+    assert_tranches_closed(deps.storage, round)?;
+    // a dispute can still change the matching
+    if DISPUTES.has(deps.storage, round) {
+        return Err(ContractError::RoundDisputed { round });
+    }
+    if let Some(settle_after) = SETTLE_AFTER.may_load(deps.storage, round)? {
+        if env.block.time.seconds() < settle_after {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Round {} can be disputed until {}",
+                round, settle_after
+            ))));
         }
+    }
 
-        let desired_amount =
-            bid_pool.total_bid_amount * exchange_rate * (Decimal::one() + bid_pool.premium_rate);
+    let bid_pool = BID_POOL.load(deps.storage, (round, bid.premium_slot))?;
+    let (_, residue_bid) = split_bid_amount(
+        bid.amount,
+        bid_pool.index_snapshot,
+        bid_pool.received_per_token,
+    )?;
+    if residue_bid.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Bid {} has no residue",
+            idx
+        ))));
+    }
 
-        let actual_amount = if desired_amount <= *distribution_amount {
-            desired_amount
-        } else {
-            *distribution_amount
-        };
+    let total_unmatched = {
+        let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+        bidding_info
+            .total_bid_amount
+            .checked_sub(bidding_info.total_bid_matched)?
+    };
+    let mut payout_totals = PAYOUT_TOTALS
+        .may_load(deps.storage, round)?
+        .unwrap_or_default();
+    payout_totals.total_residue = payout_totals.total_residue.checked_add(residue_bid)?;
+    if payout_totals.total_residue > total_unmatched {
+        return Err(ContractError::ResidueExceedsUnmatched { round });
+    }
+    PAYOUT_TOTALS.save(deps.storage, round, &payout_totals)?;
 
-        let index_snapshot = Decimal::from_ratio(actual_amount, desired_amount);
-        let received_per_token = Decimal::from_ratio(actual_amount, bid_pool.total_bid_amount);
+    bid.residue_bid = residue_bid;
+    bid.residue_claimed = true;
+    BID.save(deps.storage, idx, &bid)?;
 
-        total_matched += index_snapshot * bid_pool.total_bid_amount;
-        *distribution_amount -= actual_amount;
-        bid_pool.index_snapshot = index_snapshot;
-        bid_pool.received_per_token = received_per_token;
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "claim_residue"),
+            ("round", &round.to_string()),
+            ("bid_idx", &idx.to_string()),
+            ("residue", &residue_bid.to_string()),
+        ])
+        .add_message(into_cosmos_msg(
+            &config.underlying_token,
+            bid.bidder.to_string(),
+            residue_bid,
+        )?))
+}
 
-        if distribution_amount.is_zero() {
-            break;
+pub fn execute_withdraw_payout(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let payout = PAYOUTS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if payout.underlying_amount.is_zero() && payout.distribution_amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No payout to withdraw",
+        )));
+    }
+    PAYOUTS.remove(deps.storage, &info.sender);
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !payout.distribution_amount.is_zero() {
+        msgs.push(into_cosmos_msg(
+            &config.distribution_token,
+            info.sender.to_string(),
+            payout.distribution_amount,
+        )?);
+    }
+    if !payout.underlying_amount.is_zero() {
+        msgs.push(into_cosmos_msg(
+            &config.underlying_token,
+            info.sender.to_string(),
+            payout.underlying_amount,
+        )?);
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "withdraw_payout"),
+            ("bidder", info.sender.as_str()),
+            (
+                "distribution_amount",
+                &payout.distribution_amount.to_string(),
+            ),
+            ("underlying_amount", &payout.underlying_amount.to_string()),
+        ])
+        .add_messages(msgs))
+}
+
+// anyone can attach a bonus to some slots of a round until the round ends
+pub fn execute_add_slot_bonus(
+    deps: DepsMut,
+    env: Env,
+    sponsor: Addr,
+    round: u64,
+    min_slot: u16,
+    max_slot: u16,
+    asset: Asset,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if min_slot < 1 || min_slot > max_slot || max_slot > config.max_slot {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "bonus slots must be within the range 1 and {}",
+            config.max_slot
+        ))));
+    }
+    if asset.amount.is_zero() {
+        return Err(ContractError::InvalidFunds {});
+    }
+
+    assert_round_state(
+        deps.storage,
+        &env,
+        round,
+        &[RoundState::Created, RoundState::Open],
+    )?;
+
+    let mut bonuses = SLOT_BONUSES
+        .may_load(deps.storage, round)?
+        .unwrap_or_default();
+    // every bonus adds a transfer per distributed bid
+    if bonuses.len() >= MAX_SLOT_BONUSES_PER_ROUND {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Too many bonuses in this round",
+        )));
+    }
+    bonuses.push(SlotBonus {
+        sponsor: sponsor.clone(),
+        asset: asset.clone(),
+        min_slot,
+        max_slot,
+        eligible_matched: Uint128::zero(),
+    });
+    SLOT_BONUSES.save(deps.storage, round, &bonuses)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_slot_bonus"),
+        ("round", &round.to_string()),
+        ("sponsor", sponsor.as_str()),
+        ("amount", &asset.amount.to_string()),
+        ("min_slot", &min_slot.to_string()),
+        ("max_slot", &max_slot.to_string()),
+    ]))
+}
+
+// only the bidder can update the options, until the bid has been distributed
+pub fn execute_update_bid_options(
+    deps: DepsMut,
+    info: MessageInfo,
+    idx: u64,
+    auto_compound: Option<bool>,
+    stake_residue: Option<bool>,
+    reinvest: Option<bool>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut bid = BID.load(deps.storage, idx)?;
+    if bid.bidder != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if bid.is_distributed {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Bid has already been distributed",
+        )));
+    }
+    if let Some(auto_compound) = auto_compound {
+        if auto_compound && config.staking_contract.is_none() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Staking contract is not configured",
+            )));
+        }
+        bid.auto_compound = auto_compound;
+    }
+    if let Some(stake_residue) = stake_residue {
+        if stake_residue && config.residue_staking.is_none() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Residue staking contract is not configured",
+            )));
+        }
+        bid.stake_residue = stake_residue;
+    }
+    if let Some(reinvest) = reinvest {
+        if reinvest && !REINVEST_CONFIG.exists(deps.storage) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Reinvest pair is not configured",
+            )));
         }
+        bid.reinvest = reinvest;
+    }
+    // the rewards are either bonded or swapped, not both
+    if bid.auto_compound && bid.reinvest {
+        return Err(ContractError::Std(StdError::generic_err(
+            "A bid can't both auto-compound and reinvest its rewards",
+        )));
+    }
+    BID.save(deps.storage, idx, &bid)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_bid_options"),
+        ("bid_idx", &idx.to_string()),
+        ("auto_compound", &bid.auto_compound.to_string()),
+        ("stake_residue", &bid.stake_residue.to_string()),
+        ("reinvest", &bid.reinvest.to_string()),
+    ]))
+}
+
+pub fn execute_split_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    idx: u64,
+    amount: Uint128,
+    new_slot: u16,
+) -> Result<Response, ContractError> {
+    if read_pause_flags(deps.storage)?.submit_bid {
+        return Err(ContractError::Paused {
+            operation: "submit_bid".to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let mut bid = BID.load(deps.storage, idx)?;
+    if bid.bidder != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    // the underlying amount of LP bids is only known once the liquidity is withdrawn
+    if !bid.lp_amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "LP bids cannot be split",
+        )));
+    }
+    if LOCKED_BIDS.has(deps.storage, idx) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Locked bids cannot be split",
+        )));
+    }
+
+    let bidding_info = BIDDING_INFO.load(deps.storage, bid.round)?;
+    assert_round_state(deps.storage, &env, bid.round, &[RoundState::Open])?;
+
+    if new_slot < 1 || new_slot > config.max_slot {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "premium slot must be within the range 1 and {}, reaching {}",
+            config.max_slot, new_slot
+        ))));
+    }
+
+    // both parts must still meet the minimum deposit
+    let remaining = bid.amount.checked_sub(amount)?;
+    if config.min_deposit_amount > amount || config.min_deposit_amount > remaining {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Both parts of a split bid must be at least {}",
+            config.min_deposit_amount
+        ))));
+    }
+
+    // move the split amount from the pool of the bid to the new slot
+    let round = bid.round;
+    let now = env.block.time.seconds();
+    let mut bid_pool = BID_POOL.load(deps.storage, (round, bid.premium_slot))?;
+    bid_pool.total_bid_amount = bid_pool.total_bid_amount.checked_sub(amount)?;
+    BID_POOL.save(deps.storage, (round, bid.premium_slot), &bid_pool)?;
+    checkpoint_round_totals(deps.storage, now, &bidding_info, &bid_pool)?;
+
+    let mut new_bid_pool = read_or_create_bid_pool(deps.storage, round, new_slot)?;
+    new_bid_pool.total_bid_amount = new_bid_pool.total_bid_amount.checked_add(amount)?;
+    BID_POOL.save(deps.storage, (round, new_slot), &new_bid_pool)?;
+    checkpoint_round_totals(deps.storage, now, &bidding_info, &new_bid_pool)?;
+
+    bid.amount = remaining;
+    bid.residue_bid = remaining;
+    BID.save(deps.storage, idx, &bid)?;
+
+    // the new bid keeps the options of the bid it was split from
+    let new_bid_idx = pop_bid_idx(deps.storage)?;
+    let new_bid = Bid {
+        idx: new_bid_idx,
+        timestamp: now,
+        height: env.block.height,
+        premium_slot: new_slot,
+        amount,
+        residue_bid: amount,
+        ..bid
+    };
+    store_bid(deps.storage, new_bid_idx, &new_bid)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "split_bid"),
+        ("round", &round.to_string()),
+        ("bid_idx", &idx.to_string()),
+        ("new_bid_idx", &new_bid_idx.to_string()),
+        ("premium_slot", &new_slot.to_string()),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+// the bid is emptied rather than removed, its amount is credited back to the deposit of the bidder.
+// Only the satellite driving the bids of the bidder can cancel them, see execute_process_bid_packet
+pub fn execute_cancel_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    idx: u64,
+) -> Result<Response, ContractError> {
+    let mut bid = BID.load(deps.storage, idx)?;
+    if bid.bidder != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !bid.lp_amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "LP bids cannot be cancelled",
+        )));
     }
+    if LOCKED_BIDS.has(deps.storage, idx) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Locked bids cannot be cancelled",
+        )));
+    }
+    if bid.amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Bid {} is already cancelled",
+            idx
+        ))));
+    }
+
+    let round = bid.round;
+    let mut bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    assert_round_state(deps.storage, &env, round, &[RoundState::Open])?;
 
-    Ok(total_matched)
+    let amount = bid.amount;
+    let mut bid_pool = BID_POOL.load(deps.storage, (round, bid.premium_slot))?;
+    bid_pool.total_bid_amount = bid_pool.total_bid_amount.checked_sub(amount)?;
+    bidding_info.total_bid_amount = bidding_info.total_bid_amount.checked_sub(amount)?;
+    BID_POOL.save(deps.storage, (round, bid.premium_slot), &bid_pool)?;
+    BIDDING_INFO.save(deps.storage, round, &bidding_info)?;
+    checkpoint_round_totals(
+        deps.storage,
+        env.block.time.seconds(),
+        &bidding_info,
+        &bid_pool,
+    )?;
+
+    bid.amount = Uint128::zero();
+    bid.residue_bid = Uint128::zero();
+    BID.save(deps.storage, idx, &bid)?;
+
+    let balance = DEPOSITS
+        .may_load(deps.storage, &bid.bidder)?
+        .unwrap_or_default()
+        .checked_add(amount)?;
+    DEPOSITS.save(deps.storage, &bid.bidder, &balance)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "cancel_bid"),
+        ("round", &round.to_string()),
+        ("bidder", bid.bidder.as_str()),
+        ("bid_idx", &idx.to_string()),
+        ("amount", &amount.to_string()),
+        ("balance", &balance.to_string()),
+    ]))
 }
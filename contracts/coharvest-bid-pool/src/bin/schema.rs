@@ -1,6 +1,6 @@
 use cosmwasm_schema::write_api;
 
-use coharvest_bid_pool::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use coharvest_bid_pool::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
@@ -8,5 +8,6 @@ fn main() {
         execute: ExecuteMsg,
         query: QueryMsg,
         migrate: MigrateMsg,
+        sudo: SudoMsg,
     }
 }
@@ -1,61 +1,200 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Uint128,
+    from_json, to_json_binary, Attribute, Binary, Decimal, Deps, DepsMut, Env, Event, MessageInfo,
+    Order, Reply, ReplyOn, Response, StdError, StdResult, Uint128,
 };
+use cw2::{set_contract_version, CONTRACT};
 use cw20::Cw20ReceiveMsg;
-use cw_utils::one_coin;
+use cw_storage_plus::Bound;
+use cw_utils::{nonpayable, one_coin};
 use oraiswap::asset::{Asset, AssetInfo};
 
 use crate::{
     bid::{
-        execute_create_new_round, execute_create_new_round_from_treasury, execute_distribute,
-        execute_finalize_bidding_round_result, execute_submit_bid, execute_update_round,
-        process_calc_distribution_amount,
+        execute_add_slot_bonus, execute_add_tranche, execute_cancel_ica_round,
+        execute_cancel_round, execute_claim_residue, execute_close_tranches,
+        execute_continue_finalize, execute_create_new_round,
+        execute_create_new_round_from_treasury, execute_create_round_from_template,
+        execute_deposit, execute_dispute_round, execute_distribute, execute_distribute_bids,
+        execute_finalize_at_oracle_rate, execute_finalize_bidding_round_result,
+        execute_fund_ica_round, execute_prune_round, execute_recompute_round_totals,
+        execute_register_bidder_pubkey, execute_request_ica_round, execute_resolve_dispute,
+        execute_save_round_template, execute_set_bid_notifier, execute_set_oracle_rate_band,
+        execute_split_bid, execute_start_finalize, execute_submit_bid, execute_submit_bid_from,
+        execute_submit_bid_from_balance, execute_submit_lp_bid, execute_submit_lsd_bid,
+        execute_submit_routed_bids, execute_submit_signed_bid, execute_trigger_refunds,
+        execute_update_bid_options, execute_update_round, execute_update_round_config,
+        execute_withdraw, execute_withdraw_payout, native_funds_to_underlying, reply_lsd_redeem,
+        reply_transfer, tally_round_bids, DEFAULT_REFUND_DEADLINE, LSD_REDEEM_REPLY_ID,
+        MIN_REFUND_DEADLINE, NOTIFY_REPLY_ID, TRANSFER_REPLY_ID,
     },
     error::ContractError,
+    helper::{
+        asset_info_label, optional_label, push_change, query_balance, query_pair_exchange_rate,
+        record_token_flows,
+    },
+    ibc::{
+        execute_process_bid_packet, execute_set_ibc_delegate, execute_set_ibc_satellite,
+        query_ibc_channels, reply_bid_packet, IBC_PACKET_REPLY_ID,
+    },
+    idle_funds::{
+        execute_deposit_idle_funds, execute_force_recall_distribution, execute_lend_distribution,
+        execute_set_lending_market, execute_set_yield_venue, execute_unwind_idle_funds,
+        execute_withdraw_idle_funds,
+    },
+    lock::{
+        execute_enter_locked_bids, execute_fund_lock_boosts, execute_set_lock_boosts,
+        execute_submit_locked_bid,
+    },
+    matching::{
+        checked_mul_decimal, performance_fee, process_calc_distribution_amount,
+        process_calc_rebate_amount, split_bid_amount,
+    },
     msg::{
-        BiddingInfoResponse, Cw20HookMsg, EstimateAmountReceiveOfBidResponse, ExecuteMsg,
-        InstantiateMsg, MigrateMsg, QueryMsg,
+        BidPoolResponse, BidPosition, BidResponse, BidStatus, BiddingInfoResponse,
+        BurnHistoryResponse, ClearingSlotResponse, ConfigResponse, ContractInfoResponse,
+        CountersResponse, Cw20HookMsg, EpochBurnStats, EstimateAmountReceiveOfBidResponse,
+        EstimateWithAdditionalBidsResponse, ExecuteMsg, FillForecastEntry, IcaRoundResponse,
+        InstantiateMsg, InvariantMismatch, InvariantsResponse, LockBoostsResponse, MigrateMsg,
+        PageResponse, PositionResponse, PremiumScheduleEntry, QueryMsg, RoundBidPoolsResponse,
+        RoundResultResponse, RoundStatus, ShadowBalanceResponse, SimulateMatchingResponse,
+        SlotFill, SlotFillEstimate, SlotResult, StateMigrateMsg, SudoMsg, TotalBidCheckpoint,
+        UnaccountedBalanceResponse, UserRoundRoi, VerifyRoundResponse,
     },
+    reinvest::{execute_set_reinvest_config, reply_reinvest, REINVEST_REPLY_ID},
     state::{
-        count_number_bids_in_round, read_bids_by_round, Bid, BidPool, Config, BID, BIDDING_INFO,
-        BIDS_BY_USER, BID_POOL, CONFIG, DISTRIBUTION_INFO, LAST_ROUND_ID,
+        count_number_bidders_in_round, count_number_bids_in_round, init_counters,
+        migrate_slot_keys, read_bid_idxs, read_bidders_in_round, read_bids_by_height,
+        read_bids_by_round, read_bids_by_time, read_bids_in_slot, read_burn_stats,
+        read_pause_flags, read_pool_total_at, read_round_config, read_round_ids, read_round_state,
+        read_round_total_at, read_round_total_history, read_shadow_balance, read_trusted_routers,
+        save_shadow_balance, Bid, BidPool, BidRateLimit, Config, DisputeParams, DistributionInfo,
+        FinalizerGroup, PostFinalizeAction, RoundState, BID, BIDDING_INFO, BIDS_BY_SLOT,
+        BIDS_BY_TIME, BIDS_BY_USER, BID_IDX, BID_NONCE, BID_POOL, BID_RATE_LIMIT, CONFIG, DEPOSITS,
+        DISPUTES, DISPUTE_PARAMS, DISTRIBUTION_INFO, EPOCH_DURATION, FINALIZER_GROUP,
+        FINALIZE_BOUNTY, HOOKS, IBC_SATELLITE, IBC_VOUCHER_DENOM, ICA_FUNDING, ICA_ROUND_REQUEST,
+        ICA_TREASURY, LAST_ROUND_ID, LEFTOVER_AIRDROP, LENDING_MARKET, LENT_DISTRIBUTION,
+        LOCKED_BIDS, LOCKS, LOCK_BOOSTS, LOCK_BOOST_RESERVE, LP_UNSETTLED_SHARES,
+        LP_WITHDRAW_RATES, MIGRATION_CURSOR, MIN_EXCHANGE_RATES, NUM_BIDS_BY_ROUND,
+        NUM_PRUNED_ROUNDS, OPEN_TRANCHES, ORACLE_RATE_BANDS, PAUSE_FLAGS, PAYOUTS, PAYOUT_TOTALS,
+        POST_FINALIZE_ACTIONS, REFUND_DEADLINE, REINVEST_CONFIG, ROUND_SUMMARIES, ROUND_TEMPLATES,
+        SLOT_BONUSES, SLOT_KEY_MIGRATION, TRANSFER_TAX, TRUSTED_ROUTERS, USER_TOTAL_MATCHED,
+        YIELD_POSITION, YIELD_VENUE,
     },
 };
 
-#[entry_point]
+// version info for migration
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// bids indexed by each ContinueMigration call
+const DEFAULT_MIGRATION_LIMIT: u64 = 100;
+const MAX_MIGRATION_LIMIT: u64 = 500;
+const MAX_POST_FINALIZE_ACTIONS: usize = 10;
+// rounds returned by a single BidPoolsByRounds query
+const MAX_ROUNDS_PER_QUERY: usize = 30;
+const MAX_BATCH_MSGS: usize = 20;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    validate_params(
+        msg.max_slot,
+        msg.premium_rate_per_slot,
+        msg.min_deposit_amount,
+    )?;
     let config = Config {
-        owner: msg.owner,
+        owner: deps.api.addr_validate(&msg.owner)?,
         underlying_token: msg.underlying_token,
         distribution_token: msg.distribution_token,
         max_slot: msg.max_slot,
         premium_rate_per_slot: msg.premium_rate_per_slot,
         min_deposit_amount: msg.min_deposit_amount,
-        treasury: msg.treasury,
+        treasury: deps.api.addr_validate(&msg.treasury)?,
         bidding_duration: msg.bidding_duration,
+        oracle_pair: msg
+            .oracle_pair
+            .map(|pair| deps.api.addr_validate(&pair))
+            .transpose()?,
+        vesting_contract: msg
+            .vesting_contract
+            .map(|vesting_contract| deps.api.addr_validate(&vesting_contract))
+            .transpose()?,
+        keeper_registry: msg
+            .keeper_registry
+            .map(|keeper_registry| deps.api.addr_validate(&keeper_registry))
+            .transpose()?,
+        staking_contract: msg
+            .staking_contract
+            .map(|staking_contract| deps.api.addr_validate(&staking_contract))
+            .transpose()?,
+        residue_staking: msg
+            .residue_staking
+            .map(|residue_staking| deps.api.addr_validate(&residue_staking))
+            .transpose()?,
+        wrapped_denom: msg.wrapped_denom,
+        min_gap_between_rounds: msg.min_gap_between_rounds.unwrap_or_default(),
     };
 
     // store config
     CONFIG.save(deps.storage, &config)?;
-    LAST_ROUND_ID.save(deps.storage, &0)?;
+    init_counters(deps.storage)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::default())
 }
 
-#[entry_point]
+// every token received or sent by an execute goes through the shadow balances
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let mut inflows: Vec<Asset> = info
+        .funds
+        .iter()
+        .map(|coin| Asset {
+            info: AssetInfo::NativeToken {
+                denom: coin.denom.clone(),
+            },
+            amount: coin.amount,
+        })
+        .collect();
+    if let ExecuteMsg::Receive(cw20_msg) = &msg {
+        inflows.push(Asset {
+            info: AssetInfo::Token {
+                contract_addr: info.sender.clone(),
+            },
+            amount: cw20_msg.amount,
+        });
+    }
+
+    let response = dispatch_execute(deps.branch(), env, info, msg)?;
+    let config = CONFIG.load(deps.storage)?;
+    record_token_flows(deps.storage, &config, inflows, &response.messages)?;
+    Ok(response)
+}
+
+fn dispatch_execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // bids, pools and cursors written to the u16 keyed maps would be overwritten by the entries
+    // still waiting in the u8 keyed ones, nothing but the migration itself runs until it is done
+    if SLOT_KEY_MIGRATION.exists(deps.storage)
+        && !matches!(msg, ExecuteMsg::ContinueMigration { .. })
+    {
+        return Err(ContractError::SlotKeyMigrationInProgress {});
+    }
+
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::UpdateConfig {
@@ -67,6 +206,13 @@ pub fn execute(
             min_deposit_amount,
             treasury,
             bidding_duration,
+            oracle_pair,
+            vesting_contract,
+            keeper_registry,
+            staking_contract,
+            residue_staking,
+            wrapped_denom,
+            min_gap_between_rounds,
         } => execute_update_config(
             deps,
             info,
@@ -78,39 +224,83 @@ pub fn execute(
             min_deposit_amount,
             treasury,
             bidding_duration,
+            oracle_pair,
+            vesting_contract,
+            keeper_registry,
+            staking_contract,
+            residue_staking,
+            wrapped_denom,
+            min_gap_between_rounds,
         ),
         ExecuteMsg::CreateNewRound {
             start_time,
             end_time,
             total_distribution,
-        } => execute_create_new_round(deps, env, info, start_time, end_time, total_distribution),
+            min_exchange_rate,
+        } => execute_create_new_round(
+            deps,
+            env,
+            info,
+            start_time,
+            end_time,
+            total_distribution,
+            min_exchange_rate,
+        ),
         ExecuteMsg::FinalizeBiddingRoundResult {
             round,
             exchange_rate,
         } => execute_finalize_bidding_round_result(deps, env, info, round, exchange_rate),
+        ExecuteMsg::StartFinalize {
+            round,
+            exchange_rate,
+        } => execute_start_finalize(deps, env, info, round, exchange_rate),
+        ExecuteMsg::TriggerRefunds { round, limit } => {
+            execute_trigger_refunds(deps, env, round, limit)
+        }
+        ExecuteMsg::FinalizeAtOracleRate { round } => {
+            execute_finalize_at_oracle_rate(deps, env, info, round)
+        }
+        ExecuteMsg::AddTranche { round } => {
+            let coin = one_coin(&info)?;
+            let asset: Asset = Asset {
+                amount: coin.amount,
+                info: AssetInfo::NativeToken { denom: coin.denom },
+            };
+            execute_add_tranche(deps, env, info.sender, round, asset)
+        }
+        ExecuteMsg::CloseTranches { round } => execute_close_tranches(deps, env, info, round),
+        ExecuteMsg::ContinueFinalize { round, limit } => {
+            execute_continue_finalize(deps, env, info, round, limit)
+        }
         ExecuteMsg::Distribute {
             round,
             start_after,
             limit,
-        } => execute_distribute(deps, round, start_after, limit),
+        } => execute_distribute(deps, env, round, start_after, limit),
+        ExecuteMsg::RecomputeRoundTotals { round } => {
+            execute_recompute_round_totals(deps, env, info, round)
+        }
+        ExecuteMsg::PruneRound { round, limit } => {
+            execute_prune_round(deps, env, info, round, limit)
+        }
+        ExecuteMsg::DistributeBids { round, idxs } => {
+            execute_distribute_bids(deps, env, round, idxs)
+        }
         ExecuteMsg::SubmitBid {
             round,
             premium_slot,
         } => {
-            let coin = one_coin(&info)?;
-            let asset_info = AssetInfo::NativeToken { denom: coin.denom };
-            let asset: Asset = Asset {
-                amount: coin.amount,
-                info: asset_info,
-            };
-            execute_submit_bid(
+            let config = CONFIG.load(deps.storage)?;
+            let (asset, wrap_msg) = native_funds_to_underlying(&config, one_coin(&info)?)?;
+            let response = execute_submit_bid(
                 deps,
                 env,
                 round,
                 premium_slot,
                 info.sender.to_string(),
                 asset,
-            )
+            )?;
+            Ok(response.add_messages(wrap_msg))
         }
         ExecuteMsg::CreateNewRoundFromTreasury {} => {
             let coin = one_coin(&info)?;
@@ -122,6 +312,32 @@ pub fn execute(
             let sender = info.sender.clone();
             execute_create_new_round_from_treasury(deps, env, sender, asset)
         }
+        ExecuteMsg::SubmitRoutedBids { bids } => {
+            let config = CONFIG.load(deps.storage)?;
+            let (asset, wrap_msg) = native_funds_to_underlying(&config, one_coin(&info)?)?;
+            let response = execute_submit_routed_bids(deps, env, info.sender, bids, asset)?;
+            Ok(response.add_messages(wrap_msg))
+        }
+        ExecuteMsg::AddTrustedRouter { router } => execute_add_trusted_router(deps, info, router),
+        ExecuteMsg::RemoveTrustedRouter { router } => {
+            execute_remove_trusted_router(deps, info, router)
+        }
+        ExecuteMsg::ResetShadowBalances {} => execute_reset_shadow_balances(deps, env, info),
+        ExecuteMsg::RequestIcaRound { total_distribution } => {
+            execute_request_ica_round(deps, env, info, total_distribution)
+        }
+        ExecuteMsg::FundIcaRound {} => {
+            let coin = one_coin(&info)?;
+            let asset = Asset {
+                amount: coin.amount,
+                info: AssetInfo::NativeToken { denom: coin.denom },
+            };
+            execute_fund_ica_round(deps, env, asset)
+        }
+        ExecuteMsg::CancelIcaRound {} => execute_cancel_ica_round(deps, info),
+        ExecuteMsg::SetIcaTreasury { address } => execute_set_ica_treasury(deps, info, address),
+        ExecuteMsg::CancelRound { round } => execute_cancel_round(deps, env, info, round),
+        ExecuteMsg::ClaimResidue { idx } => execute_claim_residue(deps, env, info, idx),
         ExecuteMsg::UpdateRound {
             idx,
             start_time,
@@ -136,6 +352,196 @@ pub fn execute(
             end_time,
             total_distribution,
         ),
+        ExecuteMsg::UpdateRoundConfig {
+            round,
+            round_config,
+        } => execute_update_round_config(deps, env, info, round, round_config),
+        ExecuteMsg::RegisterBidderPubkey { pubkey } => {
+            execute_register_bidder_pubkey(deps, info, pubkey)
+        }
+        ExecuteMsg::SubmitSignedBid {
+            bid,
+            signature,
+            pubkey,
+        } => execute_submit_signed_bid(deps, env, bid, signature, pubkey),
+        ExecuteMsg::Deposit {} => {
+            let config = CONFIG.load(deps.storage)?;
+            let (asset, wrap_msg) = native_funds_to_underlying(&config, one_coin(&info)?)?;
+            let response = execute_deposit(deps, info.sender, asset)?;
+            Ok(response.add_messages(wrap_msg))
+        }
+        ExecuteMsg::Withdraw { amount } => execute_withdraw(deps, info, amount),
+        ExecuteMsg::SaveRoundTemplate { name, params } => {
+            execute_save_round_template(deps, info, name, params)
+        }
+        ExecuteMsg::CreateRoundFromTemplate { name, start_time } => {
+            execute_create_round_from_template(deps, env, info, name, start_time)
+        }
+        ExecuteMsg::SetDisputeParams { params } => execute_set_dispute_params(deps, info, params),
+        ExecuteMsg::DisputeRound { round } => execute_dispute_round(deps, env, info, round),
+        ExecuteMsg::ResolveDispute {
+            round,
+            exchange_rate,
+        } => execute_resolve_dispute(deps, env, info, round, exchange_rate),
+        ExecuteMsg::SetPostFinalizeActions { actions } => {
+            execute_set_post_finalize_actions(deps, info, actions)
+        }
+        ExecuteMsg::SetBidRateLimit { rate_limit } => {
+            execute_set_bid_rate_limit(deps, info, rate_limit)
+        }
+        ExecuteMsg::ContinueMigration { limit } => execute_continue_migration(deps, info, limit),
+        ExecuteMsg::RegisterHook { addr } => execute_register_hook(deps, info, addr),
+        ExecuteMsg::RemoveHook { addr } => execute_remove_hook(deps, info, addr),
+        ExecuteMsg::SetYieldVenue { venue } => execute_set_yield_venue(deps, info, venue),
+        ExecuteMsg::DepositIdleFunds { round, amount } => {
+            execute_deposit_idle_funds(deps, env, info, round, amount)
+        }
+        ExecuteMsg::WithdrawIdleFunds {} => execute_withdraw_idle_funds(deps, env, info),
+        ExecuteMsg::UnwindIdleFunds {} => execute_unwind_idle_funds(deps, info),
+        ExecuteMsg::SetLendingMarket { market } => execute_set_lending_market(deps, info, market),
+        ExecuteMsg::LendDistribution { round } => execute_lend_distribution(deps, env, info, round),
+        ExecuteMsg::ForceRecallDistribution { round } => {
+            execute_force_recall_distribution(deps, info, round)
+        }
+        ExecuteMsg::SetPauseFlags {
+            submit_bid,
+            create_round,
+            finalize,
+            distribute,
+        } => execute_set_pause_flags(deps, info, submit_bid, create_round, finalize, distribute),
+        ExecuteMsg::SetLeftoverAirdrop { address } => {
+            execute_set_leftover_airdrop(deps, info, address)
+        }
+        ExecuteMsg::SubmitLockedBid {
+            round,
+            premium_slot,
+            lock_rounds,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let (asset, wrap_msg) = native_funds_to_underlying(&config, one_coin(&info)?)?;
+            let response = execute_submit_locked_bid(
+                deps,
+                env,
+                round,
+                premium_slot,
+                lock_rounds,
+                info.sender.to_string(),
+                asset,
+            )?;
+            Ok(response.add_messages(wrap_msg))
+        }
+        ExecuteMsg::EnterLockedBids { round, lock_ids } => {
+            execute_enter_locked_bids(deps, env, round, lock_ids)
+        }
+        ExecuteMsg::SetLockBoosts { boosts } => execute_set_lock_boosts(deps, info, boosts),
+        ExecuteMsg::SetReinvestConfig { reinvest_config } => {
+            execute_set_reinvest_config(deps, info, reinvest_config)
+        }
+        ExecuteMsg::FundLockBoosts {} => {
+            let coin = one_coin(&info)?;
+            let asset: Asset = Asset {
+                amount: coin.amount,
+                info: AssetInfo::NativeToken { denom: coin.denom },
+            };
+            execute_fund_lock_boosts(deps, asset)
+        }
+        ExecuteMsg::ExecuteBatch { msgs } => execute_batch(deps, env, info, msgs),
+        ExecuteMsg::SetRefundDeadline { refund_deadline } => {
+            execute_set_refund_deadline(deps, info, refund_deadline)
+        }
+        ExecuteMsg::SetPermissionlessFinalize { bounty } => {
+            execute_set_permissionless_finalize(deps, info, bounty)
+        }
+        ExecuteMsg::SetOracleRateBand { round, band } => {
+            execute_set_oracle_rate_band(deps, env, info, round, band)
+        }
+        ExecuteMsg::SetFinalizerGroup { group, quorum } => {
+            execute_set_finalizer_group(deps, info, group, quorum)
+        }
+        ExecuteMsg::AddSlotBonus {
+            round,
+            min_slot,
+            max_slot,
+        } => {
+            let coin = one_coin(&info)?;
+            let asset: Asset = Asset {
+                amount: coin.amount,
+                info: AssetInfo::NativeToken { denom: coin.denom },
+            };
+            execute_add_slot_bonus(deps, env, info.sender, round, min_slot, max_slot, asset)
+        }
+        ExecuteMsg::WithdrawPayout {} => execute_withdraw_payout(deps, info),
+        ExecuteMsg::UpdateBidOptions {
+            idx,
+            auto_compound,
+            stake_residue,
+            reinvest,
+        } => execute_update_bid_options(deps, info, idx, auto_compound, stake_residue, reinvest),
+        ExecuteMsg::SetBidNotifier { idx, notifier } => {
+            execute_set_bid_notifier(deps, info, idx, notifier)
+        }
+        ExecuteMsg::SplitBid {
+            idx,
+            amount,
+            new_slot,
+        } => execute_split_bid(deps, env, info, idx, amount, new_slot),
+        ExecuteMsg::SubmitRemoteBid {
+            round,
+            premium_slot,
+            bidder,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let coin = one_coin(&info)?;
+            if IBC_VOUCHER_DENOM.may_load(deps.storage)?.as_ref() != Some(&coin.denom) {
+                return Err(ContractError::InvalidFunds {});
+            }
+            let (asset, wrap_msg) = native_funds_to_underlying(&config, coin)?;
+            let response = execute_submit_bid(deps, env, round, premium_slot, bidder, asset)?;
+            Ok(response
+                .add_attribute("remote_sender", info.sender)
+                .add_messages(wrap_msg))
+        }
+        ExecuteMsg::SetIbcVoucherDenom { denom } => {
+            execute_set_ibc_voucher_denom(deps, info, denom)
+        }
+        ExecuteMsg::SubmitBidFrom {
+            round,
+            premium_slot,
+            amount,
+        } => execute_submit_bid_from(deps, env, info, round, premium_slot, amount),
+        ExecuteMsg::SetTransferTax { enabled } => execute_set_transfer_tax(deps, info, enabled),
+        ExecuteMsg::SubmitLsdBid {
+            round,
+            premium_slot,
+        } => {
+            let coin = one_coin(&info)?;
+            let asset: Asset = Asset {
+                amount: coin.amount,
+                info: AssetInfo::NativeToken { denom: coin.denom },
+            };
+            execute_submit_lsd_bid(
+                deps,
+                env,
+                round,
+                premium_slot,
+                info.sender.to_string(),
+                asset,
+            )
+        }
+        ExecuteMsg::SubmitBidFromBalance {
+            round,
+            premium_slot,
+            amount,
+        } => execute_submit_bid_from_balance(deps, env, info, round, premium_slot, amount),
+        ExecuteMsg::SetIbcSatellite { satellite } => {
+            execute_set_ibc_satellite(deps, info, satellite)
+        }
+        ExecuteMsg::SetIbcDelegate { channel_id } => {
+            execute_set_ibc_delegate(deps, info, channel_id)
+        }
+        ExecuteMsg::ProcessBidPacket { channel_id, packet } => {
+            execute_process_bid_packet(deps, env, info, channel_id, packet)
+        }
     }
 }
 
@@ -145,6 +551,18 @@ fn receive_cw20(
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
+    // the amount sent by a taxed token is not what the contract received
+    let config = CONFIG.load(deps.storage)?;
+    let is_underlying = matches!(
+        &config.underlying_token,
+        AssetInfo::Token { contract_addr } if *contract_addr == info.sender
+    );
+    if is_underlying && TRANSFER_TAX.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The underlying token takes a transfer tax, bid with SubmitBidFrom",
+        )));
+    }
+
     match from_json(&cw20_msg.msg)? {
         Cw20HookMsg::SubmitBid {
             round,
@@ -171,6 +589,116 @@ fn receive_cw20(
 
             execute_create_new_round_from_treasury(deps, env, sender, asset)
         }
+        Cw20HookMsg::SubmitRoutedBids { bids } => {
+            let asset = Asset {
+                amount: cw20_msg.amount,
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+            };
+            let router = deps.api.addr_validate(&cw20_msg.sender)?;
+            execute_submit_routed_bids(deps, env, router, bids, asset)
+        }
+        Cw20HookMsg::FundIcaRound {} => {
+            let asset = Asset {
+                amount: cw20_msg.amount,
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+            };
+            execute_fund_ica_round(deps, env, asset)
+        }
+        Cw20HookMsg::SubmitLpBid {
+            round,
+            premium_slot,
+        } => execute_submit_lp_bid(
+            deps,
+            env,
+            round,
+            premium_slot,
+            cw20_msg.sender,
+            info.sender,
+            cw20_msg.amount,
+        ),
+        Cw20HookMsg::SubmitLsdBid {
+            round,
+            premium_slot,
+        } => {
+            let asset: Asset = Asset {
+                amount: cw20_msg.amount,
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+            };
+            execute_submit_lsd_bid(deps, env, round, premium_slot, cw20_msg.sender, asset)
+        }
+        Cw20HookMsg::Deposit {} => {
+            let asset: Asset = Asset {
+                amount: cw20_msg.amount,
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+            };
+            let depositor = deps.api.addr_validate(&cw20_msg.sender)?;
+
+            execute_deposit(deps, depositor, asset)
+        }
+        Cw20HookMsg::AddSlotBonus {
+            round,
+            min_slot,
+            max_slot,
+        } => {
+            let asset: Asset = Asset {
+                amount: cw20_msg.amount,
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+            };
+            let sponsor = deps.api.addr_validate(&cw20_msg.sender)?;
+
+            execute_add_slot_bonus(deps, env, sponsor, round, min_slot, max_slot, asset)
+        }
+        Cw20HookMsg::SubmitLockedBid {
+            round,
+            premium_slot,
+            lock_rounds,
+        } => {
+            let asset: Asset = Asset {
+                amount: cw20_msg.amount,
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+            };
+            execute_submit_locked_bid(
+                deps,
+                env,
+                round,
+                premium_slot,
+                lock_rounds,
+                cw20_msg.sender,
+                asset,
+            )
+        }
+        Cw20HookMsg::AddTranche { round } => {
+            let asset: Asset = Asset {
+                amount: cw20_msg.amount,
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+            };
+            let sender = deps.api.addr_validate(&cw20_msg.sender)?;
+
+            execute_add_tranche(deps, env, sender, round, asset)
+        }
+        Cw20HookMsg::FundLockBoosts {} => {
+            let asset: Asset = Asset {
+                amount: cw20_msg.amount,
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+            };
+            execute_fund_lock_boosts(deps, asset)
+        }
     }
 }
 
@@ -178,22 +706,30 @@ fn receive_cw20(
 fn execute_update_config(
     deps: DepsMut,
     info: MessageInfo,
-    owner: Option<Addr>,
+    owner: Option<String>,
     underlying_token: Option<AssetInfo>,
     distribution_token: Option<AssetInfo>,
-    max_slot: Option<u8>,
+    max_slot: Option<u16>,
     premium_rate_per_slot: Option<Decimal>,
     min_deposit_amount: Option<Uint128>,
-    treasury: Option<Addr>,
+    treasury: Option<String>,
     bidding_duration: Option<u64>,
+    oracle_pair: Option<String>,
+    vesting_contract: Option<String>,
+    keeper_registry: Option<String>,
+    staking_contract: Option<String>,
+    residue_staking: Option<String>,
+    wrapped_denom: Option<String>,
+    min_gap_between_rounds: Option<u64>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
+    let old_config = config.clone();
     if let Some(owner) = owner {
-        config.owner = owner;
+        config.owner = deps.api.addr_validate(&owner)?;
     }
     if let Some(underlying_token) = underlying_token {
         config.underlying_token = underlying_token;
@@ -211,74 +747,1267 @@ fn execute_update_config(
         config.min_deposit_amount = min_deposit_amount;
     }
     if let Some(treasury) = treasury {
-        config.treasury = treasury;
+        config.treasury = deps.api.addr_validate(&treasury)?;
     }
     if let Some(bidding_duration) = bidding_duration {
         config.bidding_duration = bidding_duration;
     }
+    if let Some(oracle_pair) = oracle_pair {
+        config.oracle_pair = Some(deps.api.addr_validate(&oracle_pair)?);
+    }
+    if let Some(vesting_contract) = vesting_contract {
+        config.vesting_contract = Some(deps.api.addr_validate(&vesting_contract)?);
+    }
+    if let Some(keeper_registry) = keeper_registry {
+        config.keeper_registry = Some(deps.api.addr_validate(&keeper_registry)?);
+    }
+    if let Some(staking_contract) = staking_contract {
+        config.staking_contract = Some(deps.api.addr_validate(&staking_contract)?);
+    }
+    if let Some(residue_staking) = residue_staking {
+        config.residue_staking = Some(deps.api.addr_validate(&residue_staking)?);
+    }
+    if let Some(wrapped_denom) = wrapped_denom {
+        config.wrapped_denom = Some(wrapped_denom);
+    }
+    if let Some(min_gap_between_rounds) = min_gap_between_rounds {
+        config.min_gap_between_rounds = min_gap_between_rounds;
+    }
 
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::default().add_attribute("action", "update_config"))
+    Ok(Response::default()
+        .add_attribute("action", "update_config")
+        .add_attributes(config_changes(&old_config, &config)))
 }
 
-#[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
-        QueryMsg::Bid { idx } => to_json_binary(&BID.load(deps.storage, idx)?),
-        QueryMsg::BiddingInfo { round } => to_json_binary(&query_bidding_info(deps, round)?),
-        QueryMsg::LastRoundId {} => to_json_binary(&LAST_ROUND_ID.load(deps.storage)?),
-        QueryMsg::BidPool { round, slot } => {
-            to_json_binary(&BID_POOL.load(deps.storage, (round, slot))?)
-        }
-        QueryMsg::AllBidPoolInRound { round } => {
-            to_json_binary(&query_all_bid_pool_in_round(deps, round)?)
-        }
-        QueryMsg::BidsIdxByUser { round, user } => {
-            to_json_binary(&BIDS_BY_USER.load(deps.storage, (round, user))?)
-        }
-        QueryMsg::EstimateAmountReceiveOfBid {
-            round,
-            idx,
-            exchange_rate,
-        } => to_json_binary(&query_estimate_amount_receive_of_bid(
-            deps,
-            round,
-            idx,
-            exchange_rate,
-        )?),
-        QueryMsg::EstimateAmountReceive {
-            round,
-            slot,
-            bid_amount,
-            exchange_rate,
-        } => to_json_binary(&query_estimate_amount_receive(
-            deps,
-            round,
-            slot,
-            bid_amount,
-            exchange_rate,
-        )?),
-        QueryMsg::AllBidInRound {
-            round,
-            start_after,
-            limit,
-            order_by,
-        } => to_json_binary(&read_bids_by_round(
-            deps.storage,
-            round,
-            start_after,
-            limit,
-            order_by,
-        )?),
+// before and after values of the fields changed by an update, unchanged fields are left out
+fn config_changes(old: &Config, new: &Config) -> Vec<Attribute> {
+    let mut changes = vec![];
+    push_change(
+        &mut changes,
+        "owner",
+        old.owner.to_string(),
+        new.owner.to_string(),
+    );
+    push_change(
+        &mut changes,
+        "underlying_token",
+        asset_info_label(&old.underlying_token),
+        asset_info_label(&new.underlying_token),
+    );
+    push_change(
+        &mut changes,
+        "distribution_token",
+        asset_info_label(&old.distribution_token),
+        asset_info_label(&new.distribution_token),
+    );
+    push_change(
+        &mut changes,
+        "max_slot",
+        old.max_slot.to_string(),
+        new.max_slot.to_string(),
+    );
+    push_change(
+        &mut changes,
+        "premium_rate_per_slot",
+        old.premium_rate_per_slot.to_string(),
+        new.premium_rate_per_slot.to_string(),
+    );
+    push_change(
+        &mut changes,
+        "min_deposit_amount",
+        old.min_deposit_amount.to_string(),
+        new.min_deposit_amount.to_string(),
+    );
+    push_change(
+        &mut changes,
+        "treasury",
+        old.treasury.to_string(),
+        new.treasury.to_string(),
+    );
+    push_change(
+        &mut changes,
+        "bidding_duration",
+        old.bidding_duration.to_string(),
+        new.bidding_duration.to_string(),
+    );
+    for (field, old, new) in [
+        ("oracle_pair", &old.oracle_pair, &new.oracle_pair),
+        (
+            "vesting_contract",
+            &old.vesting_contract,
+            &new.vesting_contract,
+        ),
+        (
+            "keeper_registry",
+            &old.keeper_registry,
+            &new.keeper_registry,
+        ),
+        (
+            "staking_contract",
+            &old.staking_contract,
+            &new.staking_contract,
+        ),
+        (
+            "residue_staking",
+            &old.residue_staking,
+            &new.residue_staking,
+        ),
+    ] {
+        push_change(
+            &mut changes,
+            field,
+            optional_label(old),
+            optional_label(new),
+        );
+    }
+    push_change(
+        &mut changes,
+        "wrapped_denom",
+        optional_label(&old.wrapped_denom),
+        optional_label(&new.wrapped_denom),
+    );
+    push_change(
+        &mut changes,
+        "min_gap_between_rounds",
+        old.min_gap_between_rounds.to_string(),
+        new.min_gap_between_rounds.to_string(),
+    );
+    changes
+}
+
+fn execute_set_dispute_params(
+    deps: DepsMut,
+    info: MessageInfo,
+    params: Option<DisputeParams>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let Some(params) = params else {
+        DISPUTE_PARAMS.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "disable_disputes"));
+    };
+    DISPUTE_PARAMS.save(deps.storage, &params)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_dispute_params"),
+        ("bond", &params.bond.to_string()),
+        ("max_deviation", &params.max_deviation.to_string()),
+        ("dispute_period", &params.dispute_period.to_string()),
+    ]))
+}
+
+fn execute_reset_shadow_balances(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut response = Response::new().add_attribute("action", "reset_shadow_balances");
+    for asset_info in tracked_assets(&config) {
+        let balance = query_balance(&deps.querier, &asset_info, env.contract.address.clone())?;
+        save_shadow_balance(deps.storage, &asset_info, &balance)?;
+        response = response.add_attribute(asset_info_label(&asset_info), balance);
+    }
+    Ok(response)
+}
+
+fn execute_set_transfer_tax(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    TRANSFER_TAX.save(deps.storage, &enabled)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_transfer_tax"),
+        ("enabled", &enabled.to_string()),
+    ]))
+}
+
+fn execute_set_ica_treasury(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if ICA_ROUND_REQUEST.exists(deps.storage) || ICA_FUNDING.exists(deps.storage) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "An interchain account round is still pending",
+        )));
+    }
+
+    let Some(address) = address else {
+        ICA_TREASURY.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_ica_treasury"));
+    };
+    let address = deps.api.addr_validate(&address)?;
+    ICA_TREASURY.save(deps.storage, &address)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_ica_treasury"),
+        ("ica_treasury", address.as_str()),
+    ]))
+}
+
+// only owner, the messages are dispatched in order as if sent one by one by the owner and a
+// failing one reverts the batch. Funds can't be attached, every message would see them. The data
+// of the messages is returned as a list, none for the messages without data
+fn execute_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<ExecuteMsg>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    nonpayable(&info)?;
+    if msgs.is_empty() || msgs.len() > MAX_BATCH_MSGS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "A batch must hold between 1 and {} messages",
+            MAX_BATCH_MSGS
+        ))));
+    }
+
+    let mut response = Response::new().add_attributes(vec![
+        ("action", "execute_batch"),
+        ("num_msgs", &msgs.len().to_string()),
+    ]);
+    let mut data: Vec<Option<Binary>> = vec![];
+    for msg in msgs {
+        if matches!(
+            msg,
+            ExecuteMsg::Receive(_) | ExecuteMsg::ExecuteBatch { .. }
+        ) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "A batch can't hold Receive or ExecuteBatch messages",
+            )));
+        }
+        let msg_response = dispatch_execute(deps.branch(), env.clone(), info.clone(), msg)?;
+        // these replies resume from a single pending item and a balance measured before their
+        // submessage, the other messages of the batch would overwrite or skew them
+        if msg_response.messages.iter().any(|sub_msg| {
+            sub_msg.reply_on != ReplyOn::Never
+                && matches!(
+                    sub_msg.id,
+                    TRANSFER_REPLY_ID | LSD_REDEEM_REPLY_ID | REINVEST_REPLY_ID
+                )
+        }) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "A batch can't hold messages completed in a reply",
+            )));
+        }
+        // one event per message, with the attributes it would have returned alone
+        response = response
+            .add_submessages(msg_response.messages)
+            .add_events(msg_response.events)
+            .add_event(Event::new("batch_msg").add_attributes(msg_response.attributes));
+        data.push(msg_response.data);
+    }
+    if data.iter().any(Option::is_some) {
+        response = response.set_data(to_json_binary(&data)?);
+    }
+    Ok(response)
+}
+
+// the deadline can't be removed, it is the backstop of the bidders against a round never finalized
+fn execute_set_refund_deadline(
+    deps: DepsMut,
+    info: MessageInfo,
+    refund_deadline: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if refund_deadline < MIN_REFUND_DEADLINE {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Refund deadline must be at least {} seconds",
+            MIN_REFUND_DEADLINE
+        ))));
+    }
+    REFUND_DEADLINE.save(deps.storage, &refund_deadline)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_refund_deadline"),
+        ("refund_deadline", &refund_deadline.to_string()),
+    ]))
+}
+
+fn execute_set_permissionless_finalize(
+    deps: DepsMut,
+    info: MessageInfo,
+    bounty: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let Some(bounty) = bounty else {
+        FINALIZE_BOUNTY.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "disable_permissionless_finalize"));
+    };
+    if config.oracle_pair.is_none() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Oracle pair is not configured",
+        )));
+    }
+    FINALIZE_BOUNTY.save(deps.storage, &bounty)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "enable_permissionless_finalize"),
+        ("bounty", &bounty.to_string()),
+    ]))
+}
+
+fn execute_set_finalizer_group(
+    deps: DepsMut,
+    info: MessageInfo,
+    group: Option<String>,
+    quorum: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let Some(group) = group else {
+        FINALIZER_GROUP.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_finalizer_group"));
+    };
+    if quorum.map_or(false, |quorum| quorum.is_zero() || quorum > Decimal::one()) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Quorum must be above zero and at most one",
+        )));
+    }
+    let finalizer_group = FinalizerGroup {
+        group: deps.api.addr_validate(&group)?,
+        quorum,
+    };
+    FINALIZER_GROUP.save(deps.storage, &finalizer_group)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_finalizer_group"),
+        ("group", finalizer_group.group.as_str()),
+        ("quorum", &optional_label(&quorum)),
+    ]))
+}
+
+fn execute_set_leftover_airdrop(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let Some(address) = address else {
+        LEFTOVER_AIRDROP.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_leftover_airdrop"));
+    };
+    let address = deps.api.addr_validate(&address)?;
+    LEFTOVER_AIRDROP.save(deps.storage, &address)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_leftover_airdrop"),
+        ("leftover_airdrop", address.as_str()),
+    ]))
+}
+
+// the voucher is converted like the other native funds, it must be the underlying denom or
+// the denom wrapped into the underlying token
+fn execute_set_ibc_voucher_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let Some(denom) = denom else {
+        IBC_VOUCHER_DENOM.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_ibc_voucher_denom"));
+    };
+    let is_underlying = match &config.underlying_token {
+        AssetInfo::NativeToken { denom: underlying } => *underlying == denom,
+        AssetInfo::Token { .. } => config.wrapped_denom.as_ref() == Some(&denom),
+    };
+    if !is_underlying {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Voucher denom {} is not accepted as the underlying token",
+            denom
+        ))));
+    }
+    IBC_VOUCHER_DENOM.save(deps.storage, &denom)?;
+
+    Ok(
+        Response::new()
+            .add_attributes(vec![("action", "set_ibc_voucher_denom"), ("denom", &denom)]),
+    )
+}
+
+fn execute_set_post_finalize_actions(
+    deps: DepsMut,
+    info: MessageInfo,
+    actions: Vec<PostFinalizeAction>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if actions.is_empty() {
+        POST_FINALIZE_ACTIONS.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_post_finalize_actions"));
+    }
+    if actions.len() > MAX_POST_FINALIZE_ACTIONS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "At most {} post-finalize actions can be set",
+            MAX_POST_FINALIZE_ACTIONS
+        ))));
+    }
+    // an invalid address would make every finalize fail
+    for action in actions.iter() {
+        match action {
+            PostFinalizeAction::Transfer { recipient, .. } => {
+                deps.api.addr_validate(recipient)?;
+            }
+            PostFinalizeAction::Send { contract, .. }
+            | PostFinalizeAction::Execute { contract, .. } => {
+                deps.api.addr_validate(contract)?;
+            }
+        }
+    }
+    POST_FINALIZE_ACTIONS.save(deps.storage, &actions)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_post_finalize_actions"),
+        ("num_actions", &actions.len().to_string()),
+    ]))
+}
+
+fn execute_set_bid_rate_limit(
+    deps: DepsMut,
+    info: MessageInfo,
+    rate_limit: Option<BidRateLimit>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let Some(rate_limit) = rate_limit else {
+        BID_RATE_LIMIT.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_bid_rate_limit"));
+    };
+    if rate_limit.max_bids == 0 || rate_limit.window == 0 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Rate limit must allow at least one bid in a non-empty window",
+        )));
+    }
+    BID_RATE_LIMIT.save(deps.storage, &rate_limit)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_bid_rate_limit"),
+        ("max_bids", &rate_limit.max_bids.to_string()),
+        ("window", &rate_limit.window.to_string()),
+    ]))
+}
+
+// moves the u8 slot keyed entries to the u16 keyed maps, or indexes the bids stored before
+// BIDS_BY_SLOT and BIDS_BY_TIME existed, in chunks of limit entries
+fn execute_continue_migration(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let limit = limit
+        .unwrap_or(DEFAULT_MIGRATION_LIMIT)
+        .min(MAX_MIGRATION_LIMIT) as usize;
+
+    if SLOT_KEY_MIGRATION.exists(deps.storage) {
+        let moved = migrate_slot_keys(deps.storage, limit)?;
+        let finished = moved < limit;
+        if finished {
+            SLOT_KEY_MIGRATION.remove(deps.storage);
+        }
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "continue_migration"),
+            ("num_migrated", &moved.to_string()),
+            ("finished", &finished.to_string()),
+        ]));
+    }
+
+    let Some(cursor) = MIGRATION_CURSOR.may_load(deps.storage)? else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No migration in progress",
+        )));
+    };
+
+    let bids = BID
+        .range(
+            deps.storage,
+            Some(Bound::exclusive(cursor)),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<(u64, Bid)>>>()?;
+    for (idx, bid) in bids.iter() {
+        BIDS_BY_SLOT.save(deps.storage, (bid.round, bid.premium_slot, *idx), &true)?;
+        BIDS_BY_TIME.save(deps.storage, (bid.round, bid.timestamp, *idx), &true)?;
+    }
+
+    // a partial chunk means every bid has been processed
+    let last_idx = bids.last().map_or(cursor, |(idx, _)| *idx);
+    let finished = bids.len() < limit;
+    if finished {
+        MIGRATION_CURSOR.remove(deps.storage);
+    } else {
+        MIGRATION_CURSOR.save(deps.storage, &last_idx)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "continue_migration"),
+        ("num_migrated", &bids.len().to_string()),
+        ("last_idx", &last_idx.to_string()),
+        ("finished", &finished.to_string()),
+    ]))
+}
+
+fn execute_register_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    HOOKS.add_hook(deps.storage, deps.api.addr_validate(&addr)?)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "register_hook"), ("hook", &addr)]))
+}
+
+fn execute_remove_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    HOOKS.remove_hook(deps.storage, deps.api.addr_validate(&addr)?)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "remove_hook"), ("hook", &addr)]))
+}
+
+fn execute_add_trusted_router(
+    deps: DepsMut,
+    info: MessageInfo,
+    router: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    TRUSTED_ROUTERS.save(deps.storage, &deps.api.addr_validate(&router)?, &true)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "add_trusted_router"), ("router", &router)]))
+}
+
+fn execute_remove_trusted_router(
+    deps: DepsMut,
+    info: MessageInfo,
+    router: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    TRUSTED_ROUTERS.remove(deps.storage, &deps.api.addr_validate(&router)?);
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "remove_trusted_router"),
+        ("router", &router),
+    ]))
+}
+
+fn execute_set_pause_flags(
+    deps: DepsMut,
+    info: MessageInfo,
+    submit_bid: Option<bool>,
+    create_round: Option<bool>,
+    finalize: Option<bool>,
+    distribute: Option<bool>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut pause_flags = read_pause_flags(deps.storage)?;
+    if let Some(submit_bid) = submit_bid {
+        pause_flags.submit_bid = submit_bid;
+    }
+    if let Some(create_round) = create_round {
+        pause_flags.create_round = create_round;
+    }
+    if let Some(finalize) = finalize {
+        pause_flags.finalize = finalize;
+    }
+    if let Some(distribute) = distribute {
+        pause_flags.distribute = distribute;
+    }
+    PAUSE_FLAGS.save(deps.storage, &pause_flags)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_pause_flags"),
+        ("submit_bid", &pause_flags.submit_bid.to_string()),
+        ("create_round", &pause_flags.create_round.to_string()),
+        ("finalize", &pause_flags.finalize.to_string()),
+        ("distribute", &pause_flags.distribute.to_string()),
+    ]))
+}
+
+// chain governance adjusts the market parameters without the owner key
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::UpdateParams {
+            max_slot,
+            premium_rate_per_slot,
+            min_deposit_amount,
+            bidding_duration,
+            treasury,
+            oracle_pair,
+        } => {
+            let mut config = CONFIG.load(deps.storage)?;
+            if let Some(max_slot) = max_slot {
+                // the stored rounds may have bids up to the current max_slot, the distribution
+                // indexes them in vectors sized by max_slot
+                if max_slot < config.max_slot {
+                    return Err(ContractError::InvalidParams {
+                        reason: format!("max_slot can't be lowered below {}", config.max_slot),
+                    });
+                }
+                config.max_slot = max_slot;
+            }
+            if let Some(premium_rate_per_slot) = premium_rate_per_slot {
+                config.premium_rate_per_slot = premium_rate_per_slot;
+            }
+            if let Some(min_deposit_amount) = min_deposit_amount {
+                config.min_deposit_amount = min_deposit_amount;
+            }
+            if let Some(bidding_duration) = bidding_duration {
+                config.bidding_duration = bidding_duration;
+            }
+            if let Some(treasury) = treasury {
+                config.treasury = deps.api.addr_validate(&treasury)?;
+            }
+            if let Some(oracle_pair) = oracle_pair {
+                config.oracle_pair = Some(deps.api.addr_validate(&oracle_pair)?);
+            }
+            validate_params(
+                config.max_slot,
+                config.premium_rate_per_slot,
+                config.min_deposit_amount,
+            )?;
+
+            CONFIG.save(deps.storage, &config)?;
+
+            Ok(Response::default().add_attribute("action", "sudo_update_params"))
+        }
+    }
+}
+
+// the premium ladder needs at least one slot, a premium step and a minimum bid
+fn validate_params(
+    max_slot: u16,
+    premium_rate_per_slot: Decimal,
+    min_deposit_amount: Uint128,
+) -> Result<(), ContractError> {
+    let reason = if max_slot == 0 {
+        "max_slot must be above 0"
+    } else if premium_rate_per_slot.is_zero() {
+        "premium_rate_per_slot must be above 0"
+    } else if min_deposit_amount.is_zero() {
+        "min_deposit_amount must be above 0"
+    } else {
+        return Ok(());
+    };
+    Err(ContractError::InvalidParams {
+        reason: reason.to_string(),
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Hooks {} => to_json_binary(&HOOKS.query_hooks(deps)?),
+        QueryMsg::ContractInfo {} => to_json_binary(&ContractInfoResponse {
+            cw2: CONTRACT.may_load(deps.storage)?,
+            crate_version: CONTRACT_VERSION.to_string(),
+            git_commit: option_env!("GIT_COMMIT").map(str::to_string),
+        }),
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::YieldVenue {} => to_json_binary(&YIELD_VENUE.may_load(deps.storage)?),
+        QueryMsg::YieldPosition {} => to_json_binary(&YIELD_POSITION.may_load(deps.storage)?),
+        QueryMsg::LendingMarket {} => to_json_binary(&LENDING_MARKET.may_load(deps.storage)?),
+        QueryMsg::LentDistribution { round } => to_json_binary(
+            &LENT_DISTRIBUTION
+                .may_load(deps.storage, round)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::Bid { idx } => to_json_binary(&BidResponse::from(BID.load(deps.storage, idx)?)),
+        QueryMsg::BiddingInfo { round } => to_json_binary(&query_bidding_info(deps, round)?),
+        QueryMsg::LastRoundId {} => to_json_binary(&LAST_ROUND_ID.load(deps.storage)?),
+        QueryMsg::Counters {} => to_json_binary(&CountersResponse {
+            last_round_id: LAST_ROUND_ID.load(deps.storage)?,
+            next_bid_idx: BID_IDX.load(deps.storage)?,
+        }),
+        QueryMsg::LeftoverAirdrop {} => to_json_binary(&LEFTOVER_AIRDROP.may_load(deps.storage)?),
+        QueryMsg::FinalizerGroup {} => to_json_binary(&FINALIZER_GROUP.may_load(deps.storage)?),
+        QueryMsg::LockBoosts {} => to_json_binary(&LockBoostsResponse {
+            boosts: LOCK_BOOSTS.may_load(deps.storage)?.unwrap_or_default(),
+            reserve: LOCK_BOOST_RESERVE
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        }),
+        QueryMsg::Lock { lock_id } => to_json_binary(&LOCKS.load(deps.storage, lock_id)?),
+        QueryMsg::LockOfBid { idx } => to_json_binary(&LOCKED_BIDS.may_load(deps.storage, idx)?),
+        QueryMsg::ReinvestConfig {} => to_json_binary(&REINVEST_CONFIG.may_load(deps.storage)?),
+        QueryMsg::RefundDeadline {} => to_json_binary(
+            &REFUND_DEADLINE
+                .may_load(deps.storage)?
+                .unwrap_or(DEFAULT_REFUND_DEADLINE),
+        ),
+        QueryMsg::OpenTranches { round } => {
+            to_json_binary(&OPEN_TRANCHES.may_load(deps.storage, round)?)
+        }
+        QueryMsg::FinalizeBounty {} => to_json_binary(&FINALIZE_BOUNTY.may_load(deps.storage)?),
+        QueryMsg::BidPool { round, slot } => to_json_binary(&BidPoolResponse::from(
+            BID_POOL.load(deps.storage, (round, slot))?,
+        )),
+        QueryMsg::AllBidPoolInRound {
+            round,
+            start_after,
+            limit,
+        } => to_json_binary(&query_all_bid_pool_in_round(
+            deps,
+            round,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::BidPoolsByRounds { rounds } => {
+            to_json_binary(&query_bid_pools_by_rounds(deps, rounds)?)
+        }
+        QueryMsg::BidsIdxByUser { round, user } => {
+            let user = deps.api.addr_validate(&user)?;
+            to_json_binary(&BIDS_BY_USER.load(deps.storage, (round, user))?)
+        }
+        QueryMsg::EstimateAmountReceiveOfBid {
+            round,
+            idx,
+            exchange_rate,
+        } => to_json_binary(&query_estimate_amount_receive_of_bid(
+            deps,
+            round,
+            idx,
+            exchange_rate,
+        )?),
+        QueryMsg::EstimateAmountReceive {
+            round,
+            slot,
+            bid_amount,
+            exchange_rate,
+        } => to_json_binary(&query_estimate_amount_receive(
+            deps,
+            round,
+            slot,
+            bid_amount,
+            exchange_rate,
+        )?),
+        QueryMsg::EstimateAmountReceiveOfBidAtMarket { round, idx } => {
+            let exchange_rate = query_market_exchange_rate(deps)?;
+            to_json_binary(&query_estimate_amount_receive_of_bid(
+                deps,
+                round,
+                idx,
+                exchange_rate,
+            )?)
+        }
+        QueryMsg::EstimateAmountReceiveAtMarket {
+            round,
+            slot,
+            bid_amount,
+        } => {
+            let exchange_rate = query_market_exchange_rate(deps)?;
+            to_json_binary(&query_estimate_amount_receive(
+                deps,
+                round,
+                slot,
+                bid_amount,
+                exchange_rate,
+            )?)
+        }
+        QueryMsg::PremiumSchedule { round } => {
+            to_json_binary(&query_premium_schedule(deps, round)?)
+        }
+        QueryMsg::FillForecast {
+            round,
+            exchange_rate,
+        } => to_json_binary(&query_fill_forecast(deps, round, exchange_rate)?),
+        QueryMsg::ClearingSlot {
+            round,
+            exchange_rate,
+        } => to_json_binary(&query_clearing_slot(deps, round, exchange_rate)?),
+        QueryMsg::Position { round, user } => {
+            to_json_binary(&query_position(deps, env, round, user)?)
+        }
+        QueryMsg::UserTotalMatched { user } => {
+            let user = deps.api.addr_validate(&user)?;
+            to_json_binary(
+                &USER_TOTAL_MATCHED
+                    .may_load(deps.storage, &user)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::UserRoi { user, rounds } => to_json_binary(&query_user_roi(deps, user, rounds)?),
+        QueryMsg::RoundResult { round } => to_json_binary(&query_round_result(deps, round)?),
+        QueryMsg::RoundSummary { round } => {
+            to_json_binary(&ROUND_SUMMARIES.may_load(deps.storage, round)?)
+        }
+        QueryMsg::RoundConfig { round } => to_json_binary(&read_round_config(deps.storage, round)?),
+        QueryMsg::MinExchangeRate { round } => {
+            to_json_binary(&MIN_EXCHANGE_RATES.may_load(deps.storage, round)?)
+        }
+        QueryMsg::OracleRateBand { round } => {
+            to_json_binary(&ORACLE_RATE_BANDS.may_load(deps.storage, round)?)
+        }
+        QueryMsg::VerifyRound { round } => to_json_binary(&query_verify_round(deps, round)?),
+        QueryMsg::DisputeParams {} => to_json_binary(&DISPUTE_PARAMS.may_load(deps.storage)?),
+        QueryMsg::Dispute { round } => to_json_binary(&DISPUTES.may_load(deps.storage, round)?),
+        QueryMsg::BidRateLimit {} => to_json_binary(&BID_RATE_LIMIT.may_load(deps.storage)?),
+        QueryMsg::PostFinalizeActions {} => to_json_binary(
+            &POST_FINALIZE_ACTIONS
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::TrustedRouters { start_after, limit } => {
+            let start_after = start_after
+                .map(|addr| deps.api.addr_validate(&addr))
+                .transpose()?;
+            to_json_binary(&read_trusted_routers(deps.storage, start_after, limit)?)
+        }
+        QueryMsg::ShadowBalances {} => to_json_binary(&query_shadow_balances(deps, env)?),
+        QueryMsg::UnaccountedBalances {} => to_json_binary(&query_unaccounted_balances(deps, env)?),
+        QueryMsg::CheckInvariants { round } => {
+            to_json_binary(&query_check_invariants(deps, round)?)
+        }
+        QueryMsg::IbcSatellite {} => to_json_binary(&IBC_SATELLITE.may_load(deps.storage)?),
+        QueryMsg::IcaRound {} => to_json_binary(&IcaRoundResponse {
+            ica_treasury: ICA_TREASURY.may_load(deps.storage)?,
+            requested_distribution: ICA_ROUND_REQUEST.may_load(deps.storage)?,
+            funded: ICA_FUNDING.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::IbcChannels {} => to_json_binary(&query_ibc_channels(deps)?),
+        QueryMsg::PauseFlags {} => to_json_binary(&read_pause_flags(deps.storage)?),
+        QueryMsg::RoundTemplate { name } => {
+            to_json_binary(&ROUND_TEMPLATES.load(deps.storage, &name)?)
+        }
+        QueryMsg::SlotBonuses { round } => to_json_binary(
+            &SLOT_BONUSES
+                .may_load(deps.storage, round)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::Payout { user } => {
+            let user = deps.api.addr_validate(&user)?;
+            to_json_binary(&PAYOUTS.may_load(deps.storage, &user)?.unwrap_or_default())
+        }
+        QueryMsg::BalanceOf { user } => {
+            let user = deps.api.addr_validate(&user)?;
+            to_json_binary(&DEPOSITS.may_load(deps.storage, &user)?.unwrap_or_default())
+        }
+        QueryMsg::BidNonce { bidder } => {
+            let bidder = deps.api.addr_validate(&bidder)?;
+            to_json_binary(
+                &BID_NONCE
+                    .may_load(deps.storage, &bidder)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::RoundIds { start_after, limit } => {
+            to_json_binary(&query_round_ids(deps, start_after, limit)?)
+        }
+        QueryMsg::BidIdxs { start_after, limit } => {
+            to_json_binary(&read_bid_idxs(deps.storage, start_after, limit)?)
+        }
+        QueryMsg::BiddersInRound {
+            round,
+            start_after,
+            limit,
+        } => {
+            let start_after = start_after
+                .map(|bidder| deps.api.addr_validate(&bidder))
+                .transpose()?;
+            to_json_binary(&read_bidders_in_round(
+                deps.storage,
+                round,
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::AllBidInRound {
+            round,
+            start_after,
+            limit,
+            order_by,
+        } => to_json_binary(&query_all_bid_in_round(
+            deps,
+            round,
+            start_after,
+            limit,
+            order_by,
+        )?),
+        QueryMsg::BidsBySlot {
+            round,
+            slot,
+            start_after,
+            limit,
+        } => to_json_binary(&query_bids_by_slot(deps, round, slot, start_after, limit)?),
         QueryMsg::BidsByUser { round, user } => {
             to_json_binary(&query_bids_by_user(deps, round, user)?)
         }
+        QueryMsg::BidsByTimeRange {
+            round,
+            from_ts,
+            to_ts,
+            limit,
+        } => to_json_binary(
+            &read_bids_by_time(deps.storage, round, from_ts, to_ts, limit)?
+                .into_iter()
+                .map(|idx| Ok(BidResponse::from(BID.load(deps.storage, idx)?)))
+                .collect::<StdResult<Vec<BidResponse>>>()?,
+        ),
+        QueryMsg::BidsByHeightRange {
+            round,
+            from,
+            to,
+            limit,
+        } => to_json_binary(
+            &read_bids_by_height(deps.storage, round, from, to, limit)?
+                .into_iter()
+                .map(|idx| Ok(BidResponse::from(BID.load(deps.storage, idx)?)))
+                .collect::<StdResult<Vec<BidResponse>>>()?,
+        ),
         QueryMsg::NumbersBidInRound { round } => {
-            to_json_binary(&count_number_bids_in_round(deps.storage, round))
+            to_json_binary(&count_number_bids_in_round(deps.storage, round)?)
+        }
+        QueryMsg::TotalBidAt { round, timestamp } => {
+            to_json_binary(&read_round_total_at(deps.storage, round, timestamp)?)
+        }
+        QueryMsg::BidPoolTotalAt {
+            round,
+            slot,
+            timestamp,
+        } => to_json_binary(&read_pool_total_at(deps.storage, round, slot, timestamp)?),
+        QueryMsg::TotalBidHistory {
+            round,
+            start_after,
+            limit,
+        } => to_json_binary(&query_total_bid_history(deps, round, start_after, limit)?),
+        QueryMsg::BurnHistory { start, end } => {
+            to_json_binary(&query_burn_history(deps, start, end)?)
+        }
+        QueryMsg::RoundStatus { round } => to_json_binary(&query_round_status(deps, env, round)?),
+        QueryMsg::EstimateAmountReceiveOfUser {
+            round,
+            user,
+            exchange_rate,
+        } => to_json_binary(&query_estimate_amount_receive_of_user(
+            deps,
+            round,
+            user,
+            exchange_rate,
+        )?),
+        QueryMsg::EstimateWithAdditionalBids {
+            round,
+            additions,
+            exchange_rate,
+        } => to_json_binary(&query_estimate_with_additional_bids(
+            deps,
+            round,
+            additions,
+            exchange_rate,
+        )?),
+        QueryMsg::SimulateMatching {
+            pools,
+            distribution,
+            exchange_rate,
+        } => to_json_binary(&query_simulate_matching(
+            deps,
+            pools,
+            distribution,
+            exchange_rate,
+        )?),
+    }
+}
+
+fn query_simulate_matching(
+    deps: Deps,
+    mut pools: Vec<BidPool>,
+    distribution: Uint128,
+    exchange_rate: Decimal,
+) -> StdResult<SimulateMatchingResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    for pool in pools.iter_mut() {
+        pool.index_snapshot = Decimal::zero();
+        pool.received_per_token = Decimal::zero();
+        pool.matched_amount = Uint128::zero();
+        pool.payout_amount = Uint128::zero();
+    }
+    let mut remaining = distribution;
+
+    let total_matched = if config.is_rebate() {
+        process_calc_rebate_amount(&mut pools, &mut remaining)
+    } else {
+        process_calc_distribution_amount(&mut pools, &mut remaining, exchange_rate)
+    }
+    .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(SimulateMatchingResponse {
+        pools,
+        total_matched,
+        remaining,
+    })
+}
+
+fn query_round_status(deps: Deps, env: Env, round: u64) -> StdResult<RoundStatus> {
+    Ok(match read_round_state(deps.storage, &env, round)? {
+        RoundState::Created => RoundStatus::Upcoming,
+        RoundState::Open => RoundStatus::Open,
+        RoundState::Ended => RoundStatus::Ended,
+        RoundState::Finalized | RoundState::Distributing => RoundStatus::Finalized,
+        RoundState::Completed => RoundStatus::FullyDistributed,
+        RoundState::Cancelled => RoundStatus::Cancelled,
+        RoundState::Failed => RoundStatus::Failed,
+    })
+}
+
+// a page holding more entries is followed by another one when an entry comes after its last one
+fn query_round_ids(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<PageResponse<u64>> {
+    let round_ids = read_round_ids(deps.storage, start_after, limit)?;
+    let last = round_ids.last().copied();
+    let has_more = match last {
+        Some(last) => !read_round_ids(deps.storage, Some(last), Some(1))?.is_empty(),
+        None => false,
+    };
+
+    Ok(PageResponse {
+        items: round_ids,
+        total: Some(
+            LAST_ROUND_ID.load(deps.storage)?
+                - NUM_PRUNED_ROUNDS
+                    .may_load(deps.storage)?
+                    .unwrap_or_default(),
+        ),
+        next_start_after: last.filter(|_| has_more),
+        has_more,
+    })
+}
+
+fn query_all_bid_in_round(
+    deps: Deps,
+    round: u64,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+    order_by: Option<i32>,
+) -> StdResult<PageResponse<BidResponse>> {
+    let bids_idx = read_bids_by_round(deps.storage, round, start_after, limit, order_by)?;
+    let last = bids_idx.last().copied();
+    let has_more = match last {
+        Some(last) => {
+            !read_bids_by_round(deps.storage, round, Some(last), Some(1), order_by)?.is_empty()
+        }
+        None => false,
+    };
+
+    Ok(PageResponse {
+        items: bids_idx
+            .into_iter()
+            .map(|idx| Ok(BidResponse::from(BID.load(deps.storage, idx)?)))
+            .collect::<StdResult<Vec<BidResponse>>>()?,
+        // rounds stored before the bids were counted are not scanned
+        total: NUM_BIDS_BY_ROUND.may_load(deps.storage, round)?,
+        next_start_after: last.filter(|_| has_more),
+        has_more,
+    })
+}
+
+fn query_bids_by_slot(
+    deps: Deps,
+    round: u64,
+    slot: u16,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<PageResponse<BidResponse>> {
+    let bids_idx = read_bids_in_slot(deps.storage, round, slot, start_after, limit)?;
+    let last = bids_idx.last().copied();
+    let has_more = match last {
+        Some(last) => {
+            !read_bids_in_slot(deps.storage, round, slot, Some(last), Some(1))?.is_empty()
         }
+        None => false,
+    };
+
+    Ok(PageResponse {
+        items: bids_idx
+            .into_iter()
+            .map(|idx| Ok(BidResponse::from(BID.load(deps.storage, idx)?)))
+            .collect::<StdResult<Vec<BidResponse>>>()?,
+        total: None,
+        next_start_after: last.filter(|_| has_more),
+        has_more,
+    })
+}
+
+fn query_round_result(deps: Deps, round: u64) -> StdResult<RoundResultResponse> {
+    let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+
+    if !distribution_info.is_released {
+        return Err(StdError::generic_err(format!(
+            "round {} has not been finalized",
+            round
+        )));
     }
+
+    let slots = bidding_info
+        .read_all_bid_pool(deps.storage)?
+        .into_iter()
+        .map(|bid_pool| SlotResult {
+            slot: bid_pool.slot,
+            total_bid_amount: bid_pool.total_bid_amount,
+            matched_percentage: bid_pool.index_snapshot,
+            matched_amount: bid_pool.matched_amount,
+            payout_amount: bid_pool.payout_amount,
+        })
+        .collect();
+
+    Ok(RoundResultResponse {
+        round,
+        exchange_rate: distribution_info.exchange_rate,
+        total_bid_amount: bidding_info.total_bid_amount,
+        total_matched: bidding_info.total_bid_matched,
+        total_distribution: distribution_info.total_distribution,
+        actual_distributed: distribution_info.actual_distributed,
+        leftover: distribution_info
+            .total_distribution
+            .checked_sub(distribution_info.actual_distributed)?,
+        slots,
+        num_bids: count_number_bids_in_round(deps.storage, round)?,
+        num_bids_distributed: distribution_info.num_bids_distributed,
+    })
+}
+
+fn query_verify_round(deps: Deps, round: u64) -> StdResult<VerifyRoundResponse> {
+    let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    let payout_totals = PAYOUT_TOTALS
+        .may_load(deps.storage, round)?
+        .unwrap_or_default();
+    let total_unmatched = bidding_info
+        .total_bid_amount
+        .checked_sub(bidding_info.total_bid_matched)?;
+    let max_received = CONFIG
+        .load(deps.storage)?
+        .max_received(&distribution_info, &bidding_info)?;
+
+    Ok(VerifyRoundResponse {
+        round,
+        total_received: payout_totals.total_received,
+        actual_distributed: distribution_info.actual_distributed,
+        total_residue: payout_totals.total_residue,
+        total_unmatched,
+        num_bids_distributed: distribution_info.num_bids_distributed,
+        is_valid: payout_totals.total_received <= max_received
+            && payout_totals.total_residue <= total_unmatched,
+    })
+}
+
+fn query_burn_history(deps: Deps, start: u64, end: u64) -> StdResult<BurnHistoryResponse> {
+    let mut total_underlying_burned = Uint128::zero();
+    let mut total_distribution_paid = Uint128::zero();
+
+    let epochs = read_burn_stats(deps.storage, start, end)?
+        .into_iter()
+        .map(|(epoch, stats)| {
+            total_underlying_burned =
+                total_underlying_burned.checked_add(stats.underlying_burned)?;
+            total_distribution_paid =
+                total_distribution_paid.checked_add(stats.distribution_paid)?;
+            Ok(EpochBurnStats {
+                epoch_start: epoch * EPOCH_DURATION,
+                underlying_burned: stats.underlying_burned,
+                distribution_paid: stats.distribution_paid,
+            })
+        })
+        .collect::<StdResult<Vec<EpochBurnStats>>>()?;
+
+    Ok(BurnHistoryResponse {
+        total_underlying_burned,
+        total_distribution_paid,
+        epochs,
+    })
+}
+
+fn query_total_bid_history(
+    deps: Deps,
+    round: u64,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<TotalBidCheckpoint>> {
+    let history = read_round_total_history(deps.storage, round, start_after, limit)?;
+
+    Ok(history
+        .into_iter()
+        .map(|(timestamp, total_bid_amount)| TotalBidCheckpoint {
+            timestamp,
+            total_bid_amount,
+        })
+        .collect())
 }
 
 fn query_bidding_info(deps: Deps, round: u64) -> StdResult<BiddingInfoResponse> {
@@ -291,104 +2020,743 @@ fn query_bidding_info(deps: Deps, round: u64) -> StdResult<BiddingInfoResponse>
     })
 }
 
-fn query_bids_by_user(deps: Deps, round: u64, user: Addr) -> StdResult<Vec<Bid>> {
+// the distribution token is only listed once in rebate rounds
+fn tracked_assets(config: &Config) -> Vec<AssetInfo> {
+    let mut assets = vec![config.underlying_token.clone()];
+    if !config.is_rebate() {
+        assets.push(config.distribution_token.clone());
+    }
+    assets
+}
+
+fn query_shadow_balances(deps: Deps, env: Env) -> StdResult<Vec<ShadowBalanceResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+    let idle_funds = YIELD_POSITION
+        .may_load(deps.storage)?
+        .map_or(Uint128::zero(), |position| position.principal);
+    let lent_distribution = LENT_DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .try_fold(Uint128::zero(), |total, item| -> StdResult<Uint128> {
+            Ok(total.checked_add(item?.1)?)
+        })?;
+
+    tracked_assets(&config)
+        .into_iter()
+        .map(|asset_info| {
+            let mut escrowed = Uint128::zero();
+            if asset_info == config.underlying_token {
+                escrowed = escrowed.checked_add(idle_funds)?;
+            }
+            if asset_info == config.distribution_token {
+                escrowed = escrowed.checked_add(lent_distribution)?;
+            }
+            Ok(ShadowBalanceResponse {
+                expected: read_shadow_balance(deps.storage, &asset_info)?,
+                actual: query_balance(&deps.querier, &asset_info, env.contract.address.clone())?,
+                escrowed,
+                asset_info,
+            })
+        })
+        .collect()
+}
+
+// the underlying and distribution tokens are owed up to their shadow balances, other assets up
+// to the dispute bonds, the pending slot bonuses and the other leg of undistributed LP bids
+fn query_unaccounted_balances(deps: Deps, env: Env) -> StdResult<Vec<UnaccountedBalanceResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut obligations: Vec<(AssetInfo, Uint128)> = vec![];
+    let mut owe = |asset_info: AssetInfo, amount: Uint128| -> StdResult<()> {
+        if config.is_tracked(&asset_info) {
+            return Ok(());
+        }
+        match obligations.iter_mut().find(|(info, _)| info == &asset_info) {
+            Some((_, total)) => *total = total.checked_add(amount)?,
+            None => obligations.push((asset_info, amount)),
+        }
+        Ok(())
+    };
+
+    for item in DISPUTES.range(deps.storage, None, None, Order::Ascending) {
+        let (_, dispute) = item?;
+        owe(
+            AssetInfo::NativeToken {
+                denom: dispute.bond.denom,
+            },
+            dispute.bond.amount,
+        )?;
+    }
+    for item in SLOT_BONUSES.range(deps.storage, None, None, Order::Ascending) {
+        for bonus in item?.1 {
+            owe(bonus.asset.info, bonus.asset.amount)?;
+        }
+    }
+    for item in LP_UNSETTLED_SHARES.range(deps.storage, None, None, Order::Ascending) {
+        let (round, unsettled) = item?;
+        let rate = LP_WITHDRAW_RATES.load(deps.storage, round)?;
+        owe(
+            rate.other_asset,
+            checked_mul_decimal(unsettled, rate.other_per_share)?,
+        )?;
+    }
+    for asset_info in tracked_assets(&config) {
+        let expected = read_shadow_balance(deps.storage, &asset_info)?;
+        obligations.push((asset_info, expected));
+    }
+    // native tokens sent directly are only found through the bank balances
+    for coin in deps
+        .querier
+        .query_all_balances(env.contract.address.clone())?
+    {
+        let asset_info = AssetInfo::NativeToken { denom: coin.denom };
+        if !obligations.iter().any(|(info, _)| info == &asset_info) {
+            obligations.push((asset_info, Uint128::zero()));
+        }
+    }
+
+    let mut balances = vec![];
+    for (asset_info, obligations) in obligations {
+        let balance = query_balance(&deps.querier, &asset_info, env.contract.address.clone())?;
+        let unaccounted = balance.saturating_sub(obligations);
+        if !unaccounted.is_zero() {
+            balances.push(UnaccountedBalanceResponse {
+                asset_info,
+                balance,
+                obligations,
+                unaccounted,
+            });
+        }
+    }
+    Ok(balances)
+}
+
+fn query_check_invariants(deps: Deps, round: u64) -> StdResult<InvariantsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let tally = tally_round_bids(deps.storage, round)?;
+
+    let mut mismatches = vec![];
+    let mut check = |invariant: &str, slot: Option<u16>, stored: Uint128, computed: Uint128| {
+        if stored != computed {
+            mismatches.push(InvariantMismatch {
+                invariant: invariant.to_string(),
+                slot,
+                stored,
+                computed,
+            });
+        }
+    };
+
+    check(
+        "total_bid_amount",
+        None,
+        bidding_info.total_bid_amount,
+        tally.total_bid_amount,
+    );
+    let mut pool_totals = tally.pool_totals.clone();
+    for bid_pool in BID_POOL
+        .prefix(round)
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (slot, bid_pool) = bid_pool?;
+        let computed = pool_totals.remove(&slot).unwrap_or_default();
+        check(
+            "pool_total_bid_amount",
+            Some(slot),
+            bid_pool.total_bid_amount,
+            computed,
+        );
+    }
+    // bids in a slot without pool
+    for (slot, computed) in pool_totals {
+        check(
+            "pool_total_bid_amount",
+            Some(slot),
+            Uint128::zero(),
+            computed,
+        );
+    }
+    check(
+        "num_bids",
+        None,
+        count_number_bids_in_round(deps.storage, round)?.into(),
+        tally.num_bids.into(),
+    );
+    check(
+        "num_bidders",
+        None,
+        count_number_bidders_in_round(deps.storage, round)?.into(),
+        tally.num_bidders.into(),
+    );
+
+    if let Some(distribution_info) = DISTRIBUTION_INFO.may_load(deps.storage, round)? {
+        check(
+            "num_bids_distributed",
+            None,
+            distribution_info.num_bids_distributed.into(),
+            tally.num_distributed.into(),
+        );
+        let payout_totals = PAYOUT_TOTALS
+            .may_load(deps.storage, round)?
+            .unwrap_or_default();
+        check(
+            "total_received",
+            None,
+            payout_totals.total_received,
+            tally.total_received,
+        );
+        check(
+            "total_residue",
+            None,
+            payout_totals.total_residue,
+            tally.total_residue,
+        );
+        // payouts may stay below the matching because of rounding, never above it
+        if distribution_info.is_released {
+            let max_received = config.max_received(&distribution_info, &bidding_info)?;
+            if tally.total_received > max_received {
+                check("max_received", None, max_received, tally.total_received);
+            }
+        }
+    }
+
+    Ok(InvariantsResponse {
+        round,
+        is_consistent: mismatches.is_empty(),
+        mismatches,
+    })
+}
+
+fn query_bids_by_user(deps: Deps, round: u64, user: String) -> StdResult<Vec<BidResponse>> {
+    let user = deps.api.addr_validate(&user)?;
     let bids_idx = BIDS_BY_USER.load(deps.storage, (round, user))?;
 
-    let bids: Vec<Bid> = bids_idx
+    let bids: Vec<BidResponse> = bids_idx
         .iter()
-        .map(|idx| BID.load(deps.storage, *idx))
+        .map(|idx| BID.load(deps.storage, *idx).map(BidResponse::from))
         .collect::<StdResult<_>>()?;
 
     Ok(bids)
 }
 
-fn query_all_bid_pool_in_round(deps: Deps, round: u64) -> StdResult<Vec<BidPool>> {
+// (amount_received, residue_bid, performance_fee) of a bid of a finalized round, bids not
+// distributed yet are settled from the finalized pool snapshot
+fn settle_bid(
+    deps: Deps,
+    bid: &Bid,
+    distribution_info: &DistributionInfo,
+    fee_rate: Option<Decimal>,
+) -> StdResult<(Uint128, Uint128, Uint128)> {
+    if bid.is_distributed {
+        return Ok((bid.amount_received, bid.residue_bid, bid.performance_fee));
+    }
+
+    let bid_pool = BID_POOL.load(deps.storage, (bid.round, bid.premium_slot))?;
+    let (amount_received, residue_bid) = split_bid_amount(
+        bid.amount,
+        bid_pool.index_snapshot,
+        bid_pool.received_per_token,
+    )?;
+    let fee = performance_fee(
+        amount_received,
+        bid.amount.checked_sub(residue_bid)?,
+        distribution_info.exchange_rate,
+        fee_rate,
+    )?;
+    Ok((amount_received, residue_bid, fee))
+}
+
+// the bids of the user in the round with their status, and the totals of the round
+fn query_position(deps: Deps, env: Env, round: u64, user: String) -> StdResult<PositionResponse> {
+    let user = deps.api.addr_validate(&user)?;
+    let round_status = query_round_status(deps, env, round)?;
+    let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+    let fee_rate = read_round_config(deps.storage, round)?.performance_fee;
+    let bids_idx = BIDS_BY_USER
+        .may_load(deps.storage, (round, user.clone()))?
+        .unwrap_or_default();
+
+    let mut res = PositionResponse {
+        round,
+        user,
+        round_status,
+        bids: vec![],
+        total_bid_amount: Uint128::zero(),
+        total_matched: Uint128::zero(),
+        total_received: Uint128::zero(),
+        total_residue: Uint128::zero(),
+        total_pending: Uint128::zero(),
+    };
+    for idx in bids_idx {
+        let bid = BID.load(deps.storage, idx)?;
+        let position = if distribution_info.is_released {
+            let (amount_received, residue_bid, fee) =
+                settle_bid(deps, &bid, &distribution_info, fee_rate)?;
+            let matched_amount = bid.amount.checked_sub(residue_bid)?;
+            let status = if matched_amount.is_zero() {
+                BidStatus::Refunded
+            } else if residue_bid.is_zero() {
+                BidStatus::Matched
+            } else {
+                BidStatus::PartiallyMatched
+            };
+            let received_amount = amount_received.checked_sub(fee)?;
+            // paid by the next Distribute, the residue can also be claimed right away
+            let (pending_reward, claimable_residue) = if bid.is_distributed {
+                (Uint128::zero(), Uint128::zero())
+            } else if bid.residue_claimed {
+                (received_amount, Uint128::zero())
+            } else {
+                (received_amount, residue_bid)
+            };
+            BidPosition {
+                idx,
+                premium_slot: bid.premium_slot,
+                amount: bid.amount,
+                status,
+                matched_amount,
+                received_amount,
+                residue_amount: residue_bid,
+                pending_reward,
+                claimable_residue,
+            }
+        } else {
+            BidPosition {
+                idx,
+                premium_slot: bid.premium_slot,
+                amount: bid.amount,
+                status: BidStatus::Open,
+                matched_amount: Uint128::zero(),
+                received_amount: Uint128::zero(),
+                residue_amount: Uint128::zero(),
+                pending_reward: Uint128::zero(),
+                claimable_residue: Uint128::zero(),
+            }
+        };
+
+        res.total_bid_amount = res.total_bid_amount.checked_add(position.amount)?;
+        res.total_matched = res.total_matched.checked_add(position.matched_amount)?;
+        res.total_received = res.total_received.checked_add(position.received_amount)?;
+        res.total_residue = res.total_residue.checked_add(position.residue_amount)?;
+        res.total_pending = res.total_pending.checked_add(position.pending_reward)?;
+        res.bids.push(position);
+    }
+
+    Ok(res)
+}
+
+// realized premium of the user in every finalized round of `rounds` the user bid in
+fn query_user_roi(deps: Deps, user: String, rounds: Vec<u64>) -> StdResult<Vec<UserRoundRoi>> {
+    let user = deps.api.addr_validate(&user)?;
+    let mut res = vec![];
+
+    for round in rounds {
+        let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+        if !distribution_info.is_released {
+            continue;
+        }
+        let Some(bids_idx) = BIDS_BY_USER.may_load(deps.storage, (round, user.clone()))? else {
+            continue;
+        };
+
+        let fee_rate = read_round_config(deps.storage, round)?.performance_fee;
+        let mut matched_amount = Uint128::zero();
+        let mut received_amount = Uint128::zero();
+        for idx in bids_idx {
+            let bid = BID.load(deps.storage, idx)?;
+            let (amount_received, residue_bid, fee) =
+                settle_bid(deps, &bid, &distribution_info, fee_rate)?;
+            matched_amount = matched_amount.checked_add(bid.amount.checked_sub(residue_bid)?)?;
+            received_amount = received_amount.checked_add(amount_received.checked_sub(fee)?)?;
+        }
+
+        let matched_value = checked_mul_decimal(matched_amount, distribution_info.exchange_rate)?;
+        let realized_premium = if matched_value.is_zero() {
+            Decimal::zero()
+        } else {
+            Decimal::checked_from_ratio(received_amount, matched_value)
+                .map_err(|err| StdError::generic_err(err.to_string()))?
+                .saturating_sub(Decimal::one())
+        };
+
+        res.push(UserRoundRoi {
+            round,
+            exchange_rate: distribution_info.exchange_rate,
+            matched_amount,
+            received_amount,
+            realized_premium,
+        });
+    }
+
+    Ok(res)
+}
+
+fn query_all_bid_pool_in_round(
+    deps: Deps,
+    round: u64,
+    start_after: Option<u16>,
+    limit: Option<u64>,
+) -> StdResult<Vec<BidPoolResponse>> {
     let bid_info = BIDDING_INFO.load(deps.storage, round)?;
 
-    bid_info.read_all_bid_pool(deps.storage)
+    Ok(bid_info
+        .read_bid_pools(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(BidPoolResponse::from)
+        .collect())
 }
 
-fn query_estimate_amount_receive_of_bid(
+fn query_bid_pools_by_rounds(
+    deps: Deps,
+    rounds: Vec<u64>,
+) -> StdResult<Vec<RoundBidPoolsResponse>> {
+    if rounds.len() > MAX_ROUNDS_PER_QUERY {
+        return Err(StdError::generic_err(format!(
+            "At most {} rounds can be queried at once",
+            MAX_ROUNDS_PER_QUERY
+        )));
+    }
+
+    rounds
+        .into_iter()
+        .map(|round| {
+            let bid_pools = BIDDING_INFO
+                .load(deps.storage, round)?
+                .read_all_bid_pool(deps.storage)?
+                .into_iter()
+                .map(BidPoolResponse::from)
+                .collect();
+            Ok(RoundBidPoolsResponse { round, bid_pools })
+        })
+        .collect()
+}
+
+// current price of underlying_token in distribution_token, read from the oracle pair
+fn query_market_exchange_rate(deps: Deps) -> StdResult<Decimal> {
+    let config = CONFIG.load(deps.storage)?;
+    let oracle_pair = config
+        .oracle_pair
+        .ok_or_else(|| StdError::generic_err("Oracle pair is not configured"))?;
+
+    query_pair_exchange_rate(
+        &deps.querier,
+        &oracle_pair,
+        &config.underlying_token,
+        &config.distribution_token,
+    )
+}
+
+// simulate the matching of a round at exchange_rate, with extra bid amounts added to
+// some slots, returning (index_snapshot, received_per_token) indexed by slot
+fn simulate_round_matching(
     deps: Deps,
     round: u64,
-    idx: u64,
     exchange_rate: Decimal,
-) -> StdResult<EstimateAmountReceiveOfBidResponse> {
+    additions: &[(u16, Uint128)],
+) -> StdResult<Vec<(Decimal, Decimal)>> {
     let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
     let config = CONFIG.load(deps.storage)?;
-    let bid = BID.load(deps.storage, idx)?;
     let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
-    let mut bid_pools = bidding_info.read_all_bid_pool(deps.storage)?;
     let mut distribution_amount = distribution_info.total_distribution;
+    let mut bid_pools = bidding_info.read_all_bid_pool(deps.storage)?;
+    for (slot, amount) in additions {
+        if let Some(bid_pool) = bid_pools.iter_mut().find(|bid_pool| bid_pool.slot == *slot) {
+            bid_pool.total_bid_amount = bid_pool.total_bid_amount.checked_add(*amount)?;
+        }
+    }
 
-    process_calc_distribution_amount(&mut bid_pools, &mut distribution_amount, exchange_rate)?;
-
-    let mut index_snapshot = vec![Decimal::zero(); config.max_slot as usize + 1];
-    let mut receiver_per_token = vec![Decimal::zero(); config.max_slot as usize + 1];
+    if config.is_rebate() {
+        process_calc_rebate_amount(&mut bid_pools, &mut distribution_amount)
+    } else {
+        process_calc_distribution_amount(&mut bid_pools, &mut distribution_amount, exchange_rate)
+    }
+    .map_err(|err| StdError::generic_err(err.to_string()))?;
 
+    let mut snapshots = vec![(Decimal::zero(), Decimal::zero()); config.max_slot as usize + 1];
     for bid_pool in bid_pools {
-        index_snapshot[bid_pool.slot as usize] = bid_pool.index_snapshot;
-        receiver_per_token[bid_pool.slot as usize] = bid_pool.received_per_token;
+        snapshots[bid_pool.slot as usize] = (bid_pool.index_snapshot, bid_pool.received_per_token);
     }
 
-    let amount_received =
-        bid.amount * receiver_per_token[bid.premium_slot as usize] * Uint128::one();
-    let residue_bid = bid.amount * (Decimal::one() - index_snapshot[bid.premium_slot as usize]);
+    Ok(snapshots)
+}
+
+fn estimate_bid(
+    snapshots: &[(Decimal, Decimal)],
+    slot: u16,
+    amount: Uint128,
+    exchange_rate: Decimal,
+    fee_rate: Option<Decimal>,
+) -> StdResult<EstimateAmountReceiveOfBidResponse> {
+    let (index_snapshot, received_per_token) =
+        snapshots.get(slot as usize).copied().unwrap_or_default();
+    let (receive, residue_bid) = split_bid_amount(amount, index_snapshot, received_per_token)?;
+    let fee = performance_fee(
+        receive,
+        amount.checked_sub(residue_bid)?,
+        exchange_rate,
+        fee_rate,
+    )?;
 
     Ok(EstimateAmountReceiveOfBidResponse {
-        receive: amount_received,
+        receive: receive.checked_sub(fee)?,
         residue_bid,
+        performance_fee: fee,
     })
 }
 
+fn query_fill_forecast(
+    deps: Deps,
+    round: u64,
+    exchange_rate: Decimal,
+) -> StdResult<Vec<FillForecastEntry>> {
+    let snapshots = simulate_round_matching(deps, round, exchange_rate, &[])?;
+
+    Ok(BIDDING_INFO
+        .load(deps.storage, round)?
+        .read_all_bid_pool(deps.storage)?
+        .into_iter()
+        .map(|bid_pool| {
+            let matched_ratio = snapshots[bid_pool.slot as usize].0;
+            let fill = if matched_ratio.is_zero() {
+                SlotFill::Unmatched {}
+            } else if matched_ratio >= Decimal::one() {
+                SlotFill::Full {}
+            } else {
+                SlotFill::Partial { matched_ratio }
+            };
+
+            FillForecastEntry {
+                slot: bid_pool.slot,
+                total_bid_amount: bid_pool.total_bid_amount,
+                fill,
+            }
+        })
+        .collect())
+}
+
+fn query_clearing_slot(
+    deps: Deps,
+    round: u64,
+    exchange_rate: Decimal,
+) -> StdResult<ClearingSlotResponse> {
+    let snapshots = simulate_round_matching(deps, round, exchange_rate, &[])?;
+
+    // slot 0 is unused, pools are matched from the lowest slot up
+    Ok(snapshots
+        .iter()
+        .enumerate()
+        .skip(1)
+        .rev()
+        .find(|(_, (index_snapshot, _))| !index_snapshot.is_zero())
+        .map_or(
+            ClearingSlotResponse {
+                slot: None,
+                fill: Decimal::zero(),
+            },
+            |(slot, (index_snapshot, _))| ClearingSlotResponse {
+                slot: Some(slot as u16),
+                fill: *index_snapshot,
+            },
+        ))
+}
+
+fn query_premium_schedule(deps: Deps, round: u64) -> StdResult<Vec<PremiumScheduleEntry>> {
+    let config = CONFIG.load(deps.storage)?;
+    let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
+
+    // once finalized the fill is known, before that it is projected at the market rate
+    let snapshots = if distribution_info.is_released || config.oracle_pair.is_none() {
+        None
+    } else {
+        let exchange_rate = query_market_exchange_rate(deps)?;
+        Some(simulate_round_matching(deps, round, exchange_rate, &[])?)
+    };
+
+    Ok(bidding_info
+        .read_all_bid_pool(deps.storage)?
+        .into_iter()
+        .map(|bid_pool| {
+            let expected_fill = if distribution_info.is_released {
+                Some(bid_pool.index_snapshot)
+            } else {
+                snapshots
+                    .as_ref()
+                    .map(|snapshots| snapshots[bid_pool.slot as usize].0)
+            };
+
+            PremiumScheduleEntry {
+                slot: bid_pool.slot,
+                premium_rate: bid_pool.premium_rate,
+                total_bid_amount: bid_pool.total_bid_amount,
+                discount: bid_pool.premium_rate / (Decimal::one() + bid_pool.premium_rate),
+                expected_fill,
+            }
+        })
+        .collect())
+}
+
+fn query_estimate_amount_receive_of_bid(
+    deps: Deps,
+    round: u64,
+    idx: u64,
+    exchange_rate: Decimal,
+) -> StdResult<EstimateAmountReceiveOfBidResponse> {
+    let bid = BID.load(deps.storage, idx)?;
+    let snapshots = simulate_round_matching(deps, round, exchange_rate, &[])?;
+    let fee_rate = read_round_config(deps.storage, round)?.performance_fee;
+
+    estimate_bid(
+        &snapshots,
+        bid.premium_slot,
+        bid.amount,
+        exchange_rate,
+        fee_rate,
+    )
+}
+
 fn query_estimate_amount_receive(
     deps: Deps,
     round: u64,
-    slot: u8,
+    slot: u16,
     bid_amount: Uint128,
     exchange_rate: Decimal,
 ) -> StdResult<EstimateAmountReceiveOfBidResponse> {
-    let distribution_info = DISTRIBUTION_INFO.load(deps.storage, round)?;
-    let config = CONFIG.load(deps.storage)?;
-    let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
-    let mut distribution_amount = distribution_info.total_distribution;
-    let mut bid_pools = bidding_info.read_all_bid_pool(deps.storage)?;
-    for id in 0..bid_pools.len() {
-        if bid_pools[id].slot == slot {
-            bid_pools[id].total_bid_amount += bid_amount;
-            break;
-        }
-    }
+    let snapshots = simulate_round_matching(deps, round, exchange_rate, &[(slot, bid_amount)])?;
+    let fee_rate = read_round_config(deps.storage, round)?.performance_fee;
 
-    process_calc_distribution_amount(&mut bid_pools, &mut distribution_amount, exchange_rate)?;
+    estimate_bid(&snapshots, slot, bid_amount, exchange_rate, fee_rate)
+}
+
+fn query_estimate_with_additional_bids(
+    deps: Deps,
+    round: u64,
+    additions: Vec<(u16, Uint128)>,
+    exchange_rate: Decimal,
+) -> StdResult<EstimateWithAdditionalBidsResponse> {
+    let snapshots = simulate_round_matching(deps, round, exchange_rate, &additions)?;
+    let fee_rate = read_round_config(deps.storage, round)?.performance_fee;
 
-    let mut index_snapshot = vec![Decimal::zero(); config.max_slot as usize + 1];
-    let mut receiver_per_token = vec![Decimal::zero(); config.max_slot as usize + 1];
+    Ok(EstimateWithAdditionalBidsResponse {
+        additions: additions
+            .iter()
+            .map(|(slot, amount)| estimate_bid(&snapshots, *slot, *amount, exchange_rate, fee_rate))
+            .collect::<StdResult<_>>()?,
+        slots: snapshots
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(
+                |(slot, (index_snapshot, received_per_token))| SlotFillEstimate {
+                    slot: slot as u16,
+                    index_snapshot: *index_snapshot,
+                    received_per_token: *received_per_token,
+                },
+            )
+            .collect(),
+    })
+}
 
-    for bid_pool in bid_pools {
-        index_snapshot[bid_pool.slot as usize] = bid_pool.index_snapshot;
-        receiver_per_token[bid_pool.slot as usize] = bid_pool.received_per_token;
+fn query_estimate_amount_receive_of_user(
+    deps: Deps,
+    round: u64,
+    user: String,
+    exchange_rate: Decimal,
+) -> StdResult<EstimateAmountReceiveOfBidResponse> {
+    let user = deps.api.addr_validate(&user)?;
+    let bids_idx = BIDS_BY_USER
+        .may_load(deps.storage, (round, user))?
+        .unwrap_or_default();
+    let snapshots = simulate_round_matching(deps, round, exchange_rate, &[])?;
+    let fee_rate = read_round_config(deps.storage, round)?.performance_fee;
+
+    let mut total = EstimateAmountReceiveOfBidResponse {
+        receive: Uint128::zero(),
+        residue_bid: Uint128::zero(),
+        performance_fee: Uint128::zero(),
+    };
+    for idx in bids_idx {
+        let bid = BID.load(deps.storage, idx)?;
+        let estimate = estimate_bid(
+            &snapshots,
+            bid.premium_slot,
+            bid.amount,
+            exchange_rate,
+            fee_rate,
+        )?;
+        total.receive = total.receive.checked_add(estimate.receive)?;
+        total.residue_bid = total.residue_bid.checked_add(estimate.residue_bid)?;
+        total.performance_fee = total
+            .performance_fee
+            .checked_add(estimate.performance_fee)?;
     }
 
-    let amount_received = bid_amount * receiver_per_token[slot as usize] * Uint128::one();
-    let residue_bid = bid_amount * (Decimal::one() - index_snapshot[slot as usize]);
+    Ok(total)
+}
 
-    Ok(EstimateAmountReceiveOfBidResponse {
-        receive: amount_received,
-        residue_bid,
-    })
+// a failing notifier must not revert the distribution of the other bids
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        NOTIFY_REPLY_ID => Ok(Response::new().add_attribute("action", "bid_notification_failed")),
+        IBC_PACKET_REPLY_ID => reply_bid_packet(msg),
+        TRANSFER_REPLY_ID => reply_transfer(deps, env),
+        LSD_REDEEM_REPLY_ID => reply_lsd_redeem(deps, env),
+        REINVEST_REPLY_ID => reply_reinvest(deps, env, msg.result),
+        id => Err(ContractError::Std(StdError::generic_err(format!(
+            "Unknown reply id {}",
+            id
+        )))),
+    }
 }
-#[entry_point]
+
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    init_counters(deps.storage)?;
+
+    let msg = match msg {
+        MigrateMsg::UpdateConfig(msg) => *msg,
+        MigrateMsg::State(StateMigrateMsg::Backfill {}) => {
+            MIGRATION_CURSOR.save(deps.storage, &0)?;
+            return Ok(Response::new().add_attribute("action", "start_backfill"));
+        }
+        MigrateMsg::State(StateMigrateMsg::WidenSlots {}) => {
+            SLOT_KEY_MIGRATION.save(deps.storage, &true)?;
+            return Ok(Response::new().add_attribute("action", "start_widen_slots"));
+        }
+    };
+
     let config = Config {
-        owner: msg.owner,
+        owner: deps.api.addr_validate(&msg.owner)?,
         underlying_token: msg.underlying_token,
         distribution_token: msg.distribution_token,
         max_slot: msg.max_slot,
         premium_rate_per_slot: msg.premium_rate_per_slot,
         min_deposit_amount: msg.min_deposit_amount,
-        treasury: msg.treasury,
+        treasury: deps.api.addr_validate(&msg.treasury)?,
         bidding_duration: msg.bidding_duration,
+        oracle_pair: msg
+            .oracle_pair
+            .map(|pair| deps.api.addr_validate(&pair))
+            .transpose()?,
+        vesting_contract: msg
+            .vesting_contract
+            .map(|vesting_contract| deps.api.addr_validate(&vesting_contract))
+            .transpose()?,
+        keeper_registry: msg
+            .keeper_registry
+            .map(|keeper_registry| deps.api.addr_validate(&keeper_registry))
+            .transpose()?,
+        staking_contract: msg
+            .staking_contract
+            .map(|staking_contract| deps.api.addr_validate(&staking_contract))
+            .transpose()?,
+        residue_staking: msg
+            .residue_staking
+            .map(|residue_staking| deps.api.addr_validate(&residue_staking))
+            .transpose()?,
+        wrapped_denom: msg.wrapped_denom,
+        min_gap_between_rounds: msg.min_gap_between_rounds.unwrap_or_default(),
     };
 
     // store config
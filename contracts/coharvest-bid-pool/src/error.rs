@@ -1,7 +1,10 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{CheckedFromRatioError, Decimal, OverflowError, StdError, Uint128};
+use cw_controllers::HookError;
 use cw_utils::PaymentError;
 use thiserror::Error;
 
+use crate::state::RoundState;
+
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
@@ -9,8 +12,12 @@ pub enum ContractError {
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
-    #[error("OverflowError")]
-    Overflow {},
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+    #[error("{0}")]
+    CheckedFromRatio(#[from] CheckedFromRatioError),
+    #[error("{0}")]
+    Hook(#[from] HookError),
 
     #[error("Unauthorized")]
     Unauthorized {},
@@ -21,6 +28,9 @@ pub enum ContractError {
     #[error("Invalid funds")]
     InvalidFunds {},
 
+    #[error("Invalid params: {reason}")]
+    InvalidParams { reason: String },
+
     #[error("Bidding round is not opening")]
     BidNotOpen {},
 
@@ -29,4 +39,43 @@ pub enum ContractError {
 
     #[error("This round has ended")]
     RoundEnded {},
+
+    #[error("Invalid signature")]
+    InvalidSignature {},
+
+    #[error("Invalid nonce, expected {expected}")]
+    InvalidNonce { expected: u64 },
+
+    #[error("Signed bid has expired")]
+    SignatureExpired {},
+
+    #[error("{operation} is paused")]
+    Paused { operation: String },
+
+    #[error("Too many bids, at most {max_bids} every {window} seconds")]
+    RateLimited { max_bids: u32, window: u64 },
+
+    #[error("Round {round} is {state:?}")]
+    InvalidRoundState { round: u64, state: RoundState },
+
+    #[error("Round {round} is disputed")]
+    RoundDisputed { round: u64 },
+
+    #[error("Slot key migration in progress, run ContinueMigration until it finishes")]
+    SlotKeyMigrationInProgress {},
+
+    #[error("Payouts of round {round} would exceed the distributed amount")]
+    PayoutExceedsDistribution { round: u64 },
+
+    #[error("Residues of round {round} would exceed the unmatched amount")]
+    ResidueExceedsUnmatched { round: u64 },
+
+    #[error("Insufficient deposit balance, available {balance}")]
+    InsufficientBalance { balance: Uint128 },
+
+    #[error("Exchange rate {exchange_rate} is below the committed minimum {min_exchange_rate}")]
+    ExchangeRateBelowMinimum {
+        exchange_rate: Decimal,
+        min_exchange_rate: Decimal,
+    },
 }
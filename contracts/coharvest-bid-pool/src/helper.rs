@@ -1,6 +1,84 @@
-use cosmwasm_std::{to_json_binary, BankMsg, Coin, CosmosMsg, StdResult, Uint128, WasmMsg};
-use cw20::Cw20ExecuteMsg;
-use oraiswap::asset::AssetInfo;
+use cosmwasm_std::{
+    attr, from_json, to_json_binary, Addr, Attribute, BankMsg, Binary, Coin, CosmosMsg, Decimal,
+    QuerierWrapper, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+use oraiswap::{
+    asset::{Asset, AssetInfo},
+    pair::{PoolResponse, QueryMsg as PairQueryMsg},
+};
+
+use crate::{
+    msg::{BidPoolHookMsg, HookExecuteMsg, WrapperExecuteMsg},
+    state::{
+        credit_shadow_balance, debit_shadow_balance, ActionAmount, BiddingInfo, Config,
+        DistributionInfo, PostFinalizeAction, HOOKS, POST_FINALIZE_ACTIONS,
+    },
+};
+
+// notifies every registered hook contract of msg
+pub fn prepare_hook_msgs(storage: &dyn Storage, msg: BidPoolHookMsg) -> StdResult<Vec<SubMsg>> {
+    HOOKS.prepare_hooks(storage, |hook| {
+        Ok(SubMsg::new(WasmMsg::Execute {
+            contract_addr: hook.to_string(),
+            msg: to_json_binary(&HookExecuteMsg::BidPoolHook(msg.clone()))?,
+            funds: vec![],
+        }))
+    })
+}
+
+// messages of the post-finalize actions, with the amounts of the finalized round, actions
+// moving a zero amount are skipped
+pub fn prepare_post_finalize_msgs(
+    storage: &dyn Storage,
+    bidding_info: &BiddingInfo,
+    distribution_info: &DistributionInfo,
+) -> StdResult<Vec<CosmosMsg>> {
+    let leftover = distribution_info
+        .total_distribution
+        .checked_sub(distribution_info.actual_distributed)?;
+    let resolve = |amount: &ActionAmount| match amount {
+        ActionAmount::Fixed { amount } => *amount,
+        ActionAmount::Matched {} => bidding_info.total_bid_matched,
+        ActionAmount::Distributed {} => distribution_info.actual_distributed,
+        ActionAmount::Leftover {} => leftover,
+    };
+
+    let mut msgs = vec![];
+    for action in POST_FINALIZE_ACTIONS.may_load(storage)?.unwrap_or_default() {
+        match action {
+            PostFinalizeAction::Transfer {
+                asset_info,
+                recipient,
+                amount,
+            } => {
+                let amount = resolve(&amount);
+                if !amount.is_zero() {
+                    msgs.push(into_cosmos_msg(&asset_info, recipient, amount)?);
+                }
+            }
+            PostFinalizeAction::Send {
+                asset_info,
+                contract,
+                amount,
+                msg,
+            } => {
+                let amount = resolve(&amount);
+                if !amount.is_zero() {
+                    msgs.push(into_send_msg(&asset_info, contract, amount, msg)?);
+                }
+            }
+            PostFinalizeAction::Execute { contract, msg } => {
+                msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract,
+                    msg,
+                    funds: vec![],
+                }))
+            }
+        }
+    }
+    Ok(msgs)
+}
 
 pub fn into_cosmos_msg(
     asset_info: &AssetInfo,
@@ -25,3 +103,160 @@ pub fn into_cosmos_msg(
         })),
     }
 }
+
+// send tokens to a contract together with a message, through the cw20 Send hook or as
+// funds attached to the execute message for native tokens
+pub fn into_send_msg(
+    asset_info: &AssetInfo,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> StdResult<CosmosMsg> {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract,
+                amount,
+                msg,
+            })?,
+            funds: vec![],
+        })),
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract,
+            msg,
+            funds: vec![Coin {
+                denom: denom.to_owned(),
+                amount,
+            }],
+        })),
+    }
+}
+
+// price of one underlying_token in distribution_token, taken from the reserves of an oraiswap pair
+pub fn query_pair_exchange_rate(
+    querier: &QuerierWrapper,
+    pair: &Addr,
+    underlying_token: &AssetInfo,
+    distribution_token: &AssetInfo,
+) -> StdResult<Decimal> {
+    let pool: PoolResponse = querier.query_wasm_smart(pair, &PairQueryMsg::Pool {})?;
+    let reserve_of = |asset_info: &AssetInfo| -> StdResult<Uint128> {
+        pool.assets
+            .iter()
+            .find(|asset| asset.info.eq(asset_info))
+            .map(|asset| asset.amount)
+            .ok_or_else(|| StdError::generic_err("Oracle pair does not trade the bidding tokens"))
+    };
+
+    Decimal::checked_from_ratio(
+        reserve_of(distribution_token)?,
+        reserve_of(underlying_token)?,
+    )
+    .map_err(|_| StdError::generic_err("Oracle pair has no liquidity"))
+}
+
+// updates the expected holdings with the funds received by an execute and the tokens sent out by
+// its messages, wrapping native coins into the cw20 underlying token counts as an inflow
+pub fn record_token_flows(
+    storage: &mut dyn Storage,
+    config: &Config,
+    inflows: Vec<Asset>,
+    messages: &[SubMsg],
+) -> StdResult<()> {
+    for asset in inflows {
+        credit_shadow_balance(storage, config, &asset.info, asset.amount)?;
+    }
+
+    for sub_msg in messages {
+        match &sub_msg.msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. } | BankMsg::Burn { amount }) => {
+                for coin in amount {
+                    debit_shadow_balance(storage, config, &native_asset(coin), coin.amount)?;
+                }
+            }
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            }) => {
+                for coin in funds {
+                    debit_shadow_balance(storage, config, &native_asset(coin), coin.amount)?;
+                }
+                let token = AssetInfo::Token {
+                    contract_addr: Addr::unchecked(contract_addr),
+                };
+                if !config.is_tracked(&token) {
+                    continue;
+                }
+                match from_json::<Cw20ExecuteMsg>(msg) {
+                    Ok(
+                        Cw20ExecuteMsg::Transfer { amount, .. }
+                        | Cw20ExecuteMsg::Send { amount, .. }
+                        | Cw20ExecuteMsg::Burn { amount },
+                    ) => debit_shadow_balance(storage, config, &token, amount)?,
+                    Ok(_) => {}
+                    Err(_) => {
+                        if let Ok(WrapperExecuteMsg::Deposit {}) = from_json(msg) {
+                            let wrapped =
+                                funds.iter().try_fold(Uint128::zero(), |total, coin| {
+                                    total.checked_add(coin.amount)
+                                })?;
+                            credit_shadow_balance(storage, config, &token, wrapped)?;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn native_asset(coin: &Coin) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: coin.denom.clone(),
+    }
+}
+
+// balance of a native or cw20 token
+pub fn query_balance(
+    querier: &QuerierWrapper,
+    asset_info: &AssetInfo,
+    address: Addr,
+) -> StdResult<Uint128> {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => {
+            let res: BalanceResponse = querier.query_wasm_smart(
+                contract_addr,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )?;
+            Ok(res.balance)
+        }
+        AssetInfo::NativeToken { denom } => Ok(querier.query_balance(address, denom)?.amount),
+    }
+}
+
+// label of an asset in attributes, the contract address or the denom
+pub fn asset_info_label(asset_info: &AssetInfo) -> String {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => contract_addr.to_string(),
+        AssetInfo::NativeToken { denom } => denom.clone(),
+    }
+}
+
+// records field_old and field_new when an update changed the value of field
+pub fn push_change(changes: &mut Vec<Attribute>, field: &str, old: String, new: String) {
+    if old != new {
+        changes.push(attr(format!("{}_old", field), old));
+        changes.push(attr(format!("{}_new", field), new));
+    }
+}
+
+pub fn optional_label<T: ToString>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "none".to_string(), ToString::to_string)
+}
@@ -0,0 +1,270 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Binary, Deps, DepsMut, Env, Ibc3ChannelOpenResponse,
+    IbcBasicResponse, IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, SubMsg, SubMsgResult, WasmMsg,
+};
+use cw_utils::parse_execute_response_data;
+
+use crate::{
+    bid::{execute_cancel_bid, execute_claim_residue, submit_bid_from_balance},
+    error::ContractError,
+    msg::{BidPacketAck, BidPacketMsg, ExecuteMsg},
+    state::{IbcSatellite, CONFIG, IBC_CHANNELS, IBC_DELEGATIONS, IBC_SATELLITE},
+};
+
+pub const IBC_VERSION: &str = "coharvest-bid-1";
+pub const IBC_ORDERING: IbcOrder = IbcOrder::Unordered;
+pub const IBC_PACKET_REPLY_ID: u64 = 2;
+
+fn ack_success(data: Binary) -> Binary {
+    to_json_binary(&BidPacketAck::Result(data)).unwrap()
+}
+
+fn ack_fail(err: String) -> Binary {
+    to_json_binary(&BidPacketAck::Error(err)).unwrap()
+}
+
+// only the satellite set by the owner can open a channel, with the version of the protocol
+fn assert_satellite_channel(
+    deps: &DepsMut,
+    channel: &IbcChannel,
+    counterparty_version: Option<&str>,
+) -> Result<(), ContractError> {
+    let satellite = IBC_SATELLITE.may_load(deps.storage)?;
+    if satellite.map_or(true, |satellite| {
+        satellite.connection_id != channel.connection_id
+            || satellite.port_id != channel.counterparty_endpoint.port_id
+    }) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Port {} on {} is not the satellite",
+            channel.counterparty_endpoint.port_id, channel.connection_id
+        ))));
+    }
+    if channel.order != IBC_ORDERING {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Only unordered channels are supported",
+        )));
+    }
+    if channel.version != IBC_VERSION || counterparty_version.map_or(false, |v| v != IBC_VERSION) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Channel version must be {}",
+            IBC_VERSION
+        ))));
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<Option<Ibc3ChannelOpenResponse>, ContractError> {
+    assert_satellite_channel(&deps, msg.channel(), msg.counterparty_version())?;
+    Ok(None)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel = msg.channel();
+    assert_satellite_channel(&deps, channel, msg.counterparty_version())?;
+    IBC_CHANNELS.save(deps.storage, &channel.endpoint.channel_id, &true)?;
+
+    Ok(IbcBasicResponse::new().add_attributes(vec![
+        ("action", "ibc_channel_connect"),
+        ("channel_id", &channel.endpoint.channel_id),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel = msg.channel();
+    IBC_CHANNELS.remove(deps.storage, &channel.endpoint.channel_id);
+
+    Ok(IbcBasicResponse::new().add_attributes(vec![
+        ("action", "ibc_channel_close"),
+        ("channel_id", &channel.endpoint.channel_id),
+    ]))
+}
+
+// the packet is executed by a submessage to the contract itself: a failure reverts its state
+// changes and the reply replaces the ack with an error, the satellite then refunds the sender
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let channel_id = msg.packet.dest.channel_id;
+    if !IBC_CHANNELS.has(deps.storage, &channel_id) {
+        return Ok(IbcReceiveResponse::new()
+            .set_ack(ack_fail(format!("Channel {} is not open", channel_id))));
+    }
+    let packet: BidPacketMsg = match from_json(&msg.packet.data) {
+        Ok(packet) => packet,
+        Err(err) => return Ok(IbcReceiveResponse::new().set_ack(ack_fail(err.to_string()))),
+    };
+
+    Ok(IbcReceiveResponse::new()
+        .set_ack(ack_success(Binary::default()))
+        .add_submessage(SubMsg::reply_always(
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&ExecuteMsg::ProcessBidPacket {
+                    channel_id: channel_id.clone(),
+                    packet,
+                })?,
+                funds: vec![],
+            },
+            IBC_PACKET_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            ("action", "ibc_packet_receive"),
+            ("channel_id", &channel_id),
+        ]))
+}
+
+// the contract never sends packets, there is nothing to acknowledge or refund on this side
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new().add_attribute("action", "ibc_packet_ack"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new().add_attribute("action", "ibc_packet_timeout"))
+}
+
+// the data set by the reply becomes the acknowledgement of the packet
+pub fn reply_bid_packet(msg: Reply) -> Result<Response, ContractError> {
+    let ack = match msg.result {
+        SubMsgResult::Ok(response) => {
+            let data = match response.data {
+                Some(data) => parse_execute_response_data(&data)
+                    .map_err(|err| StdError::generic_err(err.to_string()))?
+                    .data
+                    .unwrap_or_default(),
+                None => Binary::default(),
+            };
+            ack_success(data)
+        }
+        SubMsgResult::Err(err) => ack_fail(err),
+    };
+    Ok(Response::new().set_data(ack))
+}
+
+pub fn execute_process_bid_packet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: String,
+    packet: BidPacketMsg,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let bidder = match &packet {
+        BidPacketMsg::SubmitBid { bidder, .. }
+        | BidPacketMsg::CancelBid { bidder, .. }
+        | BidPacketMsg::Claim { bidder, .. } => deps.api.addr_validate(bidder)?,
+    };
+    if IBC_DELEGATIONS.may_load(deps.storage, &bidder)?.as_ref() != Some(&channel_id) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let info = MessageInfo {
+        sender: bidder,
+        funds: vec![],
+    };
+
+    match packet {
+        BidPacketMsg::SubmitBid {
+            round,
+            premium_slot,
+            amount,
+            ..
+        } => {
+            let (response, idx) =
+                submit_bid_from_balance(deps, env, &info.sender, round, premium_slot, amount)?;
+            Ok(response.set_data(to_json_binary(&idx)?))
+        }
+        BidPacketMsg::CancelBid { idx, .. } => {
+            Ok(execute_cancel_bid(deps, env, info, idx)?.set_data(to_json_binary(&idx)?))
+        }
+        BidPacketMsg::Claim { idx, .. } => {
+            Ok(execute_claim_residue(deps, env, info, idx)?.set_data(to_json_binary(&idx)?))
+        }
+    }
+}
+
+pub fn execute_set_ibc_satellite(
+    deps: DepsMut,
+    info: MessageInfo,
+    satellite: Option<IbcSatellite>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let Some(satellite) = satellite else {
+        IBC_SATELLITE.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_ibc_satellite"));
+    };
+    IBC_SATELLITE.save(deps.storage, &satellite)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_ibc_satellite"),
+        ("connection_id", &satellite.connection_id),
+        ("port_id", &satellite.port_id),
+    ]))
+}
+
+pub fn execute_set_ibc_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let Some(channel_id) = channel_id else {
+        IBC_DELEGATIONS.remove(deps.storage, &info.sender);
+        return Ok(Response::new().add_attribute("action", "remove_ibc_delegate"));
+    };
+    if !IBC_CHANNELS.has(deps.storage, &channel_id) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Channel {} is not open",
+            channel_id
+        ))));
+    }
+    IBC_DELEGATIONS.save(deps.storage, &info.sender, &channel_id)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_ibc_delegate"),
+        ("bidder", info.sender.as_str()),
+        ("channel_id", &channel_id),
+    ]))
+}
+
+pub fn query_ibc_channels(deps: Deps) -> StdResult<Vec<String>> {
+    IBC_CHANNELS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
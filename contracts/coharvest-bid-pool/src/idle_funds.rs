@@ -0,0 +1,390 @@
+use cosmwasm_std::{
+    to_json_binary, CosmosMsg, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+    Uint128, WasmMsg,
+};
+
+use crate::{
+    bid::assert_round_state,
+    error::ContractError,
+    helper::{into_cosmos_msg, into_send_msg},
+    msg::{YieldVenueExecuteMsg, YieldVenueQueryMsg},
+    state::{
+        credit_shadow_balance, read_round_config, Config, RoundState, YieldPosition, YieldVenue,
+        BIDDING_INFO, CONFIG, DISTRIBUTION_INFO, LENDING_MARKET, LENT_DISTRIBUTION, YIELD_POSITION,
+        YIELD_VENUE,
+    },
+};
+
+// the owner and the keeper registry move idle funds in and out of the venue
+fn assert_owner_or_keeper(config: &Config, info: &MessageInfo) -> Result<(), ContractError> {
+    if config.owner != info.sender && config.keeper_registry.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn execute_set_yield_venue(
+    deps: DepsMut,
+    info: MessageInfo,
+    venue: Option<YieldVenue>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(position) = YIELD_POSITION.may_load(deps.storage)? {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Idle funds of round {} are still deposited in the yield venue",
+            position.round
+        ))));
+    }
+
+    let Some(venue) = venue else {
+        YIELD_VENUE.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_yield_venue"));
+    };
+    deps.api.addr_validate(venue.contract.as_str())?;
+    if venue.yield_to_distribution && venue.reward_asset != config.distribution_token {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Yield added to the distribution must be paid in distribution_token",
+        )));
+    }
+    YIELD_VENUE.save(deps.storage, &venue)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_yield_venue"),
+        ("contract", venue.contract.as_str()),
+        (
+            "yield_to_distribution",
+            &venue.yield_to_distribution.to_string(),
+        ),
+    ]))
+}
+
+pub fn execute_deposit_idle_funds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_owner_or_keeper(&config, &info)?;
+    let venue = YIELD_VENUE
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("No yield venue is set"))?;
+
+    // only the bids of an open round are idle, they must be back before finalize
+    let bidding_info = BIDDING_INFO.load(deps.storage, round)?;
+    assert_round_state(deps.storage, &env, round, &[RoundState::Open])?;
+
+    let mut position = YIELD_POSITION
+        .may_load(deps.storage)?
+        .unwrap_or(YieldPosition {
+            round,
+            principal: Uint128::zero(),
+        });
+    if position.round != round {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Idle funds of round {} are still deposited in the yield venue",
+            position.round
+        ))));
+    }
+
+    let idle = bidding_info
+        .total_bid_amount
+        .checked_sub(position.principal)?;
+    let amount = amount.unwrap_or(idle);
+    if amount.is_zero() || amount > idle {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Only {} idle funds can be deposited",
+            idle
+        ))));
+    }
+    position.principal = position.principal.checked_add(amount)?;
+    YIELD_POSITION.save(deps.storage, &position)?;
+
+    Ok(Response::new()
+        .add_message(into_send_msg(
+            &config.underlying_token,
+            venue.contract.to_string(),
+            amount,
+            to_json_binary(&YieldVenueExecuteMsg::Deposit {})?,
+        )?)
+        .add_attributes(vec![
+            ("action", "deposit_idle_funds"),
+            ("round", &round.to_string()),
+            ("amount", &amount.to_string()),
+            ("principal", &position.principal.to_string()),
+        ]))
+}
+
+pub fn execute_withdraw_idle_funds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_owner_or_keeper(&config, &info)?;
+    let venue = YIELD_VENUE.load(deps.storage)?;
+    let position = YIELD_POSITION
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("No idle funds are deposited"))?;
+
+    // the venue pays the yield on top of the principal, it is claimed in the same transaction
+    let rewards: Uint128 = deps.querier.query_wasm_smart(
+        &venue.contract,
+        &YieldVenueQueryMsg::PendingRewards {
+            address: env.contract.address.to_string(),
+        },
+    )?;
+    YIELD_POSITION.remove(deps.storage);
+    // the venue pays back without notifying the contract
+    credit_shadow_balance(
+        deps.storage,
+        &config,
+        &config.underlying_token,
+        position.principal,
+    )?;
+    credit_shadow_balance(deps.storage, &config, &venue.reward_asset, rewards)?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![WasmMsg::Execute {
+        contract_addr: venue.contract.to_string(),
+        msg: to_json_binary(&YieldVenueExecuteMsg::Withdraw {
+            amount: position.principal,
+        })?,
+        funds: vec![],
+    }
+    .into()];
+    if !rewards.is_zero() {
+        msgs.push(
+            WasmMsg::Execute {
+                contract_addr: venue.contract.to_string(),
+                msg: to_json_binary(&YieldVenueExecuteMsg::ClaimRewards {})?,
+                funds: vec![],
+            }
+            .into(),
+        );
+        if venue.yield_to_distribution {
+            let mut distribution_info = DISTRIBUTION_INFO.load(deps.storage, position.round)?;
+            distribution_info.total_distribution =
+                distribution_info.total_distribution.checked_add(rewards)?;
+            DISTRIBUTION_INFO.save(deps.storage, position.round, &distribution_info)?;
+        } else {
+            msgs.push(into_cosmos_msg(
+                &venue.reward_asset,
+                config.treasury.to_string(),
+                rewards,
+            )?);
+        }
+    }
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        ("action", "withdraw_idle_funds"),
+        ("round", &position.round.to_string()),
+        ("principal", &position.principal.to_string()),
+        ("yield", &rewards.to_string()),
+    ]))
+}
+
+// emergency path when the venue can't pay the yield, only the principal is withdrawn
+pub fn execute_unwind_idle_funds(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let venue = YIELD_VENUE.load(deps.storage)?;
+    let position = YIELD_POSITION
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("No idle funds are deposited"))?;
+    YIELD_POSITION.remove(deps.storage);
+    credit_shadow_balance(
+        deps.storage,
+        &config,
+        &config.underlying_token,
+        position.principal,
+    )?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: venue.contract.to_string(),
+            msg: to_json_binary(&YieldVenueExecuteMsg::Withdraw {
+                amount: position.principal,
+            })?,
+            funds: vec![],
+        })
+        .add_attributes(vec![
+            ("action", "unwind_idle_funds"),
+            ("round", &position.round.to_string()),
+            ("principal", &position.principal.to_string()),
+        ]))
+}
+
+pub fn execute_set_lending_market(
+    deps: DepsMut,
+    info: MessageInfo,
+    market: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(round) = LENT_DISTRIBUTION
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?
+    {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Distribution of round {} is still lent on the money market",
+            round
+        ))));
+    }
+
+    let Some(market) = market else {
+        LENDING_MARKET.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_lending_market"));
+    };
+    let market = deps.api.addr_validate(&market)?;
+    LENDING_MARKET.save(deps.storage, &market)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_lending_market"),
+        ("market", market.as_str()),
+    ]))
+}
+
+pub fn execute_lend_distribution(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_owner_or_keeper(&config, &info)?;
+    let market = LENDING_MARKET
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("No lending market is set"))?;
+    if !read_round_config(deps.storage, round)?.lend_distribution {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} does not lend its distribution",
+            round
+        ))));
+    }
+
+    // started rounds can't be cancelled, the distribution stays escrowed until finalize
+    assert_round_state(deps.storage, &env, round, &[RoundState::Open])?;
+    if LENT_DISTRIBUTION.has(deps.storage, round) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Distribution of round {} is already lent",
+            round
+        ))));
+    }
+    let amount = DISTRIBUTION_INFO
+        .load(deps.storage, round)?
+        .total_distribution;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Round has no distribution to lend",
+        )));
+    }
+    LENT_DISTRIBUTION.save(deps.storage, round, &amount)?;
+
+    Ok(Response::new()
+        .add_message(into_send_msg(
+            &config.distribution_token,
+            market.to_string(),
+            amount,
+            to_json_binary(&YieldVenueExecuteMsg::Deposit {})?,
+        )?)
+        .add_attributes(vec![
+            ("action", "lend_distribution"),
+            ("round", &round.to_string()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+// withdraws the lent distribution of the round and sends the interest to the treasury,
+// the interest accrued by the other lent rounds is claimed along
+pub fn recall_lent_distribution(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    round: u64,
+) -> StdResult<Vec<CosmosMsg>> {
+    let Some(amount) = LENT_DISTRIBUTION.may_load(deps.storage, round)? else {
+        return Ok(vec![]);
+    };
+    let market = LENDING_MARKET.load(deps.storage)?;
+    let interest: Uint128 = deps.querier.query_wasm_smart(
+        &market,
+        &YieldVenueQueryMsg::PendingRewards {
+            address: env.contract.address.to_string(),
+        },
+    )?;
+    LENT_DISTRIBUTION.remove(deps.storage, round);
+    credit_shadow_balance(
+        deps.storage,
+        config,
+        &config.distribution_token,
+        amount.checked_add(interest)?,
+    )?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![WasmMsg::Execute {
+        contract_addr: market.to_string(),
+        msg: to_json_binary(&YieldVenueExecuteMsg::Withdraw { amount })?,
+        funds: vec![],
+    }
+    .into()];
+    if !interest.is_zero() {
+        msgs.push(
+            WasmMsg::Execute {
+                contract_addr: market.to_string(),
+                msg: to_json_binary(&YieldVenueExecuteMsg::ClaimRewards {})?,
+                funds: vec![],
+            }
+            .into(),
+        );
+        msgs.push(into_cosmos_msg(
+            &config.distribution_token,
+            config.treasury.to_string(),
+            interest,
+        )?);
+    }
+    Ok(msgs)
+}
+
+// safety when the market is paused and can't be queried or pay the interest, only the
+// lent amount is withdrawn so the round can be finalized
+pub fn execute_force_recall_distribution(
+    deps: DepsMut,
+    info: MessageInfo,
+    round: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let market = LENDING_MARKET.load(deps.storage)?;
+    let amount = LENT_DISTRIBUTION
+        .may_load(deps.storage, round)?
+        .ok_or_else(|| {
+            StdError::generic_err(format!("Distribution of round {} is not lent", round))
+        })?;
+    LENT_DISTRIBUTION.remove(deps.storage, round);
+    credit_shadow_balance(deps.storage, &config, &config.distribution_token, amount)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: market.to_string(),
+            msg: to_json_binary(&YieldVenueExecuteMsg::Withdraw { amount })?,
+            funds: vec![],
+        })
+        .add_attributes(vec![
+            ("action", "force_recall_distribution"),
+            ("round", &round.to_string()),
+            ("amount", &amount.to_string()),
+        ]))
+}
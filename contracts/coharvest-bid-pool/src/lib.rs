@@ -2,8 +2,16 @@ pub mod bid;
 pub mod contract;
 pub mod error;
 pub mod helper;
+pub mod ibc;
+pub mod idle_funds;
+pub mod lock;
+pub mod matching;
 pub mod msg;
+pub mod reinvest;
 pub mod state;
 
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
 #[cfg(test)]
 mod testing;
@@ -0,0 +1,260 @@
+// locked bids: the deposit of a lock enters lock_rounds rounds in a row, the residue of each round
+// waits in the lock and re-enters the next one, and every premium is boosted by the multiplier of
+// the tier out of the boost reserve. The deposit is only paid back with the residue of the last
+// round
+use cosmwasm_std::{
+    Decimal, DepsMut, Env, Event, MessageInfo, Response, StdError, Storage, Uint128,
+};
+use oraiswap::asset::Asset;
+
+use crate::{
+    bid::execute_submit_bid,
+    error::ContractError,
+    helper::into_cosmos_msg,
+    matching::checked_mul_decimal,
+    state::{
+        BidLock, LockBoost, BID_IDX, CONFIG, LOCKED_BIDS, LOCKS, LOCK_BOOSTS, LOCK_BOOST_RESERVE,
+        LOCK_IDX,
+    },
+};
+
+pub const MAX_LOCK_ROUNDS: u64 = 52;
+const MAX_LOCK_BOOSTS: usize = 10;
+// every lock entered submits a bid, keep batches small enough to fit in a block
+const MAX_LOCKS_ENTERED: usize = 30;
+
+pub fn execute_set_lock_boosts(
+    deps: DepsMut,
+    info: MessageInfo,
+    boosts: Vec<LockBoost>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if boosts.len() > MAX_LOCK_BOOSTS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "At most {} lock boosts can be set",
+            MAX_LOCK_BOOSTS
+        ))));
+    }
+    // tiers are ordered by lock length, the longest tier reached by a lock applies
+    let mut min_rounds = 1;
+    for boost in boosts.iter() {
+        if boost.min_rounds <= min_rounds || boost.min_rounds > MAX_LOCK_ROUNDS {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Lock boosts must be ordered by min_rounds, between 2 and {}",
+                MAX_LOCK_ROUNDS
+            ))));
+        }
+        if boost.multiplier < Decimal::one() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Lock boost multiplier must be at least 1",
+            )));
+        }
+        min_rounds = boost.min_rounds;
+    }
+
+    if boosts.is_empty() {
+        LOCK_BOOSTS.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_lock_boosts"));
+    }
+    LOCK_BOOSTS.save(deps.storage, &boosts)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_lock_boosts"),
+        ("num_boosts", &boosts.len().to_string()),
+    ]))
+}
+
+// anyone can fund the boosts, the reserve is only spent on the premiums of locked bids
+pub fn execute_fund_lock_boosts(deps: DepsMut, funds: Asset) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if funds.info != config.distribution_token || funds.amount.is_zero() {
+        return Err(ContractError::InvalidFunds {});
+    }
+    let reserve = LOCK_BOOST_RESERVE
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(funds.amount)?;
+    LOCK_BOOST_RESERVE.save(deps.storage, &reserve)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "fund_lock_boosts"),
+        ("amount", &funds.amount.to_string()),
+        ("reserve", &reserve.to_string()),
+    ]))
+}
+
+// the first bid of the lock is submitted in round, the lock then enters the next
+// lock_rounds - 1 rounds
+pub fn execute_submit_locked_bid(
+    mut deps: DepsMut,
+    env: Env,
+    round: u64,
+    premium_slot: u16,
+    lock_rounds: u64,
+    bidder: String,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    if lock_rounds > MAX_LOCK_ROUNDS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Bids can be locked for at most {} rounds",
+            MAX_LOCK_ROUNDS
+        ))));
+    }
+    let multiplier = LOCK_BOOSTS
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .iter()
+        .rev()
+        .find(|boost| boost.min_rounds <= lock_rounds)
+        .map(|boost| boost.multiplier)
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "No lock boost for a lock of {} rounds",
+                lock_rounds
+            ))
+        })?;
+
+    let response = execute_submit_bid(
+        deps.branch(),
+        env,
+        round,
+        premium_slot,
+        bidder.clone(),
+        funds,
+    )?;
+    let bid_idx = BID_IDX.load(deps.storage)? - 1;
+    let lock_id = LOCK_IDX.may_load(deps.storage)?.unwrap_or(1);
+    LOCK_IDX.save(deps.storage, &(lock_id + 1))?;
+    LOCKS.save(
+        deps.storage,
+        lock_id,
+        &BidLock {
+            bidder: deps.api.addr_validate(&bidder)?,
+            premium_slot,
+            multiplier,
+            remaining_rounds: lock_rounds - 1,
+            last_round: round,
+            pending_amount: Uint128::zero(),
+        },
+    )?;
+    LOCKED_BIDS.save(deps.storage, bid_idx, &lock_id)?;
+
+    Ok(response.add_attributes(vec![
+        ("lock_id", lock_id.to_string()),
+        ("lock_rounds", lock_rounds.to_string()),
+        ("multiplier", multiplier.to_string()),
+    ]))
+}
+
+// anyone, usually the keeper once a round opens. The pending residues of the locks are bid in
+// round, a residue below the minimum deposit can't be bid and ends its lock with a refund
+pub fn execute_enter_locked_bids(
+    mut deps: DepsMut,
+    env: Env,
+    round: u64,
+    lock_ids: Vec<u64>,
+) -> Result<Response, ContractError> {
+    if lock_ids.is_empty() || lock_ids.len() > MAX_LOCKS_ENTERED {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Between 1 and {} locks can be entered at once",
+            MAX_LOCKS_ENTERED
+        ))));
+    }
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut events = vec![];
+    let mut msgs = vec![];
+    for lock_id in lock_ids {
+        let mut lock = LOCKS.load(deps.storage, lock_id)?;
+        if lock.pending_amount.is_zero() || lock.remaining_rounds == 0 || lock.last_round >= round {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Lock {} has no residue to enter in round {}",
+                lock_id, round
+            ))));
+        }
+
+        let amount = lock.pending_amount;
+        if amount < config.min_deposit_amount {
+            LOCKS.remove(deps.storage, lock_id);
+            msgs.push(into_cosmos_msg(
+                &config.underlying_token,
+                lock.bidder.to_string(),
+                amount,
+            )?);
+            events.push(Event::new("release_lock").add_attributes(vec![
+                ("lock_id", lock_id.to_string()),
+                ("bidder", lock.bidder.to_string()),
+                ("amount", amount.to_string()),
+            ]));
+            continue;
+        }
+
+        let response = execute_submit_bid(
+            deps.branch(),
+            env.clone(),
+            round,
+            lock.premium_slot,
+            lock.bidder.to_string(),
+            Asset {
+                info: config.underlying_token.clone(),
+                amount,
+            },
+        )?;
+        let bid_idx = BID_IDX.load(deps.storage)? - 1;
+        lock.remaining_rounds -= 1;
+        lock.last_round = round;
+        lock.pending_amount = Uint128::zero();
+        LOCKS.save(deps.storage, lock_id, &lock)?;
+        LOCKED_BIDS.save(deps.storage, bid_idx, &lock_id)?;
+        events.push(
+            Event::new("enter_locked_bid")
+                .add_attributes(response.attributes)
+                .add_attribute("lock_id", lock_id.to_string()),
+        );
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_events(events)
+        .add_attributes(vec![
+            ("action", "enter_locked_bids"),
+            ("round", &round.to_string()),
+        ]))
+}
+
+// extra premium of a distributed locked bid, (multiplier - 1) times its premium capped by the
+// boost reserve
+pub fn take_lock_boost(
+    storage: &mut dyn Storage,
+    lock: &BidLock,
+    premium: Uint128,
+) -> Result<Uint128, ContractError> {
+    let reserve = LOCK_BOOST_RESERVE.may_load(storage)?.unwrap_or_default();
+    let boost =
+        checked_mul_decimal(premium, lock.multiplier.checked_sub(Decimal::one())?)?.min(reserve);
+    if !boost.is_zero() {
+        LOCK_BOOST_RESERVE.save(storage, &reserve.checked_sub(boost)?)?;
+    }
+    Ok(boost)
+}
+
+// the residue of a distributed locked bid waits in the lock for the next round, returns the
+// part still refunded to the bidder: the residue of the last round of the lock. A lock is closed
+// once it has nothing left to enter
+pub fn carry_locked_residue(
+    storage: &mut dyn Storage,
+    lock_id: u64,
+    mut lock: BidLock,
+    residue: Uint128,
+) -> Result<Uint128, ContractError> {
+    if lock.remaining_rounds == 0 || residue.is_zero() {
+        LOCKS.remove(storage, lock_id);
+        return Ok(residue);
+    }
+    lock.pending_amount = lock.pending_amount.checked_add(residue)?;
+    LOCKS.save(storage, lock_id, &lock)?;
+    Ok(Uint128::zero())
+}
@@ -0,0 +1,153 @@
+// the matching math of the rounds: how the distribution fills the bid pools and what each bid
+// receives. It only works on values, never on storage, so the contracts and off-chain simulators
+// integrating with the bid pool can reproduce the results of the chain exactly
+use cosmwasm_std::{
+    Decimal, Decimal256, OverflowError, OverflowOperation, StdError, Uint128, Uint256,
+};
+
+use crate::{error::ContractError, state::BidPool};
+
+// amount * rate rounded down like the Mul impl, returning an error instead of panicking
+pub fn checked_mul_decimal(amount: Uint128, rate: Decimal) -> Result<Uint128, OverflowError> {
+    amount
+        .checked_mul_floor(rate)
+        .map_err(|_| OverflowError::new(OverflowOperation::Mul, amount, rate))
+}
+
+// 256-bit variant for the products of several rates, which can exceed Uint128 before the result
+// is brought back in range
+fn checked_mul_decimal256(amount: Uint256, rate: Decimal256) -> Result<Uint256, OverflowError> {
+    amount
+        .checked_mul_floor(rate)
+        .map_err(|_| OverflowError::new(OverflowOperation::Mul, amount, rate))
+}
+
+// share fee_rate of the premium portion of a payout
+pub fn performance_fee(
+    amount_received: Uint128,
+    matched_amount: Uint128,
+    exchange_rate: Decimal,
+    fee_rate: Option<Decimal>,
+) -> Result<Uint128, OverflowError> {
+    let Some(fee_rate) = fee_rate else {
+        return Ok(Uint128::zero());
+    };
+    let premium = premium_amount(amount_received, matched_amount, exchange_rate)?;
+    checked_mul_decimal(premium, fee_rate)
+}
+
+// the premium portion of a payout, the amount above a 0% premium fill of the matched amount
+pub fn premium_amount(
+    amount_received: Uint128,
+    matched_amount: Uint128,
+    exchange_rate: Decimal,
+) -> Result<Uint128, OverflowError> {
+    Ok(amount_received.saturating_sub(checked_mul_decimal(matched_amount, exchange_rate)?))
+}
+
+// (amount_received, residue_bid) of a bid of amount in a pool matched at index_snapshot
+pub fn split_bid_amount(
+    amount: Uint128,
+    index_snapshot: Decimal,
+    received_per_token: Decimal,
+) -> Result<(Uint128, Uint128), OverflowError> {
+    let amount_received = checked_mul_decimal(amount, received_per_token)?;
+    let residue_bid = checked_mul_decimal(amount, Decimal::one().checked_sub(index_snapshot)?)?;
+    Ok((amount_received, residue_bid))
+}
+
+pub fn process_calc_distribution_amount(
+    bid_pools: &mut Vec<BidPool>,
+    distribution_amount: &mut Uint128,
+    exchange_rate: Decimal,
+) -> Result<Uint128, ContractError> {
+    calc_distribution_amount(bid_pools, distribution_amount, exchange_rate, false)
+}
+
+// rebate rounds pay in underlying_token: the distribution only funds the premium of the matched
+// bids, their principal is paid back along with it instead of being burned
+pub fn process_calc_rebate_amount(
+    bid_pools: &mut Vec<BidPool>,
+    distribution_amount: &mut Uint128,
+) -> Result<Uint128, ContractError> {
+    calc_distribution_amount(bid_pools, distribution_amount, Decimal::one(), true)
+}
+
+fn calc_distribution_amount(
+    bid_pools: &mut Vec<BidPool>,
+    distribution_amount: &mut Uint128,
+    exchange_rate: Decimal,
+    rebate: bool,
+) -> Result<Uint128, ContractError> {
+    let mut total_matched = Uint128::zero();
+
+    for bid_pool in bid_pools {
+        total_matched = total_matched.checked_add(fill_bid_pool(
+            bid_pool,
+            distribution_amount,
+            exchange_rate,
+            rebate,
+        )?)?;
+
+        if distribution_amount.is_zero() {
+            break;
+        }
+    }
+
+    Ok(total_matched)
+}
+
+// matches the pool with what is left of the distribution, returns the matched amount
+pub fn fill_bid_pool(
+    bid_pool: &mut BidPool,
+    distribution_amount: &mut Uint128,
+    exchange_rate: Decimal,
+    rebate: bool,
+) -> Result<Uint128, ContractError> {
+    if bid_pool.total_bid_amount.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let total_bid_amount = Uint256::from(bid_pool.total_bid_amount);
+    let premium_rate = Decimal256::from(bid_pool.premium_rate);
+    let desired_amount = if rebate {
+        checked_mul_decimal256(total_bid_amount, premium_rate)?
+    } else {
+        checked_mul_decimal256(
+            checked_mul_decimal256(total_bid_amount, Decimal256::from(exchange_rate))?,
+            Decimal256::one().checked_add(premium_rate)?,
+        )?
+    };
+
+    // the pool is too small to be worth anything at this rate, leave it unmatched
+    if desired_amount.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let actual_amount = match Uint128::try_from(desired_amount) {
+        Ok(desired_amount) if desired_amount <= *distribution_amount => desired_amount,
+        // a desired amount beyond Uint128 is always above the distribution
+        _ => *distribution_amount,
+    };
+
+    // actual_amount <= desired_amount, the ratio always fits in a Decimal
+    let index_snapshot = Decimal256::checked_from_ratio(actual_amount, desired_amount)?;
+    let index_snapshot =
+        Decimal::try_from(index_snapshot).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let matched_amount = checked_mul_decimal(bid_pool.total_bid_amount, index_snapshot)?;
+    let received_amount = if rebate {
+        actual_amount.checked_add(matched_amount)?
+    } else {
+        actual_amount
+    };
+    let received_per_token =
+        Decimal::checked_from_ratio(received_amount, bid_pool.total_bid_amount)?;
+
+    *distribution_amount = distribution_amount.checked_sub(actual_amount)?;
+    bid_pool.index_snapshot = index_snapshot;
+    bid_pool.received_per_token = received_per_token;
+    bid_pool.matched_amount = matched_amount;
+    bid_pool.payout_amount = received_amount;
+
+    Ok(matched_amount)
+}
@@ -1,94 +1,689 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
+use cw2::ContractVersion;
 use cw20::Cw20ReceiveMsg;
+use cw_controllers::HooksResponse;
 use oraiswap::asset::AssetInfo;
 
-use crate::state::{Bid, BidPool, BiddingInfo, Config, DistributionInfo};
+use crate::state::{
+    Bid, BidLock, BidPool, BidRateLimit, BiddingInfo, Config, Dispute, DisputeParams,
+    DistributionInfo, FinalizerGroup, IbcSatellite, LockBoost, PauseFlags, Payout,
+    PostFinalizeAction, RateBand, ReinvestConfig, RoundConfig, RoundSummary, RoundTemplate,
+    SlotBonus, YieldPosition, YieldVenue,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub owner: Addr,
+    pub owner: String,
     pub underlying_token: AssetInfo,
     pub distribution_token: AssetInfo,
-    pub max_slot: u8,
+    pub max_slot: u16,
     pub premium_rate_per_slot: Decimal,
     pub min_deposit_amount: Uint128,
-    pub treasury: Addr,
+    pub treasury: String,
     pub bidding_duration: u64,
+    pub oracle_pair: Option<String>,
+    pub vesting_contract: Option<String>,
+    pub keeper_registry: Option<String>,
+    pub staking_contract: Option<String>,
+    pub residue_staking: Option<String>,
+    pub wrapped_denom: Option<String>,
+    pub min_gap_between_rounds: Option<u64>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
     UpdateConfig {
-        owner: Option<Addr>,
+        owner: Option<String>,
         underlying_token: Option<AssetInfo>,
         distribution_token: Option<AssetInfo>,
-        max_slot: Option<u8>,
+        max_slot: Option<u16>,
         premium_rate_per_slot: Option<Decimal>,
         min_deposit_amount: Option<Uint128>,
-        treasury: Option<Addr>,
+        treasury: Option<String>,
         bidding_duration: Option<u64>,
+        oracle_pair: Option<String>,
+        vesting_contract: Option<String>,
+        keeper_registry: Option<String>,
+        staking_contract: Option<String>,
+        residue_staking: Option<String>,
+        wrapped_denom: Option<String>,
+        min_gap_between_rounds: Option<u64>,
     },
+    // min_exchange_rate is the lowest exchange rate the round can be finalized at
     CreateNewRound {
         start_time: u64,
         end_time: u64,
         total_distribution: Uint128,
+        min_exchange_rate: Option<Decimal>,
     },
     FinalizeBiddingRoundResult {
         round: u64,
         exchange_rate: Decimal,
     },
+    // first step of a finalize split over several transactions, then ContinueFinalize until done
+    StartFinalize {
+        round: u64,
+        exchange_rate: Decimal,
+    },
+    // anyone when permissionless finalize is enabled, finalizes the round at the rate of the
+    // oracle pair and pays the bounty to the caller
+    FinalizeAtOracleRate {
+        round: u64,
+    },
+    // anyone once a round is not finalized refund_deadline seconds after its end: the round is
+    // failed, its distribution and bonuses are sent back and its bids refunded limit at a time
+    TriggerRefunds {
+        round: u64,
+        limit: Option<u64>,
+    },
+    // owner or treasury, distribution tokens for a tranched round matched against the bids
+    // still unmatched in slot order, at the exchange rate of the finalize
+    AddTranche {
+        round: u64,
+    },
+    // only owner, stops the tranches of the round, which is then settled and distributed
+    CloseTranches {
+        round: u64,
+    },
+    // matches the next limit slots of the round, the last call completes the finalize
+    ContinueFinalize {
+        round: u64,
+        limit: Option<u64>,
+    },
     Distribute {
         round: u64,
         start_after: Option<u64>,
         limit: Option<u64>,
     },
+    // distribute only the listed bids of a finalized round
+    DistributeBids {
+        round: u64,
+        idxs: Vec<u64>,
+    },
+    // re-derives the total bid amount of the round and its pools, and the bid counters, from the
+    // bids of the round, for rounds not finalized yet
+    RecomputeRoundTotals {
+        round: u64,
+    },
+    // removes the next limit bids and checkpoints of a settled and distributed round, then its
+    // pools and info, only a RoundSummary of the round is kept
+    PruneRound {
+        round: u64,
+        limit: Option<u64>,
+    },
     SubmitBid {
         round: u64,
-        premium_slot: u8,
+        premium_slot: u16,
     },
     CreateNewRoundFromTreasury {},
+    // bids of many users submitted by a trusted router, the funds must cover their total amount
+    SubmitRoutedBids {
+        bids: Vec<RoutedBid>,
+    },
+    AddTrustedRouter {
+        router: String,
+    },
+    RemoveTrustedRouter {
+        router: String,
+    },
+    // sets the expected holdings to the current balances, for contracts deployed before the shadow
+    // balances were tracked or after the drift is explained
+    ResetShadowBalances {},
+    // announces the next round from the interchain account of the treasury, the round opens once
+    // total_distribution is funded with FundIcaRound
+    RequestIcaRound {
+        total_distribution: Uint128,
+    },
+    // funds the round requested by the interchain account, usually executed by ibc hooks when the
+    // ICS20 transfer of the treasury arrives, which may be before or after the request
+    FundIcaRound {},
+    // drops the pending request, the funding received so far is sent back to the interchain account
+    CancelIcaRound {},
+    // None removes the interchain account, it can't be changed while a round is pending
+    SetIcaTreasury {
+        address: Option<String>,
+    },
+    // the round must not have started yet and be the last one
+    CancelRound {
+        round: u64,
+    },
     UpdateRound {
         idx: u64,
         start_time: Option<u64>,
         end_time: Option<u64>,
         total_distribution: Option<Uint128>,
     },
+    UpdateRoundConfig {
+        round: u64,
+        round_config: RoundConfig,
+    },
+    // register the pubkey signing bids relayed on behalf of the sender
+    RegisterBidderPubkey {
+        pubkey: Binary,
+    },
+    // submit a bid signed off-chain, the relayer pays the gas and the funds are pulled
+    // from the bidder through a cw20 allowance
+    SubmitSignedBid {
+        bid: SignedBid,
+        signature: Binary,
+        pubkey: Binary,
+    },
+    // deposit native underlying tokens to bid from the internal balance later
+    Deposit {},
+    Withdraw {
+        amount: Uint128,
+    },
+    // bid submitted from another chain through an ICS20 transfer whose memo executes this
+    // message, the funds must be the whitelisted voucher denom and bidder is credited the bid
+    SubmitRemoteBid {
+        round: u64,
+        premium_slot: u16,
+        bidder: String,
+    },
+    // whitelists the IBC voucher denom accepted by SubmitRemoteBid, None disables remote bids
+    SetIbcVoucherDenom {
+        denom: Option<String>,
+    },
+    // pulls amount with TransferFrom and bids what the contract actually received, for cw20
+    // underlying tokens taking a tax on transfers
+    SubmitBidFrom {
+        round: u64,
+        premium_slot: u16,
+        amount: Uint128,
+    },
+    // when enabled, cw20 bids must go through SubmitBidFrom
+    SetTransferTax {
+        enabled: bool,
+    },
+    // bid with native staking derivative coins redeemed by the lsd_adapter of the round
+    SubmitLsdBid {
+        round: u64,
+        premium_slot: u16,
+    },
+    SubmitBidFromBalance {
+        round: u64,
+        premium_slot: u16,
+        amount: Uint128,
+    },
+    // only the trusted satellite can open channels, None closes the door to new channels
+    SetIbcSatellite {
+        satellite: Option<IbcSatellite>,
+    },
+    // lets the satellite behind channel_id drive the bids of the sender, None revokes it
+    SetIbcDelegate {
+        channel_id: Option<String>,
+    },
+    // executed by the contract itself for each packet received, so a failing packet is reverted
+    // and acknowledged with an error
+    ProcessBidPacket {
+        channel_id: String,
+        packet: BidPacketMsg,
+    },
+    // withdraw the payouts credited in rounds with credit_payouts enabled
+    WithdrawPayout {},
+    // sends the unmatched part of a bid once its round is finalized, ahead of the distribution
+    ClaimResidue {
+        idx: u64,
+    },
+    // auto_compound bonds the rewards of the bid in the staking contract and stake_residue
+    // bonds the residue in the residue staking contract, instead of sending them to the bidder.
+    // reinvest swaps the rewards into underlying_token and bids them in the next open round
+    UpdateBidOptions {
+        idx: u64,
+        auto_compound: Option<bool>,
+        stake_residue: Option<bool>,
+        reinvest: Option<bool>,
+    },
+    // notifier is sent NotifierExecuteMsg::BidSettled when the bid is distributed, None removes it
+    SetBidNotifier {
+        idx: u64,
+        notifier: Option<String>,
+    },
+    // moves amount of an open bid to a new bid in new_slot
+    SplitBid {
+        idx: u64,
+        amount: Uint128,
+        new_slot: u16,
+    },
+    SaveRoundTemplate {
+        name: String,
+        params: RoundTemplate,
+    },
+    CreateRoundFromTemplate {
+        name: String,
+        start_time: u64,
+    },
+    // attach the native funds sent as a bonus for the bids matched in slots min_slot..=max_slot
+    AddSlotBonus {
+        round: u64,
+        min_slot: u16,
+        max_slot: u16,
+    },
+    // None disables disputes, rounds finalized afterwards are settled right away
+    SetDisputeParams {
+        params: Option<DisputeParams>,
+    },
+    // post the bond to flag a finalized exchange rate deviating from the oracle
+    DisputeRound {
+        round: u64,
+    },
+    // re-finalize the round with exchange_rate, or reject the dispute when None
+    ResolveDispute {
+        round: u64,
+        exchange_rate: Option<Decimal>,
+    },
+    // replaces the actions executed after every finalize, an empty list removes them
+    SetPostFinalizeActions {
+        actions: Vec<PostFinalizeAction>,
+    },
+    // None removes the limit
+    SetBidRateLimit {
+        rate_limit: Option<BidRateLimit>,
+    },
+    // processes the next limit entries of a migration started by StateMigrateMsg::Backfill or
+    // StateMigrateMsg::WidenSlots
+    ContinueMigration {
+        limit: Option<u64>,
+    },
+    // registered contracts are notified of round creation, finalization and distribution
+    RegisterHook {
+        addr: String,
+    },
+    RemoveHook {
+        addr: String,
+    },
+    // None removes the venue, it can't be changed while funds are deposited
+    SetYieldVenue {
+        venue: Option<YieldVenue>,
+    },
+    // deposit amount of the bids of an open round in the yield venue, all the idle bids by default
+    DepositIdleFunds {
+        round: u64,
+        amount: Option<Uint128>,
+    },
+    // withdraw the deposited bids and claim the yield, required before the round is finalized
+    WithdrawIdleFunds {},
+    // withdraw the deposited bids without claiming the yield, when the venue can't pay it
+    UnwindIdleFunds {},
+    // None removes the market, it can't be changed while a distribution is lent
+    SetLendingMarket {
+        market: Option<String>,
+    },
+    // lend the escrowed distribution of an open round with lend_distribution, until finalize
+    LendDistribution {
+        round: u64,
+    },
+    // pull the lent distribution back without claiming the interest, when the market is paused
+    ForceRecallDistribution {
+        round: u64,
+    },
+    // each operation can be paused independently, unset flags are left unchanged
+    SetPauseFlags {
+        submit_bid: Option<bool>,
+        create_round: Option<bool>,
+        finalize: Option<bool>,
+        distribute: Option<bool>,
+    },
+    // None sends the leftover distribution of settled rounds back to the owner
+    SetLeftoverAirdrop {
+        address: Option<String>,
+    },
+    // only owner, None removes the group. With a quorum, members approve the exchange rate of a
+    // round through the finalize messages and the last approval reaching it finalizes the round
+    SetFinalizerGroup {
+        group: Option<String>,
+        quorum: Option<Decimal>,
+    },
+    // only owner, None disables permissionless finalize. The bounty is taken out of the
+    // distribution of the round before matching
+    SetPermissionlessFinalize {
+        bounty: Option<Uint128>,
+    },
+    // only owner, until the round is finalized. FinalizeAtOracleRate needs the oracle rate to be
+    // within the band of the round, None removes it
+    SetOracleRateBand {
+        round: u64,
+        band: Option<RateBand>,
+    },
+    // only owner, seconds after the end of a round before its bids can be refunded
+    SetRefundDeadline {
+        refund_deadline: u64,
+    },
+    // locks the deposit for lock_rounds rounds, this one included: the residue of each round
+    // enters the next one and the premiums are boosted by the tier of the lock
+    SubmitLockedBid {
+        round: u64,
+        premium_slot: u16,
+        lock_rounds: u64,
+    },
+    // anyone, bids the residues waiting in the locks in the open round
+    EnterLockedBids {
+        round: u64,
+        lock_ids: Vec<u64>,
+    },
+    // only owner, replaces the tiers of the locked bids, an empty list disables new locks
+    SetLockBoosts {
+        boosts: Vec<LockBoost>,
+    },
+    // adds the distribution_token sent to the reserve paying the boosted premiums
+    FundLockBoosts {},
+    // only owner, None disables reinvesting, the rewards of reinvesting bids are then paid as usual
+    SetReinvestConfig {
+        reinvest_config: Option<ReinvestConfig>,
+    },
+    // only owner, executes the messages atomically in order, e.g. from a single multisig proposal.
+    // Messages completed in a reply, like SubmitBidFrom, can't be batched
+    ExecuteBatch {
+        msgs: Vec<ExecuteMsg>,
+    },
+}
+
+#[cw_serde]
+pub struct RoutedBid {
+    pub bidder: String, // credited with the bid instead of the router
+    pub round: u64,
+    pub premium_slot: u16,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct SignedBid {
+    pub bidder: String,
+    pub round: u64,
+    pub premium_slot: u16,
+    pub amount: Uint128,
+    pub nonce: u64,
+    pub deadline: u64,
+}
+
+// the signature covers the sha256 hash of this payload serialized as json
+#[cw_serde]
+pub struct SignedBidPayload {
+    pub chain_id: String,
+    pub contract: String,
+    pub bid: SignedBid,
 }
 
 #[cw_serde]
 pub enum Cw20HookMsg {
-    SubmitBid { round: u64, premium_slot: u8 },
+    SubmitBid {
+        round: u64,
+        premium_slot: u16,
+    },
     CreateNewRoundFromTreasury {},
+    // funding of the round requested by the interchain account, relayed by cw20-ics20
+    FundIcaRound {},
+    // bids of many users sent by a trusted router
+    SubmitRoutedBids {
+        bids: Vec<RoutedBid>,
+    },
+    Deposit {},
+    // bid with LP tokens of the lp_pair of the round
+    SubmitLpBid {
+        round: u64,
+        premium_slot: u16,
+    },
+    // bid with the staking derivative redeemed by the lsd_adapter of the round
+    SubmitLsdBid {
+        round: u64,
+        premium_slot: u16,
+    },
+    AddSlotBonus {
+        round: u64,
+        min_slot: u16,
+        max_slot: u16,
+    },
+    SubmitLockedBid {
+        round: u64,
+        premium_slot: u16,
+        lock_rounds: u64,
+    },
+    FundLockBoosts {},
+    AddTranche {
+        round: u64,
+    },
+}
+
+// cw20 wrapper of a native coin, minting the wrapped tokens for the coins sent along
+#[cw_serde]
+pub enum WrapperExecuteMsg {
+    Deposit {},
+}
+
+// lifecycle events sent to the registered hook contracts
+#[cw_serde]
+pub enum BidPoolHookMsg {
+    RoundCreated { round: u64 },
+    RoundFinalized { round: u64, exchange_rate: Decimal },
+    DistributionCompleted { round: u64 },
+}
+
+// execute message expected by the hook contracts
+#[cw_serde]
+pub enum HookExecuteMsg {
+    BidPoolHook(BidPoolHookMsg),
+}
+
+// execute message sent to the notifier of a bid, a failing notifier does not revert the distribution
+#[cw_serde]
+pub enum NotifierExecuteMsg {
+    BidSettled {
+        round: u64,
+        idx: u64,
+        bidder: String,
+        amount_received: Uint128, // distribution tokens paid, net of the performance fee
+        residue: Uint128,         // underlying tokens refunded
+    },
+}
+
+// packets sent by the satellite over an IBC channel, bids are funded from the deposit of bidder
+// which must have delegated to the channel with SetIbcDelegate
+#[cw_serde]
+pub enum BidPacketMsg {
+    SubmitBid {
+        bidder: String,
+        round: u64,
+        premium_slot: u16,
+        amount: Uint128,
+    },
+    CancelBid {
+        bidder: String,
+        idx: u64,
+    },
+    Claim {
+        bidder: String,
+        idx: u64,
+    },
+}
+
+// acknowledgement written for every packet, Result holds the bid idx
+#[cw_serde]
+pub enum BidPacketAck {
+    Result(Binary),
+    Error(String),
+}
+
+// data of the Distribute and DistributeBids responses
+#[cw_serde]
+pub struct DistributeResponse {
+    pub first_idx: Option<u64>, // first bid of the batch, none for an empty batch
+    pub last_idx: Option<u64>,  // last bid of the batch
+    pub num_rewarded: u64,      // bids paid a reward
+    pub num_refunded: u64,      // unmatched bids, only refunded their residue
+    pub num_skipped: u64,       // bids of the batch distributed before
+    pub next_cursor: Option<u64>, // start_after of the next batch, none once the round is completed
+}
+
+// interface expected from the yield venue and the lending market, cw20 deposits are sent
+// with Deposit as the hook
+#[cw_serde]
+pub enum YieldVenueExecuteMsg {
+    Deposit {},
+    // returns amount of the deposited tokens to the sender
+    Withdraw { amount: Uint128 },
+    // pays the accrued yield in the reward asset to the sender
+    ClaimRewards {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum YieldVenueQueryMsg {
+    #[returns(Uint128)]
+    PendingRewards { address: String },
+}
+
+// hook expected by the vesting contract to vest the tokens sent for the recipient
+#[cw_serde]
+pub enum VestingCw20HookMsg {
+    Vest { recipient: String },
+}
+
+// hook expected by the staking contract to bond tokens on behalf of the staker
+#[cw_serde]
+pub enum StakingCw20HookMsg {
+    Bond { staker: String },
+}
+
+// interface expected from the adapter of a staking derivative of the underlying token, the
+// derivative sent with Redeem as the hook is redeemed for underlying_token sent back at once
+#[cw_serde]
+pub enum LsdAdapterExecuteMsg {
+    Redeem {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum LsdAdapterQueryMsg {
+    #[returns(RedeemRateResponse)]
+    RedeemRate {},
+}
+
+#[cw_serde]
+pub struct RedeemRateResponse {
+    pub derivative: AssetInfo, // staking derivative redeemed by the adapter
+    pub underlying_per_derivative: Decimal, // underlying_token paid for each derivative token
+}
+
+// message expected by the airdrop contract to fund the airdrop of a round with its leftover
+#[cw_serde]
+pub enum AirdropExecuteMsg {
+    Fund { round: u64 },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(Config)]
+    #[returns(ConfigResponse)]
     Config {},
-    #[returns(Bid)]
+    #[returns(Option<YieldVenue>)]
+    YieldVenue {},
+    #[returns(Option<YieldPosition>)]
+    YieldPosition {},
+    #[returns(Option<Addr>)]
+    LendingMarket {},
+    // distribution_token of the round currently lent on the money market
+    #[returns(Uint128)]
+    LentDistribution { round: u64 },
+    #[returns(ContractInfoResponse)]
+    ContractInfo {},
+    #[returns(HooksResponse)]
+    Hooks {},
+    #[returns(BidResponse)]
     Bid { idx: u64 },
     #[returns(BiddingInfoResponse)]
     BiddingInfo { round: u64 },
     #[returns(u64)]
     LastRoundId {},
-    #[returns(BidPool)]
-    BidPool { round: u64, slot: u8 },
-    #[returns(Vec<BidPool>)]
-    AllBidPoolInRound { round: u64 },
-    #[returns(Vec<Bid>)]
+    #[returns(CountersResponse)]
+    Counters {},
+    #[returns(Option<Addr>)]
+    LeftoverAirdrop {},
+    #[returns(Option<FinalizerGroup>)]
+    FinalizerGroup {},
+    #[returns(LockBoostsResponse)]
+    LockBoosts {},
+    #[returns(BidLock)]
+    Lock { lock_id: u64 },
+    // lock the bid was entered from, None once the bid is distributed
+    #[returns(Option<u64>)]
+    LockOfBid { idx: u64 },
+    #[returns(Option<ReinvestConfig>)]
+    ReinvestConfig {},
+    #[returns(u64)]
+    RefundDeadline {},
+    // tranches matched so far, None once the round no longer takes tranches
+    #[returns(Option<u32>)]
+    OpenTranches { round: u64 },
+    // None when permissionless finalize is disabled
+    #[returns(Option<Uint128>)]
+    FinalizeBounty {},
+    #[returns(BidPoolResponse)]
+    BidPool { round: u64, slot: u16 },
+    #[returns(Vec<BidPoolResponse>)]
+    AllBidPoolInRound {
+        round: u64,
+        start_after: Option<u16>,
+        limit: Option<u64>,
+    },
+    // pools of each of the rounds, for comparing the fill of a slot across rounds
+    #[returns(Vec<RoundBidPoolsResponse>)]
+    BidPoolsByRounds { rounds: Vec<u64> },
+    // ids of the rounds, to enumerate them before fetching their info
+    #[returns(PageResponse<u64>)]
+    RoundIds {
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
+    // idxs of the bids across every round
+    #[returns(Vec<u64>)]
+    BidIdxs {
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
+    // distinct bidders of the round, ordered by address
+    #[returns(Vec<Addr>)]
+    BiddersInRound {
+        round: u64,
+        start_after: Option<String>,
+        limit: Option<u64>,
+    },
+    #[returns(PageResponse<BidResponse>)]
     AllBidInRound {
         round: u64,
         start_after: Option<u64>,
         limit: Option<u64>,
         order_by: Option<i32>,
     },
+    // bids of one slot of the round, ordered by idx
+    #[returns(PageResponse<BidResponse>)]
+    BidsBySlot {
+        round: u64,
+        slot: u16,
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
     #[returns(Vec<u64>)]
-    BidsIdxByUser { round: u64, user: Addr },
-    #[returns(Vec<Bid>)]
-    BidsByUser { round: u64, user: Addr },
+    BidsIdxByUser { round: u64, user: String },
+    #[returns(Vec<BidResponse>)]
+    BidsByUser { round: u64, user: String },
+    // bids of the round submitted between the from_ts and to_ts timestamps, both included
+    #[returns(Vec<BidResponse>)]
+    BidsByTimeRange {
+        round: u64,
+        from_ts: u64,
+        to_ts: u64,
+        limit: Option<u64>,
+    },
+    // bids of the round submitted between the from and to block heights, both included
+    #[returns(Vec<BidResponse>)]
+    BidsByHeightRange {
+        round: u64,
+        from: u64,
+        to: u64,
+        limit: Option<u64>,
+    },
     #[returns(EstimateAmountReceiveOfBidResponse)]
     EstimateAmountReceiveOfBid {
         round: u64,
@@ -98,12 +693,257 @@ pub enum QueryMsg {
     #[returns(EstimateAmountReceiveOfBidResponse)]
     EstimateAmountReceive {
         round: u64,
-        slot: u8,
+        slot: u16,
         bid_amount: Uint128,
         exchange_rate: Decimal,
     },
+    // same as EstimateAmountReceiveOfBid, priced at the current rate of the oracle pair
+    #[returns(EstimateAmountReceiveOfBidResponse)]
+    EstimateAmountReceiveOfBidAtMarket { round: u64, idx: u64 },
+    // same as EstimateAmountReceive, priced at the current rate of the oracle pair
+    #[returns(EstimateAmountReceiveOfBidResponse)]
+    EstimateAmountReceiveAtMarket {
+        round: u64,
+        slot: u16,
+        bid_amount: Uint128,
+    },
+    #[returns(Vec<PremiumScheduleEntry>)]
+    PremiumSchedule { round: u64 },
+    // how each slot would be filled if the round was finalized at exchange_rate
+    #[returns(Vec<FillForecastEntry>)]
+    FillForecast { round: u64, exchange_rate: Decimal },
+    // the deepest slot reached by the distribution at exchange_rate
+    #[returns(ClearingSlotResponse)]
+    ClearingSlot { round: u64, exchange_rate: Decimal },
+    #[returns(Vec<UserRoundRoi>)]
+    UserRoi { user: String, rounds: Vec<u64> },
+    // bids of the user in the round with their status and amounts, for portfolio views
+    #[returns(PositionResponse)]
+    Position { round: u64, user: String },
+    // underlying tokens of the user matched over every round, counted as the bids are distributed
+    #[returns(Uint128)]
+    UserTotalMatched { user: String },
+    #[returns(RoundResultResponse)]
+    RoundResult { round: u64 },
+    // kept after the round is pruned, None before
+    #[returns(Option<RoundSummary>)]
+    RoundSummary { round: u64 },
+    #[returns(RoundConfig)]
+    RoundConfig { round: u64 },
+    #[returns(Option<Decimal>)]
+    MinExchangeRate { round: u64 },
+    #[returns(Option<RateBand>)]
+    OracleRateBand { round: u64 },
+    #[returns(u64)]
+    BidNonce { bidder: String },
+    #[returns(Uint128)]
+    BalanceOf { user: String },
+    #[returns(Payout)]
+    Payout { user: String },
+    #[returns(Vec<SlotBonus>)]
+    SlotBonuses { round: u64 },
+    #[returns(VerifyRoundResponse)]
+    VerifyRound { round: u64 },
+    #[returns(Option<DisputeParams>)]
+    DisputeParams {},
+    #[returns(Option<Dispute>)]
+    Dispute { round: u64 },
+    #[returns(Option<BidRateLimit>)]
+    BidRateLimit {},
+    #[returns(Vec<PostFinalizeAction>)]
+    PostFinalizeActions {},
+    #[returns(Vec<Addr>)]
+    TrustedRouters {
+        start_after: Option<String>,
+        limit: Option<u64>,
+    },
+    // recomputes the aggregates of the round from its bids and reports those differing from storage
+    #[returns(InvariantsResponse)]
+    CheckInvariants { round: u64 },
+    // expected and actual holdings of the underlying and distribution tokens
+    #[returns(Vec<ShadowBalanceResponse>)]
+    ShadowBalances {},
+    // holdings beyond what the contract owes, e.g. tokens sent without a hook: the amounts a
+    // rescue can sweep without touching the funds of bidders, sponsors or disputers
+    #[returns(Vec<UnaccountedBalanceResponse>)]
+    UnaccountedBalances {},
+    #[returns(Option<IbcSatellite>)]
+    IbcSatellite {},
+    #[returns(IcaRoundResponse)]
+    IcaRound {},
+    #[returns(Vec<String>)]
+    IbcChannels {},
+    #[returns(PauseFlags)]
+    PauseFlags {},
+    #[returns(RoundTemplate)]
+    RoundTemplate { name: String },
     #[returns(u64)]
     NumbersBidInRound { round: u64 },
+    #[returns(Uint128)]
+    TotalBidAt { round: u64, timestamp: u64 },
+    #[returns(Uint128)]
+    BidPoolTotalAt {
+        round: u64,
+        slot: u16,
+        timestamp: u64,
+    },
+    #[returns(Vec<TotalBidCheckpoint>)]
+    TotalBidHistory {
+        round: u64,
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
+    #[returns(BurnHistoryResponse)]
+    BurnHistory { start: u64, end: u64 },
+    #[returns(RoundStatus)]
+    RoundStatus { round: u64 },
+    #[returns(EstimateAmountReceiveOfBidResponse)]
+    EstimateAmountReceiveOfUser {
+        round: u64,
+        user: String,
+        exchange_rate: Decimal,
+    },
+    #[returns(EstimateWithAdditionalBidsResponse)]
+    EstimateWithAdditionalBids {
+        round: u64,
+        additions: Vec<(u16, Uint128)>,
+        exchange_rate: Decimal,
+    },
+    // runs the matching of the contract on hypothetical pools, filled in the order given like
+    // the slots of a round; only total_bid_amount and premium_rate of the pools are read
+    #[returns(SimulateMatchingResponse)]
+    SimulateMatching {
+        pools: Vec<BidPool>,
+        distribution: Uint128,
+        exchange_rate: Decimal,
+    },
+}
+
+// a page of a list query, next_start_after is passed as start_after to read the next page
+#[cw_serde]
+pub struct PageResponse<T> {
+    pub items: Vec<T>,
+    pub total: Option<u64>, // entries of the whole list, none when the list keeps no count
+    pub next_start_after: Option<u64>, // none on the last page
+    pub has_more: bool,
+}
+
+#[cw_serde]
+pub struct LockBoostsResponse {
+    pub boosts: Vec<LockBoost>,
+    pub reserve: Uint128, // distribution_token left to pay the boosts
+}
+
+// round ids and bid idxs start at 1
+#[cw_serde]
+pub struct CountersResponse {
+    pub last_round_id: u64, // 0 before the first round
+    pub next_bid_idx: u64,  // idx of the next bid, 1 before the first one
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub underlying_token: AssetInfo,
+    pub distribution_token: AssetInfo,
+    pub max_slot: u16,
+    pub premium_rate_per_slot: Decimal,
+    pub min_deposit_amount: Uint128,
+    pub treasury: Addr,
+    pub bidding_duration: u64,
+    pub oracle_pair: Option<Addr>,
+    pub vesting_contract: Option<Addr>,
+    pub keeper_registry: Option<Addr>,
+    pub staking_contract: Option<Addr>,
+    pub residue_staking: Option<Addr>,
+    pub wrapped_denom: Option<String>,
+    pub min_gap_between_rounds: u64,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            underlying_token: config.underlying_token,
+            distribution_token: config.distribution_token,
+            max_slot: config.max_slot,
+            premium_rate_per_slot: config.premium_rate_per_slot,
+            min_deposit_amount: config.min_deposit_amount,
+            treasury: config.treasury,
+            bidding_duration: config.bidding_duration,
+            oracle_pair: config.oracle_pair,
+            vesting_contract: config.vesting_contract,
+            keeper_registry: config.keeper_registry,
+            staking_contract: config.staking_contract,
+            residue_staking: config.residue_staking,
+            wrapped_denom: config.wrapped_denom,
+            min_gap_between_rounds: config.min_gap_between_rounds,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct BidResponse {
+    pub idx: u64,
+    pub round: u64,
+    pub premium_slot: u16,
+    pub timestamp: u64,
+    pub height: u64,
+    pub bidder: Addr,
+    pub amount: Uint128,
+    pub residue_bid: Uint128,
+    pub amount_received: Uint128,
+    pub is_distributed: bool,
+    pub auto_compound: bool,
+    pub stake_residue: bool,
+    pub reinvest: bool,
+    pub performance_fee: Uint128,
+}
+
+impl From<Bid> for BidResponse {
+    fn from(bid: Bid) -> Self {
+        Self {
+            idx: bid.idx,
+            round: bid.round,
+            premium_slot: bid.premium_slot,
+            timestamp: bid.timestamp,
+            height: bid.height,
+            bidder: bid.bidder,
+            amount: bid.amount,
+            residue_bid: bid.residue_bid,
+            amount_received: bid.amount_received,
+            is_distributed: bid.is_distributed,
+            auto_compound: bid.auto_compound,
+            stake_residue: bid.stake_residue,
+            reinvest: bid.reinvest,
+            performance_fee: bid.performance_fee,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct BidPoolResponse {
+    pub slot: u16,
+    pub total_bid_amount: Uint128,
+    pub premium_rate: Decimal,
+    pub index_snapshot: Decimal,
+    pub received_per_token: Decimal,
+    pub matched_amount: Uint128,
+    pub payout_amount: Uint128,
+}
+
+impl From<BidPool> for BidPoolResponse {
+    fn from(bid_pool: BidPool) -> Self {
+        Self {
+            slot: bid_pool.slot,
+            total_bid_amount: bid_pool.total_bid_amount,
+            premium_rate: bid_pool.premium_rate,
+            index_snapshot: bid_pool.index_snapshot,
+            received_per_token: bid_pool.received_per_token,
+            matched_amount: bid_pool.matched_amount,
+            payout_amount: bid_pool.payout_amount,
+        }
+    }
 }
 
 #[cw_serde]
@@ -114,18 +954,265 @@ pub struct BiddingInfoResponse {
 
 #[cw_serde]
 pub struct EstimateAmountReceiveOfBidResponse {
-    pub receive: Uint128,
+    pub receive: Uint128, // reward paid to the bidder, net of the performance fee
     pub residue_bid: Uint128,
+    pub performance_fee: Uint128,
 }
 
 #[cw_serde]
-pub struct MigrateMsg {
-    pub owner: Addr,
+pub struct SlotFillEstimate {
+    pub slot: u16,
+    pub index_snapshot: Decimal,
+    pub received_per_token: Decimal,
+}
+
+#[cw_serde]
+pub struct EstimateWithAdditionalBidsResponse {
+    pub additions: Vec<EstimateAmountReceiveOfBidResponse>, // estimate of each additional bid, in order
+    pub slots: Vec<SlotFillEstimate>,                       // resulting fill of every slot
+}
+
+#[cw_serde]
+pub struct SimulateMatchingResponse {
+    pub pools: Vec<BidPool>,    // pools with their matching snapshots set
+    pub total_matched: Uint128, // underlying_token matched by the distribution
+    pub remaining: Uint128,     // part of the distribution left unmatched
+}
+
+#[cw_serde]
+pub enum SlotFill {
+    Full {},
+    Partial { matched_ratio: Decimal },
+    Unmatched {},
+}
+
+#[cw_serde]
+pub struct FillForecastEntry {
+    pub slot: u16,
+    pub total_bid_amount: Uint128,
+    pub fill: SlotFill,
+}
+
+#[cw_serde]
+pub struct ContractInfoResponse {
+    pub cw2: Option<ContractVersion>, // None for instances never migrated to a tagged version
+    pub crate_version: String,
+    pub git_commit: Option<String>, // GIT_COMMIT environment variable of the build
+}
+
+#[cw_serde]
+pub struct ClearingSlotResponse {
+    pub slot: Option<u16>, // highest slot with any fill, None when nothing is matched
+    pub fill: Decimal,     // matched share of the pool at that slot
+}
+
+#[cw_serde]
+pub struct PremiumScheduleEntry {
+    pub slot: u16,
+    pub premium_rate: Decimal,
+    pub total_bid_amount: Uint128,
+    pub discount: Decimal, // discount to the market price on the matched part of a bid
+    pub expected_fill: Option<Decimal>, // expected matched share of the pool, None without an oracle pair
+}
+
+#[cw_serde]
+pub struct RoundBidPoolsResponse {
+    pub round: u64,
+    pub bid_pools: Vec<BidPoolResponse>,
+}
+
+#[cw_serde]
+pub struct ShadowBalanceResponse {
+    pub asset_info: AssetInfo,
+    pub expected: Uint128, // holdings derived from the tokens received and sent
+    pub actual: Uint128,   // balance of the contract
+    pub escrowed: Uint128, // deposited in the yield venue or lent, not held by the contract
+}
+
+#[cw_serde]
+pub struct UnaccountedBalanceResponse {
+    pub asset_info: AssetInfo,
+    pub balance: Uint128,     // balance of the contract
+    pub obligations: Uint128, // part of the balance owed or expected to be held
+    pub unaccounted: Uint128, // balance beyond the obligations
+}
+
+#[cw_serde]
+pub struct InvariantsResponse {
+    pub round: u64,
+    pub is_consistent: bool,
+    pub mismatches: Vec<InvariantMismatch>,
+}
+
+#[cw_serde]
+pub struct InvariantMismatch {
+    pub invariant: String, // name of the stored aggregate
+    pub slot: Option<u16>, // set for pool invariants
+    pub stored: Uint128,   // value read from storage, or the bound for max_received
+    pub computed: Uint128, // value derived from the bids
+}
+
+#[cw_serde]
+pub struct IcaRoundResponse {
+    pub ica_treasury: Option<Addr>,
+    pub requested_distribution: Option<Uint128>, // None when no round is pending
+    pub funded: Uint128,
+}
+
+#[cw_serde]
+pub enum BidStatus {
+    Open,             // the round is not finalized yet
+    Matched,          // the whole bid was matched
+    PartiallyMatched, // part of the bid was matched, the rest is refunded
+    Refunded,         // nothing was matched, the whole bid is refunded
+}
+
+#[cw_serde]
+pub struct BidPosition {
+    pub idx: u64,
+    pub premium_slot: u16,
+    pub amount: Uint128,
+    pub status: BidStatus,
+    pub matched_amount: Uint128,    // underlying tokens matched
+    pub received_amount: Uint128,   // distribution tokens received, net of the performance fee
+    pub residue_amount: Uint128,    // underlying tokens refunded
+    pub pending_reward: Uint128,    // part of received_amount not distributed yet
+    pub claimable_residue: Uint128, // part of residue_amount that can be claimed with ClaimResidue
+}
+
+#[cw_serde]
+pub struct PositionResponse {
+    pub round: u64,
+    pub user: Addr,
+    pub round_status: RoundStatus,
+    pub bids: Vec<BidPosition>,
+    pub total_bid_amount: Uint128, // underlying tokens put up in the bids of the user
+    pub total_matched: Uint128,    // underlying tokens of the user matched
+    pub total_received: Uint128,   // distribution tokens received, net of the performance fee
+    pub total_residue: Uint128,    // underlying tokens refunded
+    pub total_pending: Uint128,    // distribution tokens not distributed yet
+}
+
+#[cw_serde]
+pub struct UserRoundRoi {
+    pub round: u64,
+    pub exchange_rate: Decimal,    // finalized exchange rate of the round
+    pub matched_amount: Uint128,   // underlying tokens of the user that were matched
+    pub received_amount: Uint128,  // distribution tokens received for the matched amount
+    pub realized_premium: Decimal, // received value over matched value at the finalized rate, minus one
+}
+
+#[cw_serde]
+pub struct SlotResult {
+    pub slot: u16,
+    pub total_bid_amount: Uint128,
+    pub matched_percentage: Decimal, // share of the pool that was matched
+    pub matched_amount: Uint128,     // underlying_token of the pool that was matched
+    pub payout_amount: Uint128,      // tokens allocated to the pool, before performance fee
+}
+
+#[cw_serde]
+pub struct VerifyRoundResponse {
+    pub round: u64,
+    pub total_received: Uint128,
+    pub actual_distributed: Uint128,
+    pub total_residue: Uint128,
+    pub total_unmatched: Uint128,
+    pub num_bids_distributed: u64,
+    pub is_valid: bool, // received and residue sums are within the round totals
+}
+
+#[cw_serde]
+pub struct RoundResultResponse {
+    pub round: u64,
+    pub exchange_rate: Decimal,
+    pub total_bid_amount: Uint128,
+    pub total_matched: Uint128,
+    pub total_distribution: Uint128,
+    pub actual_distributed: Uint128,
+    pub leftover: Uint128, // distribution tokens sent back to the owner
+    pub slots: Vec<SlotResult>,
+    pub num_bids: u64,
+    pub num_bids_distributed: u64,
+}
+
+#[cw_serde]
+pub struct TotalBidCheckpoint {
+    pub timestamp: u64,
+    pub total_bid_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct EpochBurnStats {
+    pub epoch_start: u64,
+    pub underlying_burned: Uint128,
+    pub distribution_paid: Uint128,
+}
+
+#[cw_serde]
+pub struct BurnHistoryResponse {
+    pub total_underlying_burned: Uint128,
+    pub total_distribution_paid: Uint128,
+    pub epochs: Vec<EpochBurnStats>,
+}
+
+#[cw_serde]
+pub enum RoundStatus {
+    Upcoming,         // bidding has not started yet
+    Open,             // accepting bids
+    Ended,            // bidding is over, waiting for the result to be finalized
+    Finalized,        // result is finalized, bids are being distributed
+    FullyDistributed, // every bid in the round has been distributed
+    Cancelled,        // the round was cancelled before it started
+    Failed,           // the round was not finalized in time, its bids are refunded
+}
+
+#[cw_serde]
+pub enum SudoMsg {
+    UpdateParams {
+        max_slot: Option<u16>,
+        premium_rate_per_slot: Option<Decimal>,
+        min_deposit_amount: Option<Uint128>,
+        bidding_duration: Option<u64>,
+        treasury: Option<String>,
+        oracle_pair: Option<String>,
+    },
+}
+
+// untagged so the flat config of the original migrate message is still accepted as is
+#[cw_serde]
+#[serde(untagged)]
+pub enum MigrateMsg {
+    // replaces the stored config
+    UpdateConfig(Box<MigrateConfigMsg>),
+    // migrates the stored state, e.g. {"backfill":{}}
+    State(StateMigrateMsg),
+}
+
+#[cw_serde]
+pub enum StateMigrateMsg {
+    // backfills the stored bids in chunks, continued with ExecuteMsg::ContinueMigration
+    Backfill {},
+    // moves the pools, slot indexes and checkpoints keyed by u8 slots to the u16 keyed maps in
+    // chunks, continued with ExecuteMsg::ContinueMigration
+    WidenSlots {},
+}
+
+#[cw_serde]
+pub struct MigrateConfigMsg {
+    pub owner: String,
     pub underlying_token: AssetInfo,
     pub distribution_token: AssetInfo,
-    pub max_slot: u8,
+    pub max_slot: u16,
     pub premium_rate_per_slot: Decimal,
     pub min_deposit_amount: Uint128,
-    pub treasury: Addr,
+    pub treasury: String,
     pub bidding_duration: u64,
+    pub oracle_pair: Option<String>,
+    pub vesting_contract: Option<String>,
+    pub keeper_registry: Option<String>,
+    pub staking_contract: Option<String>,
+    pub residue_staking: Option<String>,
+    pub wrapped_denom: Option<String>,
+    pub min_gap_between_rounds: Option<u64>,
 }
@@ -0,0 +1,247 @@
+// reinvesting bids: the rewards of a distribute batch are swapped together through the reinvest
+// pair, the underlying_token received is split pro-rata to the rewards and bid in the next open
+// round at the slot of each distributed bid. A swap past max_spread pays the rewards as usual
+use cosmwasm_std::{
+    to_json_binary, Decimal, DepsMut, Env, Event, MessageInfo, QuerierWrapper, Response, StdError,
+    StdResult, Storage, SubMsg, SubMsgResult, Uint128,
+};
+use oraiswap::{
+    asset::{Asset, AssetInfo, PairInfo},
+    pair::{
+        Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg,
+    },
+};
+
+use crate::{
+    bid::submit_reinvested_bid,
+    error::ContractError,
+    helper::{into_cosmos_msg, into_send_msg, query_balance},
+    state::{
+        credit_shadow_balance, read_round_state, Config, PendingReinvest, ReinvestConfig,
+        ReinvestShare, RoundState, CONFIG, LAST_ROUND_ID, PENDING_REINVEST, REINVEST_CONFIG,
+    },
+};
+
+pub const REINVEST_REPLY_ID: u64 = 4;
+const MAX_REINVEST_SPREAD: Decimal = Decimal::percent(10);
+
+pub fn execute_set_reinvest_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    reinvest_config: Option<ReinvestConfig>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let Some(reinvest_config) = reinvest_config else {
+        REINVEST_CONFIG.remove(deps.storage);
+        return Ok(Response::new().add_attribute("action", "remove_reinvest_config"));
+    };
+    deps.api.addr_validate(reinvest_config.pair.as_str())?;
+    if reinvest_config.max_spread.is_zero() || reinvest_config.max_spread > MAX_REINVEST_SPREAD {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Reinvest max spread must be above 0 and at most {}",
+            MAX_REINVEST_SPREAD
+        ))));
+    }
+    let pair_info: PairInfo = deps
+        .querier
+        .query_wasm_smart(&reinvest_config.pair, &PairQueryMsg::Pair {})?;
+    if !pair_info.asset_infos.contains(&config.distribution_token)
+        || !pair_info.asset_infos.contains(&config.underlying_token)
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Reinvest pair must trade distribution_token for underlying_token",
+        )));
+    }
+    REINVEST_CONFIG.save(deps.storage, &reinvest_config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_reinvest_config"),
+        ("pair", reinvest_config.pair.as_str()),
+        ("max_spread", &reinvest_config.max_spread.to_string()),
+    ]))
+}
+
+// open round the rewards of round are bid in, None when reinvesting is disabled or no later
+// round is accepting bids
+pub fn reinvest_round(storage: &dyn Storage, env: &Env, round: u64) -> StdResult<Option<u64>> {
+    if !REINVEST_CONFIG.exists(storage) {
+        return Ok(None);
+    }
+    for next_round in round + 1..=LAST_ROUND_ID.load(storage)? {
+        if read_round_state(storage, env, next_round)? == RoundState::Open {
+            return Ok(Some(next_round));
+        }
+    }
+    Ok(None)
+}
+
+// swaps the rewards of the shares in one message, the balance is measured around the swap so
+// only the underlying_token it returned is bid in the reply
+pub fn prepare_reinvest(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    config: &Config,
+    round: u64,
+    shares: Vec<ReinvestShare>,
+) -> Result<SubMsg, ContractError> {
+    let reinvest_config = REINVEST_CONFIG.load(storage)?;
+    let total_reward = shares.iter().try_fold(Uint128::zero(), |total, share| {
+        total.checked_add(share.reward)
+    })?;
+    let balance_before = query_balance(
+        querier,
+        &config.underlying_token,
+        env.contract.address.clone(),
+    )?;
+    PENDING_REINVEST.save(
+        storage,
+        &PendingReinvest {
+            round,
+            balance_before,
+            shares,
+        },
+    )?;
+
+    let swap_msg = match &config.distribution_token {
+        AssetInfo::Token { .. } => to_json_binary(&PairCw20HookMsg::Swap {
+            belief_price: None,
+            max_spread: Some(reinvest_config.max_spread),
+            to: None,
+        })?,
+        AssetInfo::NativeToken { .. } => to_json_binary(&PairExecuteMsg::Swap {
+            offer_asset: Asset {
+                info: config.distribution_token.clone(),
+                amount: total_reward,
+            },
+            belief_price: None,
+            max_spread: Some(reinvest_config.max_spread),
+            to: None,
+        })?,
+    };
+    // a failed swap must not revert the distribution, the reply pays the rewards instead
+    Ok(SubMsg::reply_always(
+        into_send_msg(
+            &config.distribution_token,
+            reinvest_config.pair.to_string(),
+            total_reward,
+            swap_msg,
+        )?,
+        REINVEST_REPLY_ID,
+    ))
+}
+
+pub fn reply_reinvest(
+    mut deps: DepsMut,
+    env: Env,
+    result: SubMsgResult,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_REINVEST.load(deps.storage)?;
+    PENDING_REINVEST.remove(deps.storage);
+    let config = CONFIG.load(deps.storage)?;
+
+    // the rewards were never sent, they are paid to the bidders as usual
+    if let SubMsgResult::Err(err) = result {
+        let msgs = pending
+            .shares
+            .iter()
+            .map(|share| {
+                into_cosmos_msg(
+                    &config.distribution_token,
+                    share.bidder.to_string(),
+                    share.reward,
+                )
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        return Ok(Response::new().add_messages(msgs).add_attributes(vec![
+            ("action", "reinvest_failed"),
+            ("round", &pending.round.to_string()),
+            ("error", &err),
+        ]));
+    }
+
+    let balance_after = query_balance(
+        &deps.querier,
+        &config.underlying_token,
+        env.contract.address.clone(),
+    )?;
+    let received = balance_after.checked_sub(pending.balance_before)?;
+    let total_reward = pending
+        .shares
+        .iter()
+        .try_fold(Uint128::zero(), |total, share| {
+            total.checked_add(share.reward)
+        })?;
+
+    let mut msgs = vec![];
+    let mut events = vec![];
+    let mut allocated = Uint128::zero();
+    let mut refunded = Uint128::zero();
+    let num_shares = pending.shares.len();
+    for (i, share) in pending.shares.into_iter().enumerate() {
+        // the last share takes the rounding dust
+        let amount = if i + 1 == num_shares {
+            received.checked_sub(allocated)?
+        } else {
+            received.multiply_ratio(share.reward, total_reward)
+        };
+        allocated = allocated.checked_add(amount)?;
+
+        // the underlying_token of a rejected bid is sent to the bidder
+        match submit_reinvested_bid(
+            deps.branch(),
+            env.clone(),
+            pending.round,
+            share.premium_slot,
+            share.bidder.to_string(),
+            Asset {
+                info: config.underlying_token.clone(),
+                amount,
+            },
+        ) {
+            Ok(response) => events.push(
+                Event::new("reinvest_bid")
+                    .add_attributes(response.attributes)
+                    .add_attribute("from_bid_idx", share.bid_idx.to_string())
+                    .add_attribute("reward", share.reward.to_string()),
+            ),
+            Err(err) => {
+                if !amount.is_zero() {
+                    msgs.push(into_cosmos_msg(
+                        &config.underlying_token,
+                        share.bidder.to_string(),
+                        amount,
+                    )?);
+                }
+                refunded = refunded.checked_add(amount)?;
+                events.push(Event::new("reinvest_refund").add_attributes(vec![
+                    ("from_bid_idx", share.bid_idx.to_string()),
+                    ("bidder", share.bidder.to_string()),
+                    ("amount", amount.to_string()),
+                    ("reason", err.to_string()),
+                ]));
+            }
+        }
+    }
+    // the swap is not seen by the execute entry point, nor are the refunds of this reply
+    credit_shadow_balance(
+        deps.storage,
+        &config,
+        &config.underlying_token,
+        received.checked_sub(refunded)?,
+    )?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_events(events)
+        .add_attributes(vec![
+            ("action", "reinvest"),
+            ("round", &pending.round.to_string()),
+            ("total_reward", &total_reward.to_string()),
+            ("received", &received.to_string()),
+        ]))
+}
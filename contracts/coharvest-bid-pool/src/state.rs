@@ -1,23 +1,179 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Env, Order, StdError, StdResult, Storage, Uint128};
+use cosmwasm_std::{
+    Addr, Binary, Coin, Decimal, Env, Order, StdError, StdResult, Storage, Uint128,
+};
+use cw_controllers::Hooks;
 use cw_storage_plus::{Bound, Item, Map};
-use oraiswap::asset::AssetInfo;
+use oraiswap::asset::{Asset, AssetInfo};
 
 pub const CONFIG: Item<Config> = Item::new("config");
+// contracts notified of the round lifecycle
+pub const HOOKS: Hooks = Hooks::new("hooks");
 // mapping (round, slot) --> BiddingPool
-pub const BID_POOL: Map<(u64, u8), BidPool> = Map::new("bid_pool");
+pub const BID_POOL: Map<(u64, u16), BidPool> = Map::new("bid_pool_v2");
 // mapping round --> BiddingInfo
 pub const BIDDING_INFO: Map<u64, BiddingInfo> = Map::new("bidding_info");
+// ids of rounds and bids start at 1: id of the last created round, 0 before the first one
 pub const LAST_ROUND_ID: Item<u64> = Item::new("last_round_id");
 // mapping (round, address) -> vec bid_idx of user
 pub const BIDS_BY_USER: Map<(u64, Addr), Vec<u64>> = Map::new("bids_by_user");
 // mapping (round, bid_idx) --> (true - bid_idx is included in this round)
 pub const BIDS_BY_ROUND: Map<(u64, u64), bool> = Map::new("bids_by_round");
+// mapping (round, slot, bid_idx) --> true, bids of the round ordered by premium slot
+pub const BIDS_BY_SLOT: Map<(u64, u16, u64), bool> = Map::new("bids_by_slot_v2");
+// mapping (round, height, bid_idx) --> true, bids of the round ordered by block height
+pub const BIDS_BY_HEIGHT: Map<(u64, u64, u64), bool> = Map::new("bids_by_height");
+// mapping (round, timestamp, bid_idx) --> true, bids of the round ordered by submission time
+pub const BIDS_BY_TIME: Map<(u64, u64, u64), bool> = Map::new("bids_by_time");
 // mapping id --> Bid
 pub const BID: Map<u64, Bid> = Map::new("bid");
+// idx of the next bid, 1 before the first one
 pub const BID_IDX: Item<u64> = Item::new("bid_idx");
 pub const DISTRIBUTION_INFO: Map<u64, DistributionInfo> = Map::new("distribution_info");
+// mapping (round, timestamp) --> total bid amount of the round at that time
+pub const ROUND_TOTAL_CHECKPOINTS: Map<(u64, u64), Uint128> = Map::new("round_total_checkpoints");
+// mapping (round, slot, timestamp) --> total bid amount of the pool at that time
+pub const POOL_TOTAL_CHECKPOINTS: Map<(u64, u16, u64), Uint128> =
+    Map::new("pool_total_checkpoints_v2");
+// mapping round --> number of bids in the round
+pub const NUM_BIDS_BY_ROUND: Map<u64, u64> = Map::new("num_bids_by_round");
+// number of rounds removed by PruneRound, the rounds left are LAST_ROUND_ID minus these
+pub const NUM_PRUNED_ROUNDS: Item<u64> = Item::new("num_pruned_rounds");
+// mapping round --> number of distinct bidders in the round
+pub const NUM_BIDDERS_BY_ROUND: Map<u64, u64> = Map::new("num_bidders_by_round");
+// mapping round --> last bid_idx processed by distribute
+pub const DISTRIBUTE_CURSOR: Map<u64, u64> = Map::new("distribute_cursor");
+// last bid idx processed by the running backfill migration
+pub const MIGRATION_CURSOR: Item<u64> = Item::new("migration_cursor");
+// set by StateMigrateMsg::WidenSlots until the u8 slot keyed maps are moved to the u16 ones
+pub const SLOT_KEY_MIGRATION: Item<bool> = Item::new("slot_key_migration");
+// slot keyed maps of the contracts deployed with u8 slots, drained by the slot key migration
+const LEGACY_BID_POOL: Map<(u64, u8), BidPool> = Map::new("bid_pool");
+const LEGACY_BIDS_BY_SLOT: Map<(u64, u8, u64), bool> = Map::new("bids_by_slot");
+const LEGACY_POOL_TOTAL_CHECKPOINTS: Map<(u64, u8, u64), Uint128> =
+    Map::new("pool_total_checkpoints");
+const LEGACY_LP_BID_TOTALS: Map<(u64, u8), Uint128> = Map::new("lp_bid_totals");
+// mapping round --> last (slot, bid_idx) processed by distribute in slot order
+pub const SLOT_DISTRIBUTE_CURSOR: Map<u64, (u16, u64)> = Map::new("slot_distribute_cursor");
+// mapping epoch --> amount burned and distributed in that epoch
+pub const BURN_STATS: Map<u64, BurnStats> = Map::new("burn_stats");
+// mapping bidder --> secp256k1 pubkey allowed to sign bids for the bidder
+pub const BIDDER_PUBKEY: Map<&Addr, Binary> = Map::new("bidder_pubkey");
+// mapping bidder --> nonce expected in the next signed bid
+pub const BID_NONCE: Map<&Addr, u64> = Map::new("bid_nonce");
+// mapping bidder --> underlying tokens deposited and not yet used in a bid
+pub const DEPOSITS: Map<&Addr, Uint128> = Map::new("deposits");
+// mapping bidder --> payouts credited by Distribute and not yet withdrawn
+pub const PAYOUTS: Map<&Addr, Payout> = Map::new("payouts");
+// mapping (round, slot) --> LP tokens bid in the pool, converted at finalize
+pub const LP_BID_TOTALS: Map<(u64, u16), Uint128> = Map::new("lp_bid_totals_v2");
+// mapping round --> rate the LP bids of the round were withdrawn at
+pub const LP_WITHDRAW_RATES: Map<u64, LpWithdrawRate> = Map::new("lp_withdraw_rates");
+// mapping round --> LP tokens of the withdrawn bids whose other leg is not paid back yet
+pub const LP_UNSETTLED_SHARES: Map<u64, Uint128> = Map::new("lp_unsettled_shares");
+// mapping round --> extra rewards attached by partners to some slots of the round
+pub const SLOT_BONUSES: Map<u64, Vec<SlotBonus>> = Map::new("slot_bonuses");
+// mapping name --> RoundTemplate reused to create recurring rounds
+pub const ROUND_TEMPLATES: Map<&str, RoundTemplate> = Map::new("round_templates");
+// mapping round --> minimum exchange rate committed when the round was created
+pub const MIN_EXCHANGE_RATES: Map<u64, Decimal> = Map::new("min_exchange_rates");
+// mapping round --> band the oracle rate must be in to finalize the round permissionlessly, the
+// spot rate of the pair can be moved within a transaction
+pub const ORACLE_RATE_BANDS: Map<u64, RateBand> = Map::new("oracle_rate_bands");
+// mapping round --> RoundConfig, rounds without an entry use the default
+pub const ROUND_CONFIG: Map<u64, RoundConfig> = Map::new("round_config");
+// operations disabled by the owner, nothing is paused when unset
+pub const PAUSE_FLAGS: Item<PauseFlags> = Item::new("pause_flags");
+// optional cap on the number of bids an address can submit in a sliding window
+pub const BID_RATE_LIMIT: Item<BidRateLimit> = Item::new("bid_rate_limit");
+// mapping bidder --> timestamps of the bids submitted within the current window
+pub const RECENT_BIDS: Map<&Addr, Vec<u64>> = Map::new("recent_bids");
+// optional bonded disputes of the finalized exchange rates
+pub const DISPUTE_PARAMS: Item<DisputeParams> = Item::new("dispute_params");
+// mapping round --> time the burn and leftover of a disputable round are settled after
+pub const SETTLE_AFTER: Map<u64, u64> = Map::new("settle_after");
+// mapping round --> open dispute of the finalized exchange rate
+pub const DISPUTES: Map<u64, Dispute> = Map::new("disputes");
+// mapping round --> finalization of the round split over several ContinueFinalize calls
+pub const FINALIZE_PROGRESS: Map<u64, FinalizeProgress> = Map::new("finalize_progress");
+// mapping round --> running sums of the distributed bids, checked against the round totals
+pub const PAYOUT_TOTALS: Map<u64, PayoutTotals> = Map::new("payout_totals");
+// optional whitelisted venue the pooled underlying of an open round can earn yield in
+pub const YIELD_VENUE: Item<YieldVenue> = Item::new("yield_venue");
+// underlying currently deposited in the yield venue, a single round at a time
+pub const YIELD_POSITION: Item<YieldPosition> = Item::new("yield_position");
+// optional whitelisted money market the escrowed distribution of open rounds can be lent on
+pub const LENDING_MARKET: Item<Addr> = Item::new("lending_market");
+// mapping round --> distribution_token lent on the money market, pulled back at finalize
+pub const LENT_DISTRIBUTION: Map<u64, Uint128> = Map::new("lent_distribution");
+// mapping bid_idx --> contract notified once the bid is distributed
+pub const BID_NOTIFIERS: Map<u64, Addr> = Map::new("bid_notifiers");
+// mapping bidder --> underlying tokens matched over every distributed bid of the bidder
+pub const USER_TOTAL_MATCHED: Map<&Addr, Uint128> = Map::new("user_total_matched");
+// IBC voucher denom accepted by remote bids
+pub const IBC_VOUCHER_DENOM: Item<String> = Item::new("ibc_voucher_denom");
+// counterparty allowed to open channels with the contract
+pub const IBC_SATELLITE: Item<IbcSatellite> = Item::new("ibc_satellite");
+// mapping channel_id --> true while the channel with the satellite is open
+pub const IBC_CHANNELS: Map<&str, bool> = Map::new("ibc_channels");
+// mapping bidder --> channel whose satellite may submit, cancel and claim bids of the bidder
+pub const IBC_DELEGATIONS: Map<&Addr, String> = Map::new("ibc_delegations");
+// mapping router --> true, routers may submit bids credited to the bidders they name
+pub const TRUSTED_ROUTERS: Map<&Addr, bool> = Map::new("trusted_routers");
+// interchain account of a remote treasury, accepted as the treasury when creating rounds
+pub const ICA_TREASURY: Item<Addr> = Item::new("ica_treasury");
+// total distribution of the round announced by the interchain account, waiting for its funding
+pub const ICA_ROUND_REQUEST: Item<Uint128> = Item::new("ica_round_request");
+// distribution tokens received for interchain account rounds that are not opened yet
+pub const ICA_FUNDING: Item<Uint128> = Item::new("ica_funding");
+// actions executed after every finalize, with the amounts of the finalized round
+pub const POST_FINALIZE_ACTIONS: Item<Vec<PostFinalizeAction>> = Item::new("post_finalize_actions");
+// the cw20 underlying token takes a tax on transfers, bids must be measured with SubmitBidFrom
+pub const TRANSFER_TAX: Item<bool> = Item::new("transfer_tax");
+// bid waiting for its TransferFrom to be measured in the reply
+pub const PENDING_TRANSFER: Item<PendingTransfer> = Item::new("pending_transfer");
+// mapping asset --> holdings of the underlying or distribution token the contract expects
+pub const SHADOW_BALANCES: Map<&str, Uint128> = Map::new("shadow_balances");
+// mapping round --> summary of the round, kept once its bids and pools are pruned
+pub const ROUND_SUMMARIES: Map<u64, RoundSummary> = Map::new("round_summaries");
+// mapping round --> lifecycle state, rounds created before it was stored derive it in
+// read_round_state
+pub const ROUND_STATES: Map<u64, RoundState> = Map::new("round_states");
+// cw4 group whose members may finalize rounds besides the owner and the keeper registry
+pub const FINALIZER_GROUP: Item<FinalizerGroup> = Item::new("finalizer_group");
+// mapping (round, member) --> exchange rate the member approved to finalize the round at
+pub const FINALIZE_APPROVALS: Map<(u64, &Addr), Decimal> = Map::new("finalize_approvals");
+// bounty paid in distribution tokens to the caller of FinalizeAtOracleRate, set while
+// permissionless finalize is enabled
+pub const FINALIZE_BOUNTY: Item<Uint128> = Item::new("finalize_bounty");
+// seconds after the end of a round without finalize before anyone can refund its bids,
+// DEFAULT_REFUND_DEADLINE when unset
+pub const REFUND_DEADLINE: Item<u64> = Item::new("refund_deadline");
+// mapping round --> tranches matched so far, while a tranched round takes more funding
+pub const OPEN_TRANCHES: Map<u64, u32> = Map::new("open_tranches");
+// mapping round --> last bid refunded by TriggerRefunds
+pub const REFUND_CURSOR: Map<u64, u64> = Map::new("refund_cursor");
+// airdrop contract receiving the leftover distribution of settled rounds instead of the owner
+pub const LEFTOVER_AIRDROP: Item<Addr> = Item::new("leftover_airdrop");
+// tiers of the locked bids by lock length, empty or unset when locked bids are disabled
+pub const LOCK_BOOSTS: Item<Vec<LockBoost>> = Item::new("lock_boosts");
+// distribution_token funded to pay the boosted premiums of the locked bids
+pub const LOCK_BOOST_RESERVE: Item<Uint128> = Item::new("lock_boost_reserve");
+// id of the next lock
+pub const LOCK_IDX: Item<u64> = Item::new("lock_idx");
+// mapping lock_id --> deposit of a bidder entering the next rounds until the lock ends
+pub const LOCKS: Map<u64, BidLock> = Map::new("locks");
+// mapping bid_idx --> lock the bid was entered from, until the bid is distributed
+pub const LOCKED_BIDS: Map<u64, u64> = Map::new("locked_bids");
+// oraiswap pair swapping the rewards of the reinvesting bids, unset when reinvesting is disabled
+pub const REINVEST_CONFIG: Item<ReinvestConfig> = Item::new("reinvest_config");
+// rewards waiting for their swap to be measured in the reply
+pub const PENDING_REINVEST: Item<PendingReinvest> = Item::new("pending_reinvest");
+// staking derivative bids waiting for their redeem to be measured in the reply
+pub const PENDING_LSD_REDEEM: Item<PendingLsdRedeem> = Item::new("pending_lsd_redeem");
 
+// length of a burn statistics epoch, in seconds (1 day)
+pub const EPOCH_DURATION: u64 = 86400;
 const MAX_LIMIT: u64 = 1000;
 const DEFAULT_LIMIT: u64 = 30;
 
@@ -26,11 +182,357 @@ pub struct Config {
     pub owner: Addr,                    // owner address
     pub underlying_token: AssetInfo,    // token used to participate in bidding
     pub distribution_token: AssetInfo,  // tokens are used to reward bidding
-    pub max_slot: u8,                   // number of pools in a bidding round
+    pub max_slot: u16,                  // number of pools in a bidding round
     pub premium_rate_per_slot: Decimal, // Premium rate increase for each slot
     pub min_deposit_amount: Uint128,    // minimum number of tokens when participating in bidding
     pub treasury: Addr,                 // treasury address
     pub bidding_duration: u64,          // how long does a bidding round last?
+    pub oracle_pair: Option<Addr>,      // oraiswap pair used to price underlying_token
+    pub vesting_contract: Option<Addr>, // vesting contract receiving the payouts of vested rounds
+    pub keeper_registry: Option<Addr>,  // keeper registry allowed to finalize rounds
+    pub staking_contract: Option<Addr>, // earn contract receiving auto-compounded rewards
+    pub residue_staking: Option<Addr>,  // ORAIX staking contract receiving staked residues
+    pub wrapped_denom: Option<String>,  // native denom wrapped into a cw20 underlying_token
+    #[serde(default)]
+    pub min_gap_between_rounds: u64, // seconds between the end of a round and the next start
+}
+
+impl Config {
+    // rounds pay their rewards in underlying_token, see process_calc_rebate_amount
+    pub fn is_rebate(&self) -> bool {
+        self.underlying_token == self.distribution_token
+    }
+
+    // assets whose holdings are kept in SHADOW_BALANCES
+    pub fn is_tracked(&self, asset_info: &AssetInfo) -> bool {
+        self.underlying_token.eq(asset_info) || self.distribution_token.eq(asset_info)
+    }
+
+    // upper bound of the payouts of a finalized round, rebate payouts include the matched principal
+    pub fn max_received(
+        &self,
+        distribution_info: &DistributionInfo,
+        bidding_info: &BiddingInfo,
+    ) -> StdResult<Uint128> {
+        if self.is_rebate() {
+            Ok(distribution_info
+                .actual_distributed
+                .checked_add(bidding_info.total_bid_matched)?)
+        } else {
+            Ok(distribution_info.actual_distributed)
+        }
+    }
+}
+
+// per round settings, every field has a default so new ones can be added without a migration
+#[cw_serde]
+#[derive(Default)]
+pub struct RoundConfig {
+    #[serde(default)]
+    pub vested_rewards: bool, // payouts are streamed through the vesting contract
+    #[serde(default)]
+    pub credit_payouts: bool, // payouts are credited to a withdrawable balance instead of sent
+    #[serde(default)]
+    pub lp_pair: Option<Addr>, // oraiswap pair whose LP tokens are accepted as bids
+    #[serde(default)]
+    pub distribute_by_slot: bool, // distribute the lowest premium slots first instead of by bid idx
+    #[serde(default)]
+    pub total_bid_threshold: Option<Uint128>, // below this total bid volume every bid is refunded
+    #[serde(default)]
+    pub min_participants: Option<u64>, // below this number of distinct bidders every bid is refunded
+    #[serde(default)]
+    pub premium_curve: PremiumCurve, // premium rate of each slot, the linear ladder by default
+    #[serde(default)]
+    pub lend_distribution: bool, // the escrowed distribution can be lent on the money market until end_time
+    #[serde(default)]
+    pub performance_fee: Option<Decimal>, // share of the premium portion of each payout sent to the treasury
+    #[serde(default)]
+    pub tranched: bool, // funding added after finalize is matched against the unmatched bids until closed
+    #[serde(default)]
+    pub lsd_adapter: Option<Addr>, // adapter redeeming the staking derivative accepted as bids at finalize
+}
+
+#[cw_serde]
+pub enum PremiumCurve {
+    // premium_rate_per_slot * slot
+    Linear {},
+    // premium_rate_per_slot * (1 + growth)^(slot - 1)
+    Exponential { growth: Decimal },
+    // interpolated between the breakpoints, from 0 at slot 0 and flat after the last one
+    Breakpoints { points: Vec<PremiumBreakpoint> },
+}
+
+#[cw_serde]
+pub struct PremiumBreakpoint {
+    pub slot: u16,
+    pub premium_rate: Decimal,
+}
+
+impl Default for PremiumCurve {
+    fn default() -> Self {
+        PremiumCurve::Linear {}
+    }
+}
+
+impl PremiumCurve {
+    pub fn premium_rate(&self, premium_rate_per_slot: Decimal, slot: u16) -> StdResult<Decimal> {
+        match self {
+            PremiumCurve::Linear {} => Ok(premium_rate_per_slot.checked_mul(
+                Decimal::from_atomics(Uint128::from(slot as u128), 0)
+                    .map_err(|err| StdError::generic_err(err.to_string()))?,
+            )?),
+            PremiumCurve::Exponential { growth } => Ok(premium_rate_per_slot.checked_mul(
+                (Decimal::one().checked_add(*growth)?).checked_pow(slot.saturating_sub(1) as u32)?,
+            )?),
+            PremiumCurve::Breakpoints { points } => {
+                let (mut prev_slot, mut prev_rate) = (0u16, Decimal::zero());
+                for point in points {
+                    if slot <= point.slot {
+                        let step = Decimal::from_ratio(slot - prev_slot, point.slot - prev_slot);
+                        return Ok(prev_rate.checked_add(
+                            point
+                                .premium_rate
+                                .checked_sub(prev_rate)?
+                                .checked_mul(step)?,
+                        )?);
+                    }
+                    (prev_slot, prev_rate) = (point.slot, point.premium_rate);
+                }
+                Ok(prev_rate)
+            }
+        }
+    }
+}
+
+// amount of each leg returned per LP token when the LP bids of a round are withdrawn
+#[cw_serde]
+pub struct LpWithdrawRate {
+    pub underlying_per_share: Decimal, // underlying_token per LP token, used as bid amount
+    pub other_asset: AssetInfo,        // the other asset of the pair
+    pub other_per_share: Decimal,      // other asset per LP token, refunded to the bidder
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseFlags {
+    pub submit_bid: bool,   // new bids are rejected
+    pub create_round: bool, // new rounds can't be created
+    pub finalize: bool,     // ended rounds can't be finalized
+    pub distribute: bool,   // rewards of finalized rounds can't be distributed
+}
+
+// ordered by the lifecycle of a round, Open and Ended follow Created with the bidding time and are
+// never stored
+#[cw_serde]
+#[derive(Copy, PartialOrd)]
+pub enum RoundState {
+    Created,      // created, bidding has not started yet
+    Open,         // accepting bids
+    Ended,        // bidding is over, waiting for the result to be finalized
+    Finalized,    // result is finalized, no bid has been distributed yet
+    Distributing, // some bids of the round have been distributed
+    Completed,    // every bid of the round has been distributed
+    Cancelled,    // cancelled before it started, the round id is reused by the next round
+    Failed,       // not finalized before the refund deadline, its bids are refunded
+}
+
+impl RoundState {
+    pub fn can_transition_to(&self, next: RoundState) -> bool {
+        match self {
+            RoundState::Cancelled => next == RoundState::Created,
+            RoundState::Created => next == RoundState::Cancelled,
+            RoundState::Open => false,
+            RoundState::Ended => next == RoundState::Finalized || next == RoundState::Failed,
+            // an upheld dispute finalizes the round again before anything is distributed
+            RoundState::Finalized => next >= RoundState::Finalized && next <= RoundState::Completed,
+            RoundState::Distributing => {
+                next == RoundState::Distributing || next == RoundState::Completed
+            }
+            RoundState::Completed | RoundState::Failed => false,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PendingTransfer {
+    pub bidder: Addr,
+    pub round: u64,
+    pub premium_slot: u16,
+    pub amount: Uint128,         // amount pulled from the bidder
+    pub balance_before: Uint128, // balance of the contract before the transfer
+}
+
+#[cw_serde]
+pub struct RateBand {
+    pub min_rate: Decimal, // lowest oracle rate the round can be finalized at
+    pub max_rate: Decimal, // highest oracle rate the round can be finalized at
+}
+
+#[cw_serde]
+pub struct BidRateLimit {
+    pub max_bids: u32, // bids allowed per address in the window
+    pub window: u64,   // length of the sliding window, in seconds
+}
+
+#[cw_serde]
+pub struct ReinvestConfig {
+    pub pair: Addr,          // oraiswap pair of distribution_token and underlying_token
+    pub max_spread: Decimal, // the swap fails past this spread, the rewards are then paid as usual
+}
+
+#[cw_serde]
+pub struct PendingReinvest {
+    pub round: u64,                 // open round the swapped rewards are bid in
+    pub balance_before: Uint128,    // underlying_token held by the contract before the swap
+    pub shares: Vec<ReinvestShare>, // rewards swapped together, in the order of their bids
+}
+
+#[cw_serde]
+pub struct PendingLsdRedeem {
+    pub round: u64,
+    pub exchange_rate: Decimal, // rate the round is finalized at once redeemed
+    pub chunked: bool,          // finalize started by StartFinalize
+    pub derivative: AssetInfo,  // staking derivative sent to the adapter
+    pub total_derivative: Uint128,
+    pub balance_before: Uint128, // underlying_token held by the contract before the redeem
+}
+
+#[cw_serde]
+pub struct ReinvestShare {
+    pub bid_idx: u64,      // distributed bid the reward comes from
+    pub bidder: Addr,      // owner of the distributed bid, the new bid is made for them
+    pub premium_slot: u16, // slot of the distributed bid, the new bid takes the same one
+    pub reward: Uint128,   // net reward of the bid, in distribution_token
+}
+
+#[cw_serde]
+pub struct LockBoost {
+    pub min_rounds: u64,     // locks of at least min_rounds rounds get the multiplier
+    pub multiplier: Decimal, // applied to the premium of each bid of the lock, at least 1
+}
+
+#[cw_serde]
+pub struct BidLock {
+    pub bidder: Addr,
+    pub premium_slot: u16,
+    pub multiplier: Decimal, // boost of the premium, from the tier of the lock
+    pub remaining_rounds: u64, // rounds still to enter after the round of the last bid
+    pub last_round: u64,     // round of the last bid entered from the lock
+    pub pending_amount: Uint128, // residue of the last bid, waiting to enter the next round
+}
+
+#[cw_serde]
+pub struct FinalizerGroup {
+    pub group: Addr,             // cw4 group contract
+    pub quorum: Option<Decimal>, // share of the total weight to approve a rate, None: any member
+}
+
+#[cw_serde]
+pub struct IbcSatellite {
+    pub connection_id: String, // connection to the chain of the satellite
+    pub port_id: String,       // port of the satellite contract
+}
+
+// amount of a post-finalize action, read from the finalized round unless it is Fixed
+#[cw_serde]
+pub enum ActionAmount {
+    Fixed { amount: Uint128 },
+    Matched {},     // underlying tokens matched in the round
+    Distributed {}, // distribution tokens allocated to the matched bids
+    Leftover {},    // distribution tokens not allocated to the bids
+}
+
+#[cw_serde]
+pub enum PostFinalizeAction {
+    // sends amount of asset_info to recipient
+    Transfer {
+        asset_info: AssetInfo,
+        recipient: String,
+        amount: ActionAmount,
+    },
+    // sends amount of asset_info to contract with msg, through the cw20 Send hook for tokens
+    Send {
+        asset_info: AssetInfo,
+        contract: String,
+        amount: ActionAmount,
+        msg: Binary,
+    },
+    // executes contract with msg, without funds
+    Execute {
+        contract: String,
+        msg: Binary,
+    },
+}
+
+#[cw_serde]
+pub struct FinalizeProgress {
+    pub exchange_rate: Decimal,     // exchange rate the round is finalized at
+    pub last_slot: u16,             // last slot matched, 0 before the first one
+    pub distribution_left: Uint128, // distribution not allocated to the matched slots
+    pub total_matched: Uint128,     // underlying_token matched in the matched slots
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PayoutTotals {
+    pub total_received: Uint128, // sum of amount_received of the distributed bids
+    pub total_residue: Uint128,  // sum of residue_bid of the distributed bids
+}
+
+#[cw_serde]
+pub struct YieldVenue {
+    pub contract: Addr, // staking or lending contract accepting underlying_token
+    pub reward_asset: AssetInfo, // asset the yield is paid in
+    pub yield_to_distribution: bool, // yield is added to the round distribution instead of the treasury
+}
+
+#[cw_serde]
+pub struct YieldPosition {
+    pub round: u64,         // round the deposited bids belong to
+    pub principal: Uint128, // underlying deposited, withdrawn before finalize
+}
+
+#[cw_serde]
+pub struct DisputeParams {
+    pub bond: Coin,             // bond posted to dispute a round
+    pub max_deviation: Decimal, // deviation from the oracle rate tolerated without a dispute
+    pub dispute_period: u64,    // seconds a finalized round can be disputed
+}
+
+#[cw_serde]
+pub struct Dispute {
+    pub disputer: Addr,       // address that posted the bond
+    pub bond: Coin,           // refunded if upheld, slashed to the treasury otherwise
+    pub oracle_rate: Decimal, // oracle exchange rate when the dispute was raised
+}
+
+#[cw_serde]
+pub struct RoundTemplate {
+    pub duration: u64,               // bidding duration of the rounds
+    pub total_distribution: Uint128, // maximum reward distributed in each round
+    pub round_config: RoundConfig,   // settings of the rounds
+}
+
+#[cw_serde]
+pub struct SlotBonus {
+    pub sponsor: Addr,             // partner refunded if no bid matched
+    pub asset: Asset,              // bonus paid out to the matched bids of the slots
+    pub min_slot: u16,             // first slot eligible to the bonus
+    pub max_slot: u16,             // last slot eligible to the bonus
+    pub eligible_matched: Uint128, // matched amount of the eligible slots, set at finalize
+}
+
+impl SlotBonus {
+    pub fn is_eligible(&self, slot: u16) -> bool {
+        self.min_slot <= slot && slot <= self.max_slot
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Payout {
+    pub underlying_amount: Uint128,   // residue of distributed bids
+    pub distribution_amount: Uint128, // rewards of distributed bids
 }
 
 #[cw_serde]
@@ -40,6 +542,22 @@ pub struct BiddingInfo {
     pub end_time: u64,              // end time of the bidding
     pub total_bid_amount: Uint128,  // amount of tokens participating in the bidding
     pub total_bid_matched: Uint128, // the number of tokens matched in the bidding
+    #[serde(default)]
+    pub creator: Option<Addr>, // creator of the round, None for rounds created before it was recorded
+}
+
+#[cw_serde]
+pub struct RoundSummary {
+    pub round: u64,                  // round id
+    pub start_time: u64,             // start time of the bidding
+    pub end_time: u64,               // end time of the bidding
+    pub total_bid_amount: Uint128,   // amount of tokens participating in the bidding
+    pub total_bid_matched: Uint128,  // the number of tokens matched in the bidding
+    pub exchange_rate: Decimal,      // exchange rate the round was finalized at
+    pub total_distribution: Uint128, // the maximum amount of reward distributed in the bidding
+    pub actual_distributed: Uint128, // the actual token allocated in the bidding
+    pub num_bids: u64,               // number of bids in the round
+    pub num_bidders: u64,            // number of distinct bidders in the round
 }
 
 #[cw_serde]
@@ -53,34 +571,76 @@ pub struct DistributionInfo {
 
 #[cw_serde]
 pub struct BidPool {
-    pub slot: u8,                    // the premium slot
+    pub slot: u16,                   // the premium slot
     pub total_bid_amount: Uint128,   // number of tokens deposited into this pool
     pub premium_rate: Decimal,       // % bonus of the pool
     pub index_snapshot: Decimal,     // parameter that represents rate at which bids are consumed
     pub received_per_token: Decimal, //  number of reward tokens received for each token deposited into that pool
+    #[serde(default)]
+    pub matched_amount: Uint128, // underlying_token of the pool matched at finalize
+    #[serde(default)]
+    pub payout_amount: Uint128, // reward tokens allocated to the pool at finalize
 }
 
 #[cw_serde]
 pub struct Bid {
     pub idx: u64,                 // bid id
     pub round: u64,               // bidding round id
-    pub premium_slot: u8,         // the premium slot
+    pub premium_slot: u16,        // the premium slot
     pub timestamp: u64,           // time submit bit
     pub bidder: Addr,             // bidder address
     pub amount: Uint128,          // amount of underlying_token put up in bid
     pub residue_bid: Uint128,     // amount of remaining underlying_token
     pub amount_received: Uint128, // amount of tokens allocated
     pub is_distributed: bool,     // mark whether this bid has been allocated or not
+    #[serde(default)]
+    pub auto_compound: bool, // rewards are bonded in the staking contract for the bidder
+    #[serde(default)]
+    pub stake_residue: bool, // residue is bonded in the residue staking contract for the bidder
+    #[serde(default)]
+    pub reinvest: bool, // rewards are swapped into underlying_token and bid in the next open round
+    #[serde(default)]
+    pub lp_amount: Uint128, // LP or staking derivative tokens put up in the bid, amount is set when they are redeemed
+    #[serde(default)]
+    pub residue_claimed: bool, // residue was claimed after finalize, distribute only pays the reward
+    #[serde(default)]
+    pub performance_fee: Uint128, // part of amount_received sent to the treasury instead of the bidder
+    #[serde(default)]
+    pub height: u64, // block height the bid was submitted at, 0 for bids submitted before it was recorded
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct BurnStats {
+    pub underlying_burned: Uint128, // amount of underlying_token burned in the epoch
+    pub distribution_paid: Uint128, // amount of distribution_token allocated in the epoch
+}
+
+// initializes the counters missing from storage, contracts deployed before BID_IDX was set at
+// instantiate create it with their first bid
+pub fn init_counters(storage: &mut dyn Storage) -> StdResult<()> {
+    if !LAST_ROUND_ID.exists(storage) {
+        LAST_ROUND_ID.save(storage, &0)?;
+    }
+    if !BID_IDX.exists(storage) {
+        BID_IDX.save(storage, &1)?;
+    }
+    Ok(())
 }
 
 pub fn pop_bid_idx(storage: &mut dyn Storage) -> StdResult<u64> {
-    let last_idx = BID_IDX.load(storage).unwrap_or(1);
-    BID_IDX.save(storage, &(last_idx + 1))?;
-    Ok(last_idx)
+    let idx = BID_IDX.load(storage)?;
+    BID_IDX.save(storage, &(idx + 1))?;
+    Ok(idx)
 }
 
 pub fn store_bid(storage: &mut dyn Storage, bid_idx: u64, bid: &Bid) -> StdResult<()> {
     BID.save(storage, bid_idx, &bid)?;
+    if !BIDS_BY_USER.has(storage, (bid.round, bid.bidder.clone())) {
+        NUM_BIDDERS_BY_ROUND.update(storage, bid.round, |num_bidders| -> StdResult<u64> {
+            Ok(num_bidders.unwrap_or_default() + 1)
+        })?;
+    }
     BIDS_BY_USER.update(
         storage,
         (bid.round, bid.bidder.clone()),
@@ -91,14 +651,28 @@ pub fn store_bid(storage: &mut dyn Storage, bid_idx: u64, bid: &Bid) -> StdResul
         },
     )?;
     BIDS_BY_ROUND.save(storage, (bid.round, bid_idx), &true)?;
+    BIDS_BY_SLOT.save(storage, (bid.round, bid.premium_slot, bid_idx), &true)?;
+    BIDS_BY_HEIGHT.save(storage, (bid.round, bid.height, bid_idx), &true)?;
+    BIDS_BY_TIME.save(storage, (bid.round, bid.timestamp, bid_idx), &true)?;
+    NUM_BIDS_BY_ROUND.update(storage, bid.round, |num_bids| -> StdResult<u64> {
+        Ok(num_bids.unwrap_or_default() + 1)
+    })?;
 
     Ok(())
 }
 
+pub fn read_round_config(storage: &dyn Storage, round: u64) -> StdResult<RoundConfig> {
+    Ok(ROUND_CONFIG.may_load(storage, round)?.unwrap_or_default())
+}
+
+pub fn read_pause_flags(storage: &dyn Storage) -> StdResult<PauseFlags> {
+    Ok(PAUSE_FLAGS.may_load(storage)?.unwrap_or_default())
+}
+
 pub fn read_or_create_bid_pool(
     storage: &mut dyn Storage,
     round: u64,
-    premium_slot: u8,
+    premium_slot: u16,
 ) -> StdResult<BidPool> {
     let config = CONFIG.load(storage)?;
 
@@ -107,12 +681,14 @@ pub fn read_or_create_bid_pool(
         Err(_) => {
             let bid_pool = BidPool {
                 slot: premium_slot,
-                premium_rate: config.premium_rate_per_slot
-                    * Decimal::from_atomics(Uint128::from(premium_slot as u128), 0)
-                        .map_err(|err| StdError::generic_err(err.to_string()))?,
+                premium_rate: read_round_config(storage, round)?
+                    .premium_curve
+                    .premium_rate(config.premium_rate_per_slot, premium_slot)?,
                 total_bid_amount: Uint128::zero(),
                 index_snapshot: Decimal::zero(),
                 received_per_token: Decimal::zero(),
+                matched_amount: Uint128::zero(),
+                payout_amount: Uint128::zero(),
             };
             BID_POOL.save(storage, (round, premium_slot), &bid_pool)?;
 
@@ -133,20 +709,462 @@ pub fn read_bids_by_round(
         2 => Order::Descending,
         _ => Order::Ascending,
     });
-    let start = calc_range_start(start_after)?.map(Bound::ExclusiveRaw);
+    // start_after bounds the bids below it when reading them in descending order
+    let (min, max) = match order_by {
+        Order::Ascending => (
+            calc_range_start(start_after)?.map(Bound::ExclusiveRaw),
+            None,
+        ),
+        Order::Descending => (
+            None,
+            start_after.map(|idx| Bound::ExclusiveRaw(idx.to_be_bytes().into())),
+        ),
+    };
 
     BIDS_BY_ROUND
         .prefix(round)
-        .keys(storage, start, None, order_by)
+        .keys(storage, min, max, order_by)
         .take(limit)
         .collect()
 }
 
-pub fn count_number_bids_in_round(storage: &dyn Storage, round: u64) -> u64 {
-    BIDS_BY_ROUND
+pub fn read_round_ids(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<u64>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    BIDDING_INFO
+        .keys(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
+
+pub fn read_bid_idxs(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<u64>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    BID.keys(
+        storage,
+        start_after.map(Bound::exclusive),
+        None,
+        Order::Ascending,
+    )
+    .take(limit)
+    .collect()
+}
+
+pub fn read_bidders_in_round(
+    storage: &dyn Storage,
+    round: u64,
+    start_after: Option<Addr>,
+    limit: Option<u64>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    BIDS_BY_USER
+        .prefix(round)
+        .keys(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
+
+pub fn read_trusted_routers(
+    storage: &dyn Storage,
+    start_after: Option<Addr>,
+    limit: Option<u64>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    TRUSTED_ROUTERS
+        .keys(
+            storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
+
+// bids of the round submitted between the from and to block heights, both included
+pub fn read_bids_by_height(
+    storage: &dyn Storage,
+    round: u64,
+    from: u64,
+    to: u64,
+    limit: Option<u64>,
+) -> StdResult<Vec<u64>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    BIDS_BY_HEIGHT
+        .sub_prefix(round)
+        .keys(
+            storage,
+            Some(Bound::inclusive((from, 0))),
+            Some(Bound::inclusive((to, u64::MAX))),
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|key| key.map(|(_, idx)| idx))
+        .collect()
+}
+
+// bids of the round submitted between the from_ts and to_ts timestamps, both included
+pub fn read_bids_by_time(
+    storage: &dyn Storage,
+    round: u64,
+    from_ts: u64,
+    to_ts: u64,
+    limit: Option<u64>,
+) -> StdResult<Vec<u64>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    BIDS_BY_TIME
+        .sub_prefix(round)
+        .keys(
+            storage,
+            Some(Bound::inclusive((from_ts, 0))),
+            Some(Bound::inclusive((to_ts, u64::MAX))),
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|key| key.map(|(_, idx)| idx))
+        .collect()
+}
+
+// bids of the round from the lowest premium slot, as (slot, bid_idx)
+pub fn read_bids_by_slot(
+    storage: &dyn Storage,
+    round: u64,
+    start_after: Option<(u16, u64)>,
+    limit: u64,
+) -> StdResult<Vec<(u16, u64)>> {
+    BIDS_BY_SLOT
+        .sub_prefix(round)
+        .keys(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .collect()
+}
+
+// idxs of the bids of one slot of the round, in ascending order
+pub fn read_bids_in_slot(
+    storage: &dyn Storage,
+    round: u64,
+    slot: u16,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<u64>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    BIDS_BY_SLOT
+        .prefix((round, slot))
+        .keys(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
+
+// moves up to limit entries of the u8 slot keyed maps to the u16 keyed ones, returns the
+// number of entries moved, less than limit once every legacy map is empty
+pub fn migrate_slot_keys(storage: &mut dyn Storage, limit: usize) -> StdResult<usize> {
+    let mut moved = 0;
+
+    let bid_pools = LEGACY_BID_POOL
+        .range(storage, None, None, Order::Ascending)
+        .take(limit - moved)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((round, slot), bid_pool) in bid_pools.iter() {
+        LEGACY_BID_POOL.remove(storage, (*round, *slot));
+        BID_POOL.save(storage, (*round, *slot as u16), bid_pool)?;
+    }
+    moved += bid_pools.len();
+
+    let bids = LEGACY_BIDS_BY_SLOT
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit - moved)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (round, slot, idx) in bids.iter() {
+        LEGACY_BIDS_BY_SLOT.remove(storage, (*round, *slot, *idx));
+        BIDS_BY_SLOT.save(storage, (*round, *slot as u16, *idx), &true)?;
+    }
+    moved += bids.len();
+
+    let checkpoints = LEGACY_POOL_TOTAL_CHECKPOINTS
+        .range(storage, None, None, Order::Ascending)
+        .take(limit - moved)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((round, slot, timestamp), total) in checkpoints.iter() {
+        LEGACY_POOL_TOTAL_CHECKPOINTS.remove(storage, (*round, *slot, *timestamp));
+        POOL_TOTAL_CHECKPOINTS.save(storage, (*round, *slot as u16, *timestamp), total)?;
+    }
+    moved += checkpoints.len();
+
+    let lp_totals = LEGACY_LP_BID_TOTALS
+        .range(storage, None, None, Order::Ascending)
+        .take(limit - moved)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((round, slot), total) in lp_totals.iter() {
+        LEGACY_LP_BID_TOTALS.remove(storage, (*round, *slot));
+        LP_BID_TOTALS.save(storage, (*round, *slot as u16), total)?;
+    }
+    moved += lp_totals.len();
+
+    Ok(moved)
+}
+
+// record the running totals of the round and the updated pool at the given time
+pub fn checkpoint_round_totals(
+    storage: &mut dyn Storage,
+    timestamp: u64,
+    bidding_info: &BiddingInfo,
+    bid_pool: &BidPool,
+) -> StdResult<()> {
+    ROUND_TOTAL_CHECKPOINTS.save(
+        storage,
+        (bidding_info.round, timestamp),
+        &bidding_info.total_bid_amount,
+    )?;
+    POOL_TOTAL_CHECKPOINTS.save(
+        storage,
+        (bidding_info.round, bid_pool.slot, timestamp),
+        &bid_pool.total_bid_amount,
+    )?;
+
+    Ok(())
+}
+
+// total bid amount of the round at the latest checkpoint not after timestamp
+pub fn read_round_total_at(
+    storage: &dyn Storage,
+    round: u64,
+    timestamp: u64,
+) -> StdResult<Uint128> {
+    let checkpoint = ROUND_TOTAL_CHECKPOINTS
+        .prefix(round)
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(timestamp)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?;
+
+    Ok(checkpoint.map(|(_, total)| total).unwrap_or_default())
+}
+
+// total bid amount of the pool at the latest checkpoint not after timestamp
+pub fn read_pool_total_at(
+    storage: &dyn Storage,
+    round: u64,
+    slot: u16,
+    timestamp: u64,
+) -> StdResult<Uint128> {
+    let checkpoint = POOL_TOTAL_CHECKPOINTS
+        .prefix((round, slot))
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(timestamp)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?;
+
+    Ok(checkpoint.map(|(_, total)| total).unwrap_or_default())
+}
+
+pub fn read_round_total_history(
+    storage: &dyn Storage,
+    round: u64,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<(u64, Uint128)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    ROUND_TOTAL_CHECKPOINTS
+        .prefix(round)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+fn asset_key(asset_info: &AssetInfo) -> &str {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => contract_addr.as_str(),
+        AssetInfo::NativeToken { denom } => denom.as_str(),
+    }
+}
+
+pub fn read_round_state(storage: &dyn Storage, env: &Env, round: u64) -> StdResult<RoundState> {
+    let state = match ROUND_STATES.may_load(storage, round)? {
+        Some(state) => state,
+        None => {
+            let distribution_info = DISTRIBUTION_INFO.load(storage, round)?;
+            let num_bids_distributed = distribution_info.num_bids_distributed;
+            if !distribution_info.is_released {
+                RoundState::Created
+            } else if num_bids_distributed >= count_number_bids_in_round(storage, round)? {
+                RoundState::Completed
+            } else if num_bids_distributed > 0 {
+                RoundState::Distributing
+            } else {
+                RoundState::Finalized
+            }
+        }
+    };
+    if state != RoundState::Created {
+        return Ok(state);
+    }
+
+    let bidding_info = BIDDING_INFO.load(storage, round)?;
+    if bidding_info.finished(env) {
+        Ok(RoundState::Ended)
+    } else if bidding_info.opening(env) {
+        Ok(RoundState::Open)
+    } else {
+        Ok(RoundState::Created)
+    }
+}
+
+// moves the round to next, rounds never created can only be created
+pub fn save_round_state(
+    storage: &mut dyn Storage,
+    env: &Env,
+    round: u64,
+    next: RoundState,
+) -> StdResult<()> {
+    let valid = if BIDDING_INFO.has(storage, round) || ROUND_STATES.has(storage, round) {
+        read_round_state(storage, env, round)?.can_transition_to(next)
+    } else {
+        next == RoundState::Created
+    };
+    if !valid {
+        return Err(StdError::generic_err(format!(
+            "Round {} can not become {:?}",
+            round, next
+        )));
+    }
+    ROUND_STATES.save(storage, round, &next)
+}
+
+pub fn read_shadow_balance(storage: &dyn Storage, asset_info: &AssetInfo) -> StdResult<Uint128> {
+    Ok(SHADOW_BALANCES
+        .may_load(storage, asset_key(asset_info))?
+        .unwrap_or_default())
+}
+
+pub fn save_shadow_balance(
+    storage: &mut dyn Storage,
+    asset_info: &AssetInfo,
+    amount: &Uint128,
+) -> StdResult<()> {
+    SHADOW_BALANCES.save(storage, asset_key(asset_info), amount)
+}
+
+// only the underlying and distribution tokens are tracked, other assets are ignored
+pub fn credit_shadow_balance(
+    storage: &mut dyn Storage,
+    config: &Config,
+    asset_info: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<()> {
+    if !config.is_tracked(asset_info) || amount.is_zero() {
+        return Ok(());
+    }
+    let balance = read_shadow_balance(storage, asset_info)?.checked_add(amount)?;
+    save_shadow_balance(storage, asset_info, &balance)
+}
+
+// never fails, a debit larger than the expected holdings is drift that the query reports
+pub fn debit_shadow_balance(
+    storage: &mut dyn Storage,
+    config: &Config,
+    asset_info: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<()> {
+    if !config.is_tracked(asset_info) || amount.is_zero() {
+        return Ok(());
+    }
+    let balance = read_shadow_balance(storage, asset_info)?.saturating_sub(amount);
+    save_shadow_balance(storage, asset_info, &balance)
+}
+
+// accumulate finalized round results into the epoch containing timestamp
+pub fn record_burn_stats(
+    storage: &mut dyn Storage,
+    timestamp: u64,
+    underlying_burned: Uint128,
+    distribution_paid: Uint128,
+) -> StdResult<BurnStats> {
+    BURN_STATS.update(
+        storage,
+        timestamp / EPOCH_DURATION,
+        |stats| -> StdResult<BurnStats> {
+            let mut stats = stats.unwrap_or_default();
+            stats.underlying_burned = stats.underlying_burned.checked_add(underlying_burned)?;
+            stats.distribution_paid = stats.distribution_paid.checked_add(distribution_paid)?;
+            Ok(stats)
+        },
+    )
+}
+
+// all recorded epochs overlapping the time range [start, end]
+pub fn read_burn_stats(
+    storage: &dyn Storage,
+    start: u64,
+    end: u64,
+) -> StdResult<Vec<(u64, BurnStats)>> {
+    BURN_STATS
+        .range(
+            storage,
+            Some(Bound::inclusive(start / EPOCH_DURATION)),
+            Some(Bound::inclusive(end / EPOCH_DURATION)),
+            Order::Ascending,
+        )
+        .collect()
+}
+
+pub fn count_number_bids_in_round(storage: &dyn Storage, round: u64) -> StdResult<u64> {
+    if let Some(num_bids) = NUM_BIDS_BY_ROUND.may_load(storage, round)? {
+        return Ok(num_bids);
+    }
+
+    // rounds without a counter yet fall back to a full scan
+    Ok(BIDS_BY_ROUND
         .prefix(round)
         .range(storage, None, None, Order::Ascending)
-        .count() as u64
+        .count() as u64)
+}
+
+pub fn count_number_bidders_in_round(storage: &dyn Storage, round: u64) -> StdResult<u64> {
+    if let Some(num_bidders) = NUM_BIDDERS_BY_ROUND.may_load(storage, round)? {
+        return Ok(num_bidders);
+    }
+
+    // rounds without a counter yet fall back to a full scan
+    Ok(BIDS_BY_USER
+        .prefix(round)
+        .keys(storage, None, None, Order::Ascending)
+        .count() as u64)
 }
 
 impl BiddingInfo {
@@ -164,21 +1182,53 @@ impl BiddingInfo {
 
     pub fn read_all_bid_pool(&self, storage: &dyn Storage) -> StdResult<Vec<BidPool>> {
         let config = CONFIG.load(storage)?;
+        self.read_bid_pools_in_range(storage, &config, 1, config.max_slot)
+    }
+
+    // a page of the pools of the round, starting from the slot after start_after
+    pub fn read_bid_pools(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<u16>,
+        limit: Option<u64>,
+    ) -> StdResult<Vec<BidPool>> {
+        let config = CONFIG.load(storage)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u16;
+        let Some(first_slot) = start_after.map_or(Some(1), |slot| slot.checked_add(1)) else {
+            return Ok(vec![]);
+        };
+        let last_slot = first_slot
+            .saturating_add(limit)
+            .saturating_sub(1)
+            .min(config.max_slot);
+        self.read_bid_pools_in_range(storage, &config, first_slot, last_slot)
+    }
+
+    fn read_bid_pools_in_range(
+        &self,
+        storage: &dyn Storage,
+        config: &Config,
+        first_slot: u16,
+        last_slot: u16,
+    ) -> StdResult<Vec<BidPool>> {
+        let premium_curve = read_round_config(storage, self.round)?.premium_curve;
 
-        let bid_pools: Vec<BidPool> = (1..=config.max_slot)
-            .map(|slot| {
-                Ok(BID_POOL
-                    .load(storage, (self.round, slot))
-                    .unwrap_or(BidPool {
+        let bid_pools: Vec<BidPool> = (first_slot..=last_slot)
+            .map(
+                |slot| match BID_POOL.may_load(storage, (self.round, slot))? {
+                    Some(bid_pool) => Ok(bid_pool),
+                    None => Ok(BidPool {
                         slot,
                         total_bid_amount: Uint128::zero(),
-                        premium_rate: config.premium_rate_per_slot
-                            * Decimal::from_atomics(Uint128::from(slot as u128), 0)
-                                .map_err(|err| StdError::generic_err(err.to_string()))?,
+                        premium_rate: premium_curve
+                            .premium_rate(config.premium_rate_per_slot, slot)?,
                         index_snapshot: Decimal::zero(),
                         received_per_token: Decimal::zero(),
-                    }))
-            })
+                        matched_amount: Uint128::zero(),
+                        payout_amount: Uint128::zero(),
+                    }),
+                },
+            )
             .collect::<StdResult<Vec<BidPool>>>()?;
 
         Ok(bid_pools)
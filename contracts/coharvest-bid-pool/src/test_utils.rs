@@ -0,0 +1,283 @@
+// helpers shared by the tests of the bid pool and of the contracts integrating with it, enabled
+// by the test-utils feature
+use cosmwasm_std::{
+    to_json_binary, Addr, Decimal, DepsMut, Empty, Env, MessageInfo, Response, Uint128,
+};
+use cw20::{
+    BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, TokenInfoResponse,
+};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use oraiswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, instantiate, migrate, query},
+    error::ContractError,
+    msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg},
+    state::Bid,
+};
+
+pub const OWNER: &str = "owner";
+pub const TREASURY: &str = "treasury";
+pub const BIDDING_DURATION: u64 = 1000;
+
+pub fn contract_bid_pool() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_migrate(migrate))
+}
+
+pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+pub struct Suite {
+    pub app: App,
+    pub bid_pool: Addr,
+    pub underlying: Addr,
+    pub distribution: Addr,
+}
+
+impl Suite {
+    // deploy ORAIX (underlying) & USDC (distribution) cw20 tokens and the bid pool
+    pub fn new(bidders: &[(&str, u128)], treasury_balance: u128) -> Self {
+        let mut app = App::default();
+        let cw20_id = app.store_code(contract_cw20());
+        let bid_pool_id = app.store_code(contract_bid_pool());
+
+        let underlying = app
+            .instantiate_contract(
+                cw20_id,
+                Addr::unchecked(OWNER),
+                &cw20_base::msg::InstantiateMsg {
+                    name: "Oraidex token".to_string(),
+                    symbol: "ORAIX".to_string(),
+                    decimals: 6,
+                    initial_balances: bidders
+                        .iter()
+                        .map(|(address, amount)| Cw20Coin {
+                            address: address.to_string(),
+                            amount: Uint128::from(*amount),
+                        })
+                        .collect(),
+                    mint: None,
+                    marketing: None,
+                },
+                &[],
+                "oraix",
+                None,
+            )
+            .unwrap();
+
+        let distribution = app
+            .instantiate_contract(
+                cw20_id,
+                Addr::unchecked(OWNER),
+                &cw20_base::msg::InstantiateMsg {
+                    name: "USD coin".to_string(),
+                    symbol: "USDC".to_string(),
+                    decimals: 6,
+                    initial_balances: vec![Cw20Coin {
+                        address: TREASURY.to_string(),
+                        amount: Uint128::from(treasury_balance),
+                    }],
+                    mint: None,
+                    marketing: None,
+                },
+                &[],
+                "usdc",
+                None,
+            )
+            .unwrap();
+
+        let bid_pool = app
+            .instantiate_contract(
+                bid_pool_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    owner: OWNER.to_string(),
+                    underlying_token: AssetInfo::Token {
+                        contract_addr: underlying.clone(),
+                    },
+                    distribution_token: AssetInfo::Token {
+                        contract_addr: distribution.clone(),
+                    },
+                    max_slot: 25,
+                    premium_rate_per_slot: Decimal::percent(1),
+                    min_deposit_amount: Uint128::from(100_000000u128),
+                    treasury: TREASURY.to_string(),
+                    bidding_duration: BIDDING_DURATION,
+                    oracle_pair: None,
+                    vesting_contract: None,
+                    keeper_registry: None,
+                    staking_contract: None,
+                    residue_staking: None,
+                    wrapped_denom: None,
+                    min_gap_between_rounds: None,
+                },
+                &[],
+                "coharvest-bid-pool",
+                Some(OWNER.to_string()),
+            )
+            .unwrap();
+
+        Self {
+            app,
+            bid_pool,
+            underlying,
+            distribution,
+        }
+    }
+
+    pub fn create_new_round_from_treasury(&mut self, amount: u128) -> AppResponse {
+        self.app
+            .execute_contract(
+                Addr::unchecked(TREASURY),
+                self.distribution.clone(),
+                &Cw20ExecuteMsg::Send {
+                    contract: self.bid_pool.to_string(),
+                    amount: Uint128::from(amount),
+                    msg: to_json_binary(&Cw20HookMsg::CreateNewRoundFromTreasury {}).unwrap(),
+                },
+                &[],
+            )
+            .unwrap()
+    }
+
+    pub fn submit_bid(
+        &mut self,
+        bidder: &str,
+        round: u64,
+        premium_slot: u16,
+        amount: u128,
+    ) -> AppResponse {
+        self.app
+            .execute_contract(
+                Addr::unchecked(bidder),
+                self.underlying.clone(),
+                &Cw20ExecuteMsg::Send {
+                    contract: self.bid_pool.to_string(),
+                    amount: Uint128::from(amount),
+                    msg: to_json_binary(&Cw20HookMsg::SubmitBid {
+                        round,
+                        premium_slot,
+                    })
+                    .unwrap(),
+                },
+                &[],
+            )
+            .unwrap()
+    }
+
+    pub fn finalize(&mut self, round: u64, exchange_rate: Decimal) -> AppResponse {
+        self.app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                self.bid_pool.clone(),
+                &ExecuteMsg::FinalizeBiddingRoundResult {
+                    round,
+                    exchange_rate,
+                },
+                &[],
+            )
+            .unwrap()
+    }
+
+    pub fn distribute(&mut self, round: u64, limit: Option<u64>) -> AppResponse {
+        self.app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                self.bid_pool.clone(),
+                &ExecuteMsg::Distribute {
+                    round,
+                    start_after: None,
+                    limit,
+                },
+                &[],
+            )
+            .unwrap()
+    }
+
+    pub fn advance_seconds(&mut self, seconds: u64) {
+        self.app
+            .update_block(|block| block.time = block.time.plus_seconds(seconds));
+    }
+
+    pub fn balance(&self, token: &Addr, address: &str) -> Uint128 {
+        let res: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance
+    }
+
+    pub fn total_supply(&self, token: &Addr) -> Uint128 {
+        let res: TokenInfoResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(token, &Cw20QueryMsg::TokenInfo {})
+            .unwrap();
+        res.total_supply
+    }
+}
+
+// CreateNewRound starting at the block time of env and lasting BIDDING_DURATION
+pub fn mock_round(env: &Env, total_distribution: u128) -> ExecuteMsg {
+    ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(BIDDING_DURATION).seconds(),
+        total_distribution: Uint128::from(total_distribution),
+        min_exchange_rate: None,
+    }
+}
+
+// an open bid as stored by SubmitBid, before the round is finalized
+pub fn mock_bid(idx: u64, round: u64, bidder: &str, premium_slot: u16, amount: u128) -> Bid {
+    Bid {
+        idx,
+        round,
+        premium_slot,
+        timestamp: 0,
+        bidder: Addr::unchecked(bidder),
+        amount: Uint128::from(amount),
+        residue_bid: Uint128::from(amount),
+        amount_received: Uint128::zero(),
+        is_distributed: false,
+        auto_compound: false,
+        stake_residue: false,
+        reinvest: false,
+        lp_amount: Uint128::zero(),
+        residue_claimed: false,
+        performance_fee: Uint128::zero(),
+        height: 0,
+    }
+}
+
+// submits a bid as if info.sender, the cw20 underlying token, forwarded it from the bidder
+pub fn submit_bid_helper(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bidder: &str,
+    amount: Uint128,
+    round: u64,
+    premium_slot: u16,
+) -> Result<Response, ContractError> {
+    let receive = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: bidder.to_string(),
+        amount,
+        msg: to_json_binary(&Cw20HookMsg::SubmitBid {
+            round,
+            premium_slot,
+        })?,
+    });
+
+    execute(deps, env, info, receive)
+}
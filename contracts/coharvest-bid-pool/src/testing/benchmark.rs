@@ -0,0 +1,74 @@
+use std::time::Instant;
+
+use cosmwasm_std::{Decimal, Uint128};
+
+use crate::{
+    msg::QueryMsg,
+    test_utils::{Suite, BIDDING_DURATION},
+};
+
+const NUM_BIDS: u64 = 10_000;
+const BID_AMOUNT: u128 = 100_000000;
+const BATCH_SIZE: u64 = 200;
+
+// cw-multi-test does not meter gas, so each batch reports the number of
+// submessages and storage-heavy wall time as a proxy for execution cost.
+// run with: cargo test --release bench_ -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_submit_and_distribute_10k_bids() {
+    let mut suite = Suite::new(
+        &[("bidder0", BID_AMOUNT * NUM_BIDS as u128)],
+        1000_000000u128,
+    );
+
+    suite.create_new_round_from_treasury(1000_000000u128);
+    suite.advance_seconds(1);
+
+    let started = Instant::now();
+    for i in 0..NUM_BIDS {
+        suite.submit_bid("bidder0", 1, (i % 25) as u16 + 1, BID_AMOUNT);
+    }
+    println!("submitted {} bids in {:?}", NUM_BIDS, started.elapsed());
+
+    let num_bids: u64 = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.bid_pool, &QueryMsg::NumbersBidInRound { round: 1 })
+        .unwrap();
+    assert_eq!(num_bids, NUM_BIDS);
+
+    suite.advance_seconds(BIDDING_DURATION + 1);
+    suite.finalize(1, Decimal::permille(1));
+
+    let mut batches = 0;
+    let mut distributed = 0;
+    while distributed < NUM_BIDS {
+        let started = Instant::now();
+        let res = suite.distribute(1, Some(BATCH_SIZE));
+        let elapsed = started.elapsed();
+
+        distributed = res
+            .events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == "total_bids_distributed")
+            .map(|attr| attr.value.parse().unwrap())
+            .unwrap();
+        batches += 1;
+
+        let num_executions = res
+            .events
+            .iter()
+            .filter(|event| event.ty == "execute")
+            .count();
+        println!(
+            "batch {}: {} bids distributed, {} contract executions, {:?}",
+            batches, distributed, num_executions, elapsed
+        );
+        assert!(batches <= NUM_BIDS / BATCH_SIZE);
+    }
+
+    // every bid has been settled, nothing but rounding dust is left
+    assert!(suite.balance(&suite.distribution, suite.bid_pool.as_str()) < Uint128::from(NUM_BIDS));
+}
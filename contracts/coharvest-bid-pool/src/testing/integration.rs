@@ -0,0 +1,90 @@
+use cosmwasm_std::{Decimal, Uint128};
+
+use crate::{
+    msg::{BiddingInfoResponse, QueryMsg},
+    test_utils::{Suite, BIDDING_DURATION, TREASURY},
+};
+
+#[test]
+fn test_full_round_lifecycle() {
+    let mut suite = Suite::new(
+        &[("bidder0", 10000_000000u128), ("bidder1", 10000_000000u128)],
+        1000_000000u128,
+    );
+    let bid_pool = suite.bid_pool.to_string();
+    let underlying = suite.underlying.clone();
+    let distribution = suite.distribution.clone();
+
+    // treasury funds round 1, which starts in the next second
+    suite.create_new_round_from_treasury(700_000000u128);
+    assert_eq!(
+        suite.balance(&distribution, &bid_pool),
+        Uint128::from(700_000000u128)
+    );
+    assert_eq!(
+        suite.balance(&distribution, TREASURY),
+        Uint128::from(300_000000u128)
+    );
+
+    suite.advance_seconds(1);
+    suite.submit_bid("bidder0", 1, 10, 4000_000000u128);
+    suite.submit_bid("bidder1", 1, 20, 4000_000000u128);
+    assert_eq!(
+        suite.balance(&underlying, &bid_pool),
+        Uint128::from(8000_000000u128)
+    );
+
+    // exchange rate 0.1:
+    // pool at slot 10 needs 4000 * 0.1 * 1.1 = 440 => fully matched, remaining 260
+    // pool at slot 20 needs 4000 * 0.1 * 1.2 = 480 => matched 260 / 480
+    suite.advance_seconds(BIDDING_DURATION + 1);
+    suite.finalize(1, Decimal::permille(100));
+
+    let bidding_info: BiddingInfoResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.bid_pool, &QueryMsg::BiddingInfo { round: 1 })
+        .unwrap();
+    let total_matched = bidding_info.bid_info.total_bid_matched;
+    assert_eq!(total_matched, Uint128::from(6166_666666u128));
+    assert_eq!(
+        bidding_info.distribution_info.actual_distributed,
+        Uint128::from(700_000000u128)
+    );
+
+    // matched underlying is burned
+    assert_eq!(
+        suite.total_supply(&underlying),
+        Uint128::from(20000_000000u128) - total_matched
+    );
+    assert_eq!(
+        suite.balance(&underlying, &bid_pool),
+        Uint128::from(8000_000000u128) - total_matched
+    );
+
+    suite.distribute(1, None);
+
+    // bidder0 is fully matched
+    assert_eq!(
+        suite.balance(&distribution, "bidder0"),
+        Uint128::from(440_000000u128)
+    );
+    assert_eq!(
+        suite.balance(&underlying, "bidder0"),
+        Uint128::from(6000_000000u128)
+    );
+
+    // bidder1 is partially matched and gets the unmatched part back
+    assert_eq!(
+        suite.balance(&distribution, "bidder1"),
+        Uint128::from(260_000000u128)
+    );
+    assert_eq!(
+        suite.balance(&underlying, "bidder1"),
+        Uint128::from(7833_333333u128)
+    );
+
+    // the budget is exhausted, only rounding dust remains in the pool
+    assert_eq!(suite.balance(&distribution, &bid_pool), Uint128::zero());
+    assert_eq!(suite.balance(&underlying, &bid_pool), Uint128::one());
+}
@@ -0,0 +1,141 @@
+use cosmwasm_std::{Decimal, Uint128};
+
+use crate::{
+    matching::{
+        performance_fee, process_calc_distribution_amount, process_calc_rebate_amount,
+        split_bid_amount,
+    },
+    state::BidPool,
+};
+
+fn bid_pool(slot: u16, total_bid_amount: u128, premium_percent: u64) -> BidPool {
+    BidPool {
+        slot,
+        total_bid_amount: Uint128::from(total_bid_amount),
+        premium_rate: Decimal::percent(premium_percent),
+        index_snapshot: Decimal::zero(),
+        received_per_token: Decimal::zero(),
+        matched_amount: Uint128::zero(),
+        payout_amount: Uint128::zero(),
+    }
+}
+
+#[test]
+fn test_fill_pools_in_slot_order() {
+    // at 0.1, slot 1 needs 1000 * 0.1 * 1.01 = 101 and slot 2 needs 1000 * 0.1 * 1.02 = 102
+    let mut bid_pools = vec![
+        bid_pool(1, 1000, 1),
+        bid_pool(2, 1000, 2),
+        bid_pool(3, 1000, 3),
+    ];
+    let mut distribution_amount = Uint128::from(152u128);
+    let total_matched = process_calc_distribution_amount(
+        &mut bid_pools,
+        &mut distribution_amount,
+        Decimal::permille(100),
+    )
+    .unwrap();
+
+    assert_eq!(total_matched, Uint128::from(1500u128));
+    assert_eq!(distribution_amount, Uint128::zero());
+    assert_eq!(bid_pools[0].index_snapshot, Decimal::one());
+    assert_eq!(bid_pools[0].received_per_token, Decimal::permille(101));
+    assert_eq!(bid_pools[1].index_snapshot, Decimal::percent(50));
+    assert_eq!(bid_pools[1].received_per_token, Decimal::permille(51));
+    // the distribution is exhausted, the last pool is left unmatched
+    assert_eq!(bid_pools[2].index_snapshot, Decimal::zero());
+    assert_eq!(bid_pools[2].received_per_token, Decimal::zero());
+}
+
+#[test]
+fn test_skip_empty_and_worthless_pools() {
+    // slot 1 is empty, slot 2 is worth nothing at this rate
+    let mut bid_pools = vec![bid_pool(1, 0, 1), bid_pool(2, 1, 2), bid_pool(3, 1000, 3)];
+    let mut distribution_amount = Uint128::from(1000u128);
+    let total_matched = process_calc_distribution_amount(
+        &mut bid_pools,
+        &mut distribution_amount,
+        Decimal::permille(100),
+    )
+    .unwrap();
+
+    assert_eq!(total_matched, Uint128::from(1000u128));
+    assert_eq!(distribution_amount, Uint128::from(897u128));
+    assert_eq!(bid_pools[0].index_snapshot, Decimal::zero());
+    assert_eq!(bid_pools[1].index_snapshot, Decimal::zero());
+    assert_eq!(bid_pools[2].index_snapshot, Decimal::one());
+}
+
+#[test]
+fn test_rebate_pays_back_matched_principal() {
+    // the distribution funds the 10% premium only: 50 matches half of the 100 needed
+    let mut bid_pools = vec![bid_pool(10, 1000, 10)];
+    let mut distribution_amount = Uint128::from(50u128);
+    let total_matched =
+        process_calc_rebate_amount(&mut bid_pools, &mut distribution_amount).unwrap();
+
+    assert_eq!(total_matched, Uint128::from(500u128));
+    assert_eq!(distribution_amount, Uint128::zero());
+    assert_eq!(bid_pools[0].index_snapshot, Decimal::percent(50));
+    assert_eq!(bid_pools[0].received_per_token, Decimal::percent(55));
+}
+
+#[test]
+fn test_split_bid_amount() {
+    assert_eq!(
+        split_bid_amount(
+            Uint128::from(200u128),
+            Decimal::percent(50),
+            Decimal::permille(51)
+        )
+        .unwrap(),
+        (Uint128::from(10u128), Uint128::from(100u128))
+    );
+    // an unmatched bid keeps its whole amount as residue
+    assert_eq!(
+        split_bid_amount(Uint128::from(200u128), Decimal::zero(), Decimal::zero()).unwrap(),
+        (Uint128::zero(), Uint128::from(200u128))
+    );
+    assert!(split_bid_amount(
+        Uint128::from(200u128),
+        Decimal::percent(101),
+        Decimal::zero()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_performance_fee() {
+    // 110 received for 1000 matched at 0.1: the premium is 10, 20% of it goes to the treasury
+    assert_eq!(
+        performance_fee(
+            Uint128::from(110u128),
+            Uint128::from(1000u128),
+            Decimal::permille(100),
+            Some(Decimal::percent(20)),
+        )
+        .unwrap(),
+        Uint128::from(2u128)
+    );
+    assert_eq!(
+        performance_fee(
+            Uint128::from(110u128),
+            Uint128::from(1000u128),
+            Decimal::permille(100),
+            None,
+        )
+        .unwrap(),
+        Uint128::zero()
+    );
+    // no premium, no fee
+    assert_eq!(
+        performance_fee(
+            Uint128::from(90u128),
+            Uint128::from(1000u128),
+            Decimal::permille(100),
+            Some(Decimal::percent(20)),
+        )
+        .unwrap(),
+        Uint128::zero()
+    );
+}
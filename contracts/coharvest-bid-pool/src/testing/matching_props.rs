@@ -0,0 +1,110 @@
+use cosmwasm_std::{Decimal, Uint128};
+use proptest::prelude::*;
+
+use crate::{matching::process_calc_distribution_amount, state::BidPool};
+
+const MAX_AMOUNT: u128 = 1_000_000_000_000_000_000_000_000; // 10^24
+const MAX_BUDGET: u128 = 1_000_000_000_000_000_000_000_000_000_000; // 10^30
+const MAX_RATE_ATOMICS: u128 = 1_000_000_000_000_000_000_000_000; // 10^6 as Decimal
+const MAX_PREMIUM_ATOMICS: u128 = 3_000_000_000_000_000_000; // 3 as Decimal
+
+// mix tiny values in, they are the ones that hit rounding edge cases
+fn amount_strategy(max: u128) -> impl Strategy<Value = u128> {
+    prop_oneof![0u128..1_000u128, 0u128..=max]
+}
+
+fn rate_strategy() -> impl Strategy<Value = Decimal> {
+    prop_oneof![0u128..1_000_000u128, 0u128..=MAX_RATE_ATOMICS].prop_map(Decimal::raw)
+}
+
+fn bid_pools_strategy() -> impl Strategy<Value = Vec<BidPool>> {
+    prop::collection::vec(
+        (amount_strategy(MAX_AMOUNT), 0u128..=MAX_PREMIUM_ATOMICS),
+        1..=25,
+    )
+    .prop_map(|pools| {
+        pools
+            .into_iter()
+            .enumerate()
+            .map(|(i, (total_bid_amount, premium_rate))| BidPool {
+                slot: i as u16 + 1,
+                total_bid_amount: Uint128::from(total_bid_amount),
+                premium_rate: Decimal::raw(premium_rate),
+                index_snapshot: Decimal::zero(),
+                received_per_token: Decimal::zero(),
+                matched_amount: Uint128::zero(),
+                payout_amount: Uint128::zero(),
+            })
+            .collect()
+    })
+}
+
+fn total_paid(bid_pools: &[BidPool]) -> Uint128 {
+    bid_pools
+        .iter()
+        .map(|bid_pool| bid_pool.total_bid_amount * bid_pool.received_per_token)
+        .sum()
+}
+
+proptest! {
+    #[test]
+    fn payout_never_exceeds_budget(
+        mut bid_pools in bid_pools_strategy(),
+        budget in amount_strategy(MAX_BUDGET),
+        exchange_rate in rate_strategy(),
+    ) {
+        let budget = Uint128::from(budget);
+        let mut distribution_amount = budget;
+
+        process_calc_distribution_amount(&mut bid_pools, &mut distribution_amount, exchange_rate)
+            .unwrap();
+
+        prop_assert!(distribution_amount <= budget);
+        prop_assert!(total_paid(&bid_pools) <= budget - distribution_amount);
+    }
+
+    #[test]
+    fn matched_never_exceeds_pool_totals(
+        mut bid_pools in bid_pools_strategy(),
+        budget in amount_strategy(MAX_BUDGET),
+        exchange_rate in rate_strategy(),
+    ) {
+        let mut distribution_amount = Uint128::from(budget);
+
+        let total_matched = process_calc_distribution_amount(
+            &mut bid_pools,
+            &mut distribution_amount,
+            exchange_rate,
+        )
+        .unwrap();
+
+        let total_bid: Uint128 = bid_pools.iter().map(|bid_pool| bid_pool.total_bid_amount).sum();
+        prop_assert!(total_matched <= total_bid);
+        for bid_pool in bid_pools {
+            prop_assert!(bid_pool.index_snapshot <= Decimal::one());
+        }
+    }
+
+    #[test]
+    fn matched_is_monotonic_in_distribution_amount(
+        bid_pools in bid_pools_strategy(),
+        budget in amount_strategy(MAX_BUDGET),
+        extra in amount_strategy(MAX_BUDGET),
+        exchange_rate in rate_strategy(),
+    ) {
+        let mut lower_pools = bid_pools.clone();
+        let mut lower_amount = Uint128::from(budget);
+        let lower_matched =
+            process_calc_distribution_amount(&mut lower_pools, &mut lower_amount, exchange_rate)
+                .unwrap();
+
+        let mut higher_pools = bid_pools;
+        let mut higher_amount = Uint128::from(budget) + Uint128::from(extra);
+        let higher_matched =
+            process_calc_distribution_amount(&mut higher_pools, &mut higher_amount, exchange_rate)
+                .unwrap();
+
+        prop_assert!(lower_matched <= higher_matched);
+        prop_assert!(total_paid(&lower_pools) <= total_paid(&higher_pools));
+    }
+}
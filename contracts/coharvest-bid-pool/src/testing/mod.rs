@@ -1 +1,5 @@
+mod benchmark;
+mod integration;
+mod matching;
+mod matching_props;
 mod tests;
@@ -1,23 +1,66 @@
 use std::str::FromStr;
 
 use cosmwasm_std::{
-    attr, from_json,
-    testing::{mock_dependencies, mock_env, mock_info},
-    to_json_binary, Addr, Api, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, OwnedDeps, Querier,
-    Response, StdError, Storage, SubMsg, Uint128, WasmMsg,
+    attr, coins, from_json,
+    testing::{
+        mock_dependencies, mock_env, mock_ibc_channel, mock_ibc_channel_close_init,
+        mock_ibc_channel_connect_ack, mock_ibc_channel_open_init, mock_ibc_packet_recv, mock_info,
+    },
+    to_json_binary, to_json_vec, Addr, Api, BankMsg, Binary, Coin, ContractResult, CosmosMsg,
+    Decimal, Deps, DepsMut, Env, Event, IbcOrder, MessageInfo, Order, OwnedDeps, Querier, Reply,
+    Response, StdError, StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult, SystemError,
+    SystemResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw2::ContractVersion;
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw4::{Cw4QueryMsg, MemberResponse, TotalWeightResponse};
+use cw_controllers::{HookError, HooksResponse};
+use cw_storage_plus::Map;
+use cw_utils::PaymentError;
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use oraiswap::{
+    asset::{Asset, AssetInfo, PairInfo},
+    pair::{Cw20HookMsg as PairCw20HookMsg, PoolResponse, QueryMsg as PairQueryMsg},
 };
-use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use oraiswap::asset::AssetInfo;
+use sha2::{Digest, Sha256};
 
 use crate::{
-    bid::process_calc_distribution_amount,
-    contract::{execute, instantiate, query},
+    bid::{LSD_REDEEM_REPLY_ID, NOTIFY_REPLY_ID, TRANSFER_REPLY_ID},
+    contract::{execute, instantiate, migrate, query, reply, sudo},
     error::ContractError,
+    ibc::{
+        ibc_channel_close, ibc_channel_connect, ibc_channel_open, ibc_packet_receive,
+        IBC_PACKET_REPLY_ID, IBC_VERSION,
+    },
+    matching::{process_calc_distribution_amount, process_calc_rebate_amount},
     msg::{
-        BiddingInfoResponse, Cw20HookMsg, EstimateAmountReceiveOfBidResponse, ExecuteMsg,
-        InstantiateMsg, QueryMsg,
+        AirdropExecuteMsg, BidPacketAck, BidPacketMsg, BidPoolHookMsg, BidPoolResponse,
+        BidPosition, BidResponse, BidStatus, BiddingInfoResponse, BurnHistoryResponse,
+        ClearingSlotResponse, ConfigResponse, ContractInfoResponse, CountersResponse, Cw20HookMsg,
+        DistributeResponse, EpochBurnStats, EstimateAmountReceiveOfBidResponse,
+        EstimateWithAdditionalBidsResponse, ExecuteMsg, FillForecastEntry, HookExecuteMsg,
+        IcaRoundResponse, InstantiateMsg, InvariantMismatch, InvariantsResponse,
+        LockBoostsResponse, LsdAdapterExecuteMsg, LsdAdapterQueryMsg, MigrateMsg,
+        NotifierExecuteMsg, PageResponse, PositionResponse, PremiumScheduleEntry, QueryMsg,
+        RedeemRateResponse, RoundBidPoolsResponse, RoundResultResponse, RoundStatus, RoutedBid,
+        ShadowBalanceResponse, SignedBid, SignedBidPayload, SimulateMatchingResponse, SlotFill,
+        SlotFillEstimate, SlotResult, StakingCw20HookMsg, StateMigrateMsg, SudoMsg,
+        TotalBidCheckpoint, UnaccountedBalanceResponse, UserRoundRoi, VerifyRoundResponse,
+        VestingCw20HookMsg, WrapperExecuteMsg, YieldVenueExecuteMsg,
+    },
+    reinvest::REINVEST_REPLY_ID,
+    state::{
+        read_bids_by_slot, read_bids_by_time, ActionAmount, BidLock, BidPool, BidRateLimit,
+        BiddingInfo, DisputeParams, DistributionInfo, FinalizeProgress, FinalizerGroup,
+        IbcSatellite, LockBoost, LpWithdrawRate, PauseFlags, Payout, PostFinalizeAction,
+        PremiumBreakpoint, PremiumCurve, RateBand, ReinvestConfig, RoundConfig, RoundState,
+        RoundSummary, RoundTemplate, SlotBonus, YieldPosition, YieldVenue, BID, BIDDING_INFO,
+        BIDS_BY_SLOT, BIDS_BY_TIME, BID_IDX, BID_POOL, DISTRIBUTION_INFO, FINALIZE_PROGRESS,
+        IBC_DELEGATIONS, LOCKS, LP_UNSETTLED_SHARES, LP_WITHDRAW_RATES, MIGRATION_CURSOR,
+        NUM_BIDDERS_BY_ROUND, NUM_BIDS_BY_ROUND, POOL_TOTAL_CHECKPOINTS, ROUND_STATES,
+        YIELD_POSITION,
     },
-    state::{Bid, BidPool, BiddingInfo, Config, DistributionInfo},
+    test_utils::submit_bid_helper,
 };
 
 const OWNER: &str = "owner";
@@ -26,7 +69,7 @@ const USDC: &str = "orai15un8msx3n5zf9ahlxmfeqd2kwa5wm0nrpxer304m9nd5q6qq0g6sku5
 
 pub fn init<S: Storage, A: Api, Q: Querier>(deps: &mut OwnedDeps<S, A, Q>) {
     let msg = InstantiateMsg {
-        owner: Addr::unchecked(OWNER),
+        owner: OWNER.to_string(),
         underlying_token: AssetInfo::Token {
             contract_addr: Addr::unchecked(ORAIX_ADDR),
         },
@@ -36,26 +79,42 @@ pub fn init<S: Storage, A: Api, Q: Querier>(deps: &mut OwnedDeps<S, A, Q>) {
         max_slot: 25,
         premium_rate_per_slot: Decimal::from_str("0.01").unwrap(),
         min_deposit_amount: Uint128::from(100_000000u128),
-        treasury: Addr::unchecked("treasury"),
+        treasury: "treasury".to_string(),
         bidding_duration: 86400, //
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
     };
 
     let info = mock_info(OWNER, &[]);
     instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 }
 
+// config as stored by the deployed contract, before any field was added and max_slot widened
+fn seed_baseline_config(storage: &mut dyn Storage) {
+    let config = format!(
+        r#"{{"owner":"{}","underlying_token":{{"token":{{"contract_addr":"{}"}}}},"distribution_token":{{"token":{{"contract_addr":"{}"}}}},"max_slot":25,"premium_rate_per_slot":"0.01","min_deposit_amount":"100000000","treasury":"treasury","bidding_duration":86400}}"#,
+        OWNER, ORAIX_ADDR, USDC
+    );
+    storage.set(b"config", config.as_bytes());
+}
+
 #[test]
 fn proper_initialization() {
     let mut deps = mock_dependencies();
     init(&mut deps);
 
     // check config storge
-    let config: Config =
+    let config: ConfigResponse =
         from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
 
     assert_eq!(
         config,
-        Config {
+        ConfigResponse {
             owner: Addr::unchecked(OWNER),
             underlying_token: AssetInfo::Token {
                 contract_addr: Addr::unchecked(ORAIX_ADDR),
@@ -67,7 +126,14 @@ fn proper_initialization() {
             premium_rate_per_slot: Decimal::from_str("0.01").unwrap(),
             min_deposit_amount: Uint128::from(100_000000u128),
             treasury: Addr::unchecked("treasury"),
-            bidding_duration: 86400
+            bidding_duration: 86400,
+            oracle_pair: None,
+            vesting_contract: None,
+            keeper_registry: None,
+            staking_contract: None,
+            residue_staking: None,
+            wrapped_denom: None,
+            min_gap_between_rounds: 0,
         }
     )
 }
@@ -83,6 +149,7 @@ fn test_create_new_round() {
         start_time: env.block.time.seconds(),
         end_time: env.block.time.plus_seconds(1000).seconds(),
         total_distribution: Uint128::from(20000_000000u128),
+        min_exchange_rate: None,
     };
     let err = execute(
         deps.as_mut(),
@@ -126,7 +193,8 @@ fn test_create_new_round() {
                 start_time: env.block.time.seconds(),
                 end_time: env.block.time.plus_seconds(1000).seconds(),
                 total_bid_amount: Uint128::zero(),
-                total_bid_matched: Uint128::zero()
+                total_bid_matched: Uint128::zero(),
+                creator: Some(Addr::unchecked(OWNER)),
             },
             distribution_info: DistributionInfo {
                 total_distribution: Uint128::from(20000_000000u128),
@@ -224,6 +292,7 @@ fn test_update_round() {
         start_time: env.block.time.seconds(),
         end_time: env.block.time.plus_seconds(1000).seconds(),
         total_distribution: Uint128::from(20000_000000u128),
+        min_exchange_rate: None,
     };
 
     execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
@@ -298,13 +367,29 @@ fn test_update_round() {
         total_distribution: Some(Uint128::from(20000_000000u128)),
     };
 
-    execute(
+    let res = execute(
         deps.as_mut(),
         env.clone(),
         mock_info(OWNER, &vec![]),
         msg.clone(),
     )
     .unwrap();
+    // only the end time changed
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_round"),
+            attr("round", "1"),
+            attr(
+                "end_time_old",
+                env.block.time.plus_seconds(1100).seconds().to_string()
+            ),
+            attr(
+                "end_time_new",
+                env.block.time.plus_seconds(1000).seconds().to_string()
+            ),
+        ]
+    );
 
     // read bidding info & distribution info
     let bidding_info: BiddingInfoResponse = from_json(
@@ -324,7 +409,8 @@ fn test_update_round() {
                 start_time: env.block.time.plus_seconds(100).seconds(),
                 end_time: env.block.time.plus_seconds(1000).seconds(),
                 total_bid_amount: Uint128::zero(),
-                total_bid_matched: Uint128::zero()
+                total_bid_matched: Uint128::zero(),
+                creator: Some(Addr::unchecked(OWNER)),
             },
             distribution_info: DistributionInfo {
                 total_distribution: Uint128::from(20000_000000u128),
@@ -348,6 +434,7 @@ fn test_submit_bids_and_querier() {
         start_time: env.block.time.seconds(),
         end_time: env.block.time.plus_seconds(1000).seconds(),
         total_distribution: Uint128::from(20000_000000u128),
+        min_exchange_rate: None,
     };
     let err = execute(
         deps.as_mut(),
@@ -426,24 +513,32 @@ fn test_submit_bids_and_querier() {
             attr("bidder", "addr000"),
             attr("bid_idx", "1"),
             attr("premium_slot", "1"),
-            attr("amount", "100000000")
+            attr("amount", "100000000"),
+            attr("pool_total_bid_amount", "100000000"),
+            attr("round_total_bid_amount", "100000000"),
+            attr("timestamp", env.block.time.seconds().to_string()),
         ]
     );
     // query bid info
-    let bid: Bid =
+    let bid: BidResponse =
         from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Bid { idx: 1 }).unwrap()).unwrap();
     assert_eq!(
         bid,
-        Bid {
+        BidResponse {
             idx: 1,
             round: 1,
             bidder: Addr::unchecked("addr000"),
             timestamp: mock_env().block.time.seconds(),
+            height: mock_env().block.height,
             amount: Uint128::from(100_000000u128),
             residue_bid: Uint128::from(100_000000u128),
             premium_slot: 1,
             amount_received: Uint128::zero(),
-            is_distributed: false
+            is_distributed: false,
+            auto_compound: false,
+            stake_residue: false,
+            reinvest: false,
+            performance_fee: Uint128::zero(),
         }
     );
 
@@ -472,7 +567,7 @@ fn test_submit_bids_and_querier() {
     .unwrap();
 
     // query bid info
-    let bid_pool: BidPool = from_json(
+    let bid_pool: BidPoolResponse = from_json(
         &query(
             deps.as_ref(),
             mock_env(),
@@ -483,11 +578,13 @@ fn test_submit_bids_and_querier() {
     .unwrap();
     assert_eq!(
         bid_pool,
-        BidPool {
+        BidPoolResponse {
             total_bid_amount: Uint128::from(300_000000u128),
             premium_rate: Decimal::from_str("0.01").unwrap(),
             index_snapshot: Decimal::zero(),
             received_per_token: Decimal::zero(),
+            matched_amount: Uint128::zero(),
+            payout_amount: Uint128::zero(),
             slot: 1
         }
     );
@@ -509,7 +606,8 @@ fn test_submit_bids_and_querier() {
                 start_time: env.block.time.seconds(),
                 end_time: env.block.time.plus_seconds(1000).seconds(),
                 total_bid_amount: Uint128::from(600_000000u128),
-                total_bid_matched: Uint128::zero()
+                total_bid_matched: Uint128::zero(),
+                creator: Some(Addr::unchecked(OWNER)),
             },
             distribution_info: DistributionInfo {
                 total_distribution: Uint128::from(20000_000000u128),
@@ -533,7 +631,9 @@ fn test_submit_bids_and_querier() {
             total_bid_amount: Uint128::from(300_000000u128),
             premium_rate: Decimal::from_str("0.01").unwrap(),
             index_snapshot: Decimal::zero(),
-            received_per_token: Decimal::zero()
+            received_per_token: Decimal::zero(),
+            matched_amount: Uint128::zero(),
+            payout_amount: Uint128::zero()
         }
     );
     assert_eq!(
@@ -543,24 +643,28 @@ fn test_submit_bids_and_querier() {
             total_bid_amount: Uint128::from(300_000000u128),
             premium_rate: Decimal::from_str("0.02").unwrap(),
             index_snapshot: Decimal::zero(),
-            received_per_token: Decimal::zero()
+            received_per_token: Decimal::zero(),
+            matched_amount: Uint128::zero(),
+            payout_amount: Uint128::zero()
         }
     );
     for i in 2..bid_pools.len() {
         assert_eq!(
             bid_pools[i],
             BidPool {
-                slot: i as u8 + 1,
+                slot: i as u16 + 1,
                 total_bid_amount: Uint128::zero(),
                 premium_rate: Decimal::from_ratio(i as u128 + 1, 100u128),
                 index_snapshot: Decimal::zero(),
-                received_per_token: Decimal::zero()
+                received_per_token: Decimal::zero(),
+                matched_amount: Uint128::zero(),
+                payout_amount: Uint128::zero()
             }
         );
     }
 
     // query all bid by bid_pool
-    let bids: Vec<u64> = from_json(
+    let bids: PageResponse<BidResponse> = from_json(
         &query(
             deps.as_ref(),
             mock_env(),
@@ -574,7 +678,75 @@ fn test_submit_bids_and_querier() {
         .unwrap(),
     )
     .unwrap();
-    assert_eq!(bids, vec![1, 2, 3]);
+    assert_eq!(
+        bids.items.iter().map(|bid| bid.idx).collect::<Vec<u64>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(bids.total, Some(3));
+    assert_eq!(bids.next_start_after, None);
+    assert!(!bids.has_more);
+
+    // the newest bids first, two by two
+    let bids: PageResponse<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllBidInRound {
+                round: 1,
+                start_after: None,
+                limit: Some(2),
+                order_by: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bids.items.iter().map(|bid| bid.idx).collect::<Vec<u64>>(),
+        vec![3, 2]
+    );
+    assert_eq!(bids.next_start_after, Some(2));
+    assert!(bids.has_more);
+    let bids: PageResponse<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllBidInRound {
+                round: 1,
+                start_after: bids.next_start_after,
+                limit: Some(2),
+                order_by: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bids.items.iter().map(|bid| bid.idx).collect::<Vec<u64>>(),
+        vec![1]
+    );
+    assert!(!bids.has_more);
+
+    let bids: PageResponse<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BidsBySlot {
+                round: 1,
+                slot: 1,
+                start_after: Some(1),
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bids.items.iter().map(|bid| bid.idx).collect::<Vec<u64>>(),
+        vec![2]
+    );
+    assert_eq!(bids.total, None);
+    assert!(!bids.has_more);
 
     let bids_by_users: Vec<u64> = from_json(
         &query(
@@ -582,7 +754,7 @@ fn test_submit_bids_and_querier() {
             mock_env(),
             QueryMsg::BidsIdxByUser {
                 round: 1,
-                user: Addr::unchecked("addr000"),
+                user: "addr000".to_string(),
             },
         )
         .unwrap(),
@@ -591,6 +763,246 @@ fn test_submit_bids_and_querier() {
     assert_eq!(bids_by_users, vec![1, 2]);
 }
 
+#[test]
+fn test_signed_bid() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+    let pubkey = Binary::from(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes(),
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::RegisterBidderPubkey {
+            pubkey: pubkey.clone(),
+        },
+    )
+    .unwrap();
+
+    let bid = SignedBid {
+        bidder: "addr000".to_string(),
+        round: 1,
+        premium_slot: 1,
+        amount: Uint128::from(200_000000u128),
+        nonce: 0,
+        deadline: env.block.time.plus_seconds(100).seconds(),
+    };
+    let payload = SignedBidPayload {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        bid: bid.clone(),
+    };
+    let signature: Signature = signing_key
+        .sign_prehash(&Sha256::digest(to_json_vec(&payload).unwrap()))
+        .unwrap();
+    let submit_signed_bid = ExecuteMsg::SubmitSignedBid {
+        bid,
+        signature: Binary::from(signature.to_bytes().as_slice()),
+        pubkey,
+    };
+
+    // the relayer submits the bid, the funds are pulled from the bidder
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &vec![]),
+        submit_signed_bid.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "addr000".to_string(),
+                recipient: env.contract.address.to_string(),
+                amount: Uint128::from(200_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert!(res.attributes.contains(&attr("nonce", "0")));
+
+    // the signature can't be replayed
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &vec![]),
+        submit_signed_bid,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidNonce { expected: 1 });
+
+    // the pulled ORAIX are expected in the shadow balance
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } => {
+            let balance = if contract_addr == ORAIX_ADDR {
+                200_000000u128
+            } else {
+                0
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&BalanceResponse {
+                    balance: Uint128::from(balance),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+    let shadow_balances: Vec<ShadowBalanceResponse> =
+        from_json(&query(deps.as_ref(), env, QueryMsg::ShadowBalances {}).unwrap()).unwrap();
+    assert!(shadow_balances
+        .iter()
+        .all(|balance| balance.expected == balance.actual));
+}
+
+#[test]
+fn test_total_bid_checkpoints() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let start_time = env.block.time.seconds();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time,
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(20000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    env.block.time = env.block.time.plus_seconds(100);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr001".to_string(),
+        Uint128::from(200_000000u128),
+        1,
+        2,
+    )
+    .unwrap();
+
+    // before any bid
+    let total: Uint128 = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::TotalBidAt {
+                round: 1,
+                timestamp: start_time - 1,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(total, Uint128::zero());
+
+    // between the two bids
+    let total: Uint128 = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::TotalBidAt {
+                round: 1,
+                timestamp: start_time + 50,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(total, Uint128::from(100_000000u128));
+
+    // after the second bid
+    let total: Uint128 = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::TotalBidAt {
+                round: 1,
+                timestamp: start_time + 100,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(total, Uint128::from(300_000000u128));
+
+    // pool at slot 2 was empty before the second bid
+    let pool_total: Uint128 = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidPoolTotalAt {
+                round: 1,
+                slot: 2,
+                timestamp: start_time + 50,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pool_total, Uint128::zero());
+
+    let history: Vec<TotalBidCheckpoint> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::TotalBidHistory {
+                round: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        history,
+        vec![
+            TotalBidCheckpoint {
+                timestamp: start_time,
+                total_bid_amount: Uint128::from(100_000000u128),
+            },
+            TotalBidCheckpoint {
+                timestamp: start_time + 100,
+                total_bid_amount: Uint128::from(300_000000u128),
+            },
+        ]
+    );
+}
+
 #[test]
 fn test_full_amount_to_be_distributed() {
     let mut bid_pools: Vec<BidPool> = vec![];
@@ -603,6 +1015,8 @@ fn test_full_amount_to_be_distributed() {
             premium_rate: Decimal::from_ratio(slot as u128, 100u128),
             index_snapshot: Decimal::zero(),
             received_per_token: Decimal::zero(),
+            matched_amount: Uint128::zero(),
+            payout_amount: Uint128::zero(),
         });
     }
 
@@ -640,6 +1054,8 @@ fn test_partial_amount_to_be_distributed() {
             premium_rate: Decimal::from_ratio(slot as u128, 100u128),
             index_snapshot: Decimal::zero(),
             received_per_token: Decimal::zero(),
+            matched_amount: Uint128::zero(),
+            payout_amount: Uint128::zero(),
         });
     }
 
@@ -676,6 +1092,8 @@ fn test_one_bid_pool_is_partially_matched() {
         premium_rate: Decimal::from_ratio(10u128, 100u128),
         index_snapshot: Decimal::zero(),
         received_per_token: Decimal::zero(),
+        matched_amount: Uint128::zero(),
+        payout_amount: Uint128::zero(),
     });
     bid_pools.push(BidPool {
         slot: 20,
@@ -683,6 +1101,8 @@ fn test_one_bid_pool_is_partially_matched() {
         premium_rate: Decimal::from_ratio(20u128, 100u128),
         index_snapshot: Decimal::zero(),
         received_per_token: Decimal::zero(),
+        matched_amount: Uint128::zero(),
+        payout_amount: Uint128::zero(),
     });
 
     let mut distribution_amount = Uint128::from(20_000000u128);
@@ -705,6 +1125,8 @@ fn test_one_bid_pool_is_partially_matched() {
             premium_rate: Decimal::from_ratio(10u128, 100u128),
             index_snapshot: Decimal::one(),
             received_per_token: Decimal::from_ratio(11u128, 1000u128),
+            matched_amount: Uint128::from(1000_000000u128),
+            payout_amount: Uint128::from(11_000000u128),
         }
     );
     assert_eq!(
@@ -715,6 +1137,8 @@ fn test_one_bid_pool_is_partially_matched() {
             premium_rate: Decimal::from_ratio(20u128, 100u128),
             index_snapshot: Decimal::from_ratio(3u128, 4u128),
             received_per_token: Decimal::from_ratio(9u128, 1000u128),
+            matched_amount: Uint128::from(750_000000u128),
+            payout_amount: Uint128::from(9_000000u128),
         }
     )
 }
@@ -731,6 +1155,8 @@ fn test_all_bid_matched_but_distribution_amount_remains() {
             premium_rate: Decimal::from_ratio(slot as u128, 100u128),
             index_snapshot: Decimal::zero(),
             received_per_token: Decimal::zero(),
+            matched_amount: Uint128::zero(),
+            payout_amount: Uint128::zero(),
         });
     }
 
@@ -758,6 +1184,7 @@ fn test_finalize_bidding_round_result() {
         start_time: env.block.time.seconds(),
         end_time: env.block.time.plus_seconds(1000).seconds(),
         total_distribution: Uint128::from(1080_000000u128),
+        min_exchange_rate: None,
     };
     execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
 
@@ -836,6 +1263,7 @@ fn test_finalize_bidding_round_result() {
         start_time: env.block.time.seconds(),
         end_time: env.block.time.plus_seconds(1000).seconds(),
         total_distribution: Uint128::from(1200_000000u128),
+        min_exchange_rate: None,
     };
     execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
 
@@ -896,6 +1324,84 @@ fn test_finalize_bidding_round_result() {
             }))
         ]
     );
+
+    // both rounds are finalized in the same epoch
+    let burn_history: BurnHistoryResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BurnHistory {
+                start: mock_env().block.time.seconds(),
+                end: env.block.time.seconds(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let epoch = env.block.time.seconds() / 86400;
+    assert_eq!(
+        burn_history,
+        BurnHistoryResponse {
+            total_underlying_burned: Uint128::from(196000_000000u128),
+            total_distribution_paid: Uint128::from(2210_000000u128),
+            epochs: vec![EpochBurnStats {
+                epoch_start: epoch * 86400,
+                underlying_burned: Uint128::from(196000_000000u128),
+                distribution_paid: Uint128::from(2210_000000u128),
+            }]
+        }
+    );
+
+    // round 2 summary, every pool is fully matched and 70 USDC went back to the owner
+    let result: RoundResultResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundResult { round: 2 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        result,
+        RoundResultResponse {
+            round: 2,
+            exchange_rate: Decimal::from_ratio(1u128, 100u128),
+            total_bid_amount: Uint128::from(100000_000000u128),
+            total_matched: Uint128::from(100000_000000u128),
+            total_distribution: Uint128::from(1200_000000u128),
+            actual_distributed: Uint128::from(1130_000000u128),
+            leftover: Uint128::from(70_000000u128),
+            slots: (1..=25)
+                .map(|slot| SlotResult {
+                    slot,
+                    total_bid_amount: Uint128::from(4000_000000u128),
+                    matched_percentage: Decimal::one(),
+                    matched_amount: Uint128::from(4000_000000u128),
+                    // 4000 * 0.01 * (1 + slot%)
+                    payout_amount: Uint128::from(40_000000u128 + 400000u128 * slot as u128),
+                })
+                .collect(),
+            num_bids: 25,
+            num_bids_distributed: 0,
+        }
+    );
+
+    // not finalized rounds have no result
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1200_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RoundResult { round: 3 },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("round 3 has not been finalized"));
 }
 
 #[test]
@@ -909,6 +1415,7 @@ fn test_distribute() {
         start_time: env.block.time.seconds(),
         end_time: env.block.time.plus_seconds(1000).seconds(),
         total_distribution: Uint128::from(1200_000000u128),
+        min_exchange_rate: None,
     };
     execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
 
@@ -993,6 +1500,7 @@ fn test_distribute() {
         start_time: env.block.time.seconds(),
         end_time: env.block.time.plus_seconds(1000).seconds(),
         total_distribution: Uint128::from(1055_200000u128),
+        min_exchange_rate: None,
     };
     execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
 
@@ -1117,6 +1625,7 @@ fn test_estimate_token_received() {
         start_time: env.block.time.seconds(),
         end_time: env.block.time.plus_seconds(1000).seconds(),
         total_distribution: Uint128::from(1130_000000u128),
+        min_exchange_rate: None,
     };
     execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
 
@@ -1151,19 +1660,19 @@ fn test_estimate_token_received() {
         res,
         EstimateAmountReceiveOfBidResponse {
             receive: Uint128::from(44_000000u128),
-            residue_bid: Uint128::zero()
+            residue_bid: Uint128::zero(),
+            performance_fee: Uint128::zero(),
         }
     );
 
-    // because all bids will be matched, so say submit another bid at slot 25 with 4000 tokens ==> all bids at slot 25 will match only half
+    // sum over all 25 bids of the user
     let res: EstimateAmountReceiveOfBidResponse = from_json(
         &query(
             deps.as_ref(),
             mock_env(),
-            QueryMsg::EstimateAmountReceive {
+            QueryMsg::EstimateAmountReceiveOfUser {
                 round: 1,
-                slot: 25,
-                bid_amount: Uint128::from(4000_000000u128),
+                user: "addr000".to_string(),
                 exchange_rate: Decimal::from_ratio(1u128, 100u128),
             },
         )
@@ -1173,17 +1682,79 @@ fn test_estimate_token_received() {
     assert_eq!(
         res,
         EstimateAmountReceiveOfBidResponse {
-            receive: Uint128::from(25_000000u128),
-            residue_bid: Uint128::from(2000_000000u128),
+            receive: Uint128::from(1130_000000u128),
+            residue_bid: Uint128::zero(),
+            performance_fee: Uint128::zero(),
         }
     );
 
-    // try submit this bid
-    do_submit_bid(
-        deps.as_mut(),
-        env.clone(),
-        mock_info(ORAIX_ADDR, &vec![]),
-        "addr000".to_string(),
+    // because all bids will be matched, so say submit another bid at slot 25 with 4000 tokens ==> all bids at slot 25 will match only half
+    let res: EstimateAmountReceiveOfBidResponse = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::EstimateAmountReceive {
+                round: 1,
+                slot: 25,
+                bid_amount: Uint128::from(4000_000000u128),
+                exchange_rate: Decimal::from_ratio(1u128, 100u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        EstimateAmountReceiveOfBidResponse {
+            receive: Uint128::from(25_000000u128),
+            residue_bid: Uint128::from(2000_000000u128),
+            performance_fee: Uint128::zero(),
+        }
+    );
+
+    // the same amount split into 2 bids has the same effect on the pool
+    let res: EstimateWithAdditionalBidsResponse = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::EstimateWithAdditionalBids {
+                round: 1,
+                additions: vec![
+                    (25, Uint128::from(2000_000000u128)),
+                    (25, Uint128::from(2000_000000u128)),
+                ],
+                exchange_rate: Decimal::from_ratio(1u128, 100u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.additions,
+        vec![
+            EstimateAmountReceiveOfBidResponse {
+                receive: Uint128::from(12_500000u128),
+                residue_bid: Uint128::from(1000_000000u128),
+                performance_fee: Uint128::zero(),
+            };
+            2
+        ]
+    );
+    assert_eq!(
+        res.slots[24],
+        SlotFillEstimate {
+            slot: 25,
+            index_snapshot: Decimal::from_ratio(1u128, 2u128),
+            received_per_token: Decimal::from_ratio(625u128, 100000u128),
+        }
+    );
+
+    // try submit this bid
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
         Uint128::from(4000_000000u128),
         1,
         25,
@@ -1207,29 +1778,8743 @@ fn test_estimate_token_received() {
         EstimateAmountReceiveOfBidResponse {
             receive: Uint128::from(25_000000u128),
             residue_bid: Uint128::from(2000_000000u128),
+            performance_fee: Uint128::zero(),
         }
     );
 }
-pub fn do_submit_bid(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    sender: String,
-    amount: Uint128,
-    round: u64,
-    premium_slot: u8,
-) -> Result<Response, ContractError> {
-    let msg = Cw20HookMsg::SubmitBid {
-        round,
-        premium_slot,
+
+#[test]
+fn test_round_status() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.plus_seconds(10).seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
     };
-    let receive = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender,
-        amount,
-        msg: to_json_binary(&msg).unwrap(),
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let query_status = |deps: Deps, env: Env| -> RoundStatus {
+        from_json(&query(deps, env, QueryMsg::RoundStatus { round: 1 }).unwrap()).unwrap()
+    };
+    assert_eq!(
+        query_status(deps.as_ref(), env.clone()),
+        RoundStatus::Upcoming
+    );
+
+    env.block.time = env.block.time.plus_seconds(10);
+    assert_eq!(query_status(deps.as_ref(), env.clone()), RoundStatus::Open);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    env.block.time = env.block.time.plus_seconds(991);
+    assert_eq!(query_status(deps.as_ref(), env.clone()), RoundStatus::Ended);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::FinalizeBiddingRoundResult {
+            round: 1,
+            exchange_rate: Decimal::from_ratio(1u128, 100u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_status(deps.as_ref(), env.clone()),
+        RoundStatus::Finalized
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_status(deps.as_ref(), env.clone()),
+        RoundStatus::FullyDistributed
+    );
+}
+
+#[test]
+fn test_estimate_at_market_rate() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1130_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    for i in 1..=25 {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(4000_000000u128),
+            1,
+            i,
+        )
+        .unwrap();
+    }
+
+    // without an oracle pair the schedule has no fill projection
+    let schedule: Vec<PremiumScheduleEntry> = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PremiumSchedule { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(schedule.len(), 25);
+    assert_eq!(
+        schedule[9],
+        PremiumScheduleEntry {
+            slot: 10,
+            premium_rate: Decimal::from_ratio(1u128, 10u128),
+            total_bid_amount: Uint128::from(4000_000000u128),
+            discount: Decimal::from_ratio(1u128, 11u128),
+            expected_fill: None,
+        }
+    );
+
+    // no oracle pair configured yet
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::EstimateAmountReceiveOfBidAtMarket { round: 1, idx: 10 },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("Oracle pair is not configured"));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: Some("oracle_pair".to_string()),
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // pair reserves give 1 ORAIX = 0.01 USDC
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == "oracle_pair" => {
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&PoolResponse {
+                    assets: [
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(USDC),
+                            },
+                            amount: Uint128::from(10_000_000000u128),
+                        },
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(ORAIX_ADDR),
+                            },
+                            amount: Uint128::from(1_000_000_000000u128),
+                        },
+                    ],
+                    total_share: Uint128::zero(),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
     });
 
-    execute(deps, env, info, receive)
+    let res: EstimateAmountReceiveOfBidResponse = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::EstimateAmountReceiveOfBidAtMarket { round: 1, idx: 10 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        EstimateAmountReceiveOfBidResponse {
+            receive: Uint128::from(44_000000u128),
+            residue_bid: Uint128::zero(),
+            performance_fee: Uint128::zero(),
+        }
+    );
+
+    // the whole budget covers every pool at the market rate
+    let schedule: Vec<PremiumScheduleEntry> = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PremiumSchedule { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(schedule
+        .iter()
+        .all(|entry| entry.expected_fill == Some(Decimal::one())));
+
+    let res: EstimateAmountReceiveOfBidResponse = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::EstimateAmountReceiveAtMarket {
+                round: 1,
+                slot: 25,
+                bid_amount: Uint128::from(4000_000000u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        EstimateAmountReceiveOfBidResponse {
+            receive: Uint128::from(25_000000u128),
+            residue_bid: Uint128::from(2000_000000u128),
+            performance_fee: Uint128::zero(),
+        }
+    );
+}
+
+#[test]
+fn test_user_roi() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    for slot in [10, 20] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(1000_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+
+    // round not finalized yet, nothing realized
+    let res: Vec<UserRoundRoi> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::UserRoi {
+                user: "addr000".to_string(),
+                rounds: vec![1],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, vec![]);
+
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // 1000 * 0.01 * 1.1 + 1000 * 0.01 * 1.2 = 23 received for 20 worth of matched tokens
+    let expected = vec![UserRoundRoi {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+        matched_amount: Uint128::from(2000_000000u128),
+        received_amount: Uint128::from(23_000000u128),
+        realized_premium: Decimal::from_ratio(15u128, 100u128),
+    }];
+    let res: Vec<UserRoundRoi> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::UserRoi {
+                user: "addr000".to_string(),
+                rounds: vec![1],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, expected);
+
+    // same result once the bids are distributed
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let res: Vec<UserRoundRoi> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::UserRoi {
+                user: "addr000".to_string(),
+                rounds: vec![1],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn test_distribute_vested_rewards() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // vested rounds need a vesting contract
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            vested_rewards: true,
+            credit_payouts: false,
+            lp_pair: None,
+            distribute_by_slot: false,
+            total_bid_threshold: None,
+            min_participants: None,
+            premium_curve: PremiumCurve::Linear {},
+            lend_distribution: false,
+            performance_fee: None,
+            tranched: false,
+            lsd_adapter: None,
+        },
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Vesting contract is not configured"))
+    );
+
+    let update_config = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: None,
+        vesting_contract: Some("vesting".to_string()),
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        update_config,
+    )
+    .unwrap();
+
+    // only owner can configure the round
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let round_config: RoundConfig = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundConfig { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        round_config,
+        RoundConfig {
+            vested_rewards: true,
+            credit_payouts: false,
+            lp_pair: None,
+            distribute_by_slot: false,
+            total_bid_threshold: None,
+            min_participants: None,
+            premium_curve: PremiumCurve::Linear {},
+            lend_distribution: false,
+            performance_fee: None,
+            tranched: false,
+            lsd_adapter: None,
+        }
+    );
+
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        10,
+    )
+    .unwrap();
+
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // the payout goes to the vesting contract on behalf of the bidder
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: "vesting".to_string(),
+                amount: Uint128::from(11_000000u128),
+                msg: to_json_binary(&VestingCw20HookMsg::Vest {
+                    recipient: "addr000".to_string(),
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_finalize_by_keeper_registry() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(1001);
+
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper_registry", &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let update_config = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: Some("keeper_registry".to_string()),
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        update_config,
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper_registry", &vec![]),
+        msg,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_deposit_and_bid_from_balance() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // deposit with the wrong token
+    let deposit = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(3000_000000u128),
+        msg: to_json_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+    });
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &vec![]),
+        deposit.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        deposit,
+    )
+    .unwrap();
+
+    // bid twice from the internal balance
+    for slot in [1u16, 2u16] {
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0000", &vec![]),
+            ExecuteMsg::SubmitBidFromBalance {
+                round: 1,
+                premium_slot: slot,
+                amount: Uint128::from(1000_000000u128),
+            },
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+    }
+    let bids: Vec<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidsByUser {
+                round: 1,
+                user: "addr0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bids.len(), 2);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &vec![]),
+        ExecuteMsg::SubmitBidFromBalance {
+            round: 1,
+            premium_slot: 3,
+            amount: Uint128::from(2000_000000u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientBalance {
+            balance: Uint128::from(1000_000000u128)
+        }
+    );
+
+    // withdraw what is left
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &vec![]),
+        ExecuteMsg::Withdraw {
+            amount: Uint128::from(1000_000000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(1000_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let balance: Uint128 = from_json(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::BalanceOf {
+                user: "addr0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(balance, Uint128::zero());
+}
+
+#[test]
+fn test_credit_and_withdraw_payouts() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_500000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            vested_rewards: false,
+            credit_payouts: true,
+            lp_pair: None,
+            distribute_by_slot: false,
+            total_bid_threshold: None,
+            min_participants: None,
+            premium_curve: PremiumCurve::Linear {},
+            lend_distribution: false,
+            performance_fee: None,
+            tranched: false,
+            lsd_adapter: None,
+        },
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        10,
+    )
+    .unwrap();
+
+    // half of the bid is matched
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // nothing is transferred, the payout is credited to the bidder
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert!(res.messages.is_empty());
+    let payout: Payout = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Payout {
+                user: "addr000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        payout,
+        Payout {
+            underlying_amount: Uint128::from(500_000000u128),
+            distribution_amount: Uint128::from(5_500000u128),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::WithdrawPayout {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr000".to_string(),
+                    amount: Uint128::from(5_500000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr000".to_string(),
+                    amount: Uint128::from(500_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    // payout can only be withdrawn once
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::WithdrawPayout {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("No payout to withdraw"))
+    );
+}
+
+#[test]
+fn test_auto_compound_rewards() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(11_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    for bidder in ["addr000", "addr001"] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(500_000000u128),
+            1,
+            10,
+        )
+        .unwrap();
+    }
+
+    let msg = ExecuteMsg::UpdateBidOptions {
+        idx: 1,
+        auto_compound: Some(true),
+        stake_residue: None,
+        reinvest: None,
+    };
+    // only the bidder can opt in
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr001", &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Staking contract is not configured"))
+    );
+
+    let update_config = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: Some("staking".to_string()),
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        update_config,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        msg,
+    )
+    .unwrap();
+
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // rewards of the first bid are bonded for the bidder, the second bid is paid out as usual
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "staking".to_string(),
+                    amount: Uint128::from(5_500000u128),
+                    msg: to_json_binary(&StakingCw20HookMsg::Bond {
+                        staker: "addr000".to_string(),
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr001".to_string(),
+                    amount: Uint128::from(5_500000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_stake_residue() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_500000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        10,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::UpdateBidOptions {
+        idx: 1,
+        auto_compound: None,
+        stake_residue: Some(true),
+        reinvest: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Residue staking contract is not configured"
+        ))
+    );
+
+    let update_config = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: Some("oraix_staking".to_string()),
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        update_config,
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_bid_options"),
+            attr("bid_idx", "1"),
+            attr("auto_compound", "false"),
+            attr("stake_residue", "true"),
+            attr("reinvest", "false"),
+        ]
+    );
+
+    // half of the bid is matched
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr000".to_string(),
+                    amount: Uint128::from(5_500000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "oraix_staking".to_string(),
+                    amount: Uint128::from(500_000000u128),
+                    msg: to_json_binary(&StakingCw20HookMsg::Bond {
+                        staker: "addr000".to_string(),
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_submit_bid_with_wrapped_native() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    let msg = ExecuteMsg::SubmitBid {
+        round: 1,
+        premium_slot: 1,
+    };
+    // native coins are rejected until the wrapped denom is configured
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &coins(1000_000000u128, "orai")),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+
+    let update_config = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: Some("orai".to_string()),
+        min_gap_between_rounds: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        update_config,
+    )
+    .unwrap();
+
+    // other denoms are still rejected
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &coins(1000_000000u128, "atom")),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+
+    // the coins are wrapped and the bid is recorded in the cw20 underlying token
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &coins(1000_000000u128, "orai")),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&WrapperExecuteMsg::Deposit {}).unwrap(),
+            funds: coins(1000_000000u128, "orai"),
+        }))]
+    );
+    let bid: BidResponse =
+        from_json(&query(deps.as_ref(), env, QueryMsg::Bid { idx: 1 }).unwrap()).unwrap();
+    assert_eq!(bid.amount, Uint128::from(1000_000000u128));
+    assert_eq!(bid.bidder, Addr::unchecked("addr000"));
+}
+
+#[test]
+fn test_lp_bids() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    // 1 LP token is worth 2 ORAIX and 1 ORAI
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == "lp_pair" => {
+            let res = match from_json(msg).unwrap() {
+                PairQueryMsg::Pair {} => to_json_binary(&PairInfo {
+                    oracle_addr: Addr::unchecked("oracle"),
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: Addr::unchecked(ORAIX_ADDR),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "orai".to_string(),
+                        },
+                    ],
+                    contract_addr: Addr::unchecked("lp_pair"),
+                    liquidity_token: Addr::unchecked("lp_token"),
+                    commission_rate: "0.003".to_string(),
+                }),
+                PairQueryMsg::Pool {} => to_json_binary(&PoolResponse {
+                    assets: [
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(ORAIX_ADDR),
+                            },
+                            amount: Uint128::from(2_000_000_000000u128),
+                        },
+                        Asset {
+                            info: AssetInfo::NativeToken {
+                                denom: "orai".to_string(),
+                            },
+                            amount: Uint128::from(1_000_000_000000u128),
+                        },
+                    ],
+                    total_share: Uint128::from(1_000_000_000000u128),
+                }),
+                _ => panic!("unexpected pair query"),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.plus_seconds(100).seconds(),
+        end_time: env.block.time.plus_seconds(1100).seconds(),
+        total_distribution: Uint128::from(5_500000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let submit_lp_bid = |deps: DepsMut, env: Env, lp_token: &str| {
+        execute(
+            deps,
+            env,
+            mock_info(lp_token, &vec![]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "addr000".to_string(),
+                amount: Uint128::from(500_000000u128),
+                msg: to_json_binary(&Cw20HookMsg::SubmitLpBid {
+                    round: 1,
+                    premium_slot: 10,
+                })
+                .unwrap(),
+            }),
+        )
+    };
+
+    let err = submit_lp_bid(deps.as_mut(), env.clone(), "lp_token").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Round does not accept LP tokens"))
+    );
+
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            vested_rewards: false,
+            credit_payouts: false,
+            lp_pair: Some(Addr::unchecked("lp_pair")),
+            distribute_by_slot: false,
+            total_bid_threshold: None,
+            min_participants: None,
+            premium_curve: PremiumCurve::Linear {},
+            lend_distribution: false,
+            performance_fee: None,
+            tranched: false,
+            lsd_adapter: None,
+        },
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(110);
+    // only the LP token of the pair is accepted
+    let err = submit_lp_bid(deps.as_mut(), env.clone(), "other_lp_token").unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+    submit_lp_bid(deps.as_mut(), env.clone(), "lp_token").unwrap();
+
+    // the LP tokens are withdrawn before the matched ORAIX is burnt
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "lp_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "lp_pair".to_string(),
+                    amount: Uint128::from(500_000000u128),
+                    msg: to_json_binary(&PairCw20HookMsg::WithdrawLiquidity {}).unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::from(500_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    assert_eq!(
+        LP_UNSETTLED_SHARES.load(deps.as_ref().storage, 1).unwrap(),
+        Uint128::from(500_000000u128)
+    );
+
+    // the ORAI leg is refunded along with the reward and the unmatched ORAIX
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr000".to_string(),
+                amount: coins(500_000000u128, "orai"),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr000".to_string(),
+                    amount: Uint128::from(5_500000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr000".to_string(),
+                    amount: Uint128::from(500_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    let bid: BidResponse =
+        from_json(&query(deps.as_ref(), env, QueryMsg::Bid { idx: 1 }).unwrap()).unwrap();
+    assert_eq!(bid.amount, Uint128::from(1000_000000u128));
+    assert!(!LP_UNSETTLED_SHARES.has(deps.as_ref().storage, 1));
+}
+
+#[test]
+fn test_slot_bonuses() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_750000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // bonus for the high slots in a native token
+    let msg = ExecuteMsg::AddSlotBonus {
+        round: 1,
+        min_slot: 10,
+        max_slot: 30,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("partner", &coins(100_000000u128, "ubonus")),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "bonus slots must be within the range 1 and 25"
+        ))
+    );
+    let msg = ExecuteMsg::AddSlotBonus {
+        round: 1,
+        min_slot: 10,
+        max_slot: 25,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("partner", &coins(100_000000u128, "ubonus")),
+        msg,
+    )
+    .unwrap();
+
+    // bonus for the first slot in a cw20 token, nobody bids there
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "partner".to_string(),
+        amount: Uint128::from(50_000000u128),
+        msg: to_json_binary(&Cw20HookMsg::AddSlotBonus {
+            round: 1,
+            min_slot: 1,
+            max_slot: 1,
+        })
+        .unwrap(),
+    });
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("partner_token", &vec![]),
+        msg,
+    )
+    .unwrap();
+
+    for (bidder, slot) in [("addr000", 10u16), ("addr001", 5u16)] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(500_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+
+    // the bonus without any matched bid goes back to the partner
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::from(1000_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "partner_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "partner".to_string(),
+                    amount: Uint128::from(50_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    let bonuses: Vec<SlotBonus> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::SlotBonuses { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bonuses[0].eligible_matched, Uint128::from(500_000000u128));
+    assert_eq!(bonuses[1].eligible_matched, Uint128::zero());
+
+    // only the bid in the eligible slots receives the bonus
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr000".to_string(),
+                    amount: Uint128::from(5_500000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr000".to_string(),
+                amount: coins(100_000000u128, "ubonus"),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr001".to_string(),
+                    amount: Uint128::from(5_250000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_round_templates() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let env = mock_env();
+    let template = RoundTemplate {
+        duration: 1000,
+        total_distribution: Uint128::from(10_000000u128),
+        round_config: RoundConfig {
+            vested_rewards: false,
+            credit_payouts: true,
+            lp_pair: None,
+            distribute_by_slot: false,
+            total_bid_threshold: None,
+            min_participants: None,
+            premium_curve: PremiumCurve::Linear {},
+            lend_distribution: false,
+            performance_fee: None,
+            tranched: false,
+            lsd_adapter: None,
+        },
+    };
+
+    // only owner can save templates
+    let msg = ExecuteMsg::SaveRoundTemplate {
+        name: "weekly".to_string(),
+        params: template.clone(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let saved: RoundTemplate = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundTemplate {
+                name: "weekly".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(saved, template);
+
+    // unknown template
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::CreateRoundFromTemplate {
+            name: "daily".to_string(),
+            start_time: env.block.time.seconds(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Round template daily does not exist"))
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::CreateRoundFromTemplate {
+            name: "weekly".to_string(),
+            start_time: env.block.time.seconds(),
+        },
+    )
+    .unwrap();
+
+    let bidding_info: BiddingInfoResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BiddingInfo { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bidding_info.bid_info.end_time,
+        env.block.time.plus_seconds(1000).seconds()
+    );
+    assert_eq!(
+        bidding_info.distribution_info.total_distribution,
+        Uint128::from(10_000000u128)
+    );
+    let round_config: RoundConfig = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundConfig { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(round_config, template.round_config);
+}
+
+#[test]
+fn test_sudo_update_params() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let msg = SudoMsg::UpdateParams {
+        max_slot: Some(30),
+        premium_rate_per_slot: Some(Decimal::from_ratio(2u128, 100u128)),
+        min_deposit_amount: None,
+        bidding_duration: None,
+        treasury: None,
+        oracle_pair: Some("oracle_pair".to_string()),
+    };
+    sudo(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.owner, Addr::unchecked(OWNER));
+    assert_eq!(config.max_slot, 30);
+    assert_eq!(
+        config.premium_rate_per_slot,
+        Decimal::from_ratio(2u128, 100u128)
+    );
+    assert_eq!(config.oracle_pair, Some(Addr::unchecked("oracle_pair")));
+
+    // bids of the stored rounds may sit in the highest slots
+    let msg = SudoMsg::UpdateParams {
+        max_slot: Some(29),
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        bidding_duration: None,
+        treasury: None,
+        oracle_pair: None,
+    };
+    let err = sudo(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidParams {
+            reason: "max_slot can't be lowered below 30".to_string(),
+        }
+    );
+    let msg = SudoMsg::UpdateParams {
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: Some(Uint128::zero()),
+        bidding_duration: None,
+        treasury: None,
+        oracle_pair: None,
+    };
+    let err = sudo(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidParams {
+            reason: "min_deposit_amount must be above 0".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_pause_flags() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::SetPauseFlags {
+        submit_bid: Some(true),
+        create_round: None,
+        finalize: None,
+        distribute: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let pause_flags: PauseFlags =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::PauseFlags {}).unwrap()).unwrap();
+    assert_eq!(
+        pause_flags,
+        PauseFlags {
+            submit_bid: true,
+            create_round: false,
+            finalize: false,
+            distribute: false,
+        }
+    );
+
+    // rounds can still be created while bids are paused
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    let err = do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Paused {
+            operation: "submit_bid".to_string()
+        }
+    );
+
+    let msg = ExecuteMsg::SetPauseFlags {
+        submit_bid: Some(false),
+        create_round: Some(true),
+        finalize: None,
+        distribute: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.plus_seconds(1000).seconds(),
+        end_time: env.block.time.plus_seconds(2000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Paused {
+            operation: "create_round".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_bid_rate_limit() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(10000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // at most 2 bids per hour
+    let msg = ExecuteMsg::SetBidRateLimit {
+        rate_limit: Some(BidRateLimit {
+            max_bids: 2,
+            window: 3600,
+        }),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    for _ in 0..2 {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            1,
+        )
+        .unwrap();
+        env.block.time = env.block.time.plus_seconds(1000);
+    }
+    let err = do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RateLimited {
+            max_bids: 2,
+            window: 3600
+        }
+    );
+
+    // other addresses are not affected
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr001".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    // the first bid leaves the window
+    env.block.time = env.block.time.plus_seconds(1600);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_min_gap_of_baseline_config() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let env = mock_env();
+
+    // the config stored before the upgrade has no gap, rounds can still be created back to back
+    seed_baseline_config(deps.as_mut().storage);
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.min_gap_between_rounds, 0);
+    for start in [0, 1000] {
+        let msg = ExecuteMsg::CreateNewRound {
+            start_time: env.block.time.plus_seconds(start).seconds(),
+            end_time: env.block.time.plus_seconds(start + 1000).seconds(),
+            total_distribution: Uint128::from(10_000000u128),
+            min_exchange_rate: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    }
+}
+
+#[test]
+fn test_min_gap_between_rounds() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: Some(3600),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // back-to-back round is rejected
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.plus_seconds(1001).seconds(),
+        end_time: env.block.time.plus_seconds(2000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "A new round must start at least 3600 seconds after the last round ends"
+        ))
+    );
+
+    // rounds created by the treasury start after the gap
+    env.block.time = env.block.time.plus_seconds(10);
+    let res = do_create_new_round(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &vec![]),
+        "treasury".to_string(),
+        Uint128::one(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr(
+            "start_time",
+            env.block
+                .time
+                .plus_seconds(990 + 3600)
+                .seconds()
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_dispute_exchange_rate() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    // pair reserves give 1 ORAIX = 0.01 USDC
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == "oracle_pair" => {
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&PoolResponse {
+                    assets: [
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(USDC),
+                            },
+                            amount: Uint128::from(10_000_000000u128),
+                        },
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(ORAIX_ADDR),
+                            },
+                            amount: Uint128::from(1_000_000_000000u128),
+                        },
+                    ],
+                    total_share: Uint128::zero(),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+    let msg = SudoMsg::UpdateParams {
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        bidding_duration: None,
+        treasury: None,
+        oracle_pair: Some("oracle_pair".to_string()),
+    };
+    sudo(deps.as_mut(), env.clone(), msg).unwrap();
+    let msg = ExecuteMsg::SetDisputeParams {
+        params: Some(DisputeParams {
+            bond: Coin::new(100_000000, "orai"),
+            max_deviation: Decimal::from_ratio(1u128, 10u128),
+            dispute_period: 3600,
+        }),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(1000);
+
+    // the matched tokens are not burned while the round can be disputed
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(2u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(res.attributes[3], attr("total_matched", "247524752"));
+
+    let distribute = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        distribute.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(format!(
+            "Round 1 can be disputed until {}",
+            env.block.time.plus_seconds(3600).seconds()
+        )))
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("disputer", &coins(10_000000, "orai")),
+        ExecuteMsg::DisputeRound { round: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("disputer", &coins(100_000000, "orai")),
+        ExecuteMsg::DisputeRound { round: 1 },
+    )
+    .unwrap();
+
+    // distribution is frozen until the owner resolves the dispute
+    env.block.time = env.block.time.plus_seconds(3600);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        distribute.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RoundDisputed { round: 1 });
+
+    let msg = ExecuteMsg::ResolveDispute {
+        round: 1,
+        exchange_rate: Some(Decimal::from_ratio(1u128, 100u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(res.attributes[4], attr("total_matched", "495049504"));
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "disputer".to_string(),
+            amount: coins(100_000000, "orai"),
+        }))]
+    );
+
+    // the round is settled with the corrected rate
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        distribute,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Burn {
+                amount: Uint128::from(495049504u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    );
+}
+
+#[test]
+fn test_finalize_round_without_bids() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(1010);
+
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "finalize_bidding_round_result"),
+            attr("round", "1"),
+            attr("exchange_rate", "0.01"),
+            attr("total_matched", "0"),
+            attr("reclaimed_distribution", "10000000"),
+        ]
+    );
+    // nothing is burned, the distribution goes back to the owner
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: OWNER.to_string(),
+                amount: Uint128::from(10_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let bidding_info: BiddingInfoResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BiddingInfo { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(bidding_info.distribution_info.is_released);
+    assert_eq!(
+        bidding_info.distribution_info.actual_distributed,
+        Uint128::zero()
+    );
+}
+
+#[test]
+fn test_matching_overflow() {
+    let mut bid_pools = vec![BidPool {
+        slot: 1,
+        total_bid_amount: Uint128::from(2u128 * 10u128.pow(38)),
+        premium_rate: Decimal::from_ratio(1u128, 100u128),
+        index_snapshot: Decimal::zero(),
+        received_per_token: Decimal::zero(),
+        matched_amount: Uint128::zero(),
+        payout_amount: Uint128::zero(),
+    }];
+    let mut distribution_amount = Uint128::from(10u128.pow(38));
+
+    // the desired amount 2e38 * 2 * 1.01 does not fit in a Uint128, the pool is still matched
+    let total_matched = process_calc_distribution_amount(
+        &mut bid_pools,
+        &mut distribution_amount,
+        Decimal::from_ratio(2u128, 1u128),
+    )
+    .unwrap();
+    assert_eq!(distribution_amount, Uint128::zero());
+    assert_eq!(
+        bid_pools[0].index_snapshot,
+        Decimal::raw(247524752475247524u128)
+    );
+    assert_eq!(
+        total_matched,
+        Uint128::from(49504950495049504800000000000000000000u128)
+    );
+    assert_eq!(bid_pools[0].received_per_token, Decimal::percent(50));
+
+    // a result out of range is still an error
+    let mut distribution_amount = Uint128::MAX;
+    let mut bid_pools = vec![BidPool {
+        slot: 1,
+        total_bid_amount: Uint128::MAX,
+        premium_rate: Decimal::percent(100),
+        index_snapshot: Decimal::zero(),
+        received_per_token: Decimal::zero(),
+        matched_amount: Uint128::zero(),
+        payout_amount: Uint128::zero(),
+    }];
+    let err = process_calc_rebate_amount(&mut bid_pools, &mut distribution_amount).unwrap_err();
+    assert!(matches!(err, ContractError::Overflow(_)));
+}
+
+#[test]
+fn test_verify_round() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    for bidder in ["addr000", "addr001"] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(500_000000u128),
+            1,
+            1,
+        )
+        .unwrap();
+    }
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // a corrupted round total trips the circuit breaker instead of overpaying
+    let mut distribution_info = DISTRIBUTION_INFO.load(&deps.storage, 1).unwrap();
+    let actual_distributed = distribution_info.actual_distributed;
+    distribution_info.actual_distributed = Uint128::from(2_000000u128);
+    DISTRIBUTION_INFO
+        .save(&mut deps.storage, 1, &distribution_info)
+        .unwrap();
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.events,
+        vec![Event::new("invariant_breach").add_attributes(vec![
+            ("round", "1"),
+            ("reason", "payout_exceeds_distribution"),
+        ])]
+    );
+    let pause_flags: PauseFlags =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::PauseFlags {}).unwrap()).unwrap();
+    assert_eq!(
+        pause_flags,
+        PauseFlags {
+            submit_bid: true,
+            create_round: true,
+            finalize: true,
+            distribute: true,
+        }
+    );
+    let distribution: DistributionInfo = DISTRIBUTION_INFO.load(&deps.storage, 1).unwrap();
+    assert_eq!(distribution.num_bids_distributed, 0);
+
+    // stays paused until the owner unpauses after the investigation
+    distribution_info.actual_distributed = actual_distributed;
+    DISTRIBUTION_INFO
+        .save(&mut deps.storage, 1, &distribution_info)
+        .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Paused {
+            operation: "distribute".to_string()
+        }
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetPauseFlags {
+            submit_bid: Some(false),
+            create_round: Some(false),
+            finalize: Some(false),
+            distribute: Some(false),
+        },
+    )
+    .unwrap();
+    // the cursor did not move past the batch that tripped the breaker
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1], attr("total_bids_distributed", "2"));
+    assert_eq!(
+        from_json::<DistributeResponse>(&res.data.unwrap())
+            .unwrap()
+            .first_idx,
+        Some(1)
+    );
+
+    let verify: VerifyRoundResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::VerifyRound { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        verify,
+        VerifyRoundResponse {
+            round: 1,
+            total_received: Uint128::from(5_000000u128),
+            actual_distributed: Uint128::from(5_000000u128),
+            total_residue: Uint128::from(504950494u128),
+            total_unmatched: Uint128::from(504950496u128),
+            num_bids_distributed: 2,
+            is_valid: true,
+        }
+    );
+}
+
+#[test]
+fn test_distribute_bids() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    for bidder in ["addr000", "addr001", "addr002"] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            1,
+        )
+        .unwrap();
+    }
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("support", &vec![]),
+        ExecuteMsg::DistributeBids {
+            round: 1,
+            idxs: vec![2, 99],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Bid 99 is not in round 1"))
+    );
+
+    // only the second bid is pushed through
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("support", &vec![]),
+        ExecuteMsg::DistributeBids {
+            round: 1,
+            idxs: vec![2],
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1], attr("total_bids_distributed", "1"));
+    assert_eq!(
+        res.events,
+        vec![Event::new("distribute_bid").add_attributes(vec![
+            ("round", "1"),
+            ("bid_idx", "2"),
+            ("bidder", "addr001"),
+            ("reward", "1010000"),
+            ("residue", "0"),
+            ("performance_fee", "0"),
+        ])]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr001".to_string(),
+                amount: Uint128::from(1_010000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    // explicit bids have no cursor to resume from
+    assert_eq!(
+        from_json::<DistributeResponse>(&res.data.unwrap()).unwrap(),
+        DistributeResponse {
+            first_idx: Some(2),
+            last_idx: Some(2),
+            num_rewarded: 1,
+            num_refunded: 0,
+            num_skipped: 0,
+            next_cursor: None,
+        }
+    );
+    for (idx, distributed) in [(1, false), (2, true), (3, false)] {
+        let bid: BidResponse =
+            from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Bid { idx }).unwrap()).unwrap();
+        assert_eq!(bid.is_distributed, distributed);
+    }
+
+    // the cursor still goes through the other bids
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1], attr("total_bids_distributed", "3"));
+    assert_eq!(res.messages.len(), 2);
+    // the round is completed, there is no next batch
+    assert_eq!(
+        from_json::<DistributeResponse>(&res.data.unwrap()).unwrap(),
+        DistributeResponse {
+            first_idx: Some(1),
+            last_idx: Some(3),
+            num_rewarded: 2,
+            num_refunded: 0,
+            num_skipped: 1,
+            next_cursor: None,
+        }
+    );
+}
+
+#[test]
+fn test_distribute_by_slot() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            vested_rewards: false,
+            credit_payouts: false,
+            lp_pair: None,
+            distribute_by_slot: true,
+            total_bid_threshold: None,
+            min_participants: None,
+            premium_curve: PremiumCurve::Linear {},
+            lend_distribution: false,
+            performance_fee: None,
+            tranched: false,
+            lsd_adapter: None,
+        },
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(10);
+    for (bidder, slot) in [("addr000", 3), ("addr001", 1), ("addr002", 2)] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // the lowest slot is settled first, then the next ones in slot order
+    for (idx, bidder, amount) in [(2, "addr001", 1_010000u128), (3, "addr002", 1_020000u128)] {
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &vec![]),
+            ExecuteMsg::Distribute {
+                round: 1,
+                start_after: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: bidder.to_string(),
+                    amount: Uint128::from(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+        let data: DistributeResponse = from_json(&res.data.unwrap()).unwrap();
+        assert_eq!(data.next_cursor, Some(idx));
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1], attr("total_bids_distributed", "3"));
+    let bid: BidResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Bid { idx: 1 }).unwrap()).unwrap();
+    assert!(bid.is_distributed);
+}
+
+#[test]
+fn test_total_bid_threshold() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            vested_rewards: false,
+            credit_payouts: false,
+            lp_pair: None,
+            distribute_by_slot: false,
+            total_bid_threshold: Some(Uint128::from(1000_000000u128)),
+            min_participants: None,
+            premium_curve: PremiumCurve::Linear {},
+            lend_distribution: false,
+            performance_fee: None,
+            tranched: false,
+            lsd_adapter: None,
+        },
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(10);
+    for bidder in ["addr000", "addr001"] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            1,
+        )
+        .unwrap();
+    }
+    env.block.time = env.block.time.plus_seconds(1000);
+
+    // 200 ORAIX is below the floor, nothing is matched nor burned
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(res.attributes[3], attr("total_matched", "0"));
+    assert_eq!(res.attributes[5], attr("total_bid_threshold", "1000000000"));
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: OWNER.to_string(),
+                amount: Uint128::from(10_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // every bid gets its full amount back
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        ["addr000", "addr001"]
+            .into_iter()
+            .map(|bidder| SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: bidder.to_string(),
+                    amount: Uint128::from(100_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })))
+            .collect::<Vec<SubMsg>>()
+    );
+}
+
+#[test]
+fn test_min_participants() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            vested_rewards: false,
+            credit_payouts: false,
+            lp_pair: None,
+            distribute_by_slot: false,
+            total_bid_threshold: None,
+            min_participants: Some(2),
+            premium_curve: PremiumCurve::Linear {},
+            lend_distribution: false,
+            performance_fee: None,
+            tranched: false,
+            lsd_adapter: None,
+        },
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // a single bidder splitting the volume over several bids
+    env.block.time = env.block.time.plus_seconds(10);
+    for slot in [1, 2] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(1000_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+    env.block.time = env.block.time.plus_seconds(1000);
+
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.attributes[3..],
+        vec![
+            attr("total_matched", "0"),
+            attr("reclaimed_distribution", "10000000"),
+            attr("min_participants", "2"),
+            attr("num_participants", "1"),
+        ]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    let bids: PageResponse<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::AllBidInRound {
+                round: 1,
+                start_after: None,
+                limit: None,
+                order_by: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    for bid in bids.items {
+        assert_eq!(bid.residue_bid, Uint128::from(1000_000000u128));
+    }
+}
+
+#[test]
+fn test_split_bid() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    let split = |amount: u128| ExecuteMsg::SplitBid {
+        idx: 1,
+        amount: Uint128::from(amount),
+        new_slot: 3,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr001", &vec![]),
+        split(400_000000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the remaining part would be below the minimum deposit
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        split(950_000000),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Both parts of a split bid must be at least 100000000"
+        ))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        split(400_000000),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[3], attr("new_bid_idx", "2"));
+
+    let bid: BidResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Bid { idx: 1 }).unwrap()).unwrap();
+    assert_eq!(bid.amount, Uint128::from(600_000000u128));
+    let bid: BidResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Bid { idx: 2 }).unwrap()).unwrap();
+    assert_eq!(bid.premium_slot, 3);
+    assert_eq!(bid.amount, Uint128::from(400_000000u128));
+    assert_eq!(bid.bidder, Addr::unchecked("addr000"));
+
+    for (slot, amount) in [(1, 600_000000u128), (3, 400_000000u128)] {
+        let bid_pool: BidPoolResponse = from_json(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::BidPool { round: 1, slot },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(bid_pool.total_bid_amount, Uint128::from(amount));
+    }
+
+    // bids can no longer be split once the round has ended
+    env.block.time = env.block.time.plus_seconds(1000);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        split(100_000000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BidNotOpen {});
+}
+
+#[test]
+fn test_premium_curves() {
+    let rate = Decimal::from_ratio(1u128, 100u128);
+    let exponential = PremiumCurve::Exponential {
+        growth: Decimal::from_ratio(1u128, 2u128),
+    };
+    let breakpoints = PremiumCurve::Breakpoints {
+        points: vec![
+            PremiumBreakpoint {
+                slot: 2,
+                premium_rate: Decimal::from_ratio(2u128, 100u128),
+            },
+            PremiumBreakpoint {
+                slot: 6,
+                premium_rate: Decimal::from_ratio(10u128, 100u128),
+            },
+        ],
+    };
+    for (slot, linear_rate, exponential_rate, breakpoints_rate) in [
+        (1u16, "0.01", "0.01", "0.01"),
+        (3, "0.03", "0.0225", "0.04"),
+        (6, "0.06", "0.0759375", "0.1"),
+        (10, "0.1", "0.38443359375", "0.1"),
+    ] {
+        assert_eq!(
+            PremiumCurve::Linear {}.premium_rate(rate, slot).unwrap(),
+            Decimal::from_str(linear_rate).unwrap()
+        );
+        assert_eq!(
+            exponential.premium_rate(rate, slot).unwrap(),
+            Decimal::from_str(exponential_rate).unwrap()
+        );
+        assert_eq!(
+            breakpoints.premium_rate(rate, slot).unwrap(),
+            Decimal::from_str(breakpoints_rate).unwrap()
+        );
+    }
+
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.plus_seconds(10).seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let round_config = |premium_curve: PremiumCurve| ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            vested_rewards: false,
+            credit_payouts: false,
+            lp_pair: None,
+            distribute_by_slot: false,
+            total_bid_threshold: None,
+            min_participants: None,
+            premium_curve,
+            lend_distribution: false,
+            performance_fee: None,
+            tranched: false,
+            lsd_adapter: None,
+        },
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        round_config(PremiumCurve::Breakpoints { points: vec![] }),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Premium breakpoints must have increasing slots and premium rates"
+        ))
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        round_config(breakpoints),
+    )
+    .unwrap();
+    let schedule: Vec<PremiumScheduleEntry> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PremiumSchedule { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(schedule[3].premium_rate, Decimal::from_str("0.06").unwrap());
+    assert_eq!(schedule[24].premium_rate, Decimal::from_str("0.1").unwrap());
+}
+
+#[test]
+fn test_min_exchange_rate() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: Some(Decimal::from_ratio(1u128, 100u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert!(res.attributes.contains(&attr("min_exchange_rate", "0.01")));
+
+    let min_exchange_rate: Option<Decimal> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::MinExchangeRate { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(min_exchange_rate, Some(Decimal::from_ratio(1u128, 100u128)));
+
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(1000);
+
+    let finalize = |exchange_rate: Decimal| ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        finalize(Decimal::from_ratio(9u128, 1000u128)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ExchangeRateBelowMinimum {
+            exchange_rate: Decimal::from_ratio(9u128, 1000u128),
+            min_exchange_rate: Decimal::from_ratio(1u128, 100u128),
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        finalize(Decimal::from_ratio(1u128, 100u128)),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_fill_forecast() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    for slot in [1, 2, 3] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(200_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+
+    // 2.02 + 2.04 USDC fill the first two slots, 0.94 of 2.06 is left for the third
+    let forecast: Vec<FillForecastEntry> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::FillForecast {
+                round: 1,
+                exchange_rate: Decimal::from_ratio(1u128, 100u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(forecast.len(), 25);
+    assert_eq!(forecast[0].fill, SlotFill::Full {});
+    assert_eq!(forecast[1].fill, SlotFill::Full {});
+    assert_eq!(
+        forecast[2].fill,
+        SlotFill::Partial {
+            matched_ratio: Decimal::from_ratio(940000u128, 2060000u128)
+        }
+    );
+    assert_eq!(
+        forecast[3],
+        FillForecastEntry {
+            slot: 4,
+            total_bid_amount: Uint128::zero(),
+            fill: SlotFill::Unmatched {},
+        }
+    );
+
+    let clearing_slot = |exchange_rate: Decimal| -> ClearingSlotResponse {
+        from_json(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ClearingSlot {
+                    round: 1,
+                    exchange_rate,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        clearing_slot(Decimal::from_ratio(1u128, 100u128)),
+        ClearingSlotResponse {
+            slot: Some(3),
+            fill: Decimal::from_ratio(940000u128, 2060000u128),
+        }
+    );
+    // at a lower rate every slot is filled and the last one with bids clears
+    assert_eq!(
+        clearing_slot(Decimal::from_ratio(1u128, 1000u128)),
+        ClearingSlotResponse {
+            slot: Some(3),
+            fill: Decimal::one(),
+        }
+    );
+}
+
+#[test]
+fn test_backfill_migration() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    for slot in [3, 1, 2] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+    // bids stored before the slot and time indexes existed
+    for (slot, idx) in [(3, 1), (1, 2), (2, 3)] {
+        BIDS_BY_SLOT.remove(deps.as_mut().storage, (1, slot, idx));
+        BIDS_BY_TIME.remove(deps.as_mut().storage, (1, env.block.time.seconds(), idx));
+    }
+
+    let continue_migration = |limit: Option<u64>| ExecuteMsg::ContinueMigration { limit };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        continue_migration(None),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("No migration in progress"))
+    );
+
+    migrate(
+        deps.as_mut(),
+        env.clone(),
+        MigrateMsg::State(StateMigrateMsg::Backfill {}),
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        continue_migration(Some(2)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "continue_migration"),
+            attr("num_migrated", "2"),
+            attr("last_idx", "2"),
+            attr("finished", "false"),
+        ]
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        continue_migration(Some(2)),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[3], attr("finished", "true"));
+
+    assert_eq!(
+        read_bids_by_slot(deps.as_ref().storage, 1, None, 10).unwrap(),
+        vec![(1, 2), (2, 3), (3, 1)]
+    );
+    assert_eq!(
+        read_bids_by_time(
+            deps.as_ref().storage,
+            1,
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+            None
+        )
+        .unwrap(),
+        vec![1, 2, 3]
+    );
+    assert!(MIGRATION_CURSOR
+        .may_load(deps.as_ref().storage)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_migrate_msg_formats() {
+    // the flat config sent to migrate before the state migrations existed
+    let msg: MigrateMsg = from_json(format!(
+        r#"{{"owner":"{}","underlying_token":{{"token":{{"contract_addr":"{}"}}}},"distribution_token":{{"token":{{"contract_addr":"{}"}}}},"max_slot":25,"premium_rate_per_slot":"0.01","min_deposit_amount":"100000000","treasury":"treasury","bidding_duration":86400}}"#,
+        OWNER, ORAIX_ADDR, USDC
+    ))
+    .unwrap();
+    let MigrateMsg::UpdateConfig(config) = msg else {
+        panic!("expected the flat config to be parsed as UpdateConfig");
+    };
+    assert_eq!(config.max_slot, 25);
+    assert_eq!(config.oracle_pair, None);
+    assert_eq!(config.min_gap_between_rounds, None);
+
+    let msg: MigrateMsg = from_json(br#"{"backfill":{}}"#).unwrap();
+    assert_eq!(msg, MigrateMsg::State(StateMigrateMsg::Backfill {}));
+    let msg: MigrateMsg = from_json(br#"{"widen_slots":{}}"#).unwrap();
+    assert_eq!(msg, MigrateMsg::State(StateMigrateMsg::WidenSlots {}));
+}
+
+#[test]
+fn test_contract_info() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let contract_info: ContractInfoResponse =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap()).unwrap();
+    assert_eq!(
+        contract_info.cw2,
+        Some(ContractVersion {
+            contract: "coharvest-bid-pool".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        })
+    );
+    assert_eq!(contract_info.crate_version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_round_creator_permissions() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    do_create_new_round(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &vec![]),
+        "treasury".to_string(),
+        Uint128::from(10_000000u128),
+    )
+    .unwrap();
+    let bidding_info: BiddingInfoResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BiddingInfo { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bidding_info.bid_info.creator,
+        Some(Addr::unchecked("treasury"))
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::CancelRound { round: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the treasury manages its own round before it starts
+    let end_time = bidding_info.bid_info.end_time + 1000;
+    let update_round = ExecuteMsg::UpdateRound {
+        idx: 1,
+        start_time: None,
+        end_time: Some(end_time),
+        total_distribution: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("treasury", &vec![]),
+        update_round.clone(),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("treasury", &vec![]),
+        ExecuteMsg::CancelRound { round: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "treasury".to_string(),
+                amount: Uint128::from(10_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let last_round_id: u64 =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::LastRoundId {}).unwrap()).unwrap();
+    assert_eq!(last_round_id, 0);
+
+    // once started only the owner can change it
+    do_create_new_round(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &vec![]),
+        "treasury".to_string(),
+        Uint128::from(10_000000u128),
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("treasury", &vec![]),
+        update_round,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Round 1 has already started"))
+    );
+}
+
+#[test]
+fn test_lifecycle_hooks() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let register = ExecuteMsg::RegisterHook {
+        addr: "notifier".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        register.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        register.clone(),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        register,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Hook(HookError::HookAlreadyRegistered {})
+    );
+
+    let hooks: HooksResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Hooks {}).unwrap()).unwrap();
+    assert_eq!(hooks.hooks, vec!["notifier".to_string()]);
+
+    let hook_msg = |msg: BidPoolHookMsg| {
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: "notifier".to_string(),
+            msg: to_json_binary(&HookExecuteMsg::BidPoolHook(msg)).unwrap(),
+            funds: vec![],
+        })
+    };
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![hook_msg(BidPoolHookMsg::RoundCreated { round: 1 })]
+    );
+
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(1000);
+
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages.last(),
+        Some(&hook_msg(BidPoolHookMsg::RoundFinalized {
+            round: 1,
+            exchange_rate: Decimal::from_ratio(1u128, 100u128),
+        }))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages.last(),
+        Some(&hook_msg(BidPoolHookMsg::DistributionCompleted {
+            round: 1
+        }))
+    );
+
+    // removed hooks are no longer notified
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::RemoveHook {
+            addr: "notifier".to_string(),
+        },
+    )
+    .unwrap();
+    let hooks: HooksResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Hooks {}).unwrap()).unwrap();
+    assert!(hooks.hooks.is_empty());
+}
+
+#[test]
+fn test_claim_residue() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    let claim_residue = ExecuteMsg::ClaimResidue { idx: 1 };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        claim_residue.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BidNotEnded {});
+
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr001", &vec![]),
+        claim_residue.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // 5 USDC covers 495.04 ORAIX of the bid, the rest is returned at once
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        claim_residue.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr000".to_string(),
+                amount: Uint128::from(504950495u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        claim_residue,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Residue of bid 1 has already been paid"
+        ))
+    );
+
+    // the distribution only pays the reward
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr000".to_string(),
+                amount: Uint128::from(5_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_idle_funds_yield() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == "yield_venue" => {
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&Uint128::from(1_000000u128)).unwrap(),
+            ))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+
+    let mut venue = YieldVenue {
+        contract: Addr::unchecked("yield_venue"),
+        reward_asset: AssetInfo::Token {
+            contract_addr: Addr::unchecked(ORAIX_ADDR),
+        },
+        yield_to_distribution: true,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetYieldVenue {
+            venue: Some(venue.clone()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Yield added to the distribution must be paid in distribution_token"
+        ))
+    );
+    venue.reward_asset = AssetInfo::Token {
+        contract_addr: Addr::unchecked(USDC),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetYieldVenue {
+            venue: Some(venue.clone()),
+        },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    // the whole idle amount is deposited when no amount is given
+    let deposit = ExecuteMsg::DepositIdleFunds {
+        round: 1,
+        amount: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        deposit.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: "yield_venue".to_string(),
+                amount: Uint128::from(1000_000000u128),
+                msg: to_json_binary(&YieldVenueExecuteMsg::Deposit {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        deposit,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Only 0 idle funds can be deposited"))
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetYieldVenue { venue: None },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Idle funds of round 1 are still deposited in the yield venue"
+        ))
+    );
+
+    env.block.time = env.block.time.plus_seconds(1000);
+    let finalize = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        finalize.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Idle funds of round 1 must be withdrawn from the yield venue before finalize"
+        ))
+    );
+
+    // the principal comes back and the 1 USDC of yield raises the distribution of the round
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::WithdrawIdleFunds {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "yield_venue".to_string(),
+                msg: to_json_binary(&YieldVenueExecuteMsg::Withdraw {
+                    amount: Uint128::from(1000_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "yield_venue".to_string(),
+                msg: to_json_binary(&YieldVenueExecuteMsg::ClaimRewards {}).unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    assert_eq!(
+        DISTRIBUTION_INFO
+            .load(deps.as_ref().storage, 1)
+            .unwrap()
+            .total_distribution,
+        Uint128::from(6_000000u128)
+    );
+    let position: Option<YieldPosition> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::YieldPosition {}).unwrap()).unwrap();
+    assert_eq!(position, None);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        finalize,
+    )
+    .unwrap();
+
+    // the emergency unwind only withdraws the principal
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        2,
+        1,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::DepositIdleFunds {
+            round: 2,
+            amount: Some(Uint128::from(400_000000u128)),
+        },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::UnwindIdleFunds {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::UnwindIdleFunds {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "yield_venue".to_string(),
+            msg: to_json_binary(&YieldVenueExecuteMsg::Withdraw {
+                amount: Uint128::from(400_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_lend_distribution() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == "money_market" => {
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&Uint128::from(200000u128)).unwrap(),
+            ))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetLendingMarket {
+            market: Some("money_market".to_string()),
+        },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let lend = ExecuteMsg::LendDistribution { round: 1 };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        lend.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Round 1 does not lend its distribution"
+        ))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::UpdateRoundConfig {
+            round: 1,
+            round_config: RoundConfig {
+                lend_distribution: true,
+                ..RoundConfig::default()
+            },
+        },
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        lend.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: "money_market".to_string(),
+                amount: Uint128::from(5_000000u128),
+                msg: to_json_binary(&YieldVenueExecuteMsg::Deposit {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let err = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), lend).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Distribution of round 1 is already lent"
+        ))
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetLendingMarket { market: None },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Distribution of round 1 is still lent on the money market"
+        ))
+    );
+
+    // finalize withdraws the distribution first and sends the interest to the treasury
+    env.block.time = env.block.time.plus_seconds(1000);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::FinalizeBiddingRoundResult {
+            round: 1,
+            exchange_rate: Decimal::from_ratio(1u128, 100u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[..3],
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "money_market".to_string(),
+                msg: to_json_binary(&YieldVenueExecuteMsg::Withdraw {
+                    amount: Uint128::from(5_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "money_market".to_string(),
+                msg: to_json_binary(&YieldVenueExecuteMsg::ClaimRewards {}).unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "treasury".to_string(),
+                    amount: Uint128::from(200000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    let lent: Uint128 = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::LentDistribution { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(lent, Uint128::zero());
+
+    // a paused market only has to return the lent amount
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::UpdateRoundConfig {
+            round: 2,
+            round_config: RoundConfig {
+                lend_distribution: true,
+                ..RoundConfig::default()
+            },
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::LendDistribution { round: 2 },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::ForceRecallDistribution { round: 2 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "money_market".to_string(),
+            msg: to_json_binary(&YieldVenueExecuteMsg::Withdraw {
+                amount: Uint128::from(5_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_performance_fee() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(100_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let round_config = |fee_rate: &str| ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            performance_fee: Some(Decimal::from_str(fee_rate).unwrap()),
+            ..RoundConfig::default()
+        },
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        round_config("1.5"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Performance fee must not exceed 100%"
+        ))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        round_config("0.2"),
+    )
+    .unwrap();
+
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(1000_000000u128),
+        1,
+        10,
+    )
+    .unwrap();
+
+    // 11 USDC at a 10% premium, 1 USDC above the 0% premium fill of which 20% is the fee
+    let estimate: EstimateAmountReceiveOfBidResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::EstimateAmountReceiveOfBid {
+                round: 1,
+                idx: 1,
+                exchange_rate: Decimal::from_ratio(1u128, 100u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        estimate,
+        EstimateAmountReceiveOfBidResponse {
+            receive: Uint128::from(10_800000u128),
+            residue_bid: Uint128::zero(),
+            performance_fee: Uint128::from(200000u128),
+        }
+    );
+
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr000".to_string(),
+                    amount: Uint128::from(10_800000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "treasury".to_string(),
+                    amount: Uint128::from(200000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    let bid: BidResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Bid { idx: 1 }).unwrap()).unwrap();
+    assert_eq!(bid.amount_received, Uint128::from(11_000000u128));
+    assert_eq!(bid.performance_fee, Uint128::from(200000u128));
+}
+
+#[test]
+fn test_rebate_round() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        underlying_token: AssetInfo::Token {
+            contract_addr: Addr::unchecked(ORAIX_ADDR),
+        },
+        distribution_token: AssetInfo::Token {
+            contract_addr: Addr::unchecked(ORAIX_ADDR),
+        },
+        max_slot: 25,
+        premium_rate_per_slot: Decimal::from_str("0.01").unwrap(),
+        min_deposit_amount: Uint128::from(100_000000u128),
+        treasury: "treasury".to_string(),
+        bidding_duration: 86400,
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    for (bidder, slot) in [("addr000", 1), ("addr001", 2)] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(1000_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+
+    // the 10 ORAIX only fund the 1% premium of the first slot, the principal comes back on top
+    let estimate: EstimateAmountReceiveOfBidResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::EstimateAmountReceiveOfBid {
+                round: 1,
+                idx: 1,
+                exchange_rate: Decimal::one(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        estimate,
+        EstimateAmountReceiveOfBidResponse {
+            receive: Uint128::from(1010_000000u128),
+            residue_bid: Uint128::zero(),
+            performance_fee: Uint128::zero(),
+        }
+    );
+
+    env.block.time = env.block.time.plus_seconds(1000);
+    let finalize = |exchange_rate: Decimal| ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        finalize(Decimal::from_ratio(1u128, 100u128)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Rebate rounds are finalized at an exchange rate of 1"
+        ))
+    );
+
+    // nothing is burned
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        finalize(Decimal::one()),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr000".to_string(),
+                    amount: Uint128::from(1010_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr001".to_string(),
+                    amount: Uint128::from(1000_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    let verify: VerifyRoundResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::VerifyRound { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(verify.total_received, Uint128::from(1010_000000u128));
+    assert_eq!(verify.actual_distributed, Uint128::from(10_000000u128));
+    assert!(verify.is_valid);
+}
+
+#[test]
+fn test_chunked_finalize() {
+    let mut single = mock_dependencies();
+    let mut chunked = mock_dependencies();
+    let mut env = mock_env();
+    let start_time = env.block.time.seconds();
+    env.block.time = env.block.time.plus_seconds(10);
+    for deps in [&mut single, &mut chunked] {
+        init(deps);
+        let msg = ExecuteMsg::CreateNewRound {
+            start_time,
+            end_time: start_time + 1000,
+            total_distribution: Uint128::from(10_000000u128),
+            min_exchange_rate: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &vec![]), msg).unwrap();
+        for (bidder, slot) in [("addr000", 1), ("addr001", 20)] {
+            do_submit_bid(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ORAIX_ADDR, &vec![]),
+                bidder.to_string(),
+                Uint128::from(1000_000000u128),
+                1,
+                slot,
+            )
+            .unwrap();
+        }
+    }
+    env.block.time = env.block.time.plus_seconds(1000);
+    let exchange_rate = Decimal::from_ratio(1u128, 100u128);
+
+    let expected = execute(
+        single.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::FinalizeBiddingRoundResult {
+            round: 1,
+            exchange_rate,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        chunked.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::StartFinalize {
+            round: 1,
+            exchange_rate,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+    let continue_finalize = ExecuteMsg::ContinueFinalize {
+        round: 1,
+        limit: None,
+    };
+    for last_slot in ["10", "20"] {
+        let res = execute(
+            chunked.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &vec![]),
+            continue_finalize.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("last_slot", last_slot));
+        assert_eq!(res.messages, vec![]);
+    }
+
+    // the round is not released until every slot is matched
+    let err = execute(
+        chunked.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::FinalizeBiddingRoundResult {
+            round: 1,
+            exchange_rate,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Finalize of round 1 is in progress"))
+    );
+    let err = execute(
+        chunked.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BidNotEnded {});
+
+    // the last call burns and refunds exactly like a single finalize
+    let res = execute(
+        chunked.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        continue_finalize.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, expected.messages);
+    assert_eq!(res.attributes, expected.attributes);
+    for query_msg in [
+        QueryMsg::BiddingInfo { round: 1 },
+        QueryMsg::AllBidPoolInRound {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    ] {
+        assert_eq!(
+            query(chunked.as_ref(), env.clone(), query_msg.clone()).unwrap(),
+            query(single.as_ref(), env.clone(), query_msg).unwrap()
+        );
+    }
+
+    let err = execute(
+        chunked.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        continue_finalize,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Finalize of round 1 has not started"))
+    );
+}
+
+#[test]
+fn test_widen_slots() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    // 0.1% steps up to 50%
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: Some(500),
+        premium_rate_per_slot: Some(Decimal::from_str("0.001").unwrap()),
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        300,
+    )
+    .unwrap();
+
+    let bid_pool: BidPoolResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidPool {
+                round: 1,
+                slot: 300,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bid_pool.total_bid_amount, Uint128::from(100_000000u128));
+    assert_eq!(bid_pool.premium_rate, Decimal::from_str("0.3").unwrap());
+
+    let query_pools = |deps: Deps, start_after: Option<u16>, limit: Option<u64>| {
+        from_json::<Vec<BidPoolResponse>>(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::AllBidPoolInRound {
+                    round: 1,
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let pools = query_pools(deps.as_ref(), None, None);
+    assert_eq!(
+        pools.iter().map(|pool| pool.slot).collect::<Vec<u16>>(),
+        (1..=30).collect::<Vec<u16>>()
+    );
+    let pools = query_pools(deps.as_ref(), Some(298), Some(3));
+    assert_eq!(
+        pools.iter().map(|pool| pool.slot).collect::<Vec<u16>>(),
+        vec![299, 300, 301]
+    );
+    assert_eq!(pools[1].total_bid_amount, Uint128::from(100_000000u128));
+    assert_eq!(query_pools(deps.as_ref(), Some(497), None).len(), 3);
+    assert!(query_pools(deps.as_ref(), Some(500), None).is_empty());
+}
+
+#[test]
+fn test_widen_slots_migration() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    for slot in [1, 3] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+
+    // pools, slot index and checkpoints stored before the slots were widened
+    let legacy_bid_pool: Map<(u64, u8), BidPool> = Map::new("bid_pool");
+    let legacy_bids_by_slot: Map<(u64, u8, u64), bool> = Map::new("bids_by_slot");
+    let legacy_checkpoints: Map<(u64, u8, u64), Uint128> = Map::new("pool_total_checkpoints");
+    let storage = deps.as_mut().storage;
+    let bid_pools = BID_POOL
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()
+        .unwrap();
+    for ((round, slot), bid_pool) in bid_pools {
+        BID_POOL.remove(storage, (round, slot));
+        legacy_bid_pool
+            .save(storage, (round, slot as u8), &bid_pool)
+            .unwrap();
+    }
+    let bids = BIDS_BY_SLOT
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()
+        .unwrap();
+    for (round, slot, idx) in bids {
+        BIDS_BY_SLOT.remove(storage, (round, slot, idx));
+        legacy_bids_by_slot
+            .save(storage, (round, slot as u8, idx), &true)
+            .unwrap();
+    }
+    let checkpoints = POOL_TOTAL_CHECKPOINTS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()
+        .unwrap();
+    for ((round, slot, timestamp), total) in checkpoints {
+        POOL_TOTAL_CHECKPOINTS.remove(storage, (round, slot, timestamp));
+        legacy_checkpoints
+            .save(storage, (round, slot as u8, timestamp), &total)
+            .unwrap();
+    }
+    // the WidenSlots migration leaves the stored config untouched
+    seed_baseline_config(storage);
+
+    migrate(
+        deps.as_mut(),
+        env.clone(),
+        MigrateMsg::State(StateMigrateMsg::WidenSlots {}),
+    )
+    .unwrap();
+    let continue_migration = |limit: Option<u64>| ExecuteMsg::ContinueMigration { limit };
+
+    // a bid would land in a pool the migration overwrites
+    let err = do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        3,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SlotKeyMigrationInProgress {});
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        continue_migration(Some(4)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "continue_migration"),
+            attr("num_migrated", "4"),
+            attr("finished", "false"),
+        ]
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        continue_migration(Some(4)),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1], attr("num_migrated", "2"));
+    assert_eq!(res.attributes[2], attr("finished", "true"));
+
+    let bid_pool: BidPoolResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidPool { round: 1, slot: 3 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bid_pool.total_bid_amount, Uint128::from(100_000000u128));
+    assert_eq!(
+        read_bids_by_slot(deps.as_ref().storage, 1, None, 10).unwrap(),
+        vec![(1, 1), (3, 2)]
+    );
+    assert_eq!(
+        POOL_TOTAL_CHECKPOINTS
+            .load(deps.as_ref().storage, (1, 3, env.block.time.seconds()))
+            .unwrap(),
+        Uint128::from(100_000000u128)
+    );
+    assert!(legacy_bid_pool
+        .range(deps.as_ref().storage, None, None, Order::Ascending)
+        .next()
+        .is_none());
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        continue_migration(None),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("No migration in progress"))
+    );
+}
+
+#[test]
+fn test_bid_pools_by_rounds() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    for (round, amount) in [(1, 100_000000u128), (2, 300_000000u128)] {
+        let msg = ExecuteMsg::CreateNewRound {
+            start_time: env.block.time.seconds(),
+            end_time: env.block.time.plus_seconds(1000).seconds(),
+            total_distribution: Uint128::from(10_000000u128),
+            min_exchange_rate: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(amount),
+            round,
+            10,
+        )
+        .unwrap();
+        env.block.time = env.block.time.plus_seconds(10);
+    }
+
+    let res: Vec<RoundBidPoolsResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidPoolsByRounds { rounds: vec![2, 1] },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.iter().map(|pools| pools.round).collect::<Vec<u64>>(),
+        vec![2, 1]
+    );
+    assert_eq!(res[0].bid_pools.len(), 25);
+    assert_eq!(
+        res.iter()
+            .map(|pools| pools.bid_pools[9].total_bid_amount)
+            .collect::<Vec<Uint128>>(),
+        vec![Uint128::from(300_000000u128), Uint128::from(100_000000u128)]
+    );
+
+    // unknown rounds are not silently skipped
+    query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BidPoolsByRounds { rounds: vec![3] },
+    )
+    .unwrap_err();
+
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BidPoolsByRounds {
+            rounds: (1..=31).collect(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("At most 30 rounds can be queried at once")
+    );
+}
+
+#[test]
+fn test_prune_round() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    for (bidder, slot) in [("addr000", 1), ("addr001", 2)] {
+        env.block.time = env.block.time.plus_seconds(10);
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let prune_round = |round: u64| ExecuteMsg::PruneRound {
+        round,
+        limit: Some(4),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        prune_round(1),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        prune_round(2),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("The last round can not be pruned"))
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        prune_round(1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Bid 1 of round 1 is not distributed"))
+    );
+
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let distribution_info = DISTRIBUTION_INFO.load(deps.as_ref().storage, 1).unwrap();
+
+    // 2 bids and 2 round checkpoints, then the 2 pool checkpoints
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        prune_round(1),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "prune_round"),
+            attr("round", "1"),
+            attr("num_pruned", "4"),
+            attr("finished", "false"),
+        ]
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        prune_round(1),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("num_pruned", "2"));
+    assert_eq!(res.attributes[3], attr("finished", "true"));
+
+    let summary: Option<RoundSummary> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundSummary { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        summary,
+        Some(RoundSummary {
+            round: 1,
+            start_time: mock_env().block.time.seconds(),
+            end_time: mock_env().block.time.plus_seconds(1000).seconds(),
+            total_bid_amount: Uint128::from(200_000000u128),
+            total_bid_matched: Uint128::from(200_000000u128),
+            exchange_rate: Decimal::from_ratio(1u128, 100u128),
+            total_distribution: Uint128::from(10_000000u128),
+            actual_distributed: distribution_info.actual_distributed,
+            num_bids: 2,
+            num_bidders: 2,
+        })
+    );
+    query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BiddingInfo { round: 1 },
+    )
+    .unwrap_err();
+    query(deps.as_ref(), env.clone(), QueryMsg::Bid { idx: 1 }).unwrap_err();
+    let round_ids: PageResponse<u64> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundIds {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(round_ids.items, vec![2]);
+    assert_eq!(round_ids.total, Some(1));
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        prune_round(1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Round 1 is already pruned"))
+    );
+}
+
+#[test]
+fn test_keys_only_queries() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let env = mock_env();
+
+    for _ in 0..2 {
+        let msg = ExecuteMsg::CreateNewRound {
+            start_time: env.block.time.seconds(),
+            end_time: env.block.time.plus_seconds(1000).seconds(),
+            total_distribution: Uint128::from(10_000000u128),
+            min_exchange_rate: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    }
+    for (bidder, round) in [
+        ("addr001", 1),
+        ("addr000", 1),
+        ("addr001", 1),
+        ("addr002", 2),
+    ] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(100_000000u128),
+            round,
+            1,
+        )
+        .unwrap();
+    }
+
+    let round_ids: PageResponse<u64> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundIds {
+                start_after: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        round_ids,
+        PageResponse {
+            items: vec![1],
+            total: Some(2),
+            next_start_after: Some(1),
+            has_more: true,
+        }
+    );
+    let round_ids: PageResponse<u64> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundIds {
+                start_after: Some(1),
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(round_ids.items, vec![2]);
+    assert!(!round_ids.has_more);
+
+    let bid_idxs: Vec<u64> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidIdxs {
+                start_after: Some(1),
+                limit: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bid_idxs, vec![2, 3]);
+
+    let bidders: Vec<Addr> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BiddersInRound {
+                round: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bidders,
+        vec![Addr::unchecked("addr000"), Addr::unchecked("addr001")]
+    );
+    let bidders: Vec<Addr> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BiddersInRound {
+                round: 1,
+                start_after: Some("addr000".to_string()),
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bidders, vec![Addr::unchecked("addr001")]);
+}
+
+#[test]
+fn test_bids_by_height_range() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    for _ in 0..3 {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            1,
+        )
+        .unwrap();
+        env.block.height += 5;
+    }
+
+    let query_bids = |from: u64, to: u64, limit: Option<u64>| {
+        from_json::<Vec<BidResponse>>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::BidsByHeightRange {
+                    round: 1,
+                    from,
+                    to,
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|bid| (bid.idx, bid.height))
+        .collect::<Vec<(u64, u64)>>()
+    };
+    let height = mock_env().block.height;
+    assert_eq!(
+        query_bids(height + 1, height + 10, None),
+        vec![(2, height + 5), (3, height + 10)]
+    );
+    assert_eq!(
+        query_bids(height, height + 10, Some(2)),
+        vec![(1, height), (2, height + 5)]
+    );
+    assert_eq!(query_bids(height + 11, height + 20, None), vec![]);
+}
+
+#[test]
+fn test_bids_by_time_range() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(7200).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    for (elapsed, amount) in [
+        (0, 100_000000u128),
+        (3600, 200_000000u128),
+        (7000, 300_000000u128),
+    ] {
+        env.block.time = mock_env().block.time.plus_seconds(elapsed);
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(amount),
+            1,
+            1,
+        )
+        .unwrap();
+    }
+
+    // bid in the last hour of the round
+    let start = mock_env().block.time.seconds();
+    let bids: Vec<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidsByTimeRange {
+                round: 1,
+                from_ts: start + 3600,
+                to_ts: start + 7200,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bids.iter()
+            .map(|bid| (bid.idx, bid.amount))
+            .collect::<Vec<(u64, Uint128)>>(),
+        vec![
+            (2, Uint128::from(200_000000u128)),
+            (3, Uint128::from(300_000000u128))
+        ]
+    );
+
+    let bids: Vec<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidsByTimeRange {
+                round: 1,
+                from_ts: start,
+                to_ts: start + 7200,
+                limit: Some(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bids.len(), 1);
+    assert_eq!(bids[0].idx, 1);
+}
+
+#[test]
+fn test_user_total_matched() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    // round 1 only matches half of slot 1, round 2 matches every bid
+    let rounds = [
+        (
+            1_010000u128,
+            vec![("addr000", 1), ("addr001", 1), ("addr000", 2)],
+        ),
+        (10_000000u128, vec![("addr000", 1)]),
+    ];
+    for (round, (total_distribution, bids)) in rounds.into_iter().enumerate() {
+        let round = round as u64 + 1;
+        let msg = ExecuteMsg::CreateNewRound {
+            start_time: env.block.time.seconds(),
+            end_time: env.block.time.plus_seconds(1000).seconds(),
+            total_distribution: Uint128::from(total_distribution),
+            min_exchange_rate: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+        for (bidder, slot) in bids {
+            do_submit_bid(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ORAIX_ADDR, &vec![]),
+                bidder.to_string(),
+                Uint128::from(100_000000u128),
+                round,
+                slot,
+            )
+            .unwrap();
+        }
+        env.block.time = env.block.time.plus_seconds(1001);
+        let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+            round,
+            exchange_rate: Decimal::from_ratio(1u128, 100u128),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+        let msg = ExecuteMsg::Distribute {
+            round,
+            start_after: None,
+            limit: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    }
+
+    let total_matched = |user: &str| -> Uint128 {
+        from_json(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::UserTotalMatched {
+                    user: user.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(total_matched("addr000"), Uint128::from(150_000000u128));
+    assert_eq!(total_matched("addr001"), Uint128::from(50_000000u128));
+    assert_eq!(total_matched("addr002"), Uint128::zero());
+}
+
+#[test]
+fn test_position() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    // half of slot 1 is matched, slot 2 is refunded
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1_010000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    for (bidder, slot) in [("addr000", 1), ("addr001", 1), ("addr000", 2)] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+
+    let position = |deps: Deps, env: Env| -> PositionResponse {
+        from_json(
+            &query(
+                deps,
+                env,
+                QueryMsg::Position {
+                    round: 1,
+                    user: "addr000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let res = position(deps.as_ref(), env.clone());
+    assert_eq!(res.round_status, RoundStatus::Open);
+    assert_eq!(
+        res.bids
+            .iter()
+            .map(|bid| (bid.idx, bid.status.clone()))
+            .collect::<Vec<(u64, BidStatus)>>(),
+        vec![(1, BidStatus::Open), (3, BidStatus::Open)]
+    );
+    assert_eq!(res.total_bid_amount, Uint128::from(200_000000u128));
+    assert_eq!(res.total_matched, Uint128::zero());
+
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // 50 matched at 0.01 * 1.01
+    let res = position(deps.as_ref(), env.clone());
+    assert_eq!(res.round_status, RoundStatus::Finalized);
+    assert_eq!(
+        res.bids,
+        vec![
+            BidPosition {
+                idx: 1,
+                premium_slot: 1,
+                amount: Uint128::from(100_000000u128),
+                status: BidStatus::PartiallyMatched,
+                matched_amount: Uint128::from(50_000000u128),
+                received_amount: Uint128::from(505000u128),
+                residue_amount: Uint128::from(50_000000u128),
+                pending_reward: Uint128::from(505000u128),
+                claimable_residue: Uint128::from(50_000000u128),
+            },
+            BidPosition {
+                idx: 3,
+                premium_slot: 2,
+                amount: Uint128::from(100_000000u128),
+                status: BidStatus::Refunded,
+                matched_amount: Uint128::zero(),
+                received_amount: Uint128::zero(),
+                residue_amount: Uint128::from(100_000000u128),
+                pending_reward: Uint128::zero(),
+                claimable_residue: Uint128::from(100_000000u128),
+            },
+        ]
+    );
+    assert_eq!(res.total_matched, Uint128::from(50_000000u128));
+    assert_eq!(res.total_received, Uint128::from(505000u128));
+    assert_eq!(res.total_residue, Uint128::from(150_000000u128));
+    assert_eq!(res.total_pending, Uint128::from(505000u128));
+
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let res = position(deps.as_ref(), env.clone());
+    assert_eq!(res.round_status, RoundStatus::FullyDistributed);
+    assert_eq!(res.bids[0].status, BidStatus::PartiallyMatched);
+    assert_eq!(res.bids[0].pending_reward, Uint128::zero());
+    assert_eq!(res.bids[1].claimable_residue, Uint128::zero());
+    assert_eq!(res.total_received, Uint128::from(505000u128));
+    assert_eq!(res.total_pending, Uint128::zero());
+}
+
+#[test]
+fn test_bid_notifier() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "vault".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    let set_notifier = ExecuteMsg::SetBidNotifier {
+        idx: 1,
+        notifier: Some("vault".to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        set_notifier.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vault", &vec![]),
+        set_notifier,
+    )
+    .unwrap();
+
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // 100 matched at 0.01 * 1.01
+    let notification = SubMsg::reply_on_error(
+        WasmMsg::Execute {
+            contract_addr: "vault".to_string(),
+            msg: to_json_binary(&NotifierExecuteMsg::BidSettled {
+                round: 1,
+                idx: 1,
+                bidder: "vault".to_string(),
+                amount_received: Uint128::from(1_010000u128),
+                residue: Uint128::zero(),
+            })
+            .unwrap(),
+            funds: vec![],
+        },
+        NOTIFY_REPLY_ID,
+    )
+    .with_gas_limit(200_000);
+    assert!(res.messages.contains(&notification));
+
+    // a failing notifier is ignored
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: NOTIFY_REPLY_ID,
+            result: SubMsgResult::Err("out of gas".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "bid_notification_failed")]
+    );
+}
+
+#[test]
+fn test_post_finalize_actions() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let actions = vec![
+        PostFinalizeAction::Transfer {
+            asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(USDC),
+            },
+            recipient: "reserve".to_string(),
+            amount: ActionAmount::Leftover {},
+        },
+        PostFinalizeAction::Execute {
+            contract: "dashboard".to_string(),
+            msg: Binary::from(br#"{"refresh":{}}"#.as_slice()),
+        },
+        // zero amounts are skipped
+        PostFinalizeAction::Transfer {
+            asset_info: AssetInfo::NativeToken {
+                denom: "orai".to_string(),
+            },
+            recipient: "reserve".to_string(),
+            amount: ActionAmount::Fixed {
+                amount: Uint128::zero(),
+            },
+        },
+    ];
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::SetPostFinalizeActions {
+            actions: actions.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetPostFinalizeActions {
+            actions: actions.clone(),
+        },
+    )
+    .unwrap();
+    let res: Vec<PostFinalizeAction> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::PostFinalizeActions {}).unwrap())
+            .unwrap();
+    assert_eq!(res, actions);
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    // 10 - 100 * 0.01 * 1.01 left over
+    let transfer = SubMsg::new(WasmMsg::Execute {
+        contract_addr: USDC.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: "reserve".to_string(),
+            amount: Uint128::from(8_990000u128),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    let notify = SubMsg::new(WasmMsg::Execute {
+        contract_addr: "dashboard".to_string(),
+        msg: Binary::from(br#"{"refresh":{}}"#.as_slice()),
+        funds: vec![],
+    });
+    assert!(res.messages.contains(&transfer));
+    assert!(res.messages.contains(&notify));
+    assert!(!res
+        .messages
+        .iter()
+        .any(|msg| matches!(msg.msg, CosmosMsg::Bank(_))));
+}
+
+#[test]
+fn test_remote_bid() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    let voucher = "ibc/ORAIX";
+    let set_voucher = ExecuteMsg::SetIbcVoucherDenom {
+        denom: Some(voucher.to_string()),
+    };
+    // the voucher must be wrapped into the cw20 underlying token
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        set_voucher.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Voucher denom ibc/ORAIX is not accepted as the underlying token"
+        ))
+    );
+    let update_config = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: Some(voucher.to_string()),
+        min_gap_between_rounds: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        update_config,
+    )
+    .unwrap();
+
+    // the ibc hooks sender is not the bidder
+    let msg = ExecuteMsg::SubmitRemoteBid {
+        round: 1,
+        premium_slot: 1,
+        bidder: "addr000".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("ibc_hooks_sender", &coins(100_000000u128, voucher)),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        set_voucher,
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("ibc_hooks_sender", &coins(100_000000u128, voucher)),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&WrapperExecuteMsg::Deposit {}).unwrap(),
+            funds: coins(100_000000u128, voucher),
+        }))]
+    );
+    assert!(res
+        .attributes
+        .contains(&attr("remote_sender", "ibc_hooks_sender")));
+
+    let bids: Vec<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidsByUser {
+                round: 1,
+                user: "addr000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bids.iter()
+            .map(|bid| (bid.premium_slot, bid.amount))
+            .collect::<Vec<(u16, Uint128)>>(),
+        vec![(1, Uint128::from(100_000000u128))]
+    );
+}
+
+#[test]
+fn test_ibc_bid_channel() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // channels can only be opened by the satellite
+    let channel = mock_ibc_channel("channel-1", IbcOrder::Unordered, IBC_VERSION);
+    let open = mock_ibc_channel_open_init("channel-1", IbcOrder::Unordered, IBC_VERSION);
+    let err = ibc_channel_open(deps.as_mut(), env.clone(), open.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(format!(
+            "Port {} on {} is not the satellite",
+            channel.counterparty_endpoint.port_id, channel.connection_id
+        )))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetIbcSatellite {
+            satellite: Some(IbcSatellite {
+                connection_id: channel.connection_id.clone(),
+                port_id: channel.counterparty_endpoint.port_id.clone(),
+            }),
+        },
+    )
+    .unwrap();
+    let err = ibc_channel_open(
+        deps.as_mut(),
+        env.clone(),
+        mock_ibc_channel_open_init("channel-1", IbcOrder::Ordered, IBC_VERSION),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Only unordered channels are supported"
+        ))
+    );
+    ibc_channel_open(deps.as_mut(), env.clone(), open).unwrap();
+    ibc_channel_connect(
+        deps.as_mut(),
+        env.clone(),
+        mock_ibc_channel_connect_ack("channel-1", IbcOrder::Unordered, IBC_VERSION),
+    )
+    .unwrap();
+    let channels: Vec<String> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::IbcChannels {}).unwrap()).unwrap();
+    assert_eq!(channels, vec!["channel-1".to_string()]);
+
+    // the packet is executed by the contract itself
+    let packet = BidPacketMsg::SubmitBid {
+        bidder: "addr000".to_string(),
+        round: 1,
+        premium_slot: 1,
+        amount: Uint128::from(100_000000u128),
+    };
+    let res = ibc_packet_receive(
+        deps.as_mut(),
+        env.clone(),
+        mock_ibc_packet_recv("channel-1", &packet).unwrap(),
+    )
+    .unwrap();
+    let process = ExecuteMsg::ProcessBidPacket {
+        channel_id: "channel-1".to_string(),
+        packet,
+    };
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_always(
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&process).unwrap(),
+                funds: vec![],
+            },
+            IBC_PACKET_REPLY_ID,
+        )]
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        process.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the bidder has not delegated to the channel, the failure is acknowledged with an error
+    let contract_info = mock_info(env.contract.address.as_str(), &vec![]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        contract_info.clone(),
+        process.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: IBC_PACKET_REPLY_ID,
+            result: SubMsgResult::Err(err.to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_json::<BidPacketAck>(&res.data.unwrap()).unwrap(),
+        BidPacketAck::Error(ContractError::Unauthorized {}.to_string())
+    );
+
+    let deposit = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr000".to_string(),
+        amount: Uint128::from(300_000000u128),
+        msg: to_json_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+    });
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        deposit,
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::SetIbcDelegate {
+            channel_id: Some("channel-2".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Channel channel-2 is not open"))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::SetIbcDelegate {
+            channel_id: Some("channel-1".to_string()),
+        },
+    )
+    .unwrap();
+
+    // the bid is funded from the deposit of the bidder
+    let res = execute(deps.as_mut(), env.clone(), contract_info.clone(), process).unwrap();
+    assert_eq!(res.data, Some(to_json_binary(&1u64).unwrap()));
+    let balance: Uint128 = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BalanceOf {
+                user: "addr000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(balance, Uint128::from(200_000000u128));
+
+    // cancelling the bid credits the deposit back
+    let cancel = ExecuteMsg::ProcessBidPacket {
+        channel_id: "channel-1".to_string(),
+        packet: BidPacketMsg::CancelBid {
+            bidder: "addr000".to_string(),
+            idx: 1,
+        },
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        contract_info.clone(),
+        cancel.clone(),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("action", "cancel_bid")));
+    let balance: Uint128 = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BalanceOf {
+                user: "addr000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(balance, Uint128::from(300_000000u128));
+    let bidding_info: BiddingInfoResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BiddingInfo { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bidding_info.bid_info.total_bid_amount, Uint128::zero());
+    let err = execute(deps.as_mut(), env.clone(), contract_info, cancel).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Bid 1 is already cancelled"))
+    );
+
+    // the channel is forgotten once closed
+    ibc_channel_close(
+        deps.as_mut(),
+        env.clone(),
+        mock_ibc_channel_close_init("channel-1", IbcOrder::Unordered, IBC_VERSION),
+    )
+    .unwrap();
+    let res = ibc_packet_receive(
+        deps.as_mut(),
+        env.clone(),
+        mock_ibc_packet_recv(
+            "channel-1",
+            &BidPacketMsg::Claim {
+                bidder: "addr000".to_string(),
+                idx: 1,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        from_json::<BidPacketAck>(&res.acknowledgement).unwrap(),
+        BidPacketAck::Error("Channel channel-1 is not open".to_string())
+    );
+}
+
+#[test]
+fn test_ica_round() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let env = mock_env();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetIcaTreasury {
+            address: Some("ica_treasury".to_string()),
+        },
+    )
+    .unwrap();
+
+    // the ICS20 funding arrives before the request of the interchain account
+    let fund = |amount: u128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "cw20_ics20".to_string(),
+            amount: Uint128::from(amount),
+            msg: to_json_binary(&Cw20HookMsg::FundIcaRound {}).unwrap(),
+        })
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        fund(600_000000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &vec![]),
+        fund(600_000000),
+    )
+    .unwrap();
+
+    let request = ExecuteMsg::RequestIcaRound {
+        total_distribution: Uint128::from(1000_000000u128),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        request.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("ica_treasury", &vec![]),
+        request,
+    )
+    .unwrap();
+    assert!(!res.attributes.contains(&attr("created_by", "treasury")));
+    let ica_round: IcaRoundResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::IcaRound {}).unwrap()).unwrap();
+    assert_eq!(
+        ica_round,
+        IcaRoundResponse {
+            ica_treasury: Some(Addr::unchecked("ica_treasury")),
+            requested_distribution: Some(Uint128::from(1000_000000u128)),
+            funded: Uint128::from(600_000000u128),
+        }
+    );
+
+    // the interchain account can't be changed while the round is pending
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetIcaTreasury { address: None },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "An interchain account round is still pending"
+        ))
+    );
+
+    // the round opens once fully funded, the extra funding is kept for the next request
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &vec![]),
+        fund(500_000000),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("created_by", "treasury")));
+    let bidding_info: BiddingInfoResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BiddingInfo { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bidding_info.distribution_info.total_distribution,
+        Uint128::from(1000_000000u128)
+    );
+    let ica_round: IcaRoundResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::IcaRound {}).unwrap()).unwrap();
+    assert_eq!(ica_round.requested_distribution, None);
+    assert_eq!(ica_round.funded, Uint128::from(100_000000u128));
+
+    // cancelling refunds what is left to the interchain account
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("ica_treasury", &vec![]),
+        ExecuteMsg::CancelIcaRound {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "ica_treasury".to_string(),
+                amount: Uint128::from(100_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetIcaTreasury { address: None },
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_routed_bids() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    let routed_bids = |amount: u128, bids: Vec<(&str, u16, u128)>| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "router".to_string(),
+            amount: Uint128::from(amount),
+            msg: to_json_binary(&Cw20HookMsg::SubmitRoutedBids {
+                bids: bids
+                    .into_iter()
+                    .map(|(bidder, premium_slot, amount)| RoutedBid {
+                        bidder: bidder.to_string(),
+                        round: 1,
+                        premium_slot,
+                        amount: Uint128::from(amount),
+                    })
+                    .collect(),
+            })
+            .unwrap(),
+        })
+    };
+    let msg = routed_bids(
+        300_000000,
+        vec![("addr000", 1, 100_000000), ("addr001", 2, 200_000000)],
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::AddTrustedRouter {
+            router: "router".to_string(),
+        },
+    )
+    .unwrap();
+    let routers: Vec<Addr> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::TrustedRouters {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(routers, vec![Addr::unchecked("router")]);
+
+    // the funds must cover the bids exactly
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        routed_bids(
+            250_000000,
+            vec![("addr000", 1, 100_000000), ("addr001", 2, 200_000000)],
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Routed bids total 300000000, got 250000000"
+        ))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.events.len(), 2);
+    assert!(res.attributes.contains(&attr("router", "router")));
+    let idxs: Vec<u64> = from_json(&res.data.unwrap()).unwrap();
+    assert_eq!(idxs, vec![1, 2]);
+
+    // the bids belong to the bidders, not to the router
+    for (bidder, premium_slot, amount) in [
+        ("addr000", 1u16, 100_000000u128),
+        ("addr001", 2u16, 200_000000u128),
+    ] {
+        let bids: Vec<BidResponse> = from_json(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::BidsByUser {
+                    round: 1,
+                    user: bidder.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(bids.len(), 1);
+        assert_eq!(bids[0].premium_slot, premium_slot);
+        assert_eq!(bids[0].amount, Uint128::from(amount));
+    }
+    // the router has no bid in the round
+    assert!(query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BidsByUser {
+            round: 1,
+            user: "router".to_string(),
+        },
+    )
+    .is_err());
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::RemoveTrustedRouter {
+            router: "router".to_string(),
+        },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn test_recompute_round_totals() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    for (bidder, amount, slot) in [
+        ("addr000", 100_000000u128, 1u16),
+        ("addr001", 200_000000u128, 1u16),
+        ("addr000", 300_000000u128, 2u16),
+    ] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(amount),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+
+    // leave the aggregates inconsistent with the bids
+    let mut bidding_info = BIDDING_INFO.load(deps.as_ref().storage, 1).unwrap();
+    bidding_info.total_bid_amount = Uint128::one();
+    BIDDING_INFO
+        .save(deps.as_mut().storage, 1, &bidding_info)
+        .unwrap();
+    let mut bid_pool = BID_POOL.load(deps.as_ref().storage, (1, 1)).unwrap();
+    bid_pool.total_bid_amount = Uint128::from(5u128);
+    BID_POOL
+        .save(deps.as_mut().storage, (1, 1), &bid_pool)
+        .unwrap();
+    BID_POOL.remove(deps.as_mut().storage, (1, 2));
+    NUM_BIDS_BY_ROUND
+        .save(deps.as_mut().storage, 1, &10)
+        .unwrap();
+    NUM_BIDDERS_BY_ROUND
+        .save(deps.as_mut().storage, 1, &0)
+        .unwrap();
+
+    let msg = ExecuteMsg::RecomputeRoundTotals { round: 1 };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("previous_total_bid_amount", "1")));
+
+    let bidding_info: BiddingInfoResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BiddingInfo { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bidding_info.bid_info.total_bid_amount,
+        Uint128::from(600_000000u128)
+    );
+    for (slot, total) in [(1u16, 300_000000u128), (2u16, 300_000000u128)] {
+        let bid_pool: BidPoolResponse = from_json(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::BidPool { round: 1, slot },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(bid_pool.total_bid_amount, Uint128::from(total));
+    }
+    let num_bids: u64 = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::NumbersBidInRound { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(num_bids, 3);
+    assert_eq!(
+        NUM_BIDDERS_BY_ROUND.load(deps.as_ref().storage, 1).unwrap(),
+        2
+    );
+
+    // finalized rounds keep their totals
+    env.block.time = env.block.time.plus_seconds(1000);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::FinalizeBiddingRoundResult {
+            round: 1,
+            exchange_rate: Decimal::from_str("1").unwrap(),
+        },
+    )
+    .unwrap();
+    let err = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Round 1 is finalized, its totals can not be recomputed"
+        ))
+    );
+}
+
+#[test]
+fn test_check_invariants() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    for (bidder, amount, slot) in [
+        ("addr000", 100_000000u128, 1u16),
+        ("addr001", 200_000000u128, 2u16),
+    ] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(amount),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+    let check_invariants = |deps: Deps| -> InvariantsResponse {
+        from_json(&query(deps, mock_env(), QueryMsg::CheckInvariants { round: 1 }).unwrap())
+            .unwrap()
+    };
+    assert_eq!(
+        check_invariants(deps.as_ref()),
+        InvariantsResponse {
+            round: 1,
+            is_consistent: true,
+            mismatches: vec![],
+        }
+    );
+
+    // the payouts of a distributed round match their totals
+    env.block.time = env.block.time.plus_seconds(1000);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::FinalizeBiddingRoundResult {
+            round: 1,
+            exchange_rate: Decimal::from_ratio(1u128, 100u128),
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert!(check_invariants(deps.as_ref()).is_consistent);
+
+    let mut bidding_info = BIDDING_INFO.load(deps.as_ref().storage, 1).unwrap();
+    bidding_info.total_bid_amount += Uint128::one();
+    BIDDING_INFO
+        .save(deps.as_mut().storage, 1, &bidding_info)
+        .unwrap();
+    BID_POOL.remove(deps.as_mut().storage, (1, 2));
+    assert_eq!(
+        check_invariants(deps.as_ref()),
+        InvariantsResponse {
+            round: 1,
+            is_consistent: false,
+            mismatches: vec![
+                InvariantMismatch {
+                    invariant: "total_bid_amount".to_string(),
+                    slot: None,
+                    stored: Uint128::from(300_000001u128),
+                    computed: Uint128::from(300_000000u128),
+                },
+                InvariantMismatch {
+                    invariant: "pool_total_bid_amount".to_string(),
+                    slot: Some(2),
+                    stored: Uint128::zero(),
+                    computed: Uint128::from(200_000000u128),
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_shadow_balances() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    for (amount, slot) in [(100_000000u128, 1u16), (200_000000u128, 2u16)] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            "addr000".to_string(),
+            Uint128::from(amount),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+    let deposit = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr001".to_string(),
+        amount: Uint128::from(50_000000u128),
+        msg: to_json_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+    });
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        deposit,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr001", &vec![]),
+        ExecuteMsg::Withdraw {
+            amount: Uint128::from(20_000000u128),
+        },
+    )
+    .unwrap();
+
+    // 7 ORAIX were sent to the contract directly, the distribution was never accounted
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } => {
+            let balance = if contract_addr == ORAIX_ADDR {
+                337_000000u128
+            } else {
+                1000_000000u128
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&BalanceResponse {
+                    balance: Uint128::from(balance),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+    let shadow_balances = |deps: Deps| -> Vec<ShadowBalanceResponse> {
+        from_json(&query(deps, mock_env(), QueryMsg::ShadowBalances {}).unwrap()).unwrap()
+    };
+    assert_eq!(
+        shadow_balances(deps.as_ref()),
+        vec![
+            ShadowBalanceResponse {
+                asset_info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked(ORAIX_ADDR),
+                },
+                expected: Uint128::from(330_000000u128),
+                actual: Uint128::from(337_000000u128),
+                escrowed: Uint128::zero(),
+            },
+            ShadowBalanceResponse {
+                asset_info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked(USDC),
+                },
+                expected: Uint128::zero(),
+                actual: Uint128::from(1000_000000u128),
+                escrowed: Uint128::zero(),
+            },
+        ]
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::ResetShadowBalances {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::ResetShadowBalances {},
+    )
+    .unwrap();
+    assert!(shadow_balances(deps.as_ref())
+        .iter()
+        .all(|balance| balance.expected == balance.actual));
+}
+
+#[test]
+fn test_submit_bid_from_taxed_token() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetTransferTax { enabled: true },
+    )
+    .unwrap();
+    let err = do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(200_000000u128),
+        1,
+        1,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The underlying token takes a transfer tax, bid with SubmitBidFrom"
+        ))
+    );
+    // a signed bid would be recorded for the amount before the tax
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &vec![]),
+        ExecuteMsg::SubmitSignedBid {
+            bid: SignedBid {
+                bidder: "addr000".to_string(),
+                round: 1,
+                premium_slot: 1,
+                amount: Uint128::from(200_000000u128),
+                nonce: 0,
+                deadline: env.block.time.plus_seconds(100).seconds(),
+            },
+            signature: Binary::default(),
+            pubkey: Binary::default(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The underlying token takes a transfer tax, bid with SubmitBidFrom"
+        ))
+    );
+
+    let mock_balance = |balance: u128| {
+        move |query: &WasmQuery| match query {
+            WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&BalanceResponse {
+                    balance: Uint128::from(balance),
+                })
+                .unwrap(),
+            )),
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            }),
+        }
+    };
+    deps.querier.update_wasm(mock_balance(500_000000));
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::SubmitBidFrom {
+            round: 1,
+            premium_slot: 1,
+            amount: Uint128::from(200_000000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr000".to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: Uint128::from(200_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            },
+            TRANSFER_REPLY_ID,
+        )]
+    );
+
+    // the token kept 2% of the transfer, only the received amount is bid
+    deps.querier.update_wasm(mock_balance(696_000000));
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: TRANSFER_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("transfer_tax", "4000000")));
+    let bids: Vec<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidsByUser {
+                round: 1,
+                user: "addr000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bids.len(), 1);
+    assert_eq!(bids[0].amount, Uint128::from(196_000000u128));
+}
+
+#[test]
+fn test_unaccounted_balances() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(300_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    // 5 ORAIX and 12 ORAI were sent without a hook, the bid is owed
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } => {
+            let balance = if contract_addr == ORAIX_ADDR {
+                305_000000u128
+            } else {
+                0u128
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&BalanceResponse {
+                    balance: Uint128::from(balance),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+    deps.querier
+        .update_balance(env.contract.address.clone(), coins(12_000000, "orai"));
+    // 4 ORAI are the other leg of withdrawn LP bids
+    LP_WITHDRAW_RATES
+        .save(
+            deps.as_mut().storage,
+            1,
+            &LpWithdrawRate {
+                underlying_per_share: Decimal::one(),
+                other_asset: AssetInfo::NativeToken {
+                    denom: "orai".to_string(),
+                },
+                other_per_share: Decimal::percent(4),
+            },
+        )
+        .unwrap();
+    LP_UNSETTLED_SHARES
+        .save(deps.as_mut().storage, 1, &Uint128::from(100_000000u128))
+        .unwrap();
+
+    let balances: Vec<UnaccountedBalanceResponse> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::UnaccountedBalances {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        balances,
+        vec![
+            UnaccountedBalanceResponse {
+                asset_info: AssetInfo::NativeToken {
+                    denom: "orai".to_string(),
+                },
+                balance: Uint128::from(12_000000u128),
+                obligations: Uint128::from(4_000000u128),
+                unaccounted: Uint128::from(8_000000u128),
+            },
+            UnaccountedBalanceResponse {
+                asset_info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked(ORAIX_ADDR),
+                },
+                balance: Uint128::from(305_000000u128),
+                obligations: Uint128::from(300_000000u128),
+                unaccounted: Uint128::from(5_000000u128),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_simulate_matching() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    // snapshots of the inputs are ignored
+    let pools = vec![
+        BidPool {
+            slot: 1,
+            total_bid_amount: Uint128::from(1000_000000u128),
+            premium_rate: Decimal::percent(1),
+            index_snapshot: Decimal::one(),
+            received_per_token: Decimal::one(),
+            matched_amount: Uint128::from(1000_000000u128),
+            payout_amount: Uint128::from(1000_000000u128),
+        },
+        BidPool {
+            slot: 2,
+            total_bid_amount: Uint128::from(1000_000000u128),
+            premium_rate: Decimal::percent(2),
+            index_snapshot: Decimal::zero(),
+            received_per_token: Decimal::zero(),
+            matched_amount: Uint128::zero(),
+            payout_amount: Uint128::zero(),
+        },
+    ];
+    let simulate = |distribution: u128| -> SimulateMatchingResponse {
+        from_json(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SimulateMatching {
+                    pools: pools.clone(),
+                    distribution: Uint128::from(distribution),
+                    exchange_rate: Decimal::permille(100),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    // slot 1 needs 101 and is filled, slot 2 needs 102 and gets the remaining 51
+    let res = simulate(152_000000);
+    assert_eq!(res.total_matched, Uint128::from(1500_000000u128));
+    assert_eq!(res.remaining, Uint128::zero());
+    assert_eq!(res.pools[0].index_snapshot, Decimal::one());
+    assert_eq!(res.pools[0].received_per_token, Decimal::permille(101));
+    assert_eq!(res.pools[1].index_snapshot, Decimal::percent(50));
+    assert_eq!(res.pools[1].received_per_token, Decimal::permille(51));
+    assert_eq!(res.pools[0].matched_amount, Uint128::from(1000_000000u128));
+    assert_eq!(res.pools[0].payout_amount, Uint128::from(101_000000u128));
+    assert_eq!(res.pools[1].matched_amount, Uint128::from(500_000000u128));
+    assert_eq!(res.pools[1].payout_amount, Uint128::from(51_000000u128));
+
+    // a larger budget fills both slots and leaves the rest unmatched
+    let res = simulate(300_000000);
+    assert_eq!(res.total_matched, Uint128::from(2000_000000u128));
+    assert_eq!(res.remaining, Uint128::from(97_000000u128));
+    assert_eq!(res.pools[1].index_snapshot, Decimal::one());
+    assert_eq!(res.pools[1].payout_amount, Uint128::from(102_000000u128));
+}
+
+#[test]
+fn test_round_state() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+    let round_state = |deps: Deps| ROUND_STATES.load(deps.storage, 1).unwrap();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    // open and ended follow the bidding time, only created is stored
+    assert_eq!(round_state(deps.as_ref()), RoundState::Created);
+    for bidder in ["addr000", "addr001"] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            1,
+        )
+        .unwrap();
+    }
+
+    // a round that never started can not be distributed
+    let distribute = |limit: Option<u64>| ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        distribute(None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BidNotEnded {});
+
+    env.block.time = env.block.time.plus_seconds(1001);
+    let finalize = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::percent(1),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        finalize.clone(),
+    )
+    .unwrap();
+    assert_eq!(round_state(deps.as_ref()), RoundState::Finalized);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        finalize,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidRoundState {
+            round: 1,
+            state: RoundState::Finalized
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        distribute(Some(1)),
+    )
+    .unwrap();
+    assert_eq!(round_state(deps.as_ref()), RoundState::Distributing);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        distribute(None),
+    )
+    .unwrap();
+    assert_eq!(round_state(deps.as_ref()), RoundState::Completed);
+
+    // a cancelled round id is reused by the next round
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.plus_seconds(100).seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::zero(),
+        min_exchange_rate: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        msg.clone(),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::CancelRound { round: 2 },
+    )
+    .unwrap();
+    let status: RoundStatus = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundStatus { round: 2 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(status, RoundStatus::Cancelled);
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        ROUND_STATES.load(deps.as_ref().storage, 2).unwrap(),
+        RoundState::Created
+    );
+}
+
+#[test]
+fn test_update_config_changes() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    // max_slot is set to its current value and is left out
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: Some(25),
+        premium_rate_per_slot: Some(Decimal::percent(2)),
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: Some("oracle_pair".to_string()),
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_config"),
+            attr("premium_rate_per_slot_old", "0.01"),
+            attr("premium_rate_per_slot_new", "0.02"),
+            attr("oracle_pair_old", "none"),
+            attr("oracle_pair_new", "oracle_pair"),
+        ]
+    );
+
+    let env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.plus_seconds(100).seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            min_participants: Some(3),
+            premium_curve: PremiumCurve::Exponential {
+                growth: Decimal::percent(10),
+            },
+            performance_fee: Some(Decimal::percent(5)),
+            ..RoundConfig::default()
+        },
+    };
+    let res = execute(deps.as_mut(), env, mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_round_config"),
+            attr("round", "1"),
+            attr("min_participants_old", "none"),
+            attr("min_participants_new", "3"),
+            attr("premium_curve_old", r#"{"linear":{}}"#),
+            attr("premium_curve_new", r#"{"exponential":{"growth":"0.1"}}"#),
+            attr("performance_fee_old", "none"),
+            attr("performance_fee_new", "0.05"),
+        ]
+    );
+}
+
+#[test]
+fn test_counters() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+    let counters = |deps: Deps| -> CountersResponse {
+        from_json(&query(deps, mock_env(), QueryMsg::Counters {}).unwrap()).unwrap()
+    };
+    assert_eq!(
+        counters(deps.as_ref()),
+        CountersResponse {
+            last_round_id: 0,
+            next_bid_idx: 1,
+        }
+    );
+
+    // contracts instantiated before the bid idx was stored get it at migration
+    BID_IDX.remove(deps.as_mut().storage);
+    migrate(
+        deps.as_mut(),
+        env.clone(),
+        MigrateMsg::State(StateMigrateMsg::Backfill {}),
+    )
+    .unwrap();
+    assert_eq!(counters(deps.as_ref()).next_bid_idx, 1);
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(10_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(100_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+    let bid: BidResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Bid { idx: 1 }).unwrap()).unwrap();
+    assert_eq!(bid.round, 1);
+    assert_eq!(
+        counters(deps.as_ref()),
+        CountersResponse {
+            last_round_id: 1,
+            next_bid_idx: 2,
+        }
+    );
+}
+
+#[test]
+fn test_leftover_airdrop() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    do_submit_bid(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        "addr000".to_string(),
+        Uint128::from(4000_000000u128),
+        1,
+        1,
+    )
+    .unwrap();
+
+    let set_airdrop = ExecuteMsg::SetLeftoverAirdrop {
+        address: Some("airdrop".to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        set_airdrop.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        set_airdrop,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_leftover_airdrop"),
+            attr("leftover_airdrop", "airdrop"),
+        ]
+    );
+    let airdrop: Option<Addr> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::LeftoverAirdrop {}).unwrap())
+            .unwrap();
+    assert_eq!(airdrop, Some(Addr::unchecked("airdrop")));
+
+    // the leftover funds the airdrop of the round instead of going back to the owner
+    env.block.time = env.block.time.plus_seconds(1001);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let actual_distributed: u128 = res.attributes[4].value.parse().unwrap();
+    assert_eq!(
+        res.messages[1],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: "airdrop".to_string(),
+                amount: Uint128::from(1000_000000u128 - actual_distributed),
+                msg: to_json_binary(&AirdropExecuteMsg::Fund { round: 1 }).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetLeftoverAirdrop { address: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "remove_leftover_airdrop")]
+    );
+}
+
+#[test]
+fn test_execute_batch() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let env = mock_env();
+
+    let update_config = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: Some(30),
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: None,
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    let create_round = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    let batch = ExecuteMsg::ExecuteBatch {
+        msgs: vec![update_config, create_round],
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        batch.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &coins(100, "orai")),
+        batch.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Payment(PaymentError::NonPayable {}));
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::ExecuteBatch {
+            msgs: vec![batch.clone()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "A batch can't hold Receive or ExecuteBatch messages"
+        ))
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), batch).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "execute_batch"), attr("num_msgs", "2")]
+    );
+    let batch_events: Vec<&Event> = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "batch_msg")
+        .collect();
+    assert_eq!(batch_events.len(), 2);
+    assert!(batch_events[0]
+        .attributes
+        .contains(&attr("action", "update_config")));
+    assert!(batch_events[1]
+        .attributes
+        .contains(&attr("action", "create_new_bidding_round")));
+
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.max_slot, 30);
+    let counters: CountersResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Counters {}).unwrap()).unwrap();
+    assert_eq!(counters.last_round_id, 1);
+    assert_eq!(res.data, None);
+
+    // the bid pulled with TransferFrom is only submitted in the reply
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Ok(
+            to_json_binary(&BalanceResponse {
+                balance: Uint128::zero(),
+            })
+            .unwrap(),
+        )),
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::ExecuteBatch {
+            msgs: vec![ExecuteMsg::SubmitBidFrom {
+                round: 1,
+                premium_slot: 1,
+                amount: Uint128::from(100_000000u128),
+            }],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "A batch can't hold messages completed in a reply"
+        ))
+    );
+
+    // every distribute call returns its data
+    let mut env = env;
+    env.block.time = env.block.time.plus_seconds(10);
+    for bidder in ["addr000", "addr001"] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(100_000000u128),
+            1,
+            1,
+        )
+        .unwrap();
+    }
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let distribute = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: Some(1),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::ExecuteBatch {
+            msgs: vec![distribute.clone(), distribute],
+        },
+    )
+    .unwrap();
+    let data: Vec<Option<Binary>> = from_json(res.data.unwrap()).unwrap();
+    let data = data
+        .into_iter()
+        .map(|data| from_json(data.unwrap()).unwrap())
+        .collect::<Vec<DistributeResponse>>();
+    assert_eq!(
+        data.iter()
+            .map(|data| (data.first_idx, data.num_rewarded))
+            .collect::<Vec<_>>(),
+        vec![(Some(1), 1), (Some(2), 1)]
+    );
+}
+
+#[test]
+fn test_finalizer_group() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == "finalizers" => {
+            let weight = |addr: &str| match addr {
+                "alice" => Some(3u64),
+                "bob" => Some(2u64),
+                "carol" => Some(1u64),
+                _ => None,
+            };
+            let res = match from_json(msg).unwrap() {
+                Cw4QueryMsg::Member { addr, .. } => to_json_binary(&MemberResponse {
+                    weight: weight(&addr),
+                }),
+                Cw4QueryMsg::TotalWeight { .. } => {
+                    to_json_binary(&TotalWeightResponse { weight: 6 })
+                }
+                _ => panic!("unexpected cw4 query"),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(1001);
+    let finalize = |rate: u64| ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::permille(rate),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &vec![]),
+        finalize(10),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // half of the weight of the group must approve the same exchange rate
+    let set_group = ExecuteMsg::SetFinalizerGroup {
+        group: Some("finalizers".to_string()),
+        quorum: Some(Decimal::percent(50)),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &vec![]),
+        set_group.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        set_group,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_finalizer_group"),
+            attr("group", "finalizers"),
+            attr("quorum", "0.5"),
+        ]
+    );
+    let finalizer_group: Option<FinalizerGroup> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::FinalizerGroup {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        finalizer_group,
+        Some(FinalizerGroup {
+            group: Addr::unchecked("finalizers"),
+            quorum: Some(Decimal::percent(50)),
+        })
+    );
+
+    // dave is not a member
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("dave", &vec![]),
+        finalize(10),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    for (member, rate) in [("bob", 10), ("carol", 12)] {
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(member, &vec![]),
+            finalize(rate),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "approve_finalize"),
+                attr("round", "1"),
+                attr("finalizer", member),
+                attr("exchange_rate", Decimal::permille(rate).to_string()),
+            ]
+        );
+    }
+    // carol moves to the rate of bob, together they hold 3 of the 6 weight
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &vec![]),
+        finalize(10),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[0],
+        attr("action", "finalize_bidding_round_result")
+    );
+    assert_eq!(res.attributes[2], attr("exchange_rate", "0.01"));
+
+    // without quorum any member finalizes alone
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetFinalizerGroup {
+            group: Some("finalizers".to_string()),
+            quorum: None,
+        },
+    )
+    .unwrap();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(1001);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &vec![]),
+        ExecuteMsg::FinalizeBiddingRoundResult {
+            round: 2,
+            exchange_rate: Decimal::permille(10),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[0],
+        attr("action", "finalize_bidding_round_result")
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetFinalizerGroup {
+            group: None,
+            quorum: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "remove_finalizer_group")]
+    );
+}
+
+#[test]
+fn test_finalize_bounty() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    // pair reserves give 1 ORAIX = 0.01 USDC
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == "oracle_pair" => {
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&PoolResponse {
+                    assets: [
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(USDC),
+                            },
+                            amount: Uint128::from(10_000_000000u128),
+                        },
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(ORAIX_ADDR),
+                            },
+                            amount: Uint128::from(1_000_000_000000u128),
+                        },
+                    ],
+                    total_share: Uint128::zero(),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(1001);
+
+    let finalize = ExecuteMsg::FinalizeAtOracleRate { round: 1 };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &vec![]),
+        finalize.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Permissionless finalize is not enabled"
+        ))
+    );
+
+    // the rate of the oracle pair is used, it must be configured first
+    let enable = ExecuteMsg::SetPermissionlessFinalize {
+        bounty: Some(Uint128::from(10_000000u128)),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        enable.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Oracle pair is not configured"))
+    );
+    let update_config = ExecuteMsg::UpdateConfig {
+        owner: None,
+        underlying_token: None,
+        distribution_token: None,
+        max_slot: None,
+        premium_rate_per_slot: None,
+        min_deposit_amount: None,
+        treasury: None,
+        bidding_duration: None,
+        oracle_pair: Some("oracle_pair".to_string()),
+        vesting_contract: None,
+        keeper_registry: None,
+        staking_contract: None,
+        residue_staking: None,
+        wrapped_denom: None,
+        min_gap_between_rounds: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        update_config,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        enable,
+    )
+    .unwrap();
+    let bounty: Option<Uint128> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::FinalizeBounty {}).unwrap())
+            .unwrap();
+    assert_eq!(bounty, Some(Uint128::from(10_000000u128)));
+
+    // the spot rate of the pair can be moved in the same transaction, it must be in the band of
+    // the round
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &vec![]),
+        finalize.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Round 1 has no oracle rate band"))
+    );
+    let set_band = |min_rate: u64, max_rate: u64| ExecuteMsg::SetOracleRateBand {
+        round: 1,
+        band: Some(RateBand {
+            min_rate: Decimal::permille(min_rate),
+            max_rate: Decimal::permille(max_rate),
+        }),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        set_band(20, 5),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Oracle rate band must be non-empty and above 0"
+        ))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        set_band(5, 8),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &vec![]),
+        finalize.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Oracle rate 0.01 is outside the band of round 1"
+        ))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        set_band(5, 20),
+    )
+    .unwrap();
+
+    // the bounty is taken out of the distribution, the rest goes back as the round is empty
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &vec![]),
+        finalize,
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("exchange_rate", "0.01")));
+    assert!(res.attributes.contains(&attr("finalizer", "bot")));
+    assert!(res.attributes.contains(&attr("bounty", "10000000")));
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: OWNER.to_string(),
+                    amount: Uint128::from(990_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USDC.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bot".to_string(),
+                    amount: Uint128::from(10_000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetPermissionlessFinalize { bounty: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "disable_permissionless_finalize")]
+    );
+}
+
+#[test]
+fn test_trigger_refunds() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(1000_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    for (bidder, amount) in [("addr000", 100_000000u128), ("addr001", 200_000000u128)] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(amount),
+            1,
+            1,
+        )
+        .unwrap();
+    }
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetRefundDeadline {
+            refund_deadline: 3600,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Refund deadline must be at least 86400 seconds"
+        ))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::SetRefundDeadline {
+            refund_deadline: 86400,
+        },
+    )
+    .unwrap();
+
+    // the round is still waiting for its finalize
+    let refund_after = env.block.time.plus_seconds(1000 + 86400).seconds();
+    env.block.time = env.block.time.plus_seconds(1001);
+    let trigger_refunds = ExecuteMsg::TriggerRefunds {
+        round: 1,
+        limit: Some(1),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &vec![]),
+        trigger_refunds.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(format!(
+            "Round 1 can be refunded from {}",
+            refund_after
+        )))
+    );
+
+    // bids deposited in the yield venue are withdrawn first
+    env.block.time = env.block.time.plus_seconds(86400);
+    YIELD_POSITION
+        .save(
+            deps.as_mut().storage,
+            &YieldPosition {
+                round: 1,
+                principal: Uint128::from(300_000000u128),
+            },
+        )
+        .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &vec![]),
+        trigger_refunds.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Idle funds of round 1 must be withdrawn from the yield venue before refunds"
+        ))
+    );
+    YIELD_POSITION.remove(deps.as_mut().storage);
+
+    // a chunked finalize already withdrew the liquidity of the LP bid of addr001
+    let mut bid = BID.load(&deps.storage, 2).unwrap();
+    bid.lp_amount = Uint128::from(100_000000u128);
+    bid.amount = Uint128::zero();
+    BID.save(deps.as_mut().storage, 2, &bid).unwrap();
+    LP_WITHDRAW_RATES
+        .save(
+            deps.as_mut().storage,
+            1,
+            &LpWithdrawRate {
+                underlying_per_share: Decimal::from_ratio(2u128, 1u128),
+                other_asset: AssetInfo::NativeToken {
+                    denom: "orai".to_string(),
+                },
+                other_per_share: Decimal::one(),
+            },
+        )
+        .unwrap();
+    FINALIZE_PROGRESS
+        .save(
+            deps.as_mut().storage,
+            1,
+            &FinalizeProgress {
+                exchange_rate: Decimal::from_ratio(1u128, 100u128),
+                last_slot: 0,
+                distribution_left: Uint128::from(1000_000000u128),
+                total_matched: Uint128::zero(),
+            },
+        )
+        .unwrap();
+
+    // the first call sends the distribution back with the first bid
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &vec![]),
+        trigger_refunds.clone(),
+    )
+    .unwrap();
+    let transfer = |token: &str, recipient: &str, amount: u128| {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::from(amount),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    };
+    assert_eq!(
+        res.messages,
+        vec![
+            transfer(USDC, OWNER, 1000_000000),
+            transfer(ORAIX_ADDR, "addr000", 100_000000),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "trigger_refunds"),
+            attr("round", "1"),
+            attr("num_refunded", "1"),
+            attr("finished", "false"),
+        ]
+    );
+    assert!(!FINALIZE_PROGRESS.has(&deps.storage, 1));
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &vec![]),
+        ExecuteMsg::TriggerRefunds {
+            round: 1,
+            limit: None,
+        },
+    )
+    .unwrap();
+    // the withdrawn LP bid gets both legs of its liquidity
+    assert_eq!(
+        res.messages,
+        vec![
+            transfer(ORAIX_ADDR, "addr001", 200_000000),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr001".to_string(),
+                amount: coins(100_000000u128, "orai"),
+            })),
+        ]
+    );
+    assert_eq!(res.attributes[3], attr("finished", "true"));
+
+    // a failed round can't be finalized anymore
+    let status: RoundStatus = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoundStatus { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(status, RoundStatus::Failed);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::FinalizeBiddingRoundResult {
+            round: 1,
+            exchange_rate: Decimal::from_ratio(1u128, 100u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidRoundState {
+            round: 1,
+            state: RoundState::Failed,
+        }
+    );
+}
+
+#[test]
+fn test_locked_bids() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::SetLockBoosts {
+        boosts: vec![LockBoost {
+            min_rounds: 2,
+            multiplier: Decimal::percent(150),
+        }],
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "partner".to_string(),
+        amount: Uint128::from(1_000000u128),
+        msg: to_json_binary(&Cw20HookMsg::FundLockBoosts {}).unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(USDC, &vec![]), msg).unwrap();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    let locked_bid = |lock_rounds: u64| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr000".to_string(),
+            amount: Uint128::from(1000_000000u128),
+            msg: to_json_binary(&Cw20HookMsg::SubmitLockedBid {
+                round: 1,
+                premium_slot: 1,
+                lock_rounds,
+            })
+            .unwrap(),
+        })
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        locked_bid(1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "No lock boost for a lock of 1 rounds"
+        ))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &vec![]),
+        locked_bid(3),
+    )
+    .unwrap();
+
+    // the deposit can't leave the lock before its last round
+    IBC_DELEGATIONS
+        .save(
+            deps.as_mut().storage,
+            &Addr::unchecked("addr000"),
+            &"channel-1".to_string(),
+        )
+        .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(env.contract.address.as_str(), &vec![]),
+        ExecuteMsg::ProcessBidPacket {
+            channel_id: "channel-1".to_string(),
+            packet: BidPacketMsg::CancelBid {
+                bidder: "addr000".to_string(),
+                idx: 1,
+            },
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Locked bids cannot be cancelled"))
+    );
+
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        ExecuteMsg::ClaimResidue { idx: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Bid 1 is locked until the last round of lock 1"
+        ))
+    );
+
+    // 495.049505 ORAIX matched for 5 USDC, the 0.049505 USDC premium is boosted by half and the
+    // residue stays in the lock
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert!(res
+        .messages
+        .contains(&SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr000".to_string(),
+                amount: Uint128::from(24752u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))));
+    assert!(!res
+        .messages
+        .contains(&SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr000".to_string(),
+                amount: Uint128::from(504950495u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))));
+    let lock_boosts: LockBoostsResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::LockBoosts {}).unwrap()).unwrap();
+    assert_eq!(lock_boosts.reserve, Uint128::from(975248u128));
+    let lock: BidLock =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Lock { lock_id: 1 }).unwrap())
+            .unwrap();
+    assert_eq!(
+        lock,
+        BidLock {
+            bidder: Addr::unchecked("addr000"),
+            premium_slot: 1,
+            multiplier: Decimal::percent(150),
+            remaining_rounds: 2,
+            last_round: 1,
+            pending_amount: Uint128::from(504950495u128),
+        }
+    );
+
+    // the residue enters the next round once it opens
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::EnterLockedBids {
+        round: 2,
+        lock_ids: vec![1],
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        msg.clone(),
+    )
+    .unwrap();
+    let bid = BID.load(&deps.storage, 2).unwrap();
+    assert_eq!(bid.round, 2);
+    assert_eq!(bid.bidder, Addr::unchecked("addr000"));
+    assert_eq!(bid.amount, Uint128::from(504950495u128));
+    let lock_of_bid: Option<u64> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::LockOfBid { idx: 2 }).unwrap())
+            .unwrap();
+    assert_eq!(lock_of_bid, Some(1));
+    let lock = LOCKS.load(&deps.storage, 1).unwrap();
+    assert_eq!(lock.remaining_rounds, 1);
+    assert_eq!(lock.pending_amount, Uint128::zero());
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Lock 1 has no residue to enter in round 2"
+        ))
+    );
+}
+
+#[test]
+fn test_tranches() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            tranched: true,
+            ..RoundConfig::default()
+        },
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    for (bidder, slot) in [("addr000", 1), ("addr001", 2)] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(1000_000000u128),
+            1,
+            slot,
+        )
+        .unwrap();
+    }
+
+    // the first 5 USDC match half of the first slot, nothing is burned yet
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert!(res.messages.is_empty());
+    let open_tranches: Option<u32> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::OpenTranches { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(open_tranches, Some(1));
+    let distribute = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        distribute.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Round 1 still takes tranches"))
+    );
+
+    let tranche = |sender: &str| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::from(10_000000u128),
+            msg: to_json_binary(&Cw20HookMsg::AddTranche { round: 1 }).unwrap(),
+        })
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &vec![]),
+        tranche("addr000"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // 5.1 USDC fill the rest of the first slot, the payout rounds down and the other 4.9 USDC
+    // go to the second one
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &vec![]),
+        tranche("treasury"),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "add_tranche"),
+            attr("round", "1"),
+            attr("tranche", "2"),
+            attr("amount", "10000000"),
+            attr("total_matched", "985342750"),
+            attr("closed", "false"),
+        ]
+    );
+    let bid_pool = BID_POOL.load(&deps.storage, (1, 1)).unwrap();
+    assert_eq!(bid_pool.index_snapshot, Decimal::one());
+    assert_eq!(bid_pool.payout_amount, Uint128::from(10_099999u128));
+    let bid_pool = BID_POOL.load(&deps.storage, (1, 2)).unwrap();
+    assert_eq!(bid_pool.matched_amount, Uint128::from(480392254u128));
+    assert_eq!(bid_pool.payout_amount, Uint128::from(4_900001u128));
+    let distribution_info = DISTRIBUTION_INFO.load(&deps.storage, 1).unwrap();
+    assert_eq!(
+        distribution_info.total_distribution,
+        Uint128::from(15_000000u128)
+    );
+    assert_eq!(
+        distribution_info.actual_distributed,
+        Uint128::from(15_000000u128)
+    );
+
+    // closing burns everything matched over the tranches
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        ExecuteMsg::CloseTranches { round: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Burn {
+                amount: Uint128::from(1480392254u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let open_tranches: Option<u32> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::OpenTranches { round: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(open_tranches, None);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &vec![]),
+        distribute,
+    )
+    .unwrap();
+    let bid = BID.load(&deps.storage, 1).unwrap();
+    assert_eq!(bid.amount_received, Uint128::from(10_099999u128));
+    assert_eq!(bid.residue_bid, Uint128::zero());
+}
+
+#[test]
+fn test_lsd_bids() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    // 1 sORAIX is quoted at 1.2 ORAIX by the adapter
+    let mock_wasm = |balance: u128| {
+        move |query: &WasmQuery| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "lsd_adapter" => {
+                let res = match from_json(msg).unwrap() {
+                    LsdAdapterQueryMsg::RedeemRate {} => to_json_binary(&RedeemRateResponse {
+                        derivative: AssetInfo::Token {
+                            contract_addr: Addr::unchecked("lsd_token"),
+                        },
+                        underlying_per_derivative: Decimal::from_str("1.2").unwrap(),
+                    }),
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == ORAIX_ADDR => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&BalanceResponse {
+                        balance: Uint128::from(balance),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            }),
+        }
+    };
+    deps.querier.update_wasm(mock_wasm(0));
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.plus_seconds(100).seconds(),
+        end_time: env.block.time.plus_seconds(1100).seconds(),
+        total_distribution: Uint128::from(11_615000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    let submit_lsd_bid = |deps: DepsMut, env: Env, token: &str| {
+        execute(
+            deps,
+            env,
+            mock_info(token, &vec![]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "addr000".to_string(),
+                amount: Uint128::from(1000_000000u128),
+                msg: to_json_binary(&Cw20HookMsg::SubmitLsdBid {
+                    round: 1,
+                    premium_slot: 1,
+                })
+                .unwrap(),
+            }),
+        )
+    };
+
+    let err = submit_lsd_bid(deps.as_mut(), env.clone(), "lsd_token").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Round does not accept a staking derivative"
+        ))
+    );
+
+    // a round can't take both LP tokens and a staking derivative
+    let round_config = RoundConfig {
+        vested_rewards: false,
+        credit_payouts: false,
+        lp_pair: Some(Addr::unchecked("lp_pair")),
+        distribute_by_slot: false,
+        total_bid_threshold: None,
+        min_participants: None,
+        premium_curve: PremiumCurve::Linear {},
+        lend_distribution: false,
+        performance_fee: None,
+        tranched: false,
+        lsd_adapter: Some(Addr::unchecked("lsd_adapter")),
+    };
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: round_config.clone(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "A round accepts either LP tokens or a staking derivative"
+        ))
+    );
+    let msg = ExecuteMsg::UpdateRoundConfig {
+        round: 1,
+        round_config: RoundConfig {
+            lp_pair: None,
+            ..round_config
+        },
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(110);
+    // only the derivative of the adapter is accepted
+    let err = submit_lsd_bid(deps.as_mut(), env.clone(), ORAIX_ADDR).unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+    let res = submit_lsd_bid(deps.as_mut(), env.clone(), "lsd_token").unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "submit_lsd_bid"),
+            attr("round", "1"),
+            attr("bidder", "addr000"),
+            attr("bid_idx", "1"),
+            attr("premium_slot", "1"),
+            attr("lsd_amount", "1000000000"),
+        ]
+    );
+
+    // the derivative is redeemed first, the round is matched in the reply
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "lsd_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "lsd_adapter".to_string(),
+                    amount: Uint128::from(1000_000000u128),
+                    msg: to_json_binary(&LsdAdapterExecuteMsg::Redeem {}).unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            LSD_REDEEM_REPLY_ID,
+        )]
+    );
+
+    // the adapter paid 1150 ORAIX instead of the 1200 it quoted, only those are matched and burnt
+    deps.querier.update_wasm(mock_wasm(1150_000000));
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: LSD_REDEEM_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ORAIX_ADDR.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Burn {
+                amount: Uint128::from(1150_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert!(res.attributes.contains(&attr("redeemed", "1150000000")));
+
+    // the whole redeemed amount is matched, only the reward is paid
+    let msg = ExecuteMsg::Distribute {
+        round: 1,
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr000".to_string(),
+                amount: Uint128::from(11_615000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let bid: BidResponse =
+        from_json(&query(deps.as_ref(), env, QueryMsg::Bid { idx: 1 }).unwrap()).unwrap();
+    assert_eq!(bid.amount, Uint128::from(1150_000000u128));
+}
+
+#[test]
+fn test_reinvest_rewards() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+    let mock_wasm = |balance: u128| {
+        move |query: &WasmQuery| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "reinvest_pair" => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&PairInfo {
+                        oracle_addr: Addr::unchecked("oracle"),
+                        asset_infos: [
+                            AssetInfo::Token {
+                                contract_addr: Addr::unchecked(ORAIX_ADDR),
+                            },
+                            AssetInfo::Token {
+                                contract_addr: Addr::unchecked(USDC),
+                            },
+                        ],
+                        contract_addr: Addr::unchecked("reinvest_pair"),
+                        liquidity_token: Addr::unchecked("lp_token"),
+                        commission_rate: "0.003".to_string(),
+                    })
+                    .unwrap(),
+                ))
+            }
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == ORAIX_ADDR => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&BalanceResponse {
+                        balance: Uint128::from(balance),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            }),
+        }
+    };
+    deps.querier.update_wasm(mock_wasm(1000_000000));
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(7_575000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    // a single bid per hour, reinvested bids are not counted
+    let msg = ExecuteMsg::SetBidRateLimit {
+        rate_limit: Some(BidRateLimit {
+            max_bids: 1,
+            window: 3600,
+        }),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    for bidder in ["addr000", "addr001", "addr002"] {
+        do_submit_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ORAIX_ADDR, &vec![]),
+            bidder.to_string(),
+            Uint128::from(1000_000000u128),
+            1,
+            1,
+        )
+        .unwrap();
+    }
+
+    let reinvest = |idx: u64| ExecuteMsg::UpdateBidOptions {
+        idx,
+        auto_compound: None,
+        stake_residue: None,
+        reinvest: Some(true),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        reinvest(1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Reinvest pair is not configured"))
+    );
+
+    let reinvest_config = |max_spread: Decimal| ExecuteMsg::SetReinvestConfig {
+        reinvest_config: Some(ReinvestConfig {
+            pair: Addr::unchecked("reinvest_pair"),
+            max_spread,
+        }),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &vec![]),
+        reinvest_config(Decimal::percent(1)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        reinvest_config(Decimal::percent(20)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Reinvest max spread must be above 0 and at most 0.1"
+        ))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        reinvest_config(Decimal::percent(1)),
+    )
+    .unwrap();
+    for (idx, bidder) in [(1, "addr000"), (2, "addr001"), (3, "addr002")] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(bidder, &vec![]),
+            reinvest(idx),
+        )
+        .unwrap();
+    }
+
+    // 750 ORAIX matched for 7.575 USDC, each bid is rewarded 2.525 USDC
+    env.block.time = env.block.time.plus_seconds(1000);
+    let msg = ExecuteMsg::FinalizeBiddingRoundResult {
+        round: 1,
+        exchange_rate: Decimal::from_ratio(1u128, 100u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    let usdc_transfer = |recipient: &str| {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::from(2_525000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    };
+
+    // no round is open, the reward is paid as usual
+    let distribute_bid = |idx: u64| ExecuteMsg::DistributeBids {
+        round: 1,
+        idxs: vec![idx],
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        distribute_bid(1),
+    )
+    .unwrap();
+    assert!(res.messages.contains(&usdc_transfer("addr000")));
+
+    let msg = ExecuteMsg::CreateNewRound {
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.plus_seconds(1000).seconds(),
+        total_distribution: Uint128::from(5_000000u128),
+        min_exchange_rate: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &vec![]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+
+    // the reward is swapped before the residue is refunded
+    let swap_msg = SubMsg::reply_always(
+        WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: "reinvest_pair".to_string(),
+                amount: Uint128::from(2_525000u128),
+                msg: to_json_binary(&PairCw20HookMsg::Swap {
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    to: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        },
+        REINVEST_REPLY_ID,
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        distribute_bid(2),
+    )
+    .unwrap();
+    assert_eq!(res.messages[0], swap_msg);
+    assert!(!res.messages.contains(&usdc_transfer("addr001")));
+
+    // the swap went past the max spread, the reward is paid as usual
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: REINVEST_REPLY_ID,
+            result: SubMsgResult::Err("Operation exceeds max spread limit".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![usdc_transfer("addr001")]);
+    assert!(res.attributes.contains(&attr("action", "reinvest_failed")));
+
+    // 250 ORAIX returned by the swap are bid in round 2 at the same slot, although addr002
+    // already bid within the rate limit window
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &vec![]),
+        distribute_bid(3),
+    )
+    .unwrap();
+    assert_eq!(res.messages[0], swap_msg);
+    deps.querier.update_wasm(mock_wasm(1250_000000));
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: REINVEST_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert!(res
+        .events
+        .iter()
+        .any(|event| event.ty == "reinvest_bid"
+            && event.attributes.contains(&attr("from_bid_idx", "3"))));
+    let bids: Vec<BidResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidsByUser {
+                round: 2,
+                user: "addr002".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bids.len(), 1);
+    assert_eq!(bids[0].amount, Uint128::from(250_000000u128));
+    assert_eq!(bids[0].premium_slot, 1);
+}
+
+pub fn do_submit_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: String,
+    amount: Uint128,
+    round: u64,
+    premium_slot: u16,
+) -> Result<Response, ContractError> {
+    submit_bid_helper(deps, env, info, &sender, amount, round, premium_slot)
 }
 
 pub fn do_create_new_round(
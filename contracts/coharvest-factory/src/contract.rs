@@ -0,0 +1,218 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    instantiate2_address, to_json_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use oraiswap::asset::AssetInfo;
+
+use coharvest_bid_pool::msg::InstantiateMsg as BidPoolInstantiateMsg;
+
+use crate::{
+    error::ContractError,
+    msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
+    state::{asset_key, read_pools, Config, PoolInfo, CONFIG, POOLS, POOL_BY_PAIR, POOL_COUNT},
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        bid_pool_code_id: msg.bid_pool_code_id,
+        max_slot: msg.max_slot,
+        premium_rate_per_slot: msg.premium_rate_per_slot,
+        min_deposit_amount: msg.min_deposit_amount,
+        bidding_duration: msg.bidding_duration,
+    };
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    POOL_COUNT.save(deps.storage, &0)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            owner,
+            bid_pool_code_id,
+            max_slot,
+            premium_rate_per_slot,
+            min_deposit_amount,
+            bidding_duration,
+        } => execute_update_config(
+            deps,
+            info,
+            owner,
+            bid_pool_code_id,
+            max_slot,
+            premium_rate_per_slot,
+            min_deposit_amount,
+            bidding_duration,
+        ),
+        ExecuteMsg::CreatePool {
+            underlying_token,
+            distribution_token,
+            treasury,
+            oracle_pair,
+        } => execute_create_pool(
+            deps,
+            env,
+            info,
+            underlying_token,
+            distribution_token,
+            treasury,
+            oracle_pair,
+        ),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    bid_pool_code_id: Option<u64>,
+    max_slot: Option<u16>,
+    premium_rate_per_slot: Option<Decimal>,
+    min_deposit_amount: Option<Uint128>,
+    bidding_duration: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(bid_pool_code_id) = bid_pool_code_id {
+        config.bid_pool_code_id = bid_pool_code_id;
+    }
+    if let Some(max_slot) = max_slot {
+        config.max_slot = max_slot;
+    }
+    if let Some(premium_rate_per_slot) = premium_rate_per_slot {
+        config.premium_rate_per_slot = premium_rate_per_slot;
+    }
+    if let Some(min_deposit_amount) = min_deposit_amount {
+        config.min_deposit_amount = min_deposit_amount;
+    }
+    if let Some(bidding_duration) = bidding_duration {
+        config.bidding_duration = bidding_duration;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+// only owner can deploy a pool, one pool per pair. The pool address only depends on the
+// factory address, the bid pool code and the pool id, so it is registered right away
+fn execute_create_pool(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    underlying_token: AssetInfo,
+    distribution_token: AssetInfo,
+    treasury: String,
+    oracle_pair: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_key = (asset_key(&underlying_token), asset_key(&distribution_token));
+    if POOL_BY_PAIR.has(deps.storage, pair_key) {
+        return Err(ContractError::PoolExists {});
+    }
+
+    let id = POOL_COUNT.load(deps.storage)? + 1;
+    let salt = Binary::from(id.to_be_bytes());
+
+    let code_info = deps.querier.query_wasm_code_info(config.bid_pool_code_id)?;
+    let creator = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+    let address = instantiate2_address(code_info.checksum.as_slice(), &creator, &salt)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let address = deps.api.addr_humanize(&address)?;
+
+    let pool = PoolInfo {
+        id,
+        address: address.clone(),
+        underlying_token: underlying_token.clone(),
+        distribution_token: distribution_token.clone(),
+    };
+    POOLS.save(deps.storage, id, &pool)?;
+    POOL_BY_PAIR.save(deps.storage, pair_key, &id)?;
+    POOL_COUNT.save(deps.storage, &id)?;
+
+    let msg = WasmMsg::Instantiate2 {
+        admin: Some(config.owner.to_string()),
+        code_id: config.bid_pool_code_id,
+        label: format!("coharvest bid pool {}", id),
+        msg: to_json_binary(&BidPoolInstantiateMsg {
+            owner: config.owner.to_string(),
+            underlying_token,
+            distribution_token,
+            max_slot: config.max_slot,
+            premium_rate_per_slot: config.premium_rate_per_slot,
+            min_deposit_amount: config.min_deposit_amount,
+            treasury,
+            bidding_duration: config.bidding_duration,
+            oracle_pair,
+            vesting_contract: None,
+            keeper_registry: None,
+            staking_contract: None,
+            residue_staking: None,
+            wrapped_denom: None,
+            min_gap_between_rounds: None,
+        })?,
+        funds: vec![],
+        salt,
+    };
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "create_pool"),
+            ("pool_id", &id.to_string()),
+            ("pool_address", address.as_str()),
+        ])
+        .add_message(msg))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::Pool {
+            underlying_token,
+            distribution_token,
+        } => {
+            let id = POOL_BY_PAIR.load(
+                deps.storage,
+                (asset_key(&underlying_token), asset_key(&distribution_token)),
+            )?;
+            to_json_binary(&POOLS.load(deps.storage, id)?)
+        }
+        QueryMsg::AllPools { start_after, limit } => {
+            to_json_binary(&read_pools(deps.storage, start_after, limit)?)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
@@ -0,0 +1,77 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use oraiswap::asset::AssetInfo;
+
+use crate::state::{Config, PoolInfo};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub bid_pool_code_id: u64,
+    pub max_slot: u16,
+    pub premium_rate_per_slot: Decimal,
+    pub min_deposit_amount: Uint128,
+    pub bidding_duration: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        owner: Option<String>,
+        bid_pool_code_id: Option<u64>,
+        max_slot: Option<u16>,
+        premium_rate_per_slot: Option<Decimal>,
+        min_deposit_amount: Option<Uint128>,
+        bidding_duration: Option<u64>,
+    },
+    // deploy a bid pool for the pair with the default parameters
+    CreatePool {
+        underlying_token: AssetInfo,
+        distribution_token: AssetInfo,
+        treasury: String,
+        oracle_pair: Option<String>,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(PoolInfo)]
+    Pool {
+        underlying_token: AssetInfo,
+        distribution_token: AssetInfo,
+    },
+    #[returns(Vec<PoolInfo>)]
+    AllPools {
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub bid_pool_code_id: u64,
+    pub max_slot: u16,
+    pub premium_rate_per_slot: Decimal,
+    pub min_deposit_amount: Uint128,
+    pub bidding_duration: u64,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            bid_pool_code_id: config.bid_pool_code_id,
+            max_slot: config.max_slot,
+            premium_rate_per_slot: config.premium_rate_per_slot,
+            min_deposit_amount: config.min_deposit_amount,
+            bidding_duration: config.bidding_duration,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -0,0 +1,58 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
+use oraiswap::asset::AssetInfo;
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const POOL_COUNT: Item<u64> = Item::new("pool_count");
+// mapping pool id --> PoolInfo
+pub const POOLS: Map<u64, PoolInfo> = Map::new("pools");
+// mapping (underlying_token, distribution_token) --> pool id
+pub const POOL_BY_PAIR: Map<(&str, &str), u64> = Map::new("pool_by_pair");
+
+const MAX_LIMIT: u64 = 100;
+const DEFAULT_LIMIT: u64 = 30;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,           // owner address, also owner and admin of every pool
+    pub bid_pool_code_id: u64, // code id of the bid pool contract
+    pub max_slot: u16,         // default number of pools in a bidding round
+    pub premium_rate_per_slot: Decimal, // default premium rate increase for each slot
+    pub min_deposit_amount: Uint128, // default minimum bid
+    pub bidding_duration: u64, // default duration of a bidding round
+}
+
+#[cw_serde]
+pub struct PoolInfo {
+    pub id: u64,
+    pub address: Addr,
+    pub underlying_token: AssetInfo,
+    pub distribution_token: AssetInfo,
+}
+
+pub fn asset_key(asset_info: &AssetInfo) -> &str {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => contract_addr.as_str(),
+        AssetInfo::NativeToken { denom } => denom.as_str(),
+    }
+}
+
+pub fn read_pools(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<PoolInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    POOLS
+        .range(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, pool)| pool))
+        .collect()
+}
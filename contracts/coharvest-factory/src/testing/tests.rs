@@ -0,0 +1,226 @@
+use std::{marker::PhantomData, str::FromStr};
+
+use cosmwasm_std::{
+    from_json, instantiate2_address,
+    testing::{mock_env, mock_info, MockQuerier, MockStorage},
+    to_json_binary, Addr, Api, Binary, CodeInfoResponse, ContractResult, CosmosMsg, Decimal, Env,
+    HexBinary, OwnedDeps, SubMsg, SystemError, SystemResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw_multi_test::addons::MockApiBech32;
+use oraiswap::asset::AssetInfo;
+
+use coharvest_bid_pool::msg::InstantiateMsg as BidPoolInstantiateMsg;
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
+    state::PoolInfo,
+};
+
+const BID_POOL_CODE_ID: u64 = 7;
+const CHECKSUM: [u8; 32] = [42; 32];
+
+// instantiate2 needs real canonical addresses, so use bech32 addresses everywhere
+fn mock_deps() -> OwnedDeps<MockStorage, MockApiBech32, MockQuerier> {
+    let mut deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApiBech32::new("orai"),
+        querier: MockQuerier::new(&[]),
+        custom_query_type: PhantomData,
+    };
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::CodeInfo { code_id } => SystemResult::Ok(ContractResult::Ok(
+            to_json_binary(&CodeInfoResponse::new(
+                *code_id,
+                "creator".to_string(),
+                HexBinary::from(&CHECKSUM[..]),
+            ))
+            .unwrap(),
+        )),
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+    deps
+}
+
+fn factory_env(api: &MockApiBech32) -> Env {
+    let mut env = mock_env();
+    env.contract.address = api.addr_make("factory");
+    env
+}
+
+fn init(deps: &mut OwnedDeps<MockStorage, MockApiBech32, MockQuerier>) -> Addr {
+    let owner = deps.api.addr_make("owner");
+    let msg = InstantiateMsg {
+        owner: owner.to_string(),
+        bid_pool_code_id: BID_POOL_CODE_ID,
+        max_slot: 25,
+        premium_rate_per_slot: Decimal::from_str("0.01").unwrap(),
+        min_deposit_amount: Uint128::from(100_000000u128),
+        bidding_duration: 86400,
+    };
+
+    let env = factory_env(&deps.api);
+    instantiate(deps.as_mut(), env, mock_info(owner.as_str(), &[]), msg).unwrap();
+    owner
+}
+
+#[test]
+fn test_create_pool() {
+    let mut deps = mock_deps();
+    let owner = init(&mut deps);
+    let env = factory_env(&deps.api);
+    let treasury = deps.api.addr_make("treasury");
+    let underlying_token = AssetInfo::NativeToken {
+        denom: "oraix".to_string(),
+    };
+    let distribution_token = AssetInfo::NativeToken {
+        denom: "usdc".to_string(),
+    };
+
+    let msg = ExecuteMsg::CreatePool {
+        underlying_token: underlying_token.clone(),
+        distribution_token: distribution_token.clone(),
+        treasury: treasury.to_string(),
+        oracle_pair: None,
+    };
+
+    // only owner can create pools
+    let addr000 = deps.api.addr_make("addr000");
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(addr000.as_str(), &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner.as_str(), &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    // the pool address is known before the pool is instantiated
+    let salt = Binary::from(1u64.to_be_bytes());
+    let creator = deps
+        .api
+        .addr_canonicalize(env.contract.address.as_str())
+        .unwrap();
+    let pool_address = deps
+        .api
+        .addr_humanize(&instantiate2_address(&CHECKSUM, &creator, &salt).unwrap())
+        .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Instantiate2 {
+            admin: Some(owner.to_string()),
+            code_id: BID_POOL_CODE_ID,
+            label: "coharvest bid pool 1".to_string(),
+            msg: to_json_binary(&BidPoolInstantiateMsg {
+                owner: owner.to_string(),
+                underlying_token: underlying_token.clone(),
+                distribution_token: distribution_token.clone(),
+                max_slot: 25,
+                premium_rate_per_slot: Decimal::from_str("0.01").unwrap(),
+                min_deposit_amount: Uint128::from(100_000000u128),
+                treasury: treasury.to_string(),
+                bidding_duration: 86400,
+                oracle_pair: None,
+                vesting_contract: None,
+                keeper_registry: None,
+                staking_contract: None,
+                residue_staking: None,
+                wrapped_denom: None,
+                min_gap_between_rounds: None,
+            })
+            .unwrap(),
+            funds: vec![],
+            salt,
+        }))]
+    );
+
+    let pool: PoolInfo = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Pool {
+                underlying_token: underlying_token.clone(),
+                distribution_token: distribution_token.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        pool,
+        PoolInfo {
+            id: 1,
+            address: pool_address,
+            underlying_token: underlying_token.clone(),
+            distribution_token: distribution_token.clone(),
+        }
+    );
+
+    // one pool per pair
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner.as_str(), &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PoolExists {});
+
+    // the reverse pair is a different pool
+    let msg = ExecuteMsg::CreatePool {
+        underlying_token: distribution_token.clone(),
+        distribution_token: underlying_token.clone(),
+        treasury: treasury.to_string(),
+        oracle_pair: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner.as_str(), &[]),
+        msg,
+    )
+    .unwrap();
+
+    let pools: Vec<PoolInfo> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::AllPools {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        pools.iter().map(|pool| pool.id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    let pools: Vec<PoolInfo> = from_json(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::AllPools {
+                start_after: Some(1),
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pools.len(), 1);
+    assert_eq!(pools[0].underlying_token, distribution_token);
+}
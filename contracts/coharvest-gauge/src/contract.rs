@@ -0,0 +1,397 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, Response, StdError, StdResult, Storage, Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+use oraiswap::asset::AssetInfo;
+
+use coharvest_bid_pool::{
+    helper::{into_cosmos_msg, into_send_msg, query_balance},
+    msg::Cw20HookMsg as BidPoolCw20HookMsg,
+};
+
+use crate::{
+    error::ContractError,
+    msg::{
+        ConfigResponse, Cw20HookMsg, ExecuteMsg, GaugeResponse, InstantiateMsg, MigrateMsg,
+        QueryMsg, VoterResponse,
+    },
+    state::{Config, CONFIG, GAUGE_TALLIES, LAST_FUNDED_EPOCH, STAKES, VOTES},
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        vote_token: deps.api.addr_validate(&msg.vote_token)?,
+        funding_token: msg.funding_token,
+        epoch_duration: msg.epoch_duration,
+        budget_per_epoch: msg.budget_per_epoch,
+    };
+    assert_valid_epoch_duration(&config)?;
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            funding_token,
+            epoch_duration,
+            budget_per_epoch,
+        } => execute_update_config(
+            deps,
+            info,
+            owner,
+            funding_token,
+            epoch_duration,
+            budget_per_epoch,
+        ),
+        ExecuteMsg::AddGauge { bid_pool } => execute_add_gauge(deps, info, bid_pool),
+        ExecuteMsg::RemoveGauge { bid_pool } => execute_remove_gauge(deps, info, bid_pool),
+        ExecuteMsg::Unstake { amount } => execute_unstake(deps, info, amount),
+        ExecuteMsg::Vote { votes } => execute_vote(deps, info, votes),
+        ExecuteMsg::FundEpoch {} => execute_fund_epoch(deps, env),
+    }
+}
+
+fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::Stake {} => {
+            let config = CONFIG.load(deps.storage)?;
+            if info.sender != config.vote_token {
+                return Err(ContractError::Unauthorized {});
+            }
+            let staker = deps.api.addr_validate(&cw20_msg.sender)?;
+            execute_stake(deps, staker, cw20_msg.amount)
+        }
+    }
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    funding_token: Option<AssetInfo>,
+    epoch_duration: Option<u64>,
+    budget_per_epoch: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(funding_token) = funding_token {
+        config.funding_token = funding_token;
+    }
+    if let Some(epoch_duration) = epoch_duration {
+        config.epoch_duration = epoch_duration;
+    }
+    if let Some(budget_per_epoch) = budget_per_epoch {
+        config.budget_per_epoch = budget_per_epoch;
+    }
+    assert_valid_epoch_duration(&config)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+// only owner can add or remove the bid pools voters can vote for
+fn execute_add_gauge(
+    deps: DepsMut,
+    info: MessageInfo,
+    bid_pool: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let bid_pool = deps.api.addr_validate(&bid_pool)?;
+    if !GAUGE_TALLIES.has(deps.storage, &bid_pool) {
+        GAUGE_TALLIES.save(deps.storage, &bid_pool, &Uint128::zero())?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_gauge"),
+        ("bid_pool", bid_pool.as_str()),
+    ]))
+}
+
+fn execute_remove_gauge(
+    deps: DepsMut,
+    info: MessageInfo,
+    bid_pool: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let bid_pool = deps.api.addr_validate(&bid_pool)?;
+    if !GAUGE_TALLIES.has(deps.storage, &bid_pool) {
+        return Err(ContractError::GaugeNotFound {
+            bid_pool: bid_pool.to_string(),
+        });
+    }
+    // votes left on a removed gauge are ignored
+    GAUGE_TALLIES.remove(deps.storage, &bid_pool);
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "remove_gauge"),
+        ("bid_pool", bid_pool.as_str()),
+    ]))
+}
+
+fn execute_stake(deps: DepsMut, staker: Addr, amount: Uint128) -> Result<Response, ContractError> {
+    let stake = STAKES.may_load(deps.storage, &staker)?.unwrap_or_default();
+    update_stake(deps.storage, &staker, stake, stake + amount)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "stake"),
+        ("staker", staker.as_str()),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+fn execute_unstake(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let stake = STAKES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if stake < amount {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot unstake more than the staked amount",
+        )));
+    }
+    update_stake(deps.storage, &info.sender, stake, stake - amount)?;
+
+    let msg = into_cosmos_msg(
+        &AssetInfo::Token {
+            contract_addr: config.vote_token,
+        },
+        info.sender.to_string(),
+        amount,
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "unstake"),
+            ("staker", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ])
+        .add_message(msg))
+}
+
+fn execute_vote(
+    deps: DepsMut,
+    info: MessageInfo,
+    votes: Vec<(String, Decimal)>,
+) -> Result<Response, ContractError> {
+    let votes = votes
+        .into_iter()
+        .map(|(bid_pool, weight)| {
+            let bid_pool = deps.api.addr_validate(&bid_pool)?;
+            if !GAUGE_TALLIES.has(deps.storage, &bid_pool) {
+                return Err(ContractError::GaugeNotFound {
+                    bid_pool: bid_pool.to_string(),
+                });
+            }
+            Ok((bid_pool, weight))
+        })
+        .collect::<Result<Vec<(Addr, Decimal)>, ContractError>>()?;
+    let total_weight = votes
+        .iter()
+        .try_fold(Decimal::zero(), |total, (_, weight)| {
+            total.checked_add(*weight)
+        })
+        .map_err(|_| ContractError::InvalidVoteWeights {})?;
+    if total_weight > Decimal::one() {
+        return Err(ContractError::InvalidVoteWeights {});
+    }
+
+    // move the voting power of the sender from the previous votes to the new ones
+    let stake = STAKES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let previous_votes = VOTES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    update_tallies(deps.storage, &previous_votes, stake, false)?;
+    update_tallies(deps.storage, &votes, stake, true)?;
+    VOTES.save(deps.storage, &info.sender, &votes)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "vote"),
+        ("voter", info.sender.as_str()),
+        ("total_weight", &total_weight.to_string()),
+    ]))
+}
+
+// permissionless, once per epoch the budget is split pro-rata to the tallies read at this time
+fn execute_fund_epoch(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let epoch = config.epoch(env.block.time.seconds());
+    if LAST_FUNDED_EPOCH.may_load(deps.storage)? == Some(epoch) {
+        return Err(ContractError::EpochAlreadyFunded { epoch });
+    }
+
+    let tallies = read_tallies(deps.storage)?;
+    let total_tally: Uint128 = tallies.iter().map(|(_, tally)| tally).sum();
+    if total_tally.is_zero() {
+        return Err(ContractError::NoVotes {});
+    }
+
+    let balance = query_balance(&deps.querier, &config.funding_token, env.contract.address)?;
+    let budget = config.budget_per_epoch.min(balance);
+    if budget.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No budget left to fund the gauges",
+        )));
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for (bid_pool, tally) in tallies {
+        let amount = budget.multiply_ratio(tally, total_tally);
+        if amount.is_zero() {
+            continue;
+        }
+        msgs.push(into_send_msg(
+            &config.funding_token,
+            bid_pool.to_string(),
+            amount,
+            to_json_binary(&BidPoolCw20HookMsg::CreateNewRoundFromTreasury {})?,
+        )?);
+    }
+    LAST_FUNDED_EPOCH.save(deps.storage, &epoch)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "fund_epoch"),
+            ("epoch", &epoch.to_string()),
+            ("budget", &budget.to_string()),
+        ])
+        .add_messages(msgs))
+}
+
+fn update_stake(
+    storage: &mut dyn Storage,
+    staker: &Addr,
+    stake: Uint128,
+    new_stake: Uint128,
+) -> StdResult<()> {
+    let votes = VOTES.may_load(storage, staker)?.unwrap_or_default();
+    update_tallies(storage, &votes, stake, false)?;
+    update_tallies(storage, &votes, new_stake, true)?;
+
+    if new_stake.is_zero() {
+        STAKES.remove(storage, staker);
+    } else {
+        STAKES.save(storage, staker, &new_stake)?;
+    }
+    Ok(())
+}
+
+// adds or removes the voting power of stake from the tallies of the voted gauges
+fn update_tallies(
+    storage: &mut dyn Storage,
+    votes: &[(Addr, Decimal)],
+    stake: Uint128,
+    add: bool,
+) -> StdResult<()> {
+    for (bid_pool, weight) in votes {
+        // the gauge may have been removed since the vote
+        let Some(tally) = GAUGE_TALLIES.may_load(storage, bid_pool)? else {
+            continue;
+        };
+        let power = stake * *weight;
+        let tally = if add {
+            tally + power
+        } else {
+            tally.saturating_sub(power)
+        };
+        GAUGE_TALLIES.save(storage, bid_pool, &tally)?;
+    }
+    Ok(())
+}
+
+fn read_tallies(storage: &dyn Storage) -> StdResult<Vec<(Addr, Uint128)>> {
+    GAUGE_TALLIES
+        .range(storage, None, None, Order::Ascending)
+        .collect()
+}
+
+fn assert_valid_epoch_duration(config: &Config) -> Result<(), ContractError> {
+    if config.epoch_duration == 0 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "epoch_duration must be greater than zero",
+        )));
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::Gauges {} => to_json_binary(&query_gauges(deps)?),
+        QueryMsg::Voter { voter } => to_json_binary(&query_voter(deps, voter)?),
+    }
+}
+
+fn query_gauges(deps: Deps) -> StdResult<Vec<GaugeResponse>> {
+    let tallies = read_tallies(deps.storage)?;
+    let total_tally: Uint128 = tallies.iter().map(|(_, tally)| tally).sum();
+
+    Ok(tallies
+        .into_iter()
+        .map(|(bid_pool, tally)| GaugeResponse {
+            bid_pool,
+            tally,
+            share: Decimal::checked_from_ratio(tally, total_tally).unwrap_or_default(),
+        })
+        .collect())
+}
+
+fn query_voter(deps: Deps, voter: String) -> StdResult<VoterResponse> {
+    let voter = deps.api.addr_validate(&voter)?;
+
+    Ok(VoterResponse {
+        stake: STAKES.may_load(deps.storage, &voter)?.unwrap_or_default(),
+        votes: VOTES.may_load(deps.storage, &voter)?.unwrap_or_default(),
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
@@ -0,0 +1,23 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Gauge {bid_pool} does not exist")]
+    GaugeNotFound { bid_pool: String },
+
+    #[error("Vote weights must add up to at most one")]
+    InvalidVoteWeights {},
+
+    #[error("Epoch {epoch} has already been funded")]
+    EpochAlreadyFunded { epoch: u64 },
+
+    #[error("No gauge has received votes")]
+    NoVotes {},
+}
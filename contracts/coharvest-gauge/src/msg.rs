@@ -0,0 +1,95 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+use oraiswap::asset::AssetInfo;
+
+use crate::state::Config;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub vote_token: String,
+    pub funding_token: AssetInfo,
+    pub epoch_duration: u64,
+    pub budget_per_epoch: Uint128,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateConfig {
+        owner: Option<String>,
+        funding_token: Option<AssetInfo>,
+        epoch_duration: Option<u64>,
+        budget_per_epoch: Option<Uint128>,
+    },
+    // the gauge must be the treasury of the bid pool to create its rounds
+    AddGauge {
+        bid_pool: String,
+    },
+    RemoveGauge {
+        bid_pool: String,
+    },
+    Unstake {
+        amount: Uint128,
+    },
+    // replaces the previous votes of the sender, weights add up to at most one
+    Vote {
+        votes: Vec<(String, Decimal)>,
+    },
+    // anyone can split the budget of the current epoch across the gauges, pro-rata to the votes
+    FundEpoch {},
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    Stake {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(Vec<GaugeResponse>)]
+    Gauges {},
+    #[returns(VoterResponse)]
+    Voter { voter: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub vote_token: Addr,
+    pub funding_token: AssetInfo,
+    pub epoch_duration: u64,
+    pub budget_per_epoch: Uint128,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            vote_token: config.vote_token,
+            funding_token: config.funding_token,
+            epoch_duration: config.epoch_duration,
+            budget_per_epoch: config.budget_per_epoch,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct GaugeResponse {
+    pub bid_pool: Addr,
+    pub tally: Uint128, // voting power voting for the bid pool
+    pub share: Decimal, // share of the epoch budget the bid pool would receive now
+}
+
+#[cw_serde]
+pub struct VoterResponse {
+    pub stake: Uint128,
+    pub votes: Vec<(Addr, Decimal)>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -0,0 +1,29 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
+use oraiswap::asset::AssetInfo;
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// mapping bid pool --> voting power currently voting for it
+pub const GAUGE_TALLIES: Map<&Addr, Uint128> = Map::new("gauge_tallies");
+// mapping voter --> staked vote tokens
+pub const STAKES: Map<&Addr, Uint128> = Map::new("stakes");
+// mapping voter --> share of the voting power given to each bid pool
+pub const VOTES: Map<&Addr, Vec<(Addr, Decimal)>> = Map::new("votes");
+// last epoch whose budget was split across the gauges
+pub const LAST_FUNDED_EPOCH: Item<u64> = Item::new("last_funded_epoch");
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,               // owner address
+    pub vote_token: Addr,          // governance token staked to vote
+    pub funding_token: AssetInfo,  // distribution token of the bid pools
+    pub epoch_duration: u64,       // length of an epoch, in seconds
+    pub budget_per_epoch: Uint128, // total distribution split across the gauges every epoch
+}
+
+impl Config {
+    pub fn epoch(&self, timestamp: u64) -> u64 {
+        timestamp / self.epoch_duration
+    }
+}
@@ -0,0 +1,303 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{
+    attr, coins, from_json,
+    testing::{mock_dependencies_with_balance, mock_env, mock_info},
+    to_json_binary, Addr, Api, CosmosMsg, Decimal, DepsMut, OwnedDeps, Querier, Response, StdError,
+    Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use oraiswap::asset::AssetInfo;
+
+use coharvest_bid_pool::msg::ExecuteMsg as BidPoolExecuteMsg;
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    msg::{Cw20HookMsg, ExecuteMsg, GaugeResponse, InstantiateMsg, QueryMsg, VoterResponse},
+};
+
+const OWNER: &str = "owner";
+const VOTE_TOKEN: &str = "vote_token";
+const DENOM: &str = "usdc";
+const DAY: u64 = 86400;
+
+pub fn init<S: Storage, A: Api, Q: Querier>(deps: &mut OwnedDeps<S, A, Q>) {
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        vote_token: VOTE_TOKEN.to_string(),
+        funding_token: AssetInfo::NativeToken {
+            denom: DENOM.to_string(),
+        },
+        epoch_duration: 7 * DAY,
+        budget_per_epoch: Uint128::from(1000_000000u128),
+    };
+
+    let info = mock_info(OWNER, &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for bid_pool in ["pool1", "pool2"] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::AddGauge {
+                bid_pool: bid_pool.to_string(),
+            },
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn test_vote_and_fund_epoch() {
+    let mut deps = mock_dependencies_with_balance(&coins(10000_000000u128, DENOM));
+    init(&mut deps);
+
+    // only owner can add gauges
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::AddGauge {
+            bid_pool: "pool3".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // only the vote token can be staked
+    let err = do_stake(deps.as_mut(), "other_token", "alice", 300_000000).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    do_stake(deps.as_mut(), VOTE_TOKEN, "alice", 300_000000).unwrap();
+    do_stake(deps.as_mut(), VOTE_TOKEN, "bob", 100_000000).unwrap();
+
+    let err = do_vote(
+        deps.as_mut(),
+        "bob",
+        vec![("pool1", "0.6"), ("pool2", "0.6")],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoteWeights {});
+    let err = do_vote(deps.as_mut(), "bob", vec![("pool3", "1")]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::GaugeNotFound {
+            bid_pool: "pool3".to_string()
+        }
+    );
+
+    // nothing to split before the first vote
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundEpoch {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoVotes {});
+
+    do_vote(deps.as_mut(), "alice", vec![("pool1", "1")]).unwrap();
+    do_vote(
+        deps.as_mut(),
+        "bob",
+        vec![("pool1", "0.5"), ("pool2", "0.5")],
+    )
+    .unwrap();
+
+    let gauges: Vec<GaugeResponse> =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Gauges {}).unwrap()).unwrap();
+    assert_eq!(
+        gauges,
+        vec![
+            GaugeResponse {
+                bid_pool: Addr::unchecked("pool1"),
+                tally: Uint128::from(350_000000u128),
+                share: Decimal::from_str("0.875").unwrap(),
+            },
+            GaugeResponse {
+                bid_pool: Addr::unchecked("pool2"),
+                tally: Uint128::from(50_000000u128),
+                share: Decimal::from_str("0.125").unwrap(),
+            },
+        ]
+    );
+
+    // the epoch budget is split pro-rata to the tallies
+    let env = mock_env();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundEpoch {},
+    )
+    .unwrap();
+    let epoch = env.block.time.seconds() / (7 * DAY);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fund_epoch"),
+            attr("epoch", epoch.to_string()),
+            attr("budget", "1000000000"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pool1".to_string(),
+                msg: to_json_binary(&BidPoolExecuteMsg::CreateNewRoundFromTreasury {}).unwrap(),
+                funds: coins(875_000000u128, DENOM),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pool2".to_string(),
+                msg: to_json_binary(&BidPoolExecuteMsg::CreateNewRoundFromTreasury {}).unwrap(),
+                funds: coins(125_000000u128, DENOM),
+            })),
+        ]
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundEpoch {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EpochAlreadyFunded { epoch });
+}
+
+#[test]
+fn test_unstake_and_remove_gauge() {
+    let mut deps = mock_dependencies_with_balance(&coins(10000_000000u128, DENOM));
+    init(&mut deps);
+
+    do_stake(deps.as_mut(), VOTE_TOKEN, "alice", 300_000000).unwrap();
+    do_vote(
+        deps.as_mut(),
+        "alice",
+        vec![("pool1", "0.5"), ("pool2", "0.5")],
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Unstake {
+            amount: Uint128::from(400_000000u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Cannot unstake more than the staked amount"
+        ))
+    );
+
+    // unstaking takes the voting power out of the tallies
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Unstake {
+            amount: Uint128::from(200_000000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTE_TOKEN.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "alice".to_string(),
+                amount: Uint128::from(200_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let voter: VoterResponse = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Voter {
+                voter: "alice".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(voter.stake, Uint128::from(100_000000u128));
+
+    // votes on a removed gauge are ignored
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RemoveGauge {
+            bid_pool: "pool2".to_string(),
+        },
+    )
+    .unwrap();
+    let gauges: Vec<GaugeResponse> =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Gauges {}).unwrap()).unwrap();
+    assert_eq!(
+        gauges,
+        vec![GaugeResponse {
+            bid_pool: Addr::unchecked("pool1"),
+            tally: Uint128::from(50_000000u128),
+            share: Decimal::one(),
+        }]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundEpoch {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pool1".to_string(),
+            msg: to_json_binary(&BidPoolExecuteMsg::CreateNewRoundFromTreasury {}).unwrap(),
+            funds: coins(1000_000000u128, DENOM),
+        }))]
+    );
+}
+
+pub fn do_stake(
+    deps: DepsMut,
+    token: &str,
+    staker: &str,
+    amount: u128,
+) -> Result<Response, ContractError> {
+    let receive = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: staker.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_json_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+
+    execute(deps, mock_env(), mock_info(token, &[]), receive)
+}
+
+pub fn do_vote(
+    deps: DepsMut,
+    voter: &str,
+    votes: Vec<(&str, &str)>,
+) -> Result<Response, ContractError> {
+    let votes = votes
+        .into_iter()
+        .map(|(bid_pool, weight)| (bid_pool.to_string(), Decimal::from_str(weight).unwrap()))
+        .collect();
+
+    execute(
+        deps,
+        mock_env(),
+        mock_info(voter, &[]),
+        ExecuteMsg::Vote { votes },
+    )
+}
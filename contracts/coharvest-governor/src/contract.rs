@@ -0,0 +1,469 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Bound;
+use oraiswap::asset::AssetInfo;
+
+use coharvest_bid_pool::{
+    helper::into_cosmos_msg, matching::checked_mul_decimal, msg::ExecuteMsg as BidPoolExecuteMsg,
+};
+
+use crate::{
+    error::ContractError,
+    msg::{ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
+    state::{
+        Config, ParamChange, Proposal, ProposalStatus, CONFIG, PROPOSALS, PROPOSAL_COUNT, STAKES,
+        TOTAL_STAKED, VOTES,
+    },
+};
+
+// version info for migration
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const MAX_LIMIT: u64 = 100;
+const DEFAULT_LIMIT: u64 = 30;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        vote_token: deps.api.addr_validate(&msg.vote_token)?,
+        bid_pool: deps.api.addr_validate(&msg.bid_pool)?,
+        voting_period: msg.voting_period,
+        timelock_period: msg.timelock_period,
+        quorum: msg.quorum,
+        threshold: msg.threshold,
+    };
+    assert_rates(&config)?;
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    PROPOSAL_COUNT.save(deps.storage, &0)?;
+    TOTAL_STAKED.save(deps.storage, &Uint128::zero(), env.block.height)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            voting_period,
+            timelock_period,
+            quorum,
+            threshold,
+        } => execute_update_config(
+            deps,
+            info,
+            owner,
+            voting_period,
+            timelock_period,
+            quorum,
+            threshold,
+        ),
+        ExecuteMsg::Unstake { amount } => execute_unstake(deps, env, info, amount),
+        ExecuteMsg::Propose { title, changes } => execute_propose(deps, env, info, title, changes),
+        ExecuteMsg::Vote { proposal_id, yes } => execute_vote(deps, env, info, proposal_id, yes),
+        ExecuteMsg::ExecuteProposal { proposal_id } => {
+            execute_execute_proposal(deps, env, proposal_id)
+        }
+    }
+}
+
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::Stake {} => {
+            let config = CONFIG.load(deps.storage)?;
+            if info.sender != config.vote_token {
+                return Err(ContractError::Unauthorized {});
+            }
+            let staker = deps.api.addr_validate(&cw20_msg.sender)?;
+            execute_stake(deps, env, staker, cw20_msg.amount)
+        }
+    }
+}
+
+fn assert_rates(config: &Config) -> Result<(), ContractError> {
+    if config.quorum > Decimal::one() || config.threshold >= Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Quorum must be at most one and threshold below one",
+        )));
+    }
+    Ok(())
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    voting_period: Option<u64>,
+    timelock_period: Option<u64>,
+    quorum: Option<Decimal>,
+    threshold: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(voting_period) = voting_period {
+        config.voting_period = voting_period;
+    }
+    if let Some(timelock_period) = timelock_period {
+        config.timelock_period = timelock_period;
+    }
+    if let Some(quorum) = quorum {
+        config.quorum = quorum;
+    }
+    if let Some(threshold) = threshold {
+        config.threshold = threshold;
+    }
+    assert_rates(&config)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+fn update_stake(
+    storage: &mut dyn Storage,
+    height: u64,
+    staker: &Addr,
+    stake: Uint128,
+    new_stake: Uint128,
+) -> StdResult<()> {
+    STAKES.save(storage, staker, &new_stake, height)?;
+    let total_staked = TOTAL_STAKED.load(storage)?;
+    TOTAL_STAKED.save(storage, &(total_staked + new_stake - stake), height)
+}
+
+fn execute_stake(
+    deps: DepsMut,
+    env: Env,
+    staker: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let stake = STAKES.may_load(deps.storage, &staker)?.unwrap_or_default();
+    update_stake(
+        deps.storage,
+        env.block.height,
+        &staker,
+        stake,
+        stake + amount,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "stake"),
+        ("staker", staker.as_str()),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+// votes already cast keep the voting power read at the start of their proposal
+fn execute_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let stake = STAKES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if stake < amount {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot unstake more than the staked amount",
+        )));
+    }
+    update_stake(
+        deps.storage,
+        env.block.height,
+        &info.sender,
+        stake,
+        stake - amount,
+    )?;
+
+    let msg = into_cosmos_msg(
+        &AssetInfo::Token {
+            contract_addr: config.vote_token,
+        },
+        info.sender.to_string(),
+        amount,
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "unstake"),
+            ("staker", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ])
+        .add_message(msg))
+}
+
+fn execute_propose(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    changes: Vec<ParamChange>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if STAKES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+        .is_zero()
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    if changes.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "A proposal must change at least one parameter",
+        )));
+    }
+
+    let id = PROPOSAL_COUNT.load(deps.storage)? + 1;
+    PROPOSAL_COUNT.save(deps.storage, &id)?;
+    let end_time = env.block.time.seconds() + config.voting_period;
+    PROPOSALS.save(
+        deps.storage,
+        id,
+        &Proposal {
+            id,
+            proposer: info.sender.clone(),
+            title,
+            changes,
+            start_height: env.block.height,
+            end_time,
+            yes_votes: Uint128::zero(),
+            no_votes: Uint128::zero(),
+            status: ProposalStatus::Open,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "propose"),
+        ("proposal_id", &id.to_string()),
+        ("proposer", info.sender.as_str()),
+        ("end_time", &end_time.to_string()),
+    ]))
+}
+
+fn execute_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    yes: bool,
+) -> Result<Response, ContractError> {
+    let mut proposal = PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound { id: proposal_id })?;
+    if env.block.time.seconds() >= proposal.end_time {
+        return Err(ContractError::VotingEnded { id: proposal_id });
+    }
+    if VOTES.has(deps.storage, (proposal_id, &info.sender)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    // stake added after the proposal was created does not count
+    let power = STAKES
+        .may_load_at_height(deps.storage, &info.sender, proposal.start_height)?
+        .unwrap_or_default();
+    if power.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    if yes {
+        proposal.yes_votes = proposal.yes_votes.checked_add(power)?;
+    } else {
+        proposal.no_votes = proposal.no_votes.checked_add(power)?;
+    }
+    VOTES.save(deps.storage, (proposal_id, &info.sender), &yes)?;
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "vote"),
+        ("proposal_id", &proposal_id.to_string()),
+        ("voter", info.sender.as_str()),
+        ("yes", &yes.to_string()),
+        ("power", &power.to_string()),
+    ]))
+}
+
+// passed when the votes reach the quorum of the stake at the start of the proposal and the yes
+// votes are above the threshold of the votes
+fn is_passed(
+    storage: &dyn Storage,
+    config: &Config,
+    proposal: &Proposal,
+) -> Result<bool, ContractError> {
+    let total_staked = TOTAL_STAKED
+        .may_load_at_height(storage, proposal.start_height)?
+        .unwrap_or_default();
+    let votes = proposal.yes_votes.checked_add(proposal.no_votes)?;
+    Ok(!votes.is_zero()
+        && votes >= checked_mul_decimal(total_staked, config.quorum)?
+        && proposal.yes_votes > checked_mul_decimal(votes, config.threshold)?)
+}
+
+// the changes of the proposal as the owner messages of the bid pool
+fn bid_pool_msgs(config: &Config, changes: &[ParamChange]) -> StdResult<Vec<CosmosMsg>> {
+    let mut premium_rate_per_slot = None;
+    let mut max_slot = None;
+    let mut msgs = vec![];
+    for change in changes {
+        match change {
+            ParamChange::PremiumRatePerSlot {
+                premium_rate_per_slot: rate,
+            } => premium_rate_per_slot = Some(*rate),
+            ParamChange::MaxSlot { max_slot: slot } => max_slot = Some(*slot),
+            ParamChange::FeeSplits { actions } => msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: config.bid_pool.to_string(),
+                msg: to_json_binary(&BidPoolExecuteMsg::SetPostFinalizeActions {
+                    actions: actions.clone(),
+                })?,
+                funds: vec![],
+            })),
+        }
+    }
+    if premium_rate_per_slot.is_some() || max_slot.is_some() {
+        msgs.insert(
+            0,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: config.bid_pool.to_string(),
+                msg: to_json_binary(&BidPoolExecuteMsg::UpdateConfig {
+                    owner: None,
+                    underlying_token: None,
+                    distribution_token: None,
+                    max_slot,
+                    premium_rate_per_slot,
+                    min_deposit_amount: None,
+                    treasury: None,
+                    bidding_duration: None,
+                    oracle_pair: None,
+                    vesting_contract: None,
+                    keeper_registry: None,
+                    staking_contract: None,
+                    residue_staking: None,
+                    wrapped_denom: None,
+                    min_gap_between_rounds: None,
+                })?,
+                funds: vec![],
+            }),
+        );
+    }
+    Ok(msgs)
+}
+
+fn execute_execute_proposal(
+    deps: DepsMut,
+    env: Env,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut proposal = PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound { id: proposal_id })?;
+    if proposal.status != ProposalStatus::Open {
+        return Err(ContractError::ProposalNotOpen { id: proposal_id });
+    }
+    let eta = proposal.end_time + config.timelock_period;
+    if env.block.time.seconds() < eta {
+        return Err(ContractError::Timelocked {
+            id: proposal_id,
+            eta,
+        });
+    }
+
+    let msgs = if is_passed(deps.storage, &config, &proposal)? {
+        proposal.status = ProposalStatus::Executed;
+        bid_pool_msgs(&config, &proposal.changes)?
+    } else {
+        proposal.status = ProposalStatus::Rejected;
+        vec![]
+    };
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        ("action", "execute_proposal"),
+        ("proposal_id", &proposal_id.to_string()),
+        (
+            "status",
+            match proposal.status {
+                ProposalStatus::Executed => "executed",
+                _ => "rejected",
+            },
+        ),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::Proposal { proposal_id } => {
+            to_json_binary(&PROPOSALS.load(deps.storage, proposal_id)?)
+        }
+        QueryMsg::Proposals { start_after, limit } => {
+            to_json_binary(&query_proposals(deps, start_after, limit)?)
+        }
+        QueryMsg::Stake { staker } => to_json_binary(
+            &STAKES
+                .may_load(deps.storage, &deps.api.addr_validate(&staker)?)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::Vote { proposal_id, voter } => to_json_binary(&VOTES.may_load(
+            deps.storage,
+            (proposal_id, &deps.api.addr_validate(&voter)?),
+        )?),
+    }
+}
+
+fn query_proposals(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<Proposal>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    PROPOSALS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, proposal)| proposal))
+        .collect()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
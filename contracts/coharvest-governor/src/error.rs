@@ -0,0 +1,32 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Proposal {id} does not exist")]
+    ProposalNotFound { id: u64 },
+
+    #[error("Voting on proposal {id} has ended")]
+    VotingEnded { id: u64 },
+
+    #[error("Proposal {id} can't be executed before {eta}")]
+    Timelocked { id: u64, eta: u64 },
+
+    #[error("Proposal {id} is not open")]
+    ProposalNotOpen { id: u64 },
+
+    #[error("Already voted")]
+    AlreadyVoted {},
+
+    #[error("No voting power at the start of the proposal")]
+    NoVotingPower {},
+}
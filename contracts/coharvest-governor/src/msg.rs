@@ -0,0 +1,97 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::state::{Config, ParamChange, Proposal};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub vote_token: String,
+    pub bid_pool: String,
+    pub voting_period: u64,
+    pub timelock_period: u64,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateConfig {
+        owner: Option<String>,
+        voting_period: Option<u64>,
+        timelock_period: Option<u64>,
+        quorum: Option<Decimal>,
+        threshold: Option<Decimal>,
+    },
+    Unstake {
+        amount: Uint128,
+    },
+    // only stakers can propose
+    Propose {
+        title: String,
+        changes: Vec<ParamChange>,
+    },
+    Vote {
+        proposal_id: u64,
+        yes: bool,
+    },
+    // anyone once the timelock is over, a passed proposal sends its changes to the bid pool
+    // and a failed one is rejected
+    ExecuteProposal {
+        proposal_id: u64,
+    },
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    Stake {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(Proposal)]
+    Proposal { proposal_id: u64 },
+    #[returns(Vec<Proposal>)]
+    Proposals {
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
+    #[returns(Uint128)]
+    Stake { staker: String },
+    // None when the voter has not voted on the proposal
+    #[returns(Option<bool>)]
+    Vote { proposal_id: u64, voter: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub vote_token: Addr,
+    pub bid_pool: Addr,
+    pub voting_period: u64,
+    pub timelock_period: u64,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            vote_token: config.vote_token,
+            bid_pool: config.bid_pool,
+            voting_period: config.voting_period,
+            timelock_period: config.timelock_period,
+            quorum: config.quorum,
+            threshold: config.threshold,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -0,0 +1,63 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+
+use coharvest_bid_pool::state::PostFinalizeAction;
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// mapping staker --> staked vote tokens, voting power is read at the start of each proposal
+pub const STAKES: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "stakes",
+    "stakes__checkpoints",
+    "stakes__changelog",
+    Strategy::EveryBlock,
+);
+pub const TOTAL_STAKED: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_staked",
+    "total_staked__checkpoints",
+    "total_staked__changelog",
+    Strategy::EveryBlock,
+);
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
+pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+// mapping (proposal, voter) --> true for a yes vote
+pub const VOTES: Map<(u64, &Addr), bool> = Map::new("votes");
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,          // owner address
+    pub vote_token: Addr,     // ORAIX staked to vote
+    pub bid_pool: Addr,       // bid pool governed, owned by this contract
+    pub voting_period: u64,   // in seconds
+    pub timelock_period: u64, // delay between the end of the vote and the execution
+    pub quorum: Decimal,      // share of the total stake that must vote
+    pub threshold: Decimal,   // share of the votes cast that must be yes
+}
+
+// parameters of the bid pool a proposal can change
+#[cw_serde]
+pub enum ParamChange {
+    PremiumRatePerSlot { premium_rate_per_slot: Decimal },
+    MaxSlot { max_slot: u16 },
+    FeeSplits { actions: Vec<PostFinalizeAction> },
+}
+
+#[cw_serde]
+pub enum ProposalStatus {
+    Open,
+    Executed,
+    Rejected,
+}
+
+#[cw_serde]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Addr,
+    pub title: String,
+    pub changes: Vec<ParamChange>,
+    pub start_height: u64, // height the voting power is read at
+    pub end_time: u64,     // end of the vote, it can be executed timelock_period later
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub status: ProposalStatus,
+}
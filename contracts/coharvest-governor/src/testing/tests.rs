@@ -0,0 +1,252 @@
+use cosmwasm_std::{
+    from_json,
+    testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    to_json_binary, CosmosMsg, Decimal, Env, OwnedDeps, SubMsg, Uint128, WasmMsg,
+};
+use cw20::Cw20ReceiveMsg;
+use oraiswap::asset::AssetInfo;
+
+use coharvest_bid_pool::{
+    msg::ExecuteMsg as BidPoolExecuteMsg,
+    state::{ActionAmount, PostFinalizeAction},
+};
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg},
+    state::{ParamChange, Proposal, ProposalStatus},
+};
+
+const OWNER: &str = "owner";
+const ORAIX_ADDR: &str = "oraix";
+const BID_POOL: &str = "bid_pool";
+
+fn init() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        vote_token: ORAIX_ADDR.to_string(),
+        bid_pool: BID_POOL.to_string(),
+        voting_period: 1000,
+        timelock_period: 500,
+        quorum: Decimal::percent(30),
+        threshold: Decimal::percent(50),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    deps
+}
+
+fn stake(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: &Env,
+    staker: &str,
+    amount: u128,
+) {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_json_binary(&Cw20HookMsg::Stake {}).unwrap(),
+        }),
+    )
+    .unwrap();
+}
+
+fn next_block(env: &mut Env, seconds: u64) {
+    env.block.height += 1;
+    env.block.time = env.block.time.plus_seconds(seconds);
+}
+
+#[test]
+fn test_proposal_lifecycle() {
+    let mut deps = init();
+    let mut env = mock_env();
+    stake(&mut deps, &env, "alice", 60_000000);
+    stake(&mut deps, &env, "bob", 40_000000);
+    next_block(&mut env, 5);
+
+    let fee_splits = vec![PostFinalizeAction::Transfer {
+        asset_info: AssetInfo::NativeToken {
+            denom: "orai".to_string(),
+        },
+        recipient: "treasury".to_string(),
+        amount: ActionAmount::Leftover {},
+    }];
+    let propose = ExecuteMsg::Propose {
+        title: "Lower the premium".to_string(),
+        changes: vec![
+            ParamChange::PremiumRatePerSlot {
+                premium_rate_per_slot: Decimal::permille(5),
+            },
+            ParamChange::FeeSplits {
+                actions: fee_splits.clone(),
+            },
+            ParamChange::MaxSlot { max_slot: 20 },
+        ],
+    };
+    // only stakers can propose
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        propose.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), propose).unwrap();
+
+    // stake added once the proposal is created has no voting power on it
+    stake(&mut deps, &env, "carol", 500_000000);
+    next_block(&mut env, 5);
+    let vote = |yes: bool| ExecuteMsg::Vote {
+        proposal_id: 1,
+        yes,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        vote(false),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        vote(true),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        vote(false),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        vote(true),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AlreadyVoted {});
+
+    let execute_proposal = ExecuteMsg::ExecuteProposal { proposal_id: 1 };
+    next_block(&mut env, 1000);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        execute_proposal.clone(),
+    )
+    .unwrap_err();
+    let eta = mock_env().block.time.seconds() + 5 + 1000 + 500;
+    assert_eq!(err, ContractError::Timelocked { id: 1, eta });
+
+    // 60 yes out of 100 votes, the changes are sent to the bid pool
+    next_block(&mut env, 500);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        execute_proposal.clone(),
+    )
+    .unwrap();
+    let bid_pool_msg = |msg: BidPoolExecuteMsg| {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: BID_POOL.to_string(),
+            msg: to_json_binary(&msg).unwrap(),
+            funds: vec![],
+        }))
+    };
+    assert_eq!(
+        res.messages,
+        vec![
+            bid_pool_msg(BidPoolExecuteMsg::UpdateConfig {
+                owner: None,
+                underlying_token: None,
+                distribution_token: None,
+                max_slot: Some(20),
+                premium_rate_per_slot: Some(Decimal::permille(5)),
+                min_deposit_amount: None,
+                treasury: None,
+                bidding_duration: None,
+                oracle_pair: None,
+                vesting_contract: None,
+                keeper_registry: None,
+                staking_contract: None,
+                residue_staking: None,
+                wrapped_denom: None,
+                min_gap_between_rounds: None,
+            }),
+            bid_pool_msg(BidPoolExecuteMsg::SetPostFinalizeActions {
+                actions: fee_splits,
+            }),
+        ]
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        execute_proposal,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ProposalNotOpen { id: 1 });
+
+    // carol alone is above the quorum but votes no
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        ExecuteMsg::Propose {
+            title: "More slots".to_string(),
+            changes: vec![ParamChange::MaxSlot { max_slot: 50 }],
+        },
+    )
+    .unwrap();
+    next_block(&mut env, 5);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        ExecuteMsg::Vote {
+            proposal_id: 2,
+            yes: false,
+        },
+    )
+    .unwrap();
+    next_block(&mut env, 1500);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::ExecuteProposal { proposal_id: 2 },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    let proposals: Vec<Proposal> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Proposals {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        proposals
+            .into_iter()
+            .map(|proposal| proposal.status)
+            .collect::<Vec<_>>(),
+        vec![ProposalStatus::Executed, ProposalStatus::Rejected]
+    );
+}
@@ -0,0 +1,554 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Reply, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw_utils::must_pay;
+
+use coharvest_bid_pool::{
+    helper::query_pair_exchange_rate,
+    msg::{
+        BiddingInfoResponse, ConfigResponse as BidPoolConfigResponse,
+        ExecuteMsg as BidPoolExecuteMsg, QueryMsg as BidPoolQueryMsg,
+    },
+};
+
+use crate::{
+    error::ContractError,
+    msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, KeeperResponse, MigrateMsg, QueryMsg},
+    state::{
+        read_keepers, Config, Keeper, PendingDistribute, CONFIG, KEEPERS, PENDING_DISTRIBUTE,
+        TOTAL_BONDED,
+    },
+};
+
+const DISTRIBUTE_REPLY_ID: u64 = 1;
+// bids a full distribute_bounty is paid for, the default batch of the bid pool
+const BOUNTY_BATCH_SIZE: u64 = 100;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        bid_pool: deps.api.addr_validate(&msg.bid_pool)?,
+        bond_denom: msg.bond_denom,
+        min_bond: msg.min_bond,
+        unbonding_period: msg.unbonding_period,
+        bounty_denom: msg.bounty_denom,
+        finalize_bounty: msg.finalize_bounty,
+        distribute_bounty: msg.distribute_bounty,
+        reference_pair: msg
+            .reference_pair
+            .map(|pair| deps.api.addr_validate(&pair))
+            .transpose()?,
+        max_rate_deviation: msg.max_rate_deviation,
+        slash_amount: msg.slash_amount,
+    };
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    TOTAL_BONDED.save(deps.storage, &Uint128::zero())?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            owner,
+            bid_pool,
+            min_bond,
+            unbonding_period,
+            finalize_bounty,
+            distribute_bounty,
+            reference_pair,
+            max_rate_deviation,
+            slash_amount,
+        } => execute_update_config(
+            deps,
+            info,
+            owner,
+            bid_pool,
+            min_bond,
+            unbonding_period,
+            finalize_bounty,
+            distribute_bounty,
+            reference_pair,
+            max_rate_deviation,
+            slash_amount,
+        ),
+        ExecuteMsg::Bond {} => execute_bond(deps, info),
+        ExecuteMsg::Unbond {} => execute_unbond(deps, env, info),
+        ExecuteMsg::WithdrawBond {} => execute_withdraw_bond(deps, env, info),
+        ExecuteMsg::Finalize {
+            round,
+            exchange_rate,
+        } => execute_finalize(deps, env, info, round, exchange_rate),
+        ExecuteMsg::Distribute { round, limit } => execute_distribute(deps, info, round, limit),
+        ExecuteMsg::Slash { keeper, amount } => execute_slash(deps, info, keeper, amount),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    bid_pool: Option<String>,
+    min_bond: Option<Uint128>,
+    unbonding_period: Option<u64>,
+    finalize_bounty: Option<Uint128>,
+    distribute_bounty: Option<Uint128>,
+    reference_pair: Option<String>,
+    max_rate_deviation: Option<Decimal>,
+    slash_amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(bid_pool) = bid_pool {
+        config.bid_pool = deps.api.addr_validate(&bid_pool)?;
+    }
+    if let Some(min_bond) = min_bond {
+        config.min_bond = min_bond;
+    }
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
+    }
+    if let Some(finalize_bounty) = finalize_bounty {
+        config.finalize_bounty = finalize_bounty;
+    }
+    if let Some(distribute_bounty) = distribute_bounty {
+        config.distribute_bounty = distribute_bounty;
+    }
+    if let Some(reference_pair) = reference_pair {
+        config.reference_pair = Some(deps.api.addr_validate(&reference_pair)?);
+    }
+    if let Some(max_rate_deviation) = max_rate_deviation {
+        config.max_rate_deviation = max_rate_deviation;
+    }
+    if let Some(slash_amount) = slash_amount {
+        config.slash_amount = slash_amount;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+fn execute_bond(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = must_pay(&info, &config.bond_denom)?;
+
+    let mut keeper = KEEPERS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or(Keeper {
+            bond: Uint128::zero(),
+            unbonding_since: None,
+        });
+    if keeper.unbonding_since.is_some() {
+        return Err(ContractError::KeeperUnbonding {});
+    }
+
+    keeper.bond = keeper.bond.checked_add(amount)?;
+    KEEPERS.save(deps.storage, &info.sender, &keeper)?;
+    TOTAL_BONDED.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total.checked_add(amount)?)
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "bond"),
+        ("keeper", info.sender.as_str()),
+        ("bond", &keeper.bond.to_string()),
+    ]))
+}
+
+// the keeper is no longer authorized from now on, the bond is released after the unbonding period
+fn execute_unbond(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut keeper = KEEPERS.load(deps.storage, &info.sender)?;
+    if keeper.unbonding_since.is_some() {
+        return Err(ContractError::KeeperUnbonding {});
+    }
+
+    keeper.unbonding_since = Some(env.block.time.seconds());
+    KEEPERS.save(deps.storage, &info.sender, &keeper)?;
+
+    Ok(
+        Response::new()
+            .add_attributes(vec![("action", "unbond"), ("keeper", info.sender.as_str())]),
+    )
+}
+
+fn execute_withdraw_bond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let keeper = KEEPERS.load(deps.storage, &info.sender)?;
+
+    let Some(unbonding_since) = keeper.unbonding_since else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Keeper has not unbonded",
+        )));
+    };
+    let release_time = unbonding_since + config.unbonding_period;
+    if env.block.time.seconds() < release_time {
+        return Err(ContractError::UnbondingNotFinished { release_time });
+    }
+
+    KEEPERS.remove(deps.storage, &info.sender);
+    TOTAL_BONDED.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total - keeper.bond)
+    })?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !keeper.bond.is_zero() {
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(keeper.bond.u128(), &config.bond_denom),
+        }));
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "withdraw_bond"),
+            ("keeper", info.sender.as_str()),
+            ("amount", &keeper.bond.to_string()),
+        ])
+        .add_messages(msgs))
+}
+
+// a rate too far from the reference pair gets the keeper slashed instead of finalizing the round,
+// in oracle mode the bid pool checks the rate against its oracle pair itself
+fn execute_finalize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round: u64,
+    exchange_rate: Decimal,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_authorized_keeper(deps.storage, &config, &info.sender)?;
+
+    if let Some(reference_pair) = &config.reference_pair {
+        let bid_pool_config: BidPoolConfigResponse = deps
+            .querier
+            .query_wasm_smart(&config.bid_pool, &BidPoolQueryMsg::Config {})?;
+        if bid_pool_config.oracle_pair.is_none() {
+            let reference_rate = query_pair_exchange_rate(
+                &deps.querier,
+                reference_pair,
+                &bid_pool_config.underlying_token,
+                &bid_pool_config.distribution_token,
+            )?;
+            let diff = if exchange_rate > reference_rate {
+                exchange_rate - reference_rate
+            } else {
+                reference_rate - exchange_rate
+            };
+            let deviation = diff
+                .checked_div(reference_rate)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+            if deviation > config.max_rate_deviation {
+                let (slashed, msgs) =
+                    slash_keeper(deps.storage, &config, &info.sender, config.slash_amount)?;
+                return Ok(Response::new()
+                    .add_attributes(vec![
+                        ("action", "slash"),
+                        ("keeper", info.sender.as_str()),
+                        ("round", &round.to_string()),
+                        ("exchange_rate", &exchange_rate.to_string()),
+                        ("reference_rate", &reference_rate.to_string()),
+                        ("amount", &slashed.to_string()),
+                    ])
+                    .add_messages(msgs));
+            }
+        }
+    }
+
+    let mut msgs = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.bid_pool.to_string(),
+        msg: to_json_binary(&BidPoolExecuteMsg::FinalizeBiddingRoundResult {
+            round,
+            exchange_rate,
+        })?,
+        funds: vec![],
+    })];
+    let (bounty, bounty_msgs) = pay_bounty(
+        deps.as_ref(),
+        &env,
+        &config,
+        &info.sender,
+        config.finalize_bounty,
+    )?;
+    msgs.extend(bounty_msgs);
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "finalize"),
+            ("keeper", info.sender.as_str()),
+            ("round", &round.to_string()),
+            ("bounty", &bounty.to_string()),
+        ])
+        .add_messages(msgs))
+}
+
+fn execute_distribute(
+    deps: DepsMut,
+    info: MessageInfo,
+    round: u64,
+    limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_authorized_keeper(deps.storage, &config, &info.sender)?;
+    if limit == Some(0) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Distribute limit must be above 0",
+        )));
+    }
+
+    // only pay for batches that actually distribute something
+    let bidding_info: BiddingInfoResponse = deps
+        .querier
+        .query_wasm_smart(&config.bid_pool, &BidPoolQueryMsg::BiddingInfo { round })?;
+    let num_bids: u64 = deps.querier.query_wasm_smart(
+        &config.bid_pool,
+        &BidPoolQueryMsg::NumbersBidInRound { round },
+    )?;
+    if !bidding_info.distribution_info.is_released
+        || bidding_info.distribution_info.num_bids_distributed >= num_bids
+    {
+        return Err(ContractError::NothingToDistribute { round });
+    }
+
+    // the bounty is paid in the reply, once the bids the batch distributed can be counted
+    PENDING_DISTRIBUTE.save(
+        deps.storage,
+        &PendingDistribute {
+            keeper: info.sender.clone(),
+            round,
+            num_bids_distributed: bidding_info.distribution_info.num_bids_distributed,
+        },
+    )?;
+    let distribute_msg = SubMsg::reply_on_success(
+        WasmMsg::Execute {
+            contract_addr: config.bid_pool.to_string(),
+            msg: to_json_binary(&BidPoolExecuteMsg::Distribute {
+                round,
+                start_after: None,
+                limit,
+            })?,
+            funds: vec![],
+        },
+        DISTRIBUTE_REPLY_ID,
+    );
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "distribute"),
+            ("keeper", info.sender.as_str()),
+            ("round", &round.to_string()),
+        ])
+        .add_submessage(distribute_msg))
+}
+
+// pays distribute_bounty pro rata to the bids the batch moved num_bids_distributed by
+fn reply_distribute(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending = PENDING_DISTRIBUTE.load(deps.storage)?;
+    PENDING_DISTRIBUTE.remove(deps.storage);
+    let config = CONFIG.load(deps.storage)?;
+
+    let bidding_info: BiddingInfoResponse = deps.querier.query_wasm_smart(
+        &config.bid_pool,
+        &BidPoolQueryMsg::BiddingInfo {
+            round: pending.round,
+        },
+    )?;
+    let num_distributed = bidding_info
+        .distribution_info
+        .num_bids_distributed
+        .saturating_sub(pending.num_bids_distributed);
+    let (bounty, msgs) = pay_bounty(
+        deps.as_ref(),
+        &env,
+        &config,
+        &pending.keeper,
+        config
+            .distribute_bounty
+            .multiply_ratio(num_distributed, BOUNTY_BATCH_SIZE),
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "distribute_bounty"),
+            ("keeper", pending.keeper.as_str()),
+            ("round", &pending.round.to_string()),
+            ("num_bids_distributed", &num_distributed.to_string()),
+            ("bounty", &bounty.to_string()),
+        ])
+        .add_messages(msgs))
+}
+
+// only owner can slash a keeper, e.g. after a dispute
+fn execute_slash(
+    deps: DepsMut,
+    info: MessageInfo,
+    keeper: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let keeper = deps.api.addr_validate(&keeper)?;
+    let (slashed, msgs) = slash_keeper(deps.storage, &config, &keeper, amount)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "slash"),
+            ("keeper", keeper.as_str()),
+            ("amount", &slashed.to_string()),
+        ])
+        .add_messages(msgs))
+}
+
+fn assert_authorized_keeper(
+    storage: &dyn Storage,
+    config: &Config,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    let authorized = KEEPERS
+        .may_load(storage, sender)?
+        .map_or(false, |keeper| keeper.is_authorized(config));
+    if !authorized {
+        return Err(ContractError::KeeperNotAuthorized {
+            min_bond: config.min_bond.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// move up to `amount` of the keeper bond to the owner
+fn slash_keeper(
+    storage: &mut dyn Storage,
+    config: &Config,
+    keeper_addr: &Addr,
+    amount: Uint128,
+) -> StdResult<(Uint128, Vec<CosmosMsg>)> {
+    let mut keeper = KEEPERS.load(storage, keeper_addr)?;
+    let slashed = amount.min(keeper.bond);
+
+    keeper.bond -= slashed;
+    KEEPERS.save(storage, keeper_addr, &keeper)?;
+    TOTAL_BONDED.update(storage, |total| -> StdResult<_> { Ok(total - slashed) })?;
+
+    if slashed.is_zero() {
+        return Ok((slashed, vec![]));
+    }
+    Ok((
+        slashed,
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: config.owner.to_string(),
+            amount: coins(slashed.u128(), &config.bond_denom),
+        })],
+    ))
+}
+
+// pay the bounty out of the registry balance, never touching the bonds
+fn pay_bounty(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    keeper: &Addr,
+    bounty: Uint128,
+) -> StdResult<(Uint128, Vec<CosmosMsg>)> {
+    let mut available = deps
+        .querier
+        .query_balance(&env.contract.address, &config.bounty_denom)?
+        .amount;
+    if config.bounty_denom == config.bond_denom {
+        available = available.saturating_sub(TOTAL_BONDED.load(deps.storage)?);
+    }
+
+    let paid = bounty.min(available);
+    if paid.is_zero() {
+        return Ok((paid, vec![]));
+    }
+    Ok((
+        paid,
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: keeper.to_string(),
+            amount: coins(paid.u128(), &config.bounty_denom),
+        })],
+    ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        DISTRIBUTE_REPLY_ID => reply_distribute(deps, env),
+        id => Err(ContractError::Std(StdError::generic_err(format!(
+            "Unknown reply id {}",
+            id
+        )))),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::Keeper { address } => to_json_binary(&query_keeper(deps, address)?),
+        QueryMsg::Keepers { start_after, limit } => {
+            to_json_binary(&query_keepers(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_keeper(deps: Deps, address: String) -> StdResult<KeeperResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let address = deps.api.addr_validate(&address)?;
+    let keeper = KEEPERS.load(deps.storage, &address)?;
+
+    Ok(KeeperResponse::new(address, keeper, &config))
+}
+
+fn query_keepers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u64>,
+) -> StdResult<Vec<KeeperResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+    let start_after = start_after
+        .map(|start_after| deps.api.addr_validate(&start_after))
+        .transpose()?;
+
+    Ok(read_keepers(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(address, keeper)| KeeperResponse::new(address, keeper, &config))
+        .collect())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
@@ -0,0 +1,28 @@
+use cosmwasm_std::{OverflowError, StdError};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Keeper is not authorized, bond at least {min_bond}")]
+    KeeperNotAuthorized { min_bond: String },
+
+    #[error("Keeper is unbonding")]
+    KeeperUnbonding {},
+
+    #[error("Bond can be withdrawn at {release_time}")]
+    UnbondingNotFinished { release_time: u64 },
+
+    #[error("Nothing to distribute in round {round}")]
+    NothingToDistribute { round: u64 },
+}
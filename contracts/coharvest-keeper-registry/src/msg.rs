@@ -0,0 +1,121 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+
+use crate::state::{Config, Keeper};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub bid_pool: String,
+    pub bond_denom: String,
+    pub min_bond: Uint128,
+    pub unbonding_period: u64,
+    pub bounty_denom: String,
+    pub finalize_bounty: Uint128,
+    pub distribute_bounty: Uint128,
+    pub reference_pair: Option<String>,
+    pub max_rate_deviation: Decimal,
+    pub slash_amount: Uint128,
+}
+
+#[cw_serde]
+#[allow(clippy::large_enum_variant)]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        owner: Option<String>,
+        bid_pool: Option<String>,
+        min_bond: Option<Uint128>,
+        unbonding_period: Option<u64>,
+        finalize_bounty: Option<Uint128>,
+        distribute_bounty: Option<Uint128>,
+        reference_pair: Option<String>,
+        max_rate_deviation: Option<Decimal>,
+        slash_amount: Option<Uint128>,
+    },
+    Bond {},
+    Unbond {},
+    WithdrawBond {},
+    // keepers only, finalize the round on the bid pool and collect the bounty
+    Finalize {
+        round: u64,
+        exchange_rate: Decimal,
+    },
+    // keepers only, distribute the next batch of bids and collect the bounty
+    Distribute {
+        round: u64,
+        limit: Option<u64>,
+    },
+    Slash {
+        keeper: String,
+        amount: Uint128,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(KeeperResponse)]
+    Keeper { address: String },
+    #[returns(Vec<KeeperResponse>)]
+    Keepers {
+        start_after: Option<String>,
+        limit: Option<u64>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub bid_pool: Addr,
+    pub bond_denom: String,
+    pub min_bond: Uint128,
+    pub unbonding_period: u64,
+    pub bounty_denom: String,
+    pub finalize_bounty: Uint128,
+    pub distribute_bounty: Uint128,
+    pub reference_pair: Option<Addr>,
+    pub max_rate_deviation: Decimal,
+    pub slash_amount: Uint128,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            bid_pool: config.bid_pool,
+            bond_denom: config.bond_denom,
+            min_bond: config.min_bond,
+            unbonding_period: config.unbonding_period,
+            bounty_denom: config.bounty_denom,
+            finalize_bounty: config.finalize_bounty,
+            distribute_bounty: config.distribute_bounty,
+            reference_pair: config.reference_pair,
+            max_rate_deviation: config.max_rate_deviation,
+            slash_amount: config.slash_amount,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct KeeperResponse {
+    pub address: Addr,
+    pub bond: Uint128,
+    pub unbonding_since: Option<u64>,
+    pub is_authorized: bool,
+}
+
+impl KeeperResponse {
+    pub fn new(address: Addr, keeper: Keeper, config: &Config) -> Self {
+        Self {
+            is_authorized: keeper.is_authorized(config),
+            address,
+            bond: keeper.bond,
+            unbonding_since: keeper.unbonding_since,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
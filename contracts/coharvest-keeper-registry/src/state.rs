@@ -0,0 +1,66 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// mapping keeper address --> Keeper
+pub const KEEPERS: Map<&Addr, Keeper> = Map::new("keepers");
+// sum of all keeper bonds, the rest of the bond_denom balance is bounty money
+pub const TOTAL_BONDED: Item<Uint128> = Item::new("total_bonded");
+// distribute batch waiting for its reply, the bounty is paid for the bids it distributed
+pub const PENDING_DISTRIBUTE: Item<PendingDistribute> = Item::new("pending_distribute");
+
+const MAX_LIMIT: u64 = 100;
+const DEFAULT_LIMIT: u64 = 30;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,                  // owner address, receives slashed bonds
+    pub bid_pool: Addr,               // bid pool operated by the keepers
+    pub bond_denom: String,           // native token keepers bond
+    pub min_bond: Uint128,            // minimum bond to be authorized
+    pub unbonding_period: u64,        // time between unbonding and withdrawing the bond
+    pub bounty_denom: String,         // native token bounties are paid in
+    pub finalize_bounty: Uint128,     // bounty for finalizing a round
+    pub distribute_bounty: Uint128,   // bounty for distributing 100 bids, paid pro rata
+    pub reference_pair: Option<Addr>, // oraiswap pair checking the rates submitted by keepers
+    pub max_rate_deviation: Decimal,  // tolerated deviation from the reference rate
+    pub slash_amount: Uint128,        // amount slashed for an out of band rate
+}
+
+#[cw_serde]
+pub struct PendingDistribute {
+    pub keeper: Addr,
+    pub round: u64,
+    pub num_bids_distributed: u64, // bids of round distributed before the batch
+}
+
+#[cw_serde]
+pub struct Keeper {
+    pub bond: Uint128,
+    pub unbonding_since: Option<u64>, // keepers stop being authorized once they unbond
+}
+
+impl Keeper {
+    pub fn is_authorized(&self, config: &Config) -> bool {
+        self.unbonding_since.is_none() && self.bond >= config.min_bond
+    }
+}
+
+pub fn read_keepers(
+    storage: &dyn Storage,
+    start_after: Option<Addr>,
+    limit: Option<u64>,
+) -> StdResult<Vec<(Addr, Keeper)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    KEEPERS
+        .range(
+            storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
@@ -0,0 +1,453 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{
+    attr, coins, from_json,
+    testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    },
+    to_json_binary, Addr, BankMsg, Binary, ContractResult, CosmosMsg, Decimal, OwnedDeps, Reply,
+    StdError, SubMsg, SubMsgResponse, SubMsgResult, SystemError, SystemResult, Uint128, WasmMsg,
+    WasmQuery,
+};
+use oraiswap::{
+    asset::{Asset, AssetInfo},
+    pair::PoolResponse,
+};
+
+use coharvest_bid_pool::{
+    msg::{
+        BiddingInfoResponse, ConfigResponse as BidPoolConfigResponse,
+        ExecuteMsg as BidPoolExecuteMsg, QueryMsg as BidPoolQueryMsg,
+    },
+    state::{BiddingInfo, DistributionInfo},
+};
+
+use crate::{
+    contract::{execute, instantiate, query, reply},
+    error::ContractError,
+    msg::{ExecuteMsg, InstantiateMsg, KeeperResponse, QueryMsg},
+};
+
+const OWNER: &str = "owner";
+const BID_POOL: &str = "bid_pool";
+const PAIR: &str = "pair";
+const ORAIX_ADDR: &str = "oraix";
+const USDC: &str = "usdc";
+const KEEPER: &str = "keeper";
+
+fn bid_pool_query(msg: &Binary, oracle_pair: Option<&str>, num_bids_distributed: u64) -> Binary {
+    match from_json(msg).unwrap() {
+        BidPoolQueryMsg::Config {} => to_json_binary(&BidPoolConfigResponse {
+            owner: Addr::unchecked(OWNER),
+            underlying_token: AssetInfo::Token {
+                contract_addr: Addr::unchecked(ORAIX_ADDR),
+            },
+            distribution_token: AssetInfo::NativeToken {
+                denom: USDC.to_string(),
+            },
+            max_slot: 25,
+            premium_rate_per_slot: Decimal::from_str("0.01").unwrap(),
+            min_deposit_amount: Uint128::from(100_000000u128),
+            treasury: Addr::unchecked("treasury"),
+            bidding_duration: 86400,
+            oracle_pair: oracle_pair.map(Addr::unchecked),
+            vesting_contract: None,
+            keeper_registry: Some(Addr::unchecked("cosmos2contract")),
+            staking_contract: None,
+            residue_staking: None,
+            wrapped_denom: None,
+            min_gap_between_rounds: 0,
+        }),
+        // round 1 is finalized with some of its 3 bids distributed, round 2 is not finalized
+        BidPoolQueryMsg::BiddingInfo { round } => to_json_binary(&BiddingInfoResponse {
+            bid_info: BiddingInfo {
+                round,
+                start_time: 0,
+                end_time: 1000,
+                total_bid_amount: Uint128::from(3000_000000u128),
+                total_bid_matched: Uint128::zero(),
+                creator: None,
+            },
+            distribution_info: DistributionInfo {
+                total_distribution: Uint128::from(1000_000000u128),
+                exchange_rate: Decimal::zero(),
+                is_released: round == 1,
+                actual_distributed: Uint128::zero(),
+                num_bids_distributed,
+            },
+        }),
+        BidPoolQueryMsg::NumbersBidInRound { .. } => to_json_binary(&3u64),
+        _ => panic!("unexpected bid pool query"),
+    }
+    .unwrap()
+}
+
+fn distribute_reply() -> Reply {
+    Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    }
+}
+
+// 1 ORAIX = 0.01 USDC on the reference pair
+fn pair_query() -> Binary {
+    to_json_binary(&PoolResponse {
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDC.to_string(),
+                },
+                amount: Uint128::from(10_000_000000u128),
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked(ORAIX_ADDR),
+                },
+                amount: Uint128::from(1_000_000_000000u128),
+            },
+        ],
+        total_share: Uint128::zero(),
+    })
+    .unwrap()
+}
+
+fn mock_bid_pool(
+    querier: &mut MockQuerier,
+    oracle_pair: Option<&'static str>,
+    num_bids_distributed: u64,
+) {
+    querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == BID_POOL => SystemResult::Ok(
+            ContractResult::Ok(bid_pool_query(msg, oracle_pair, num_bids_distributed)),
+        ),
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == PAIR => {
+            SystemResult::Ok(ContractResult::Ok(pair_query()))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+}
+
+fn init() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies_with_balance(&coins(100_000000u128, USDC));
+    mock_bid_pool(&mut deps.querier, None, 1);
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        bid_pool: BID_POOL.to_string(),
+        bond_denom: "orai".to_string(),
+        min_bond: Uint128::from(100_000000u128),
+        unbonding_period: 1000,
+        bounty_denom: USDC.to_string(),
+        finalize_bounty: Uint128::from(10_000000u128),
+        distribute_bounty: Uint128::from(1_000000u128),
+        reference_pair: Some(PAIR.to_string()),
+        max_rate_deviation: Decimal::from_str("0.05").unwrap(),
+        slash_amount: Uint128::from(40_000000u128),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+    deps
+}
+
+#[test]
+fn test_finalize_and_slash() {
+    let mut deps = init();
+    let finalize = |exchange_rate: &str| ExecuteMsg::Finalize {
+        round: 1,
+        exchange_rate: Decimal::from_str(exchange_rate).unwrap(),
+    };
+
+    // bond is below the minimum
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &coins(50_000000u128, "orai")),
+        ExecuteMsg::Bond {},
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &[]),
+        finalize("0.0101"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::KeeperNotAuthorized {
+            min_bond: "100000000".to_string()
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &coins(50_000000u128, "orai")),
+        ExecuteMsg::Bond {},
+    )
+    .unwrap();
+
+    // 1% off the reference rate is tolerated
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &[]),
+        finalize("0.0101"),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: BID_POOL.to_string(),
+                msg: to_json_binary(&BidPoolExecuteMsg::FinalizeBiddingRoundResult {
+                    round: 1,
+                    exchange_rate: Decimal::from_str("0.0101").unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: KEEPER.to_string(),
+                amount: coins(10_000000u128, USDC),
+            })),
+        ]
+    );
+
+    // twice the reference rate gets the keeper slashed, the round is not finalized
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &[]),
+        finalize("0.02"),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: OWNER.to_string(),
+            amount: coins(40_000000u128, "orai"),
+        }))]
+    );
+
+    let keeper: KeeperResponse = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Keeper {
+                address: KEEPER.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        keeper,
+        KeeperResponse {
+            address: Addr::unchecked(KEEPER),
+            bond: Uint128::from(60_000000u128),
+            unbonding_since: None,
+            is_authorized: false,
+        }
+    );
+
+    // in oracle mode the rate is forwarded, the bid pool checks it against its oracle pair
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &coins(40_000000u128, "orai")),
+        ExecuteMsg::Bond {},
+    )
+    .unwrap();
+    mock_bid_pool(&mut deps.querier, Some("oracle_pair"), 1);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &[]),
+        finalize("0.02"),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[0], attr("action", "finalize"));
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: BID_POOL.to_string(),
+            msg: to_json_binary(&BidPoolExecuteMsg::FinalizeBiddingRoundResult {
+                round: 1,
+                exchange_rate: Decimal::from_str("0.02").unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    );
+}
+
+#[test]
+fn test_distribute() {
+    let mut deps = init();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &coins(100_000000u128, "orai")),
+        ExecuteMsg::Bond {},
+    )
+    .unwrap();
+
+    // an empty batch would be paid for nothing
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            limit: Some(0),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Distribute limit must be above 0"))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            limit: Some(50),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: BID_POOL.to_string(),
+                msg: to_json_binary(&BidPoolExecuteMsg::Distribute {
+                    round: 1,
+                    start_after: None,
+                    limit: Some(50),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            1,
+        )]
+    );
+
+    // the batch distributed the 2 remaining bids, 2% of the bounty for 100 bids is paid
+    mock_bid_pool(&mut deps.querier, None, 3);
+    let res = reply(deps.as_mut(), mock_env(), distribute_reply()).unwrap();
+    assert_eq!(res.attributes[3], attr("num_bids_distributed", "2"));
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: KEEPER.to_string(),
+            amount: coins(20000u128, USDC),
+        }))]
+    );
+
+    // a batch that distributed nothing pays nothing
+    mock_bid_pool(&mut deps.querier, None, 1);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let res = reply(deps.as_mut(), mock_env(), distribute_reply()).unwrap();
+    assert_eq!(res.attributes[4], attr("bounty", "0"));
+    assert!(res.messages.is_empty());
+
+    // no bounty for a round that cannot be distributed
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::Distribute {
+            round: 2,
+            limit: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToDistribute { round: 2 });
+}
+
+#[test]
+fn test_unbond() {
+    let mut deps = init();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(KEEPER, &coins(100_000000u128, "orai")),
+        ExecuteMsg::Bond {},
+    )
+    .unwrap();
+
+    let mut env = mock_env();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::Unbond {},
+    )
+    .unwrap();
+
+    // unbonding keepers cannot operate
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::Distribute {
+            round: 1,
+            limit: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::KeeperNotAuthorized {
+            min_bond: "100000000".to_string()
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::WithdrawBond {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnbondingNotFinished {
+            release_time: env.block.time.seconds() + 1000
+        }
+    );
+
+    env.block.time = env.block.time.plus_seconds(1000);
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::WithdrawBond {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: KEEPER.to_string(),
+            amount: coins(100_000000u128, "orai"),
+        }))]
+    );
+}
@@ -0,0 +1,236 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use oraiswap::{
+    asset::AssetInfo,
+    router::{Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation},
+};
+
+use coharvest_bid_pool::{
+    helper::{into_send_msg, query_balance},
+    msg::ExecuteMsg as BidPoolExecuteMsg,
+};
+
+use crate::{
+    error::ContractError,
+    msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
+    state::{asset_key, Config, CONFIG, ROUTES},
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        bid_pool: deps.api.addr_validate(&msg.bid_pool)?,
+        router: deps.api.addr_validate(&msg.router)?,
+        distribution_token: msg.distribution_token,
+    };
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            owner,
+            bid_pool,
+            router,
+        } => execute_update_config(deps, info, owner, bid_pool, router),
+        ExecuteMsg::SetRoute {
+            offer_asset,
+            operations,
+        } => execute_set_route(deps, info, offer_asset, operations),
+        ExecuteMsg::HarvestAndFund {
+            fee_tokens,
+            minimum_receive,
+        } => execute_harvest_and_fund(deps, env, info, fee_tokens, minimum_receive),
+        ExecuteMsg::FundRound { minimum_receive } => {
+            execute_fund_round(deps, env, info, minimum_receive)
+        }
+    }
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    bid_pool: Option<String>,
+    router: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(bid_pool) = bid_pool {
+        config.bid_pool = deps.api.addr_validate(&bid_pool)?;
+    }
+    if let Some(router) = router {
+        config.router = deps.api.addr_validate(&router)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+fn execute_set_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    offer_asset: AssetInfo,
+    operations: Vec<SwapOperation>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    ROUTES.save(deps.storage, asset_key(&offer_asset), &operations)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_route"),
+        ("offer_asset", asset_key(&offer_asset)),
+    ]))
+}
+
+// only owner can harvest, the swaps and the funding run in the same transaction
+fn execute_harvest_and_fund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    fee_tokens: Vec<AssetInfo>,
+    minimum_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for fee_token in fee_tokens {
+        if fee_token == config.distribution_token {
+            continue;
+        }
+
+        let amount = query_balance(&deps.querier, &fee_token, env.contract.address.clone())?;
+        if amount.is_zero() {
+            continue;
+        }
+        let operations = ROUTES
+            .may_load(deps.storage, asset_key(&fee_token))?
+            .ok_or_else(|| ContractError::NoRoute {
+                asset: asset_key(&fee_token).to_string(),
+            })?;
+
+        msgs.push(match fee_token {
+            AssetInfo::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: config.router.to_string(),
+                    amount,
+                    msg: to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations,
+                        minimum_receive: None,
+                        to: None,
+                    })?,
+                })?,
+                funds: vec![],
+            }),
+            AssetInfo::NativeToken { denom } => CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: config.router.to_string(),
+                msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: None,
+                })?,
+                funds: vec![Coin { denom, amount }],
+            }),
+        });
+    }
+
+    // fund the round once every swap has been executed
+    msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_json_binary(&ExecuteMsg::FundRound { minimum_receive })?,
+        funds: vec![],
+    }));
+
+    Ok(Response::new()
+        .add_attribute("action", "harvest_and_fund")
+        .add_messages(msgs))
+}
+
+fn execute_fund_round(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    minimum_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let amount = query_balance(
+        &deps.querier,
+        &config.distribution_token,
+        env.contract.address,
+    )?;
+    let minimum_receive = minimum_receive.unwrap_or_default();
+    if amount.is_zero() || amount < minimum_receive {
+        return Err(ContractError::MinimumReceive {
+            received: amount,
+            minimum_receive,
+        });
+    }
+
+    // the cw20 hook and the native message of the bid pool have the same shape
+    let msg = into_send_msg(
+        &config.distribution_token,
+        config.bid_pool.to_string(),
+        amount,
+        to_json_binary(&BidPoolExecuteMsg::CreateNewRoundFromTreasury {})?,
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "fund_round"),
+            ("amount", &amount.to_string()),
+        ])
+        .add_message(msg))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::Route { offer_asset } => {
+            to_json_binary(&ROUTES.load(deps.storage, asset_key(&offer_asset))?)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
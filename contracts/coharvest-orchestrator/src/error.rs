@@ -0,0 +1,20 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("No swap route for {asset}")]
+    NoRoute { asset: String },
+
+    #[error("Received {received}, less than the minimum {minimum_receive}")]
+    MinimumReceive {
+        received: Uint128,
+        minimum_receive: Uint128,
+    },
+}
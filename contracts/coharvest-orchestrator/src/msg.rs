@@ -0,0 +1,66 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+use oraiswap::{asset::AssetInfo, router::SwapOperation};
+
+use crate::state::Config;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub bid_pool: String,
+    pub router: String,
+    pub distribution_token: AssetInfo,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        owner: Option<String>,
+        bid_pool: Option<String>,
+        router: Option<String>,
+    },
+    SetRoute {
+        offer_asset: AssetInfo,
+        operations: Vec<SwapOperation>,
+    },
+    // swap the whole balance of every fee token, then fund the next round with the result
+    HarvestAndFund {
+        fee_tokens: Vec<AssetInfo>,
+        minimum_receive: Option<Uint128>,
+    },
+    // internal, runs after the swaps
+    FundRound {
+        minimum_receive: Option<Uint128>,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(Vec<SwapOperation>)]
+    Route { offer_asset: AssetInfo },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub bid_pool: Addr,
+    pub router: Addr,
+    pub distribution_token: AssetInfo,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            bid_pool: config.bid_pool,
+            router: config.router,
+            distribution_token: config.distribution_token,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
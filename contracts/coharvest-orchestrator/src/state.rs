@@ -0,0 +1,23 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+use oraiswap::{asset::AssetInfo, router::SwapOperation};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// mapping offer asset --> oraiswap operations swapping it into the distribution token
+pub const ROUTES: Map<&str, Vec<SwapOperation>> = Map::new("routes");
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,                   // owner address
+    pub bid_pool: Addr,                // bid pool funded by the orchestrator, which is its treasury
+    pub router: Addr,                  // oraiswap router
+    pub distribution_token: AssetInfo, // token every fee token is swapped into
+}
+
+pub fn asset_key(asset_info: &AssetInfo) -> &str {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => contract_addr.as_str(),
+        AssetInfo::NativeToken { denom } => denom.as_str(),
+    }
+}
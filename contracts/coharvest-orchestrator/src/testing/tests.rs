@@ -0,0 +1,236 @@
+use cosmwasm_std::{
+    coins, from_json,
+    testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    },
+    to_json_binary, Addr, ContractResult, CosmosMsg, OwnedDeps, SubMsg, SystemError, SystemResult,
+    Uint128, WasmMsg, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+use oraiswap::{
+    asset::AssetInfo,
+    router::{Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation},
+};
+
+use coharvest_bid_pool::msg::Cw20HookMsg as BidPoolCw20HookMsg;
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
+};
+
+const OWNER: &str = "owner";
+const BID_POOL: &str = "bid_pool";
+const ROUTER: &str = "router";
+const USDC: &str = "usdc_token";
+const FEE_TOKEN: &str = "fee_token";
+
+fn init() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies_with_balance(&coins(500_000000u128, "orai"));
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } => {
+            let balance = match contract_addr.as_str() {
+                USDC => 1000_000000u128,
+                FEE_TOKEN => 200_000000u128,
+                _ => panic!("unexpected token"),
+            };
+            let Cw20QueryMsg::Balance { .. } = from_json(msg).unwrap() else {
+                panic!("unexpected query")
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&BalanceResponse {
+                    balance: Uint128::from(balance),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        bid_pool: BID_POOL.to_string(),
+        router: ROUTER.to_string(),
+        distribution_token: AssetInfo::Token {
+            contract_addr: Addr::unchecked(USDC),
+        },
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+    deps
+}
+
+fn route(offer_asset_info: AssetInfo) -> Vec<SwapOperation> {
+    vec![SwapOperation::OraiSwap {
+        offer_asset_info,
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: Addr::unchecked(USDC),
+        },
+    }]
+}
+
+#[test]
+fn test_harvest_and_fund() {
+    let mut deps = init();
+    let orai = AssetInfo::NativeToken {
+        denom: "orai".to_string(),
+    };
+    let fee_token = AssetInfo::Token {
+        contract_addr: Addr::unchecked(FEE_TOKEN),
+    };
+    let msg = ExecuteMsg::HarvestAndFund {
+        fee_tokens: vec![fee_token.clone(), orai.clone()],
+        minimum_receive: Some(Uint128::from(1000_000000u128)),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NoRoute {
+            asset: FEE_TOKEN.to_string()
+        }
+    );
+
+    for offer_asset in [fee_token.clone(), orai.clone()] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::SetRoute {
+                offer_asset: offer_asset.clone(),
+                operations: route(offer_asset),
+            },
+        )
+        .unwrap();
+    }
+    let operations: Vec<SwapOperation> = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Route {
+                offer_asset: orai.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(operations, route(orai.clone()));
+
+    // every fee token is swapped, then the round is funded
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: FEE_TOKEN.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: ROUTER.to_string(),
+                    amount: Uint128::from(200_000000u128),
+                    msg: to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: route(fee_token),
+                        minimum_receive: None,
+                        to: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ROUTER.to_string(),
+                msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: route(orai),
+                    minimum_receive: None,
+                    to: None,
+                })
+                .unwrap(),
+                funds: coins(500_000000u128, "orai"),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: mock_env().contract.address.to_string(),
+                msg: to_json_binary(&ExecuteMsg::FundRound {
+                    minimum_receive: Some(Uint128::from(1000_000000u128)),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_fund_round() {
+    let mut deps = init();
+    let contract = mock_env().contract.address;
+
+    // only the orchestrator itself can fund the round
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::FundRound {
+            minimum_receive: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // swaps returned less than expected
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(contract.as_str(), &[]),
+        ExecuteMsg::FundRound {
+            minimum_receive: Some(Uint128::from(2000_000000u128)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MinimumReceive {
+            received: Uint128::from(1000_000000u128),
+            minimum_receive: Uint128::from(2000_000000u128),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(contract.as_str(), &[]),
+        ExecuteMsg::FundRound {
+            minimum_receive: Some(Uint128::from(1000_000000u128)),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: BID_POOL.to_string(),
+                amount: Uint128::from(1000_000000u128),
+                msg: to_json_binary(&BidPoolCw20HookMsg::CreateNewRoundFromTreasury {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
@@ -0,0 +1,245 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    StdError, StdResult, SubMsg, SubMsgResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ReceiveMsg;
+use cw_utils::{one_coin, parse_execute_response_data};
+use oraiswap::asset::{Asset, AssetInfo};
+
+use coharvest_bid_pool::{
+    helper::into_send_msg,
+    msg::{Cw20HookMsg as BidPoolCw20HookMsg, RoutedBid},
+};
+
+use crate::{
+    error::ContractError,
+    msg::{
+        ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RouteLeg,
+        RouteResponse,
+    },
+    state::{Config, PendingRoute, PoolBids, CONFIG, PENDING_ROUTE},
+};
+
+// version info for migration
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const ROUTE_REPLY_ID: u64 = 1;
+const MAX_LEGS: usize = 20;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+    };
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::UpdateConfig { owner } => execute_update_config(deps, info, owner),
+        ExecuteMsg::RouteBids { legs } => {
+            let coin = one_coin(&info)?;
+            execute_route_bids(
+                deps,
+                info.sender,
+                legs,
+                Asset {
+                    info: AssetInfo::NativeToken { denom: coin.denom },
+                    amount: coin.amount,
+                },
+            )
+        }
+    }
+}
+
+fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::RouteBids { legs } => {
+            let bidder = deps.api.addr_validate(&cw20_msg.sender)?;
+            execute_route_bids(
+                deps,
+                bidder,
+                legs,
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: info.sender,
+                    },
+                    amount: cw20_msg.amount,
+                },
+            )
+        }
+    }
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+// the legs of a bid pool are sent in one SubmitRoutedBids crediting the bidder, a failing bid
+// pool reverts the whole route
+fn execute_route_bids(
+    deps: DepsMut,
+    bidder: Addr,
+    legs: Vec<RouteLeg>,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    if legs.is_empty() || legs.len() > MAX_LEGS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "A route must have between 1 and {} legs",
+            MAX_LEGS
+        ))));
+    }
+    let total_amount = legs
+        .iter()
+        .try_fold(Uint128::zero(), |total, leg| total.checked_add(leg.amount))?;
+    if total_amount != funds.amount {
+        return Err(ContractError::InvalidFunds {
+            expected: total_amount,
+            received: funds.amount,
+        });
+    }
+
+    // group the legs by bid pool, keeping the order in which the bid pools first appear
+    let mut groups: Vec<(Addr, Vec<RoutedBid>)> = vec![];
+    for leg in legs {
+        let bid_pool = deps.api.addr_validate(&leg.bid_pool)?;
+        let bid = RoutedBid {
+            bidder: bidder.to_string(),
+            round: leg.round,
+            premium_slot: leg.premium_slot,
+            amount: leg.amount,
+        };
+        match groups.iter_mut().find(|(addr, _)| *addr == bid_pool) {
+            Some((_, bids)) => bids.push(bid),
+            None => groups.push((bid_pool, vec![bid])),
+        }
+    }
+
+    let mut msgs = vec![];
+    for (bid_pool, bids) in &groups {
+        let amount = bids
+            .iter()
+            .try_fold(Uint128::zero(), |total, bid| total.checked_add(bid.amount))?;
+        msgs.push(SubMsg::reply_on_success(
+            into_send_msg(
+                &funds.info,
+                bid_pool.to_string(),
+                amount,
+                to_json_binary(&BidPoolCw20HookMsg::SubmitRoutedBids { bids: bids.clone() })?,
+            )?,
+            ROUTE_REPLY_ID,
+        ));
+    }
+    PENDING_ROUTE.save(
+        deps.storage,
+        &PendingRoute {
+            bidder: bidder.clone(),
+            total_amount,
+            bid_pools: groups.into_iter().map(|(bid_pool, _)| bid_pool).collect(),
+            results: vec![],
+        },
+    )?;
+
+    Ok(Response::new().add_submessages(msgs).add_attributes(vec![
+        ("action", "route_bids"),
+        ("bidder", bidder.as_str()),
+        ("total_amount", &total_amount.to_string()),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        ROUTE_REPLY_ID => reply_route(deps, msg),
+        id => Err(ContractError::Std(StdError::generic_err(format!(
+            "Unknown reply id {}",
+            id
+        )))),
+    }
+}
+
+// records the bids created by a bid pool, the reply of the last bid pool sets the data of the
+// route as a RouteResponse
+fn reply_route(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let SubMsgResult::Ok(response) = msg.result else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Routed bids failed",
+        )));
+    };
+    let data = response
+        .data
+        .ok_or_else(|| StdError::generic_err("Bid pool returned no data"))?;
+    let idxs: Vec<u64> = from_json(
+        &parse_execute_response_data(&data)
+            .map_err(|err| StdError::generic_err(err.to_string()))?
+            .data
+            .unwrap_or_default(),
+    )?;
+
+    let mut pending = PENDING_ROUTE.load(deps.storage)?;
+    let bid_pool = pending.bid_pools[pending.results.len()].clone();
+    pending.results.push(PoolBids { bid_pool, idxs });
+    if pending.results.len() < pending.bid_pools.len() {
+        PENDING_ROUTE.save(deps.storage, &pending)?;
+        return Ok(Response::new());
+    }
+
+    PENDING_ROUTE.remove(deps.storage);
+    Ok(Response::new().set_data(to_json_binary(&RouteResponse {
+        bidder: pending.bidder,
+        total_amount: pending.total_amount,
+        bids: pending.results,
+    })?))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,62 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::state::{Config, PoolBids};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateConfig { owner: Option<String> },
+    // splits the attached native tokens across the legs, the router must be a trusted router of
+    // every bid pool. The data of the response is a RouteResponse
+    RouteBids { legs: Vec<RouteLeg> },
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    RouteBids { legs: Vec<RouteLeg> },
+}
+
+#[cw_serde]
+pub struct RouteLeg {
+    pub bid_pool: String,
+    pub round: u64,
+    pub premium_slot: u16,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct RouteResponse {
+    pub bidder: Addr,
+    pub total_amount: Uint128,
+    pub bids: Vec<PoolBids>, // in the order the bid pools first appear in the legs
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -0,0 +1,26 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::Item;
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// route being executed, filled by the replies of its bid pools
+pub const PENDING_ROUTE: Item<PendingRoute> = Item::new("pending_route");
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr, // owner address
+}
+
+#[cw_serde]
+pub struct PoolBids {
+    pub bid_pool: Addr,
+    pub idxs: Vec<u64>, // bids created in the bid pool, in the order of the legs
+}
+
+#[cw_serde]
+pub struct PendingRoute {
+    pub bidder: Addr,
+    pub total_amount: Uint128,
+    pub bid_pools: Vec<Addr>,   // in the order of their submessages
+    pub results: Vec<PoolBids>, // one per bid pool that has replied
+}
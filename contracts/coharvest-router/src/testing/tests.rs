@@ -0,0 +1,168 @@
+use cosmwasm_std::{
+    coins, from_json,
+    testing::{mock_dependencies, mock_env, mock_info},
+    to_json_binary, Addr, Binary, CosmosMsg, Reply, SubMsg, SubMsgResponse, SubMsgResult, Uint128,
+    WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+use coharvest_bid_pool::msg::{Cw20HookMsg as BidPoolCw20HookMsg, RoutedBid};
+
+use crate::{
+    contract::{execute, instantiate, reply, ROUTE_REPLY_ID},
+    error::ContractError,
+    msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, RouteLeg, RouteResponse},
+    state::PoolBids,
+};
+
+const OWNER: &str = "owner";
+const ORAIX_ADDR: &str = "oraix";
+
+fn leg(bid_pool: &str, round: u64, premium_slot: u16, amount: u128) -> RouteLeg {
+    RouteLeg {
+        bid_pool: bid_pool.to_string(),
+        round,
+        premium_slot,
+        amount: Uint128::from(amount),
+    }
+}
+
+// data of a MsgExecuteContractResponse as returned by the bid pool
+fn bid_pool_reply(idxs: Vec<u64>) -> Reply {
+    let data = to_json_binary(&idxs).unwrap();
+    let mut encoded = vec![0x0a, data.len() as u8];
+    encoded.extend_from_slice(data.as_slice());
+    Reply {
+        id: ROUTE_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(Binary::from(encoded)),
+        }),
+    }
+}
+
+#[test]
+fn test_route_bids() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        InstantiateMsg {
+            owner: OWNER.to_string(),
+        },
+    )
+    .unwrap();
+
+    let route = |amount: u128, legs: Vec<RouteLeg>| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr000".to_string(),
+            amount: Uint128::from(amount),
+            msg: to_json_binary(&Cw20HookMsg::RouteBids { legs }).unwrap(),
+        })
+    };
+    let legs = vec![
+        leg("bid_pool_a", 1, 1, 100_000000),
+        leg("bid_pool_b", 3, 2, 200_000000),
+        leg("bid_pool_a", 2, 5, 50_000000),
+    ];
+
+    // the deposit must cover the legs exactly
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &[]),
+        route(300_000000, legs.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFunds {
+            expected: Uint128::from(350_000000u128),
+            received: Uint128::from(300_000000u128),
+        }
+    );
+
+    // the legs of a bid pool are submitted together, credited to the sender of the deposit
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(ORAIX_ADDR, &[]),
+        route(350_000000, legs),
+    )
+    .unwrap();
+    let send = |bid_pool: &str, amount: u128, bids: Vec<(u64, u16, u128)>| {
+        SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ORAIX_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: bid_pool.to_string(),
+                    amount: Uint128::from(amount),
+                    msg: to_json_binary(&BidPoolCw20HookMsg::SubmitRoutedBids {
+                        bids: bids
+                            .into_iter()
+                            .map(|(round, premium_slot, amount)| RoutedBid {
+                                bidder: "addr000".to_string(),
+                                round,
+                                premium_slot,
+                                amount: Uint128::from(amount),
+                            })
+                            .collect(),
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            ROUTE_REPLY_ID,
+        )
+    };
+    assert_eq!(
+        res.messages,
+        vec![
+            send(
+                "bid_pool_a",
+                150_000000,
+                vec![(1, 1, 100_000000), (2, 5, 50_000000)]
+            ),
+            send("bid_pool_b", 200_000000, vec![(3, 2, 200_000000)]),
+        ]
+    );
+
+    // the last reply returns the bids of every bid pool
+    let res = reply(deps.as_mut(), env.clone(), bid_pool_reply(vec![7, 8])).unwrap();
+    assert_eq!(res.data, None);
+    let res = reply(deps.as_mut(), env.clone(), bid_pool_reply(vec![42])).unwrap();
+    let route: RouteResponse = from_json(&res.data.unwrap()).unwrap();
+    assert_eq!(
+        route,
+        RouteResponse {
+            bidder: Addr::unchecked("addr000"),
+            total_amount: Uint128::from(350_000000u128),
+            bids: vec![
+                PoolBids {
+                    bid_pool: Addr::unchecked("bid_pool_a"),
+                    idxs: vec![7, 8],
+                },
+                PoolBids {
+                    bid_pool: Addr::unchecked("bid_pool_b"),
+                    idxs: vec![42],
+                },
+            ],
+        }
+    );
+
+    // a route needs at least one leg
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &coins(100, "orai")),
+        ExecuteMsg::RouteBids { legs: vec![] },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Generic error: A route must have between 1 and 20 legs"
+    );
+}
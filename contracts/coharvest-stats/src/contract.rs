@@ -0,0 +1,293 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+
+use coharvest_bid_pool::msg::{
+    BidPoolHookMsg, BidResponse, PageResponse, QueryMsg as BidPoolQueryMsg, RoundResultResponse,
+};
+
+use crate::{
+    error::ContractError,
+    msg::{
+        ConfigResponse, EpochStatsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg,
+        PendingRoundResponse, QueryMsg, SlotStatsResponse,
+    },
+    state::{
+        Config, EpochStats, PendingRound, SlotStats, UserTotals, CONFIG, EPOCH_STATS,
+        PENDING_ROUNDS, PROCESSED_ROUNDS, SLOT_STATS, USER_TOTALS,
+    },
+};
+
+// version info for migration
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const MAX_LIMIT: u64 = 1000;
+const DEFAULT_LIMIT: u64 = 30;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.epoch_duration == 0 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "epoch_duration must be greater than zero",
+        )));
+    }
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        bid_pool: deps.api.addr_validate(&msg.bid_pool)?,
+        epoch_duration: msg.epoch_duration,
+    };
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig { owner } => execute_update_config(deps, info, owner),
+        ExecuteMsg::BidPoolHook(msg) => execute_bid_pool_hook(deps, env, info, msg),
+        ExecuteMsg::ProcessRound { round, limit } => execute_process_round(deps, round, limit),
+    }
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+// a failing hook reverts the distribution of the bid pool, the round is only queued here and
+// its bids are rolled up by ProcessRound
+fn execute_bid_pool_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: BidPoolHookMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.bid_pool {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the payouts of a round are final once all its bids are distributed
+    let BidPoolHookMsg::DistributionCompleted { round } = msg else {
+        return Ok(Response::new().add_attribute("action", "bid_pool_hook"));
+    };
+    if !PENDING_ROUNDS.has(deps.storage, round) && !PROCESSED_ROUNDS.has(deps.storage, round) {
+        PENDING_ROUNDS.save(
+            deps.storage,
+            round,
+            &PendingRound {
+                epoch: config.epoch(env.block.time.seconds()),
+                totals_recorded: false,
+                cursor: None,
+            },
+        )?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "queue_round"),
+        ("round", &round.to_string()),
+    ]))
+}
+
+// the first call adds the round to the epoch and slot totals, every call then adds the next
+// bids of the round to the totals of their bidder
+fn execute_process_round(
+    deps: DepsMut,
+    round: u64,
+    limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pending = PENDING_ROUNDS
+        .may_load(deps.storage, round)?
+        .ok_or(ContractError::RoundNotPending { round })?;
+
+    if !pending.totals_recorded {
+        let result: RoundResultResponse = deps
+            .querier
+            .query_wasm_smart(&config.bid_pool, &BidPoolQueryMsg::RoundResult { round })?;
+        EPOCH_STATS.update(
+            deps.storage,
+            pending.epoch,
+            |stats| -> StdResult<EpochStats> {
+                let mut stats = stats.unwrap_or_default();
+                stats.underlying_burned =
+                    stats.underlying_burned.checked_add(result.total_matched)?;
+                stats.distribution_paid = stats
+                    .distribution_paid
+                    .checked_add(result.actual_distributed)?;
+                stats.num_rounds += 1;
+                Ok(stats)
+            },
+        )?;
+        for slot in result.slots {
+            SLOT_STATS.update(deps.storage, slot.slot, |stats| -> StdResult<SlotStats> {
+                let mut stats = stats.unwrap_or_default();
+                stats.total_bid_amount =
+                    stats.total_bid_amount.checked_add(slot.total_bid_amount)?;
+                stats.total_matched = stats.total_matched.checked_add(slot.matched_amount)?;
+                stats.total_payout = stats.total_payout.checked_add(slot.payout_amount)?;
+                Ok(stats)
+            })?;
+        }
+        pending.totals_recorded = true;
+    }
+
+    let bids: PageResponse<BidResponse> = deps.querier.query_wasm_smart(
+        &config.bid_pool,
+        &BidPoolQueryMsg::AllBidInRound {
+            round,
+            start_after: pending.cursor,
+            limit,
+            order_by: None,
+        },
+    )?;
+    for bid in bids.items.iter() {
+        let matched = bid.amount.checked_sub(bid.residue_bid)?;
+        let received = bid.amount_received.checked_sub(bid.performance_fee)?;
+        USER_TOTALS.update(
+            deps.storage,
+            &bid.bidder,
+            |totals| -> StdResult<UserTotals> {
+                let mut totals = totals.unwrap_or_default();
+                totals.num_bids += 1;
+                totals.total_bid = totals.total_bid.checked_add(bid.amount)?;
+                totals.total_matched = totals.total_matched.checked_add(matched)?;
+                totals.total_received = totals.total_received.checked_add(received)?;
+                Ok(totals)
+            },
+        )?;
+        SLOT_STATS.update(
+            deps.storage,
+            bid.premium_slot,
+            |stats| -> StdResult<SlotStats> {
+                let mut stats = stats.unwrap_or_default();
+                stats.num_bids += 1;
+                Ok(stats)
+            },
+        )?;
+    }
+
+    let finished = !bids.has_more;
+    if finished {
+        PENDING_ROUNDS.remove(deps.storage, round);
+        PROCESSED_ROUNDS.save(deps.storage, round, &true)?;
+    } else {
+        pending.cursor = bids.next_start_after;
+        PENDING_ROUNDS.save(deps.storage, round, &pending)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "process_round"),
+        ("round", &round.to_string()),
+        ("num_bids", &bids.items.len().to_string()),
+        ("finished", &finished.to_string()),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::EpochStats { start_after, limit } => {
+            to_json_binary(&query_epoch_stats(deps, start_after, limit)?)
+        }
+        QueryMsg::UserTotals { user } => to_json_binary(
+            &USER_TOTALS
+                .may_load(deps.storage, &deps.api.addr_validate(&user)?)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::SlotStats {} => to_json_binary(&query_slot_stats(deps)?),
+        QueryMsg::PendingRounds {} => to_json_binary(
+            &PENDING_ROUNDS
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| {
+                    let (round, pending) = item?;
+                    Ok(PendingRoundResponse { round, pending })
+                })
+                .collect::<StdResult<Vec<PendingRoundResponse>>>()?,
+        ),
+    }
+}
+
+// start_after is an epoch number, epoch_start of the last entry divided by the epoch duration
+fn query_epoch_stats(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<EpochStatsResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    EPOCH_STATS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (epoch, stats) = item?;
+            Ok(EpochStatsResponse {
+                epoch_start: epoch * config.epoch_duration,
+                underlying_burned: stats.underlying_burned,
+                distribution_paid: stats.distribution_paid,
+                num_rounds: stats.num_rounds,
+            })
+        })
+        .collect()
+}
+
+fn query_slot_stats(deps: Deps) -> StdResult<Vec<SlotStatsResponse>> {
+    SLOT_STATS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (slot, stats) = item?;
+            Ok(SlotStatsResponse {
+                slot,
+                num_bids: stats.num_bids,
+                total_bid_amount: stats.total_bid_amount,
+                total_matched: stats.total_matched,
+                total_payout: stats.total_payout,
+            })
+        })
+        .collect()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,84 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+
+use coharvest_bid_pool::msg::BidPoolHookMsg;
+
+use crate::state::{Config, PendingRound, UserTotals};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub bid_pool: String,
+    pub epoch_duration: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    UpdateConfig { owner: Option<String> },
+    // sent by the bid pool, the contract must be registered with its AddHook
+    BidPoolHook(BidPoolHookMsg),
+    // anyone can roll up the next bids of a completed round, limit bids at a time; the round must
+    // be processed before the bid pool prunes it
+    ProcessRound { round: u64, limit: Option<u64> },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(Vec<EpochStatsResponse>)]
+    EpochStats {
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
+    #[returns(UserTotals)]
+    UserTotals { user: String },
+    #[returns(Vec<SlotStatsResponse>)]
+    SlotStats {},
+    #[returns(Vec<PendingRoundResponse>)]
+    PendingRounds {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub bid_pool: Addr,
+    pub epoch_duration: u64,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            bid_pool: config.bid_pool,
+            epoch_duration: config.epoch_duration,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct EpochStatsResponse {
+    pub epoch_start: u64,
+    pub underlying_burned: Uint128,
+    pub distribution_paid: Uint128,
+    pub num_rounds: u64,
+}
+
+#[cw_serde]
+pub struct SlotStatsResponse {
+    pub slot: u16,
+    pub num_bids: u64,
+    pub total_bid_amount: Uint128,
+    pub total_matched: Uint128,
+    pub total_payout: Uint128,
+}
+
+#[cw_serde]
+pub struct PendingRoundResponse {
+    pub round: u64,
+    pub pending: PendingRound,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -0,0 +1,61 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// mapping epoch --> totals of the rounds completed in that epoch
+pub const EPOCH_STATS: Map<u64, EpochStats> = Map::new("epoch_stats");
+// mapping bidder --> totals of the bids over every processed round
+pub const USER_TOTALS: Map<&Addr, UserTotals> = Map::new("user_totals");
+// mapping slot --> totals of the slot over every processed round
+pub const SLOT_STATS: Map<u16, SlotStats> = Map::new("slot_stats");
+// mapping round --> progress of a completed round not fully rolled up yet
+pub const PENDING_ROUNDS: Map<u64, PendingRound> = Map::new("pending_rounds");
+// mapping round --> true once every bid of the round has been rolled up
+pub const PROCESSED_ROUNDS: Map<u64, bool> = Map::new("processed_rounds");
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,         // owner address
+    pub bid_pool: Addr,      // bid pool sending the lifecycle hooks
+    pub epoch_duration: u64, // length of an epoch, in seconds
+}
+
+impl Config {
+    pub fn epoch(&self, timestamp: u64) -> u64 {
+        timestamp / self.epoch_duration
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct EpochStats {
+    pub underlying_burned: Uint128, // underlying_token matched by the rounds
+    pub distribution_paid: Uint128, // distribution_token allocated by the rounds
+    pub num_rounds: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct UserTotals {
+    pub num_bids: u64,
+    pub total_bid: Uint128,      // underlying_token put up in bids
+    pub total_matched: Uint128,  // underlying_token matched
+    pub total_received: Uint128, // rewards paid, net of the performance fee
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct SlotStats {
+    pub num_bids: u64,
+    pub total_bid_amount: Uint128, // underlying_token bid in the slot
+    pub total_matched: Uint128,    // underlying_token of the slot matched
+    pub total_payout: Uint128,     // tokens allocated to the slot
+}
+
+#[cw_serde]
+pub struct PendingRound {
+    pub epoch: u64,            // epoch the round was completed in
+    pub totals_recorded: bool, // the epoch and slot totals have been added
+    pub cursor: Option<u64>,   // last bid rolled up into the user totals
+}
@@ -0,0 +1,271 @@
+use cosmwasm_std::{
+    from_json,
+    testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    to_json_binary, Binary, ContractResult, Decimal, OwnedDeps, SystemError, SystemResult, Uint128,
+    WasmQuery,
+};
+
+use coharvest_bid_pool::{
+    msg::{
+        BidPoolHookMsg, BidResponse, PageResponse, QueryMsg as BidPoolQueryMsg,
+        RoundResultResponse, SlotResult,
+    },
+    state::Bid,
+    test_utils::mock_bid,
+};
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    msg::{
+        EpochStatsResponse, ExecuteMsg, InstantiateMsg, PendingRoundResponse, QueryMsg,
+        SlotStatsResponse,
+    },
+    state::UserTotals,
+};
+
+const OWNER: &str = "owner";
+const BID_POOL: &str = "bid_pool";
+const DAY: u64 = 86400;
+
+// alice is filled in slot 1 and refunded in slot 2, bob is half filled in slot 2
+fn round_bids() -> Vec<Bid> {
+    let mut bids = vec![
+        mock_bid(1, 1, "alice", 1, 100_000000),
+        mock_bid(2, 1, "bob", 2, 100_000000),
+        mock_bid(3, 1, "alice", 2, 100_000000),
+    ];
+    for (bid, (residue, received)) in
+        bids.iter_mut()
+            .zip([(0u128, 1_010000u128), (50_000000, 510000), (100_000000, 0)])
+    {
+        bid.residue_bid = Uint128::from(residue);
+        bid.amount_received = Uint128::from(received);
+        bid.is_distributed = true;
+    }
+    bids
+}
+
+fn bid_pool_query(msg: &Binary) -> Binary {
+    match from_json(msg).unwrap() {
+        BidPoolQueryMsg::RoundResult { round } => to_json_binary(&RoundResultResponse {
+            round,
+            exchange_rate: Decimal::percent(1),
+            total_bid_amount: Uint128::from(300_000000u128),
+            total_matched: Uint128::from(150_000000u128),
+            total_distribution: Uint128::from(10_000000u128),
+            actual_distributed: Uint128::from(1_520000u128),
+            leftover: Uint128::from(8_480000u128),
+            slots: vec![
+                SlotResult {
+                    slot: 1,
+                    total_bid_amount: Uint128::from(100_000000u128),
+                    matched_percentage: Decimal::one(),
+                    matched_amount: Uint128::from(100_000000u128),
+                    payout_amount: Uint128::from(1_010000u128),
+                },
+                SlotResult {
+                    slot: 2,
+                    total_bid_amount: Uint128::from(200_000000u128),
+                    matched_percentage: Decimal::percent(25),
+                    matched_amount: Uint128::from(50_000000u128),
+                    payout_amount: Uint128::from(510000u128),
+                },
+            ],
+            num_bids: 3,
+            num_bids_distributed: 3,
+        }),
+        BidPoolQueryMsg::AllBidInRound {
+            start_after, limit, ..
+        } => {
+            let bids: Vec<Bid> = round_bids()
+                .into_iter()
+                .filter(|bid| start_after.map_or(true, |start_after| bid.idx > start_after))
+                .collect();
+            let limit = limit.unwrap_or(30) as usize;
+            let has_more = bids.len() > limit;
+            let items: Vec<BidResponse> = bids
+                .into_iter()
+                .take(limit)
+                .map(BidResponse::from)
+                .collect();
+            to_json_binary(&PageResponse {
+                next_start_after: items.last().map(|bid| bid.idx).filter(|_| has_more),
+                items,
+                total: Some(3),
+                has_more,
+            })
+        }
+        _ => panic!("unexpected bid pool query"),
+    }
+    .unwrap()
+}
+
+fn init() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == BID_POOL => {
+            SystemResult::Ok(ContractResult::Ok(bid_pool_query(msg)))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        bid_pool: BID_POOL.to_string(),
+        epoch_duration: DAY,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    deps
+}
+
+fn hook(round: u64) -> ExecuteMsg {
+    ExecuteMsg::BidPoolHook(BidPoolHookMsg::DistributionCompleted { round })
+}
+
+fn process_round(limit: u64) -> ExecuteMsg {
+    ExecuteMsg::ProcessRound {
+        round: 1,
+        limit: Some(limit),
+    }
+}
+
+#[test]
+fn test_process_round() {
+    let mut deps = init();
+    let env = mock_env();
+
+    // only the bid pool sends hooks
+    let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), hook(1)).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        process_round(2),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RoundNotPending { round: 1 });
+
+    // the other hooks are ignored
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(BID_POOL, &[]),
+        ExecuteMsg::BidPoolHook(BidPoolHookMsg::RoundCreated { round: 2 }),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(BID_POOL, &[]),
+        hook(1),
+    )
+    .unwrap();
+
+    // two bids at a time
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        process_round(2),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[3].value, "false");
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        process_round(2),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2].value, "1");
+    assert_eq!(res.attributes[3].value, "true");
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        process_round(2),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RoundNotPending { round: 1 });
+
+    // a processed round is not queued again
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(BID_POOL, &[]),
+        hook(1),
+    )
+    .unwrap();
+    let pending: Vec<PendingRoundResponse> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::PendingRounds {}).unwrap()).unwrap();
+    assert!(pending.is_empty());
+
+    let epochs: Vec<EpochStatsResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::EpochStats {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        epochs,
+        vec![EpochStatsResponse {
+            epoch_start: env.block.time.seconds() / DAY * DAY,
+            underlying_burned: Uint128::from(150_000000u128),
+            distribution_paid: Uint128::from(1_520000u128),
+            num_rounds: 1,
+        }]
+    );
+
+    let totals: UserTotals = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::UserTotals {
+                user: "alice".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        totals,
+        UserTotals {
+            num_bids: 2,
+            total_bid: Uint128::from(200_000000u128),
+            total_matched: Uint128::from(100_000000u128),
+            total_received: Uint128::from(1_010000u128),
+        }
+    );
+
+    let slots: Vec<SlotStatsResponse> =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::SlotStats {}).unwrap()).unwrap();
+    assert_eq!(
+        slots,
+        vec![
+            SlotStatsResponse {
+                slot: 1,
+                num_bids: 1,
+                total_bid_amount: Uint128::from(100_000000u128),
+                total_matched: Uint128::from(100_000000u128),
+                total_payout: Uint128::from(1_010000u128),
+            },
+            SlotStatsResponse {
+                slot: 2,
+                num_bids: 2,
+                total_bid_amount: Uint128::from(200_000000u128),
+                total_matched: Uint128::from(50_000000u128),
+                total_payout: Uint128::from(510000u128),
+            },
+        ]
+    );
+}
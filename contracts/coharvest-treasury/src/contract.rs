@@ -0,0 +1,244 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use oraiswap::asset::{Asset, AssetInfo};
+
+use coharvest_bid_pool::{
+    helper::{into_cosmos_msg, query_balance},
+    msg::{Cw20HookMsg as BidPoolCw20HookMsg, ExecuteMsg as BidPoolExecuteMsg},
+};
+
+use crate::{
+    error::ContractError,
+    msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, StatusResponse},
+    state::{Config, CONFIG, EPOCH_SPENT, LAST_FUNDED_AT},
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        bid_pool: deps.api.addr_validate(&msg.bid_pool)?,
+        funding_token: msg.funding_token,
+        amount_per_round: msg.amount_per_round,
+        round_interval: msg.round_interval,
+        epoch_duration: msg.epoch_duration,
+        budget_per_epoch: msg.budget_per_epoch,
+    };
+    assert_valid_epoch_duration(&config)?;
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            owner,
+            bid_pool,
+            funding_token,
+            amount_per_round,
+            round_interval,
+            epoch_duration,
+            budget_per_epoch,
+        } => execute_update_config(
+            deps,
+            info,
+            owner,
+            bid_pool,
+            funding_token,
+            amount_per_round,
+            round_interval,
+            epoch_duration,
+            budget_per_epoch,
+        ),
+        ExecuteMsg::FundNextRound {} => execute_fund_next_round(deps, env),
+        ExecuteMsg::Withdraw { asset, recipient } => execute_withdraw(deps, info, asset, recipient),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    bid_pool: Option<String>,
+    funding_token: Option<AssetInfo>,
+    amount_per_round: Option<Uint128>,
+    round_interval: Option<u64>,
+    epoch_duration: Option<u64>,
+    budget_per_epoch: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(bid_pool) = bid_pool {
+        config.bid_pool = deps.api.addr_validate(&bid_pool)?;
+    }
+    if let Some(funding_token) = funding_token {
+        config.funding_token = funding_token;
+    }
+    if let Some(amount_per_round) = amount_per_round {
+        config.amount_per_round = amount_per_round;
+    }
+    if let Some(round_interval) = round_interval {
+        config.round_interval = round_interval;
+    }
+    if let Some(epoch_duration) = epoch_duration {
+        config.epoch_duration = epoch_duration;
+    }
+    if let Some(budget_per_epoch) = budget_per_epoch {
+        config.budget_per_epoch = budget_per_epoch;
+    }
+    assert_valid_epoch_duration(&config)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+// permissionless, send the budget of the next round to the bid pool once the round interval has passed
+fn execute_fund_next_round(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+
+    let next_funding_time = read_next_funding_time(deps.storage, &config)?;
+    if now < next_funding_time {
+        return Err(ContractError::FundingNotDue { next_funding_time });
+    }
+
+    // never spend more than what is left of the epoch budget, nor more than the treasury holds
+    let epoch = config.epoch(now);
+    let spent = EPOCH_SPENT
+        .may_load(deps.storage, epoch)?
+        .unwrap_or_default();
+    let balance = query_balance(
+        &deps.querier,
+        &config.funding_token,
+        env.contract.address.clone(),
+    )?;
+    let amount = config
+        .amount_per_round
+        .min(config.budget_per_epoch.saturating_sub(spent))
+        .min(balance);
+    if amount.is_zero() {
+        return Err(ContractError::NoBudgetLeft {});
+    }
+
+    EPOCH_SPENT.save(deps.storage, epoch, &(spent + amount))?;
+    LAST_FUNDED_AT.save(deps.storage, &now)?;
+
+    let msg = match config.funding_token {
+        AssetInfo::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: config.bid_pool.to_string(),
+                amount,
+                msg: to_json_binary(&BidPoolCw20HookMsg::CreateNewRoundFromTreasury {})?,
+            })?,
+            funds: vec![],
+        }),
+        AssetInfo::NativeToken { denom } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: config.bid_pool.to_string(),
+            msg: to_json_binary(&BidPoolExecuteMsg::CreateNewRoundFromTreasury {})?,
+            funds: vec![Coin { denom, amount }],
+        }),
+    };
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "fund_next_round"),
+            ("epoch", &epoch.to_string()),
+            ("amount", &amount.to_string()),
+        ])
+        .add_message(msg))
+}
+
+// only owner can withdraw tokens out of the treasury
+fn execute_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: Asset,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let msg = into_cosmos_msg(&asset.info, recipient.to_string(), asset.amount)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "withdraw"),
+            ("recipient", recipient.as_str()),
+            ("amount", &asset.amount.to_string()),
+        ])
+        .add_message(msg))
+}
+
+fn assert_valid_epoch_duration(config: &Config) -> Result<(), ContractError> {
+    if config.epoch_duration == 0 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "epoch_duration must be greater than zero",
+        )));
+    }
+    Ok(())
+}
+
+fn read_next_funding_time(storage: &dyn Storage, config: &Config) -> StdResult<u64> {
+    Ok(LAST_FUNDED_AT
+        .may_load(storage)?
+        .map_or(0, |last_funded_at| last_funded_at + config.round_interval))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::Status {} => to_json_binary(&query_status(deps, env)?),
+    }
+}
+
+fn query_status(deps: Deps, env: Env) -> StdResult<StatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let epoch = config.epoch(env.block.time.seconds());
+    let epoch_spent = EPOCH_SPENT
+        .may_load(deps.storage, epoch)?
+        .unwrap_or_default();
+
+    Ok(StatusResponse {
+        balance: query_balance(&deps.querier, &config.funding_token, env.contract.address)?,
+        epoch,
+        epoch_spent,
+        epoch_remaining_budget: config.budget_per_epoch.saturating_sub(epoch_spent),
+        next_funding_time: read_next_funding_time(deps.storage, &config)?,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
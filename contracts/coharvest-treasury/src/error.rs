@@ -0,0 +1,17 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Next round can only be funded at {next_funding_time}")]
+    FundingNotDue { next_funding_time: u64 },
+
+    #[error("No budget left to fund a round")]
+    NoBudgetLeft {},
+}
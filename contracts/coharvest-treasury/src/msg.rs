@@ -0,0 +1,81 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+use oraiswap::asset::{Asset, AssetInfo};
+
+use crate::state::Config;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub bid_pool: String,
+    pub funding_token: AssetInfo,
+    pub amount_per_round: Uint128,
+    pub round_interval: u64,
+    pub epoch_duration: u64,
+    pub budget_per_epoch: Uint128,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        owner: Option<String>,
+        bid_pool: Option<String>,
+        funding_token: Option<AssetInfo>,
+        amount_per_round: Option<Uint128>,
+        round_interval: Option<u64>,
+        epoch_duration: Option<u64>,
+        budget_per_epoch: Option<Uint128>,
+    },
+    // anyone can trigger the funding of the next round once it is due
+    FundNextRound {},
+    Withdraw {
+        asset: Asset,
+        recipient: String,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(StatusResponse)]
+    Status {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub bid_pool: Addr,
+    pub funding_token: AssetInfo,
+    pub amount_per_round: Uint128,
+    pub round_interval: u64,
+    pub epoch_duration: u64,
+    pub budget_per_epoch: Uint128,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            bid_pool: config.bid_pool,
+            funding_token: config.funding_token,
+            amount_per_round: config.amount_per_round,
+            round_interval: config.round_interval,
+            epoch_duration: config.epoch_duration,
+            budget_per_epoch: config.budget_per_epoch,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct StatusResponse {
+    pub balance: Uint128,
+    pub epoch: u64,
+    pub epoch_spent: Uint128,
+    pub epoch_remaining_budget: Uint128,
+    pub next_funding_time: u64,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
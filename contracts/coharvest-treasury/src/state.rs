@@ -0,0 +1,27 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+use oraiswap::asset::AssetInfo;
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// time the last round was funded
+pub const LAST_FUNDED_AT: Item<u64> = Item::new("last_funded_at");
+// mapping epoch --> amount sent to the bid pool in that epoch
+pub const EPOCH_SPENT: Map<u64, Uint128> = Map::new("epoch_spent");
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,               // owner address
+    pub bid_pool: Addr,            // bid pool contract funded by this treasury
+    pub funding_token: AssetInfo,  // harvest proceeds, the distribution token of the bid pool
+    pub amount_per_round: Uint128, // amount sent to the bid pool for each round
+    pub round_interval: u64,       // minimum time between two fundings
+    pub epoch_duration: u64,       // length of a budget epoch, in seconds
+    pub budget_per_epoch: Uint128, // maximum amount sent to the bid pool in an epoch
+}
+
+impl Config {
+    pub fn epoch(&self, timestamp: u64) -> u64 {
+        timestamp / self.epoch_duration
+    }
+}
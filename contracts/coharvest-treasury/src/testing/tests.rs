@@ -0,0 +1,199 @@
+use cosmwasm_std::{
+    attr, coins, from_json,
+    testing::{mock_dependencies_with_balance, mock_env, mock_info},
+    to_json_binary, Api, BankMsg, CosmosMsg, OwnedDeps, Querier, Storage, SubMsg, Uint128, WasmMsg,
+};
+use oraiswap::asset::{Asset, AssetInfo};
+
+use coharvest_bid_pool::msg::ExecuteMsg as BidPoolExecuteMsg;
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    msg::{ExecuteMsg, InstantiateMsg, QueryMsg, StatusResponse},
+};
+
+const OWNER: &str = "owner";
+const BID_POOL: &str = "bid_pool";
+const DENOM: &str = "usdc";
+const DAY: u64 = 86400;
+
+pub fn init<S: Storage, A: Api, Q: Querier>(deps: &mut OwnedDeps<S, A, Q>) {
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        bid_pool: BID_POOL.to_string(),
+        funding_token: AssetInfo::NativeToken {
+            denom: DENOM.to_string(),
+        },
+        amount_per_round: Uint128::from(1000_000000u128),
+        round_interval: DAY,
+        epoch_duration: 30 * DAY,
+        budget_per_epoch: Uint128::from(2500_000000u128),
+    };
+
+    let info = mock_info(OWNER, &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+#[test]
+fn test_fund_next_round() {
+    let mut deps = mock_dependencies_with_balance(&coins(10000_000000u128, DENOM));
+    init(&mut deps);
+
+    // anyone can fund the first round right away
+    let mut env = mock_env();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundNextRound {},
+    )
+    .unwrap();
+    let epoch = env.block.time.seconds() / (30 * DAY);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fund_next_round"),
+            attr("epoch", epoch.to_string()),
+            attr("amount", "1000000000"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: BID_POOL.to_string(),
+            msg: to_json_binary(&BidPoolExecuteMsg::CreateNewRoundFromTreasury {}).unwrap(),
+            funds: coins(1000_000000u128, DENOM),
+        }))]
+    );
+
+    // next round is not due yet
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundNextRound {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::FundingNotDue {
+            next_funding_time: env.block.time.seconds() + DAY
+        }
+    );
+
+    env.block.time = env.block.time.plus_seconds(DAY);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundNextRound {},
+    )
+    .unwrap();
+
+    // only 500 left in the epoch budget
+    env.block.time = env.block.time.plus_seconds(DAY);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundNextRound {},
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("amount", "500000000"));
+
+    let status: StatusResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Status {}).unwrap()).unwrap();
+    assert_eq!(
+        status,
+        StatusResponse {
+            // the mock querier does not move the funds sent to the bid pool
+            balance: Uint128::from(10000_000000u128),
+            epoch,
+            epoch_spent: Uint128::from(2500_000000u128),
+            epoch_remaining_budget: Uint128::zero(),
+            next_funding_time: env.block.time.seconds() + DAY,
+        }
+    );
+
+    // epoch budget exhausted
+    env.block.time = env.block.time.plus_seconds(DAY);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundNextRound {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoBudgetLeft {});
+
+    // budget resets in the next epoch
+    env.block.time = env.block.time.plus_seconds(30 * DAY);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundNextRound {},
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_fund_capped_by_balance() {
+    let mut deps = mock_dependencies_with_balance(&coins(300_000000u128, DENOM));
+    init(&mut deps);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundNextRound {},
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("amount", "300000000"));
+
+    let mut deps = mock_dependencies_with_balance(&[]);
+    init(&mut deps);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::FundNextRound {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoBudgetLeft {});
+}
+
+#[test]
+fn test_withdraw() {
+    let mut deps = mock_dependencies_with_balance(&coins(10000_000000u128, DENOM));
+    init(&mut deps);
+
+    let msg = ExecuteMsg::Withdraw {
+        asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: DENOM.to_string(),
+            },
+            amount: Uint128::from(100_000000u128),
+        },
+        recipient: "multisig".to_string(),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "multisig".to_string(),
+            amount: coins(100_000000u128, DENOM),
+        }))]
+    );
+}
@@ -0,0 +1,242 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ReceiveMsg;
+use cw_utils::one_coin;
+use oraiswap::asset::{Asset, AssetInfo};
+
+use coharvest_bid_pool::helper::into_cosmos_msg;
+
+use crate::{
+    error::ContractError,
+    msg::{
+        ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+        VestingResponse,
+    },
+    state::{pop_vesting_idx, read_vestings, Config, Vesting, CONFIG, VESTINGS},
+};
+
+// version info for migration
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        depositors: msg
+            .depositors
+            .iter()
+            .map(|depositor| deps.api.addr_validate(depositor))
+            .collect::<StdResult<_>>()?,
+        vesting_duration: msg.vesting_duration,
+    };
+
+    // store config
+    CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            depositors,
+            vesting_duration,
+        } => execute_update_config(deps, info, owner, depositors, vesting_duration),
+        ExecuteMsg::Vest { recipient } => {
+            let coin = one_coin(&info)?;
+            let asset = Asset {
+                info: AssetInfo::NativeToken { denom: coin.denom },
+                amount: coin.amount,
+            };
+            execute_vest(deps, env, info.sender, recipient, asset)
+        }
+        ExecuteMsg::Claim { start_after, limit } => {
+            execute_claim(deps, env, info, start_after, limit)
+        }
+    }
+}
+
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::Vest { recipient } => {
+            let asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+                amount: cw20_msg.amount,
+            };
+            let depositor = deps.api.addr_validate(&cw20_msg.sender)?;
+            execute_vest(deps, env, depositor, recipient, asset)
+        }
+    }
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    depositors: Option<Vec<String>>,
+    vesting_duration: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(depositors) = depositors {
+        config.depositors = depositors
+            .iter()
+            .map(|depositor| deps.api.addr_validate(depositor))
+            .collect::<StdResult<_>>()?;
+    }
+    if let Some(vesting_duration) = vesting_duration {
+        config.vesting_duration = vesting_duration;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_config"))
+}
+
+// only depositors can lock tokens for a recipient, the schedule starts now
+fn execute_vest(
+    deps: DepsMut,
+    env: Env,
+    depositor: Addr,
+    recipient: String,
+    asset: Asset,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.depositors.contains(&depositor) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let idx = pop_vesting_idx(deps.storage)?;
+    let start_time = env.block.time.seconds();
+    let vesting = Vesting {
+        idx,
+        token: asset.info,
+        total_amount: asset.amount,
+        claimed_amount: Uint128::zero(),
+        start_time,
+        end_time: start_time + config.vesting_duration,
+    };
+    VESTINGS.save(deps.storage, (recipient.clone(), idx), &vesting)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "vest"),
+        ("recipient", recipient.as_str()),
+        ("vesting_idx", &idx.to_string()),
+        ("amount", &asset.amount.to_string()),
+        ("end_time", &vesting.end_time.to_string()),
+    ]))
+}
+
+fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let vestings = read_vestings(deps.storage, info.sender.clone(), start_after, limit)?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut total_claimed = Uint128::zero();
+    for mut vesting in vestings {
+        let claimable = vesting.claimable_amount(now);
+        if claimable.is_zero() {
+            continue;
+        }
+
+        msgs.push(into_cosmos_msg(
+            &vesting.token,
+            info.sender.to_string(),
+            claimable,
+        )?);
+        total_claimed += claimable;
+
+        // fully claimed vestings are removed
+        vesting.claimed_amount += claimable;
+        if vesting.claimed_amount == vesting.total_amount {
+            VESTINGS.remove(deps.storage, (info.sender.clone(), vesting.idx));
+        } else {
+            VESTINGS.save(deps.storage, (info.sender.clone(), vesting.idx), &vesting)?;
+        }
+    }
+
+    if msgs.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "claim"),
+            ("recipient", info.sender.as_str()),
+            ("amount", &total_claimed.to_string()),
+        ])
+        .add_messages(msgs))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse::from(CONFIG.load(deps.storage)?)),
+        QueryMsg::Vestings {
+            recipient,
+            start_after,
+            limit,
+        } => to_json_binary(&query_vestings(deps, env, recipient, start_after, limit)?),
+    }
+}
+
+fn query_vestings(
+    deps: Deps,
+    env: Env,
+    recipient: String,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<VestingResponse>> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let now = env.block.time.seconds();
+
+    Ok(read_vestings(deps.storage, recipient, start_after, limit)?
+        .into_iter()
+        .map(|vesting| VestingResponse::new(vesting, now))
+        .collect())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,96 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+use oraiswap::asset::AssetInfo;
+
+use crate::state::{Config, Vesting};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub depositors: Vec<String>,
+    pub vesting_duration: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateConfig {
+        owner: Option<String>,
+        depositors: Option<Vec<String>>,
+        vesting_duration: Option<u64>,
+    },
+    // vest the attached native token for the recipient
+    Vest {
+        recipient: String,
+    },
+    // claim everything vested so far on a page of the sender's vestings
+    Claim {
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
+}
+
+// same shape as ExecuteMsg::Vest, so depositors can use one message for both token kinds
+#[cw_serde]
+pub enum Cw20HookMsg {
+    Vest { recipient: String },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(Vec<VestingResponse>)]
+    Vestings {
+        recipient: String,
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub depositors: Vec<Addr>,
+    pub vesting_duration: u64,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> Self {
+        Self {
+            owner: config.owner,
+            depositors: config.depositors,
+            vesting_duration: config.vesting_duration,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct VestingResponse {
+    pub idx: u64,
+    pub token: AssetInfo,
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub claimable_amount: Uint128,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+impl VestingResponse {
+    pub fn new(vesting: Vesting, now: u64) -> Self {
+        Self {
+            idx: vesting.idx,
+            claimable_amount: vesting.claimable_amount(now),
+            token: vesting.token,
+            total_amount: vesting.total_amount,
+            claimed_amount: vesting.claimed_amount,
+            start_time: vesting.start_time,
+            end_time: vesting.end_time,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
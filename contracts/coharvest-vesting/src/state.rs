@@ -0,0 +1,75 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
+use oraiswap::asset::AssetInfo;
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const VESTING_IDX: Item<u64> = Item::new("vesting_idx");
+// mapping (recipient, vesting idx) --> Vesting
+pub const VESTINGS: Map<(Addr, u64), Vesting> = Map::new("vestings");
+
+const MAX_LIMIT: u64 = 100;
+const DEFAULT_LIMIT: u64 = 30;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,           // owner address
+    pub depositors: Vec<Addr>, // contracts allowed to create vestings, e.g. the bid pool
+    pub vesting_duration: u64, // how long does a deposit take to be fully vested
+}
+
+#[cw_serde]
+pub struct Vesting {
+    pub idx: u64,
+    pub token: AssetInfo,        // vested token
+    pub total_amount: Uint128,   // amount deposited for the recipient
+    pub claimed_amount: Uint128, // amount already sent to the recipient
+    pub start_time: u64,         // time of the deposit
+    pub end_time: u64,           // time the whole amount is vested
+}
+
+impl Vesting {
+    // amount vested at `now`, released linearly between start_time and end_time
+    pub fn vested_amount(&self, now: u64) -> Uint128 {
+        if now >= self.end_time {
+            return self.total_amount;
+        }
+        if now <= self.start_time {
+            return Uint128::zero();
+        }
+
+        self.total_amount
+            .multiply_ratio(now - self.start_time, self.end_time - self.start_time)
+    }
+
+    pub fn claimable_amount(&self, now: u64) -> Uint128 {
+        self.vested_amount(now) - self.claimed_amount
+    }
+}
+
+pub fn pop_vesting_idx(storage: &mut dyn Storage) -> StdResult<u64> {
+    let idx = VESTING_IDX.may_load(storage)?.unwrap_or_default() + 1;
+    VESTING_IDX.save(storage, &idx)?;
+    Ok(idx)
+}
+
+pub fn read_vestings(
+    storage: &dyn Storage,
+    recipient: Addr,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Vec<Vesting>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    VESTINGS
+        .prefix(recipient)
+        .range(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, vesting)| vesting))
+        .collect()
+}
@@ -0,0 +1,167 @@
+use cosmwasm_std::{
+    attr, from_json,
+    testing::{mock_dependencies, mock_env, mock_info},
+    to_json_binary, Addr, Api, CosmosMsg, OwnedDeps, Querier, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use oraiswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, VestingResponse},
+};
+
+const OWNER: &str = "owner";
+const BID_POOL: &str = "bid_pool";
+const USDC: &str = "usdc_token";
+const DURATION: u64 = 1000;
+
+pub fn init<S: Storage, A: Api, Q: Querier>(deps: &mut OwnedDeps<S, A, Q>) {
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        depositors: vec![BID_POOL.to_string()],
+        vesting_duration: DURATION,
+    };
+
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+}
+
+fn vest_msg(depositor: &str, recipient: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: depositor.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_json_binary(&Cw20HookMsg::Vest {
+            recipient: recipient.to_string(),
+        })
+        .unwrap(),
+    })
+}
+
+#[test]
+fn test_vest_and_claim() {
+    let mut deps = mock_dependencies();
+    init(&mut deps);
+    let mut env = mock_env();
+
+    // only depositors can vest
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &[]),
+        vest_msg("addr001", "addr000", 1000_000000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USDC, &[]),
+        vest_msg(BID_POOL, "addr000", 1000_000000),
+    )
+    .unwrap();
+
+    // nothing vested yet
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &[]),
+        ExecuteMsg::Claim {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToClaim {});
+
+    // a quarter of the duration later
+    env.block.time = env.block.time.plus_seconds(DURATION / 4);
+    let vestings: Vec<VestingResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Vestings {
+                recipient: "addr000".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        vestings,
+        vec![VestingResponse {
+            idx: 1,
+            token: AssetInfo::Token {
+                contract_addr: Addr::unchecked(USDC),
+            },
+            total_amount: Uint128::from(1000_000000u128),
+            claimed_amount: Uint128::zero(),
+            claimable_amount: Uint128::from(250_000000u128),
+            start_time: mock_env().block.time.seconds(),
+            end_time: mock_env().block.time.seconds() + DURATION,
+        }]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &[]),
+        ExecuteMsg::Claim {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim"),
+            attr("recipient", "addr000"),
+            attr("amount", "250000000"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: USDC.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr000".to_string(),
+                amount: Uint128::from(250_000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // the rest once fully vested, then the vesting is gone
+    env.block.time = env.block.time.plus_seconds(DURATION);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr000", &[]),
+        ExecuteMsg::Claim {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("amount", "750000000"));
+
+    let vestings: Vec<VestingResponse> = from_json(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::Vestings {
+                recipient: "addr000".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(vestings, vec![]);
+}